@@ -4,12 +4,10 @@
 // Target: WebSocket latency < 20ms (p95)
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
-use std::sync::Arc;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc;
-use web_terminal::protocol::{ClientMessage, ServerMessage};
-use web_terminal::pty::{PtyConfig, PtyManager, ShellConfig};
-use web_terminal::session::{SessionConfig, SessionId, SessionManager, UserId};
+use web_terminal::protocol::{ClientMessage, CompressionAlgorithm, OutputEncoder, ServerMessage};
+use web_terminal::pty::{PtyConfig, PtyManager};
 
 /// Benchmark message serialization/deserialization
 fn bench_message_serialization(c: &mut Criterion) {
@@ -23,14 +21,8 @@ fn bench_message_serialization(c: &mut Criterion) {
                 data: "echo 'test'".to_string(),
             },
         ),
-        (
-            "input",
-            ClientMessage::Input {
-                data: "test input".to_string(),
-            },
-        ),
         ("resize", ClientMessage::Resize { rows: 24, cols: 80 }),
-        ("ping", ClientMessage::Ping),
+        ("ping", ClientMessage::Ping { timestamp: None }),
     ];
 
     for (name, msg) in client_messages.iter() {
@@ -46,22 +38,32 @@ fn bench_message_serialization(c: &mut Criterion) {
         (
             "output",
             ServerMessage::Output {
+                stream: None,
                 data: "test output\n".to_string(),
             },
         ),
         (
             "status",
-            ServerMessage::Status {
+            ServerMessage::ConnectionStatus {
                 status: web_terminal::protocol::ConnectionStatus::Connected,
+                session_id: None,
             },
         ),
         (
             "error",
             ServerMessage::Error {
+                code: "TEST_ERROR".to_string(),
                 message: "test error".to_string(),
+                details: None,
+            },
+        ),
+        (
+            "pong",
+            ServerMessage::Pong {
+                timestamp: None,
+                latency_ms: None,
             },
         ),
-        ("pong", ServerMessage::Pong),
     ];
 
     for (name, msg) in server_messages.iter() {
@@ -93,6 +95,7 @@ fn bench_message_throughput(c: &mut Criterion) {
                     // Simulate sending messages
                     for i in 0..count {
                         let msg = ServerMessage::Output {
+                            stream: None,
                             data: format!("Message {}\n", i),
                         };
                         tx.send(msg).unwrap();
@@ -128,7 +131,10 @@ fn bench_message_latency_by_size(c: &mut Criterion) {
 
         group.bench_with_input(BenchmarkId::from_parameter(size), &data, |b, data| {
             b.to_async(&rt).iter(|| async {
-                let msg = ServerMessage::Output { data: data.clone() };
+                let msg = ServerMessage::Output {
+                    stream: None,
+                    data: data.clone(),
+                };
 
                 // Serialize
                 let json = serde_json::to_string(black_box(&msg)).unwrap();
@@ -149,14 +155,17 @@ fn bench_round_trip_latency(c: &mut Criterion) {
     c.bench_function("websocket_round_trip_ping_pong", |b| {
         b.to_async(&rt).iter(|| async {
             // Simulate ping-pong round trip
-            let ping = ClientMessage::Ping;
+            let ping = ClientMessage::Ping { timestamp: None };
             let ping_json = serde_json::to_string(&ping).unwrap();
 
             // Simulate network serialization/deserialization
             let _decoded: ClientMessage = serde_json::from_str(&ping_json).unwrap();
 
             // Server response
-            let pong = ServerMessage::Pong;
+            let pong = ServerMessage::Pong {
+                timestamp: None,
+                latency_ms: None,
+            };
             let pong_json = serde_json::to_string(&pong).unwrap();
 
             let _decoded: ServerMessage = serde_json::from_str(&pong_json).unwrap();
@@ -180,6 +189,7 @@ fn bench_concurrent_messages(c: &mut Criterion) {
                     for i in 0..count {
                         let handle = tokio::spawn(async move {
                             let msg = ServerMessage::Output {
+                                stream: None,
                                 data: format!("Concurrent message {}\n", i),
                             };
                             serde_json::to_string(&msg).unwrap()
@@ -220,6 +230,7 @@ fn bench_pty_output_streaming(c: &mut Criterion) {
 
             // Create mock WebSocket message
             let msg = ServerMessage::Output {
+                stream: None,
                 data: "benchmark test\n".to_string(),
             };
             let _serialized = serde_json::to_string(&msg).unwrap();
@@ -244,6 +255,7 @@ fn bench_batch_message_processing(c: &mut Criterion) {
                     // Create batch of messages
                     let messages: Vec<_> = (0..size)
                         .map(|i| ServerMessage::Output {
+                            stream: None,
                             data: format!("Batch message {}\n", i),
                         })
                         .collect();
@@ -263,6 +275,50 @@ fn bench_batch_message_processing(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark output framing: JSON text vs compressed binary frames
+/// Per spec-kit/007-websocket-spec.md: Binary output framing
+fn bench_output_compression(c: &mut Criterion) {
+    let mut group = c.benchmark_group("websocket_output_compression");
+
+    // Build-log style output compresses well and is the common bulk case
+    let output: Vec<u8> = (0..2000)
+        .map(|i| {
+            format!(
+                "   Compiling crate-{} v0.1.{} (/src/crate-{})\r\n",
+                i % 13,
+                i,
+                i % 13
+            )
+        })
+        .collect::<String>()
+        .into_bytes();
+
+    for chunk_size in [256, 4096, 32768].iter() {
+        let chunk = &output[..*chunk_size];
+        group.throughput(Throughput::Bytes(*chunk_size as u64));
+
+        group.bench_with_input(BenchmarkId::new("json", chunk_size), chunk, |b, chunk| {
+            b.iter(|| {
+                let msg = ServerMessage::Output {
+                    stream: None,
+                    data: String::from_utf8_lossy(chunk).into_owned(),
+                };
+                black_box(serde_json::to_string(&msg).unwrap())
+            });
+        });
+
+        for algorithm in [CompressionAlgorithm::Deflate, CompressionAlgorithm::Zstd] {
+            let encoder = OutputEncoder::new(algorithm, 0, 3);
+            let name = format!("{:?}", algorithm).to_lowercase();
+            group.bench_with_input(BenchmarkId::new(name, chunk_size), chunk, |b, chunk| {
+                b.iter(|| black_box(encoder.encode(black_box(chunk))));
+            });
+        }
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_message_serialization,
@@ -271,6 +327,7 @@ criterion_group!(
     bench_round_trip_latency,
     bench_concurrent_messages,
     bench_pty_output_streaming,
-    bench_batch_message_processing
+    bench_batch_message_processing,
+    bench_output_compression
 );
 criterion_main!(benches);
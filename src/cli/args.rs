@@ -14,6 +14,11 @@ pub struct Cli {
     #[arg(short, long, global = true, env = "WEB_TERMINAL_CONFIG")]
     pub config: Option<PathBuf>,
 
+    /// Configuration profile applied over the config file (e.g. staging,
+    /// production)
+    #[arg(long, global = true, env = "WEB_TERMINAL_PROFILE")]
+    pub profile: Option<String>,
+
     /// Enable verbose logging
     #[arg(short, long, global = true, conflicts_with = "quiet")]
     pub verbose: bool,
@@ -40,6 +45,9 @@ pub enum Commands {
     /// Check server status
     Status(StatusArgs),
 
+    /// Attach the local terminal to a server session
+    Attach(AttachArgs),
+
     /// Session management commands
     #[command(subcommand)]
     Sessions(SessionCommands),
@@ -52,6 +60,18 @@ pub enum Commands {
     #[command(subcommand)]
     Users(UserCommands),
 
+    /// Token revocation commands
+    #[command(subcommand)]
+    Revocations(RevocationCommands),
+
+    /// Failed authentication lockout commands
+    #[command(subcommand)]
+    Lockouts(LockoutCommands),
+
+    /// Audit log commands
+    #[command(subcommand)]
+    Audit(AuditCommands),
+
     /// View server logs
     Logs(LogsArgs),
 
@@ -91,9 +111,15 @@ pub struct StartArgs {
     #[arg(long, env = "WEB_TERMINAL_TLS_KEY")]
     pub tls_key: Option<PathBuf>,
 
-    /// Run as background daemon
+    /// Detach and run in the background (output goes to `logging.file` or
+    /// the runtime directory)
     #[arg(short, long)]
     pub daemon: bool,
+
+    /// Pidfile path (default: `<runtime dir>/web-terminal.pid`); written in
+    /// the foreground too, e.g. for systemd
+    #[arg(long, env = "WEB_TERMINAL_PID_FILE")]
+    pub pid_file: Option<PathBuf>,
 }
 
 #[derive(Parser, Debug)]
@@ -102,16 +128,16 @@ pub struct StopArgs {
     #[arg(short, long)]
     pub force: bool,
 
-    /// Graceful shutdown timeout in seconds
-    #[arg(short, long, default_value = "30")]
-    pub timeout: u64,
+    /// Graceful shutdown timeout in seconds (default: `server.shutdown.timeout`)
+    #[arg(short, long)]
+    pub timeout: Option<u64>,
 }
 
 #[derive(Parser, Debug)]
 pub struct RestartArgs {
-    /// Graceful shutdown timeout in seconds
-    #[arg(short, long, default_value = "30")]
-    pub timeout: u64,
+    /// Graceful shutdown timeout in seconds (default: `server.shutdown.timeout`)
+    #[arg(short, long)]
+    pub timeout: Option<u64>,
 }
 
 #[derive(Parser, Debug)]
@@ -123,6 +149,41 @@ pub struct StatusArgs {
     /// Continuously watch status
     #[arg(short, long)]
     pub watch: bool,
+
+    /// Watch interval in seconds
+    #[arg(short, long, default_value = "2")]
+    pub interval: u64,
+}
+
+// ============================================================================
+// Terminal Commands
+// ============================================================================
+
+#[derive(Parser, Debug)]
+pub struct AttachArgs {
+    /// Session ID to attach to, or "new" to start a session
+    pub session: String,
+
+    /// Server URL
+    #[arg(
+        short,
+        long,
+        env = "WEB_TERMINAL_URL",
+        default_value = "http://localhost:8080"
+    )]
+    pub url: String,
+
+    /// JWT used to authenticate
+    #[arg(short, long, env = "WEB_TERMINAL_TOKEN", hide_env_values = true)]
+    pub token: String,
+
+    /// Detach key ("ctrl-]", "^A", or "none")
+    #[arg(short, long, default_value = "ctrl-]")]
+    pub escape: String,
+
+    /// Do not reconnect when the connection drops
+    #[arg(long)]
+    pub no_reconnect: bool,
 }
 
 // ============================================================================
@@ -147,6 +208,14 @@ pub struct SessionListArgs {
     #[arg(short, long)]
     pub user: Option<String>,
 
+    /// Filter by tenant
+    #[arg(short, long)]
+    pub tenant: Option<String>,
+
+    /// Only sessions idle for longer than the session timeout
+    #[arg(short, long)]
+    pub expired: bool,
+
     /// Output format
     #[arg(short, long, value_enum, default_value = "table")]
     pub format: OutputFormat,
@@ -161,7 +230,8 @@ pub struct SessionKillArgs {
     /// Session ID to kill
     pub session_id: String,
 
-    /// Force kill without cleanup
+    /// Kill the terminal at once instead of hanging it up and waiting
+    /// `server.shutdown.kill_after` for it to exit
     #[arg(short, long)]
     pub force: bool,
 }
@@ -181,10 +251,165 @@ pub struct SessionCleanupArgs {
 pub enum SessionSortField {
     Id,
     User,
+    /// Oldest first
     Created,
+    /// Most recently active first
     Activity,
 }
 
+// ============================================================================
+// Token Revocation Commands
+// ============================================================================
+
+#[derive(Subcommand, Debug)]
+pub enum RevocationCommands {
+    /// List active revocations
+    List(RevocationListArgs),
+
+    /// Revoke a token, a subject, or tokens issued before a time
+    Add(RevocationAddArgs),
+
+    /// Lift a revocation
+    Remove(RevocationRemoveArgs),
+}
+
+/// Server connection shared by the admin commands
+#[derive(Parser, Debug)]
+pub struct AdminServerArgs {
+    /// Server URL
+    #[arg(
+        short,
+        long,
+        env = "WEB_TERMINAL_URL",
+        default_value = "http://localhost:8080"
+    )]
+    pub url: String,
+
+    /// Token of a user with the administer permission
+    #[arg(short, long, env = "WEB_TERMINAL_TOKEN", hide_env_values = true)]
+    pub token: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct RevocationListArgs {
+    #[command(flatten)]
+    pub server: AdminServerArgs,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct RevocationAddArgs {
+    #[command(flatten)]
+    pub server: AdminServerArgs,
+
+    /// Token ID (jti claim) to revoke
+    #[arg(long)]
+    pub jti: Option<String>,
+
+    /// Subject (sub claim) to revoke
+    #[arg(long)]
+    pub sub: Option<String>,
+
+    /// Revoke tokens issued before this time (RFC 3339 or "now")
+    #[arg(long)]
+    pub issued_before: Option<String>,
+
+    /// Reason recorded with the revocation
+    #[arg(short, long)]
+    pub reason: Option<String>,
+
+    /// Drop the revocation after this time (RFC 3339)
+    #[arg(long)]
+    pub expires_at: Option<String>,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct RevocationRemoveArgs {
+    #[command(flatten)]
+    pub server: AdminServerArgs,
+
+    /// Revocation ID
+    pub id: String,
+}
+
+// ============================================================================
+// Lockout Commands
+// ============================================================================
+
+#[derive(Subcommand, Debug)]
+pub enum LockoutCommands {
+    /// List addresses and subjects locked out after failed authentications
+    List(LockoutListArgs),
+
+    /// Lift a lockout, or every lockout with --all
+    Clear(LockoutClearArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct LockoutListArgs {
+    #[command(flatten)]
+    pub server: AdminServerArgs,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct LockoutClearArgs {
+    #[command(flatten)]
+    pub server: AdminServerArgs,
+
+    /// Locked out key: ip:<address> or sub:<subject>
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    pub key: Option<String>,
+
+    /// Lift every lockout
+    #[arg(long)]
+    pub all: bool,
+}
+
+// ============================================================================
+// Audit Log Commands
+// ============================================================================
+
+#[derive(Subcommand, Debug)]
+pub enum AuditCommands {
+    /// Check the hash chain of an audit log and its rotated files
+    Verify(AuditVerifyArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct AuditVerifyArgs {
+    /// Live audit log (auth.security.audit.path); rotated files next to it
+    /// are checked first
+    pub path: PathBuf,
+
+    /// Key the records are signed with (auth.security.audit.hmac_key_file)
+    #[arg(long)]
+    pub key_file: PathBuf,
+
+    /// Check only this file, not its rotated predecessors
+    #[arg(long)]
+    pub no_rotated: bool,
+
+    /// Accept a chain whose first records were rotated away, checking it
+    /// from its first remaining record
+    #[arg(long)]
+    pub allow_partial: bool,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
 // ============================================================================
 // Configuration Commands
 // ============================================================================
@@ -0,0 +1,40 @@
+// Admin API client shared by the admin commands
+// Per spec-kit/006-api-spec.md: Admin API (/api/v1/admin)
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+
+use crate::cli::args::AdminServerArgs;
+
+/// Build a request against `/api/v1/admin/<path>`
+pub(super) fn request(
+    server: &AdminServerArgs,
+    method: reqwest::Method,
+    path: &str,
+) -> reqwest::RequestBuilder {
+    let url = format!("{}/api/v1/admin/{}", server.url.trim_end_matches('/'), path);
+    reqwest::Client::new()
+        .request(method, url)
+        .bearer_auth(&server.token)
+}
+
+/// Send `request`, turning an error response into an error carrying the
+/// server's message
+pub(super) async fn send(request: reqwest::RequestBuilder) -> Result<Value> {
+    let response = request.send().await.context("Failed to reach server")?;
+    let status = response.status();
+    let body: Value = if status == reqwest::StatusCode::NO_CONTENT {
+        Value::Null
+    } else {
+        response.json().await.unwrap_or(Value::Null)
+    };
+
+    if !status.is_success() {
+        let message = body["error"]["message"]
+            .as_str()
+            .unwrap_or_else(|| status.canonical_reason().unwrap_or("request failed"));
+        return Err(anyhow!("{} ({})", message, status));
+    }
+
+    Ok(body)
+}
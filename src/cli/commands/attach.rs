@@ -0,0 +1,303 @@
+// Attach command
+// Per spec-kit/005-cli-spec.md
+//
+// Proxies the local terminal to a server session over WebSocket: stdin is
+// forwarded as raw bytes, output is written to stdout, and window size
+// changes are sent as resize messages.
+
+use std::io::Read;
+
+use anyhow::{anyhow, Context, Result};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+
+use crate::cli::args::AttachArgs;
+use crate::client::{ClientConfig, ClientEvent, ReconnectPolicy, TerminalClient};
+use crate::protocol::ServerMessage;
+
+pub async fn execute(args: AttachArgs) -> Result<()> {
+    let escape = parse_escape_key(&args.escape)?;
+
+    let mut config = ClientConfig::new(&args.url, &args.token);
+    if args.session != "new" {
+        config = config.attach(&args.session);
+    }
+    if args.no_reconnect {
+        config = config.with_reconnect(ReconnectPolicy::disabled());
+    }
+
+    let client = TerminalClient::connect(config)
+        .await
+        .with_context(|| format!("Failed to attach to {}", args.url))?;
+
+    eprintln!(
+        "Attached to session {} as {} (detach with {})",
+        client.session_id(),
+        client.user_id(),
+        args.escape
+    );
+
+    let raw_mode = tty::RawMode::enable()?;
+    let result = proxy(client, escape).await;
+    drop(raw_mode);
+
+    match result? {
+        Exit::Detached(session_id) => {
+            eprintln!("\nDetached from session {}", session_id);
+            Ok(())
+        }
+        Exit::Closed(None) => {
+            eprintln!("\nSession ended");
+            Ok(())
+        }
+        Exit::Closed(Some(error)) => Err(anyhow!(error).context("Connection lost")),
+    }
+}
+
+/// Why the proxy loop stopped
+enum Exit {
+    /// The user pressed the escape key; the session keeps running
+    Detached(String),
+    /// The server side went away
+    Closed(Option<crate::client::ClientError>),
+}
+
+async fn proxy(mut client: TerminalClient, escape: Option<u8>) -> Result<Exit> {
+    let mut stdin = spawn_stdin_reader();
+    let mut stdin_open = true;
+    let mut stdout = tokio::io::stdout();
+    let mut resized = tty::window_changes()?;
+
+    if let Some((cols, rows)) = tty::window_size() {
+        client.resize(cols, rows)?;
+    }
+
+    loop {
+        tokio::select! {
+            read = stdin.recv(), if stdin_open => {
+                // Piped input ran out: keep streaming output until the session ends
+                let Some(chunk) = read else {
+                    stdin_open = false;
+                    continue;
+                };
+
+                let (input, detach) = split_at_escape(&chunk, escape);
+                if !input.is_empty() {
+                    client.handle().send_raw(input.to_vec())?;
+                }
+                if detach {
+                    let session_id = client.session_id();
+                    client.close().await;
+                    return Ok(Exit::Detached(session_id));
+                }
+            }
+            _ = resized.recv() => {
+                if let Some((cols, rows)) = tty::window_size() {
+                    client.resize(cols, rows)?;
+                }
+            }
+            event = client.next_event() => match event {
+                Some(ClientEvent::Output(data)) => {
+                    stdout.write_all(&data).await?;
+                    stdout.flush().await?;
+                }
+                Some(ClientEvent::Message(ServerMessage::Error { message, .. })) => {
+                    eprint!("\r\n[web-terminal] {}\r\n", message);
+                }
+                Some(ClientEvent::Message(ServerMessage::TokenExpiring { expires_in, .. })) => {
+                    eprint!(
+                        "\r\n[web-terminal] Token expires in {}s; reattach with a fresh token\r\n",
+                        expires_in
+                    );
+                }
+                Some(ClientEvent::Message(_)) => {}
+                Some(ClientEvent::Reconnecting { attempt, delay }) => {
+                    eprint!(
+                        "\r\n[web-terminal] Connection lost, reconnecting in {:?} (attempt {})\r\n",
+                        delay, attempt
+                    );
+                }
+                Some(ClientEvent::Reconnected { .. }) => {
+                    eprint!("\r\n[web-terminal] Reconnected\r\n");
+                    if let Some((cols, rows)) = tty::window_size() {
+                        client.resize(cols, rows)?;
+                    }
+                }
+                Some(ClientEvent::Closed { error }) => return Ok(Exit::Closed(error)),
+                None => return Ok(Exit::Closed(None)),
+            }
+        }
+    }
+}
+
+/// Read stdin on a plain thread; closes the channel at EOF
+///
+/// tokio's stdin reads on the blocking pool, which would keep the runtime
+/// from shutting down after a detach until another key is pressed.
+fn spawn_stdin_reader() -> mpsc::Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel(16);
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut buf = [0u8; 4096];
+        loop {
+            match stdin.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if tx.blocking_send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    rx
+}
+
+/// Parse a detach key: "ctrl-<c>", "^<c>", or "none"
+fn parse_escape_key(spec: &str) -> Result<Option<u8>> {
+    if spec.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+
+    let key = spec
+        .strip_prefix('^')
+        .or_else(|| {
+            spec.get(..5)
+                .filter(|prefix| prefix.eq_ignore_ascii_case("ctrl-"))
+                .map(|_| &spec[5..])
+        })
+        .filter(|key| key.len() == 1)
+        .and_then(|key| key.bytes().next())
+        .filter(|key| (b'@'..=b'_').contains(&key.to_ascii_uppercase()))
+        .ok_or_else(|| anyhow!("Invalid escape key '{}': expected e.g. ctrl-] or ^A", spec))?;
+
+    Ok(Some(key.to_ascii_uppercase() & 0x1f))
+}
+
+/// Split input at the escape byte: bytes to forward, and whether to detach
+fn split_at_escape(input: &[u8], escape: Option<u8>) -> (&[u8], bool) {
+    match escape.and_then(|key| input.iter().position(|&b| b == key)) {
+        Some(pos) => (&input[..pos], true),
+        None => (input, false),
+    }
+}
+
+#[cfg(unix)]
+mod tty {
+    use anyhow::{Context, Result};
+    use tokio::signal::unix::{signal, Signal, SignalKind};
+
+    /// Puts stdin in raw mode until dropped (no-op when stdin is not a tty)
+    pub struct RawMode {
+        original: Option<libc::termios>,
+    }
+
+    impl RawMode {
+        pub fn enable() -> Result<Self> {
+            // SAFETY: isatty/tcgetattr/tcsetattr only read and write the
+            // termios struct we pass for the stdin descriptor
+            unsafe {
+                if libc::isatty(libc::STDIN_FILENO) != 1 {
+                    return Ok(Self { original: None });
+                }
+
+                let mut original: libc::termios = std::mem::zeroed();
+                if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                    return Err(std::io::Error::last_os_error())
+                        .context("Failed to read terminal attributes");
+                }
+
+                let mut raw = original;
+                libc::cfmakeraw(&mut raw);
+                if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) != 0 {
+                    return Err(std::io::Error::last_os_error())
+                        .context("Failed to enable raw mode");
+                }
+
+                Ok(Self {
+                    original: Some(original),
+                })
+            }
+        }
+    }
+
+    impl Drop for RawMode {
+        fn drop(&mut self) {
+            if let Some(original) = &self.original {
+                // SAFETY: restores attributes previously read from the same descriptor
+                unsafe {
+                    libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original);
+                }
+            }
+        }
+    }
+
+    /// Current terminal size as (cols, rows)
+    pub fn window_size() -> Option<(u16, u16)> {
+        // SAFETY: TIOCGWINSZ writes a winsize struct we own
+        let size = unsafe {
+            let mut size: libc::winsize = std::mem::zeroed();
+            if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) != 0 {
+                return None;
+            }
+            size
+        };
+        (size.ws_col > 0 && size.ws_row > 0).then_some((size.ws_col, size.ws_row))
+    }
+
+    /// SIGWINCH notifications
+    pub fn window_changes() -> Result<Signal> {
+        signal(SignalKind::window_change()).context("Failed to listen for SIGWINCH")
+    }
+}
+
+#[cfg(not(unix))]
+mod tty {
+    use anyhow::{bail, Result};
+
+    pub struct RawMode;
+
+    impl RawMode {
+        pub fn enable() -> Result<Self> {
+            bail!("web-terminal attach is only supported on Unix terminals")
+        }
+    }
+
+    pub fn window_size() -> Option<(u16, u16)> {
+        None
+    }
+
+    pub struct NoSignal;
+
+    impl NoSignal {
+        pub async fn recv(&mut self) -> Option<()> {
+            std::future::pending().await
+        }
+    }
+
+    pub fn window_changes() -> Result<NoSignal> {
+        Ok(NoSignal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_escape_key() {
+        assert_eq!(parse_escape_key("ctrl-]").unwrap(), Some(0x1d));
+        assert_eq!(parse_escape_key("^A").unwrap(), Some(0x01));
+        assert_eq!(parse_escape_key("Ctrl-b").unwrap(), Some(0x02));
+        assert_eq!(parse_escape_key("none").unwrap(), None);
+        assert!(parse_escape_key("ctrl-").is_err());
+        assert!(parse_escape_key("x").is_err());
+    }
+
+    #[test]
+    fn test_split_at_escape() {
+        assert_eq!(split_at_escape(b"ls\r", Some(0x1d)), (&b"ls\r"[..], false));
+        assert_eq!(split_at_escape(b"ab\x1dcd", Some(0x1d)), (&b"ab"[..], true));
+        assert_eq!(split_at_escape(b"\x1d", None), (&b"\x1d"[..], false));
+    }
+}
@@ -0,0 +1,51 @@
+// Audit log commands
+// Per spec-kit/005-cli-spec.md
+// Per spec-kit/011-authentication-spec.md: Audit logging
+
+use anyhow::{bail, Result};
+
+use crate::cli::args::{AuditCommands, AuditVerifyArgs};
+use crate::security::audit::{chain_files, verify_chain, AuditKey};
+
+pub async fn execute(cmd: AuditCommands) -> Result<()> {
+    match cmd {
+        AuditCommands::Verify(args) => verify(args),
+    }
+}
+
+fn verify(args: AuditVerifyArgs) -> Result<()> {
+    if !args.path.exists() {
+        bail!("Audit log {} not found", args.path.display());
+    }
+    let files = if args.no_rotated {
+        vec![args.path.clone()]
+    } else {
+        chain_files(&args.path)
+    };
+
+    let key = AuditKey::read(&args.key_file)?;
+    let summary = verify_chain(&files, &key)?;
+    if !args.allow_partial {
+        summary.require_genesis()?;
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+
+    println!("✅ Audit log intact");
+    for file in &summary.files {
+        println!("  {}", file.display());
+    }
+    println!("\n  Records:   {}", summary.records);
+    if let (Some(first), Some(last)) = (summary.first_seq, summary.last_seq) {
+        println!("  Sequence:  {} - {}", first, last);
+    }
+    println!("  Last hash: {}", summary.last_hash);
+    if summary.records > 0 && !summary.from_genesis {
+        println!("\n⚠️  Earlier records were rotated away; the chain is checked from its first remaining record");
+    }
+
+    Ok(())
+}
@@ -4,57 +4,54 @@
 use crate::cli::args::{
     ConfigCommands, ConfigFormat, ConfigSetArgs, ConfigShowArgs, ConfigValidateArgs,
 };
+use crate::config::{Config, ConfigSources};
 use anyhow::{Context, Result};
+use serde::Serialize;
 use std::path::PathBuf;
 
-pub async fn execute(cmd: ConfigCommands, config_path: Option<PathBuf>) -> Result<()> {
+/// Shown instead of secrets
+const REDACTED: &str = "********";
+
+pub async fn execute(cmd: ConfigCommands, sources: ConfigSources) -> Result<()> {
     match cmd {
-        ConfigCommands::Show(args) => show(args, config_path).await,
+        ConfigCommands::Show(args) => show(args, sources).await,
         ConfigCommands::Set(args) => set(args).await,
-        ConfigCommands::Validate(args) => validate(args, config_path).await,
+        ConfigCommands::Validate(args) => validate(args, sources).await,
     }
 }
 
-async fn show(args: ConfigShowArgs, config_path: Option<PathBuf>) -> Result<()> {
-    let path = config_path.unwrap_or_else(|| PathBuf::from("config.toml"));
-
-    println!("📄 Configuration: {}", path.display());
-
-    // TODO: Load actual configuration
-    let config = r#"
-[server]
-host = "0.0.0.0"
-port = 8080
-workers = 4
-
-[session]
-timeout = 1800
-max_per_user = 10
-
-[security]
-jwt_secret_env = "WEB_TERMINAL_JWT_SECRET"
-"#;
-
-    if let Some(section) = args.section {
-        println!("\n📋 Section: {}", section);
-        println!("⚠️  Section filtering not yet implemented");
-    }
-
-    match args.format {
-        ConfigFormat::Toml => println!("{}", config),
-        ConfigFormat::Json => {
-            println!("⚠️  JSON format not yet implemented");
-            println!("{}", config);
-        }
-        ConfigFormat::Yaml => {
-            println!("⚠️  YAML format not yet implemented");
-            println!("{}", config);
-        }
-    }
+/// Display the merged configuration the server would run with
+async fn show(args: ConfigShowArgs, sources: ConfigSources) -> Result<()> {
+    let loaded = Config::load(&sources)?;
+    let mut config = loaded.config;
+    config.security.jwt_secret = REDACTED.to_string();
+
+    let rendered = match args.section.as_deref() {
+        None => render(&config, args.format)?,
+        Some("server") => render(&config.server, args.format)?,
+        Some("session") => render(&config.session, args.format)?,
+        Some("security") => render(&config.security, args.format)?,
+        Some("logging") => render(&config.logging, args.format)?,
+        Some("auth") => render(&config.auth, args.format)?,
+        Some(section) => anyhow::bail!(
+            "Unknown configuration section: {} (expected server, session, security, logging or auth)",
+            section
+        ),
+    };
+
+    println!("{}", rendered);
 
     Ok(())
 }
 
+fn render<T: Serialize>(value: &T, format: ConfigFormat) -> Result<String> {
+    Ok(match format {
+        ConfigFormat::Toml => toml::to_string_pretty(value)?,
+        ConfigFormat::Json => serde_json::to_string_pretty(value)?,
+        ConfigFormat::Yaml => serde_yaml::to_string(value)?,
+    })
+}
+
 async fn set(args: ConfigSetArgs) -> Result<()> {
     let path = args.file.unwrap_or_else(|| PathBuf::from("config.toml"));
 
@@ -69,21 +66,31 @@ async fn set(args: ConfigSetArgs) -> Result<()> {
     Ok(())
 }
 
-async fn validate(args: ConfigValidateArgs, config_path: Option<PathBuf>) -> Result<()> {
-    let path = args
-        .file
-        .or(config_path)
-        .unwrap_or_else(|| PathBuf::from("config.toml"));
+/// Load every configuration layer and fail on anything the server would
+/// reject or ignore
+async fn validate(args: ConfigValidateArgs, mut sources: ConfigSources) -> Result<()> {
+    if let Some(file) = args.file {
+        sources.file = Some(file);
+    }
+
+    println!("🔍 Validating configuration");
 
-    println!("🔍 Validating configuration: {}", path.display());
+    let loaded = Config::load(&sources)?;
+    for file in &loaded.files {
+        println!("  📄 {}", file.display());
+    }
 
-    if !path.exists() {
-        anyhow::bail!("Configuration file not found: {}", path.display());
+    if !loaded.unknown_keys.is_empty() {
+        for key in &loaded.unknown_keys {
+            println!("  ⚠️  Unknown key: {}", key);
+        }
+        anyhow::bail!(
+            "Invalid configuration: {} unknown key(s)",
+            loaded.unknown_keys.len()
+        );
     }
 
-    // TODO: Actual validation
-    println!("⚠️  Validation not yet implemented");
-    println!("✅ Basic check: file exists");
+    println!("✅ Configuration is valid");
 
     Ok(())
 }
@@ -0,0 +1,22 @@
+// Control socket client shared by the management commands
+// Per spec-kit/005-cli-spec.md: Process Management
+
+use anyhow::Result;
+
+use crate::config::{Config, ConfigSources};
+use crate::server::control::{unexpected, ControlClient, ControlRequest, ControlResponse};
+
+/// Send `request` to the server named by the configuration in `sources`
+/// and return its reply
+pub(super) async fn request(
+    sources: &ConfigSources,
+    request: &ControlRequest,
+) -> Result<ControlResponse> {
+    let socket = Config::load(sources)?.config.server.control.socket_path();
+    Ok(ControlClient::request(&socket, request).await?)
+}
+
+/// Error for a reply that does not answer the request
+pub(super) fn unexpected_reply(response: &ControlResponse) -> anyhow::Error {
+    unexpected(response).into()
+}
@@ -2,33 +2,25 @@
 // Per spec-kit/005-cli-spec.md
 
 use crate::cli::args::HealthArgs;
+use crate::config::ConfigSources;
 use anyhow::Result;
 
-pub async fn execute(args: HealthArgs) -> Result<()> {
-    println!("🏥 Health Check");
+#[cfg(unix)]
+use super::control::{self, unexpected_reply};
+#[cfg(unix)]
+use crate::server::control::{ControlRequest, ControlResponse};
 
-    let health = HealthStatus {
-        healthy: true,
-        uptime_seconds: 0,
-        checks: vec![
-            Check {
-                name: "server".to_string(),
-                status: "unknown".to_string(),
-            },
-            Check {
-                name: "sessions".to_string(),
-                status: "unknown".to_string(),
-            },
-            Check {
-                name: "memory".to_string(),
-                status: "unknown".to_string(),
-            },
-        ],
+#[cfg(unix)]
+pub async fn execute(args: HealthArgs, sources: ConfigSources) -> Result<()> {
+    let health = match control::request(&sources, &ControlRequest::Health).await? {
+        ControlResponse::Health(health) => health,
+        other => return Err(unexpected_reply(&other)),
     };
 
     if args.json {
         println!("{}", serde_json::to_string_pretty(&health)?);
     } else {
+        println!("🏥 Health Check");
         println!(
             "\nOverall: {}",
             if health.healthy {
@@ -37,30 +29,26 @@ pub async fn execute(args: HealthArgs) -> Result<()> {
                 "❌ unhealthy"
             }
         );
+        println!(
+            "Version: {} (uptime {}s)",
+            health.version, health.uptime_secs
+        );
 
-        if args.verbose {
+        if args.verbose || !health.healthy {
             println!("\nComponent Status:");
             for check in &health.checks {
-                println!("  {} - {}", check.name, check.status);
+                println!("  {} - {} ({})", check.name, check.status, check.detail);
             }
         }
-
-        println!("\n⚠️  Health check not yet implemented");
-        println!("Server health endpoint: /health");
     }
 
+    if !health.healthy {
+        anyhow::bail!("Server unhealthy");
+    }
     Ok(())
 }
 
-#[derive(Debug, serde::Serialize)]
-struct HealthStatus {
-    healthy: bool,
-    uptime_seconds: u64,
-    checks: Vec<Check>,
-}
-
-#[derive(Debug, serde::Serialize)]
-struct Check {
-    name: String,
-    status: String,
+#[cfg(not(unix))]
+pub async fn execute(_args: HealthArgs, _sources: ConfigSources) -> Result<()> {
+    anyhow::bail!("web-terminal health is only supported on Unix; use GET /api/v1/health instead")
 }
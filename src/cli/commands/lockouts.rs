@@ -0,0 +1,64 @@
+// Failed authentication lockout commands
+// Per spec-kit/005-cli-spec.md
+// Per spec-kit/006-api-spec.md: Admin API (/api/v1/admin/lockouts)
+
+use anyhow::Result;
+
+use super::admin_api::{request, send};
+use crate::cli::args::{LockoutClearArgs, LockoutCommands, LockoutListArgs};
+
+pub async fn execute(cmd: LockoutCommands) -> Result<()> {
+    match cmd {
+        LockoutCommands::List(args) => list(args).await,
+        LockoutCommands::Clear(args) => clear(args).await,
+    }
+}
+
+async fn list(args: LockoutListArgs) -> Result<()> {
+    let body = send(request(&args.server, reqwest::Method::GET, "lockouts")).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&body)?);
+        return Ok(());
+    }
+
+    let lockouts = body["lockouts"].as_array().cloned().unwrap_or_default();
+    if lockouts.is_empty() {
+        println!("No active lockouts");
+        return Ok(());
+    }
+
+    println!("🔒 Lockouts");
+    for lockout in &lockouts {
+        println!(
+            "  {:<40} until {}",
+            lockout["key"].as_str().unwrap_or_default(),
+            lockout["until"].as_str().unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+async fn clear(args: LockoutClearArgs) -> Result<()> {
+    match args.key {
+        Some(key) => {
+            send(request(
+                &args.server,
+                reqwest::Method::DELETE,
+                &format!("lockouts/{}", key),
+            ))
+            .await?;
+            println!("✅ Cleared lockout of {}", key);
+        }
+        None => {
+            let body = send(request(&args.server, reqwest::Method::DELETE, "lockouts")).await?;
+            println!(
+                "✅ Cleared {} lockout(s)",
+                body["cleared"].as_u64().unwrap_or_default()
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -2,59 +2,142 @@
 // Per spec-kit/005-cli-spec.md
 
 use crate::cli::args::{MetricsArgs, MetricsFormat};
+use crate::config::ConfigSources;
 use anyhow::Result;
 
-pub async fn execute(args: MetricsArgs) -> Result<()> {
-    if args.watch {
+#[cfg(unix)]
+use super::control::{self, unexpected_reply};
+#[cfg(unix)]
+use crate::server::control::{ControlRequest, ControlResponse, MetricsSnapshot};
+
+#[cfg(unix)]
+pub async fn execute(args: MetricsArgs, sources: ConfigSources) -> Result<()> {
+    if !args.watch {
+        let metrics = fetch(&sources).await?;
+        print_metrics(&metrics, None, args.format)?;
+        return Ok(());
+    }
+
+    if matches!(args.format, MetricsFormat::Human) {
         println!(
             "📊 Watching metrics (interval: {}s, Ctrl+C to exit)...",
             args.interval
         );
-        println!("\n⚠️  Watch mode not yet implemented");
-        return Ok(());
     }
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(args.interval.max(1)));
+    let mut previous: Option<(std::time::Instant, f64)> = None;
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+        let metrics = fetch(&sources).await?;
+        let now = std::time::Instant::now();
 
-    println!("📊 Server Metrics");
+        // CPU usage since the previous sample
+        let cpu_percent = previous.map(|(at, cpu)| {
+            let elapsed = now.duration_since(at).as_secs_f64();
+            (metrics.cpu_seconds - cpu).max(0.0) / elapsed * 100.0
+        });
+        previous = Some((now, metrics.cpu_seconds));
 
-    let metrics = Metrics {
-        active_sessions: 0,
-        total_requests: 0,
-        memory_usage_mb: 0,
-        cpu_percent: 0.0,
-        uptime_seconds: 0,
-    };
+        print_metrics(&metrics, cpu_percent, args.format)?;
+    }
+}
 
-    match args.format {
+#[cfg(not(unix))]
+pub async fn execute(_args: MetricsArgs, _sources: ConfigSources) -> Result<()> {
+    anyhow::bail!("web-terminal metrics is only supported on Unix; scrape /api/v1/metrics instead")
+}
+
+#[cfg(unix)]
+async fn fetch(sources: &ConfigSources) -> Result<MetricsSnapshot> {
+    match control::request(sources, &ControlRequest::Metrics).await? {
+        ControlResponse::Metrics(metrics) => Ok(metrics),
+        other => Err(unexpected_reply(&other)),
+    }
+}
+
+#[cfg(unix)]
+fn print_metrics(
+    metrics: &MetricsSnapshot,
+    cpu_percent: Option<f64>,
+    format: MetricsFormat,
+) -> Result<()> {
+    match format {
         MetricsFormat::Human => {
-            println!("\nPerformance:");
-            println!("  Active sessions: {}", metrics.active_sessions);
-            println!("  Total requests: {}", metrics.total_requests);
-            println!("  Memory usage: {} MB", metrics.memory_usage_mb);
-            println!("  CPU usage: {:.1}%", metrics.cpu_percent);
-            println!("  Uptime: {}s", metrics.uptime_seconds);
+            println!("\n📊 Server Metrics");
+            println!("\nSessions:");
+            println!("  Active sessions: {}", metrics.sessions);
+            println!("  Users: {}", metrics.users);
+            println!("  Connected clients: {}", metrics.clients);
+            println!(
+                "  Terminals: {} running ({} tracked)",
+                metrics.terminals_running, metrics.terminals
+            );
+            println!("\nProcess:");
+            if let Some(rss) = metrics.memory_rss_bytes {
+                println!("  Memory usage: {:.1} MB", rss as f64 / (1024.0 * 1024.0));
+            }
+            match cpu_percent {
+                Some(percent) => println!("  CPU usage: {:.1}%", percent),
+                None => println!("  CPU time: {:.1}s", metrics.cpu_seconds),
+            }
+            println!("  Uptime: {}s", metrics.uptime_secs);
+            if metrics.draining {
+                println!("  State: draining");
+            }
         }
         MetricsFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&metrics)?);
+            let mut value = serde_json::to_value(metrics)?;
+            if let Some(object) = value.as_object_mut() {
+                object.remove("prometheus");
+                if let Some(percent) = cpu_percent {
+                    object.insert("cpu_percent".to_string(), percent.into());
+                }
+            }
+            println!("{}", serde_json::to_string(&value)?);
         }
         MetricsFormat::Prometheus => {
-            println!("# HELP active_sessions Number of active sessions");
-            println!("# TYPE active_sessions gauge");
-            println!("active_sessions {}", metrics.active_sessions);
-            println!();
-            println!("# HELP total_requests Total number of requests");
-            println!("# TYPE total_requests counter");
-            println!("total_requests {}", metrics.total_requests);
+            let gauges = [
+                (
+                    "sessions_active",
+                    "Active sessions",
+                    metrics.sessions as f64,
+                ),
+                ("users_active", "Users with a session", metrics.users as f64),
+                (
+                    "clients_connected",
+                    "Attached WebSocket and ttyd clients",
+                    metrics.clients as f64,
+                ),
+                (
+                    "terminals_running",
+                    "Terminal processes running",
+                    metrics.terminals_running as f64,
+                ),
+                (
+                    "uptime_seconds",
+                    "Seconds since the server started",
+                    metrics.uptime_secs as f64,
+                ),
+            ];
+            for (name, help, value) in gauges {
+                println!("# HELP web_terminal_{} {}", name, help);
+                println!("# TYPE web_terminal_{} gauge", name);
+                println!("web_terminal_{} {}", name, value);
+            }
+            println!("# HELP web_terminal_cpu_seconds_total User and system CPU time");
+            println!("# TYPE web_terminal_cpu_seconds_total counter");
+            println!("web_terminal_cpu_seconds_total {}", metrics.cpu_seconds);
+            if let Some(rss) = metrics.memory_rss_bytes {
+                println!("# HELP web_terminal_memory_rss_bytes Resident memory");
+                println!("# TYPE web_terminal_memory_rss_bytes gauge");
+                println!("web_terminal_memory_rss_bytes {}", rss);
+            }
+            print!("{}", metrics.prometheus);
         }
     }
 
     Ok(())
 }
-
-#[derive(Debug, serde::Serialize)]
-struct Metrics {
-    active_sessions: usize,
-    total_requests: usize,
-    memory_usage_mb: usize,
-    cpu_percent: f64,
-    uptime_seconds: u64,
-}
@@ -1,41 +1,68 @@
 // CLI command implementations
 // Per spec-kit/005-cli-spec.md
 
+mod admin_api;
+mod attach;
+mod audit;
 mod completions;
 mod config;
+#[cfg(unix)]
+mod control;
 mod health;
+mod lockouts;
 mod logs;
 mod metrics;
+mod revocations;
 mod server;
 mod sessions;
 mod users;
 
 use crate::cli::args::{Cli, Commands};
+use crate::config::ConfigSources;
+use crate::server::LogFilter;
 use anyhow::Result;
+use std::sync::Arc;
 
 /// Execute CLI command
 pub async fn execute(cli: Cli) -> Result<()> {
     // Set up logging level based on verbose/quiet flags
-    setup_logging(&cli);
+    let log_filter = setup_logging(&cli);
+
+    let sources = config_sources(&cli);
 
     // Execute the command
     match cli.command {
-        Commands::Start(args) => server::start(args, cli.config).await,
-        Commands::Stop(args) => server::stop(args).await,
-        Commands::Restart(args) => server::restart(args).await,
-        Commands::Status(args) => server::status(args).await,
-        Commands::Sessions(cmd) => sessions::execute(cmd).await,
-        Commands::Config(cmd) => config::execute(cmd, cli.config).await,
+        Commands::Start(args) => server::start(args, sources, log_filter).await,
+        Commands::Stop(args) => server::stop(args, sources).await,
+        Commands::Restart(args) => server::restart(args, sources).await,
+        Commands::Status(args) => server::status(args, sources).await,
+        Commands::Attach(args) => attach::execute(args).await,
+        Commands::Sessions(cmd) => sessions::execute(cmd, sources).await,
+        Commands::Config(cmd) => config::execute(cmd, sources).await,
         Commands::Users(cmd) => users::execute(cmd).await,
+        Commands::Revocations(cmd) => revocations::execute(cmd).await,
+        Commands::Lockouts(cmd) => lockouts::execute(cmd).await,
+        Commands::Audit(cmd) => audit::execute(cmd).await,
         Commands::Logs(args) => logs::execute(args).await,
-        Commands::Metrics(args) => metrics::execute(args).await,
-        Commands::Health(args) => health::execute(args).await,
+        Commands::Metrics(args) => metrics::execute(args, sources).await,
+        Commands::Health(args) => health::execute(args, sources).await,
         Commands::Completions(args) => completions::execute(args),
     }
 }
 
-fn setup_logging(cli: &Cli) {
-    use tracing_subscriber::{fmt, EnvFilter};
+/// Config file and profile named on the command line or in the environment
+fn config_sources(cli: &Cli) -> ConfigSources {
+    ConfigSources {
+        file: cli.config.clone(),
+        profile: cli.profile.clone(),
+        ..ConfigSources::default()
+    }
+}
+
+/// Install the log subscriber; returns a handle replacing its filter unless
+/// the level is pinned by `--verbose`, `--quiet` or `RUST_LOG`
+fn setup_logging(cli: &Cli) -> Option<LogFilter> {
+    use tracing_subscriber::{fmt, prelude::*, reload, EnvFilter};
 
     let level = if cli.verbose {
         "debug"
@@ -45,7 +72,26 @@ fn setup_logging(cli: &Cli) {
         "info"
     };
 
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+    let from_env = EnvFilter::try_from_default_env().ok();
+    let pinned = cli.verbose || cli.quiet || from_env.is_some();
+    let (filter, handle) = reload::Layer::new(from_env.unwrap_or_else(|| EnvFilter::new(level)));
+
+    // Log to stderr so stdout stays clean for command output and attached terminals
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            fmt::layer()
+                .with_target(false)
+                .with_ansi(std::io::IsTerminal::is_terminal(&std::io::stderr()))
+                .with_writer(std::io::stderr),
+        )
+        .init();
 
-    fmt().with_env_filter(filter).with_target(false).init();
+    if pinned {
+        return None;
+    }
+    Some(Arc::new(move |directives: &str| {
+        let filter = EnvFilter::try_new(directives).map_err(|e| e.to_string())?;
+        handle.reload(filter).map_err(|e| e.to_string())
+    }))
 }
@@ -0,0 +1,127 @@
+// Token revocation commands
+// Per spec-kit/005-cli-spec.md
+// Per spec-kit/006-api-spec.md: Admin API (/api/v1/admin/revocations)
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use super::admin_api::{request, send};
+use crate::cli::args::{
+    RevocationAddArgs, RevocationCommands, RevocationListArgs, RevocationRemoveArgs,
+};
+
+pub async fn execute(cmd: RevocationCommands) -> Result<()> {
+    match cmd {
+        RevocationCommands::List(args) => list(args).await,
+        RevocationCommands::Add(args) => add(args).await,
+        RevocationCommands::Remove(args) => remove(args).await,
+    }
+}
+
+async fn list(args: RevocationListArgs) -> Result<()> {
+    let body = send(request(&args.server, reqwest::Method::GET, "revocations")).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&body)?);
+        return Ok(());
+    }
+
+    let revocations = body["revocations"].as_array().cloned().unwrap_or_default();
+    if revocations.is_empty() {
+        println!("No active revocations");
+        return Ok(());
+    }
+
+    println!("🚫 Revocations");
+    for revocation in &revocations {
+        print_revocation(revocation);
+    }
+
+    Ok(())
+}
+
+async fn add(args: RevocationAddArgs) -> Result<()> {
+    if args.jti.is_none() && args.sub.is_none() && args.issued_before.is_none() {
+        bail!("Specify at least one of --jti, --sub and --issued-before");
+    }
+
+    let issued_before = args
+        .issued_before
+        .as_deref()
+        .map(|value| parse_time(value, "--issued-before"))
+        .transpose()?;
+    let expires_at = args
+        .expires_at
+        .as_deref()
+        .map(|value| parse_time(value, "--expires-at"))
+        .transpose()?;
+
+    let body = send(
+        request(&args.server, reqwest::Method::POST, "revocations").json(&json!({
+            "jti": args.jti,
+            "sub": args.sub,
+            "issued_before": issued_before,
+            "reason": args.reason,
+            "expires_at": expires_at,
+        })),
+    )
+    .await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&body)?);
+    } else {
+        println!("✅ Revoked");
+        print_revocation(&body);
+    }
+
+    Ok(())
+}
+
+async fn remove(args: RevocationRemoveArgs) -> Result<()> {
+    send(request(
+        &args.server,
+        reqwest::Method::DELETE,
+        &format!("revocations/{}", args.id),
+    ))
+    .await?;
+
+    println!("✅ Removed revocation {}", args.id);
+
+    Ok(())
+}
+
+/// Parse an RFC 3339 time, or "now"
+fn parse_time(value: &str, flag: &str) -> Result<DateTime<Utc>> {
+    if value.eq_ignore_ascii_case("now") {
+        return Ok(Utc::now());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .with_context(|| {
+            format!(
+                "Invalid {} time '{}': expected RFC 3339 or \"now\"",
+                flag, value
+            )
+        })
+}
+
+fn print_revocation(revocation: &Value) {
+    let field = |name: &str| revocation[name].as_str().map(str::to_string);
+
+    println!("\n  {}", field("id").unwrap_or_default());
+    for (label, name) in [
+        ("Token", "jti"),
+        ("Subject", "sub"),
+        ("Issued before", "issued_before"),
+        ("Reason", "reason"),
+        ("Expires", "expires_at"),
+    ] {
+        if let Some(value) = field(name) {
+            println!("    {}: {}", label, value);
+        }
+    }
+    if let (Some(by), Some(at)) = (field("created_by"), field("created_at")) {
+        println!("    Revoked by {} at {}", by, at);
+    }
+}
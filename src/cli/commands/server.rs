@@ -2,166 +2,326 @@
 // Per spec-kit/005-cli-spec.md
 
 use crate::cli::args::{RestartArgs, StartArgs, StatusArgs, StopArgs};
+use crate::config::{Config, ConfigSources};
+#[cfg(unix)]
+use crate::server::control::{
+    ControlClient, ControlError, ControlRequest, ControlResponse, PidFile, ServerStatus,
+};
+use crate::server::LogFilter;
+#[cfg(unix)]
+use crate::server::ShutdownSummary;
 use anyhow::{Context, Result};
-use std::path::PathBuf;
-
-pub async fn start(args: StartArgs, config_path: Option<PathBuf>) -> Result<()> {
+#[cfg(unix)]
+use std::path::Path;
+
+pub async fn start(
+    args: StartArgs,
+    sources: ConfigSources,
+    log_filter: Option<LogFilter>,
+) -> Result<()> {
     println!("🚀 Starting web-terminal server...");
 
-    // Load configuration
-    let config = load_config(config_path, &args)?;
+    // Load configuration: defaults, config file, profile, environment, flags
+    // Per spec-kit/005-cli-spec.md: Configuration Layers
+    let sources = start_overrides(sources, &args)?;
+    let loaded = Config::load(&sources)?;
+    for file in &loaded.files {
+        println!("📄 Loaded config from: {}", file.display());
+    }
+    for key in &loaded.unknown_keys {
+        println!("⚠️  Unknown configuration key: {}", key);
+        tracing::warn!(key = %key, "Unknown configuration key");
+    }
+    let config = loaded.config;
+
+    // The configured level applies unless pinned by -v/-q or RUST_LOG
+    if let Some(log_filter) = &log_filter {
+        if let Err(e) = log_filter(&config.logging.level) {
+            println!("⚠️  Ignoring logging.level: {}", e);
+        }
+    }
 
     println!("📋 Configuration:");
-    println!("  Host: {}", config.host);
-    println!("  Port: {}", config.port);
-    println!("  Workers: {}", config.workers);
+    println!("  Host: {}", config.server.host);
+    println!("  Port: {}", config.server.port);
+    println!("  Workers: {}", config.server.worker_threads);
 
-    if let (Some(cert), Some(key)) = (&config.tls_cert, &config.tls_key) {
+    if let Some(tls) = &config.server.tls {
         println!("  TLS: enabled");
-        println!("    Certificate: {}", cert.display());
-        println!("    Key: {}", key.display());
+        println!("    Certificate: {}", tls.cert_path);
+        println!("    Key: {}", tls.key_path);
     }
 
-    if args.daemon {
+    // One server per pidfile; checked again when the server starts
+    // Per spec-kit/005-cli-spec.md: Process Management
+    #[cfg(unix)]
+    if let Some(pid) = PidFile::running(&config.server.control.pid_file()) {
+        if pid != std::process::id() {
+            return Err(ControlError::AlreadyRunning(pid).into());
+        }
+    }
+
+    if args.daemon && !daemon::is_detached() {
         println!("  Mode: daemon");
-        println!("\n⚠️  Daemon mode not yet implemented");
-        println!("For now, run in foreground with Ctrl+C to stop");
+        let log = config
+            .logging
+            .file
+            .clone()
+            .unwrap_or_else(|| config.server.control.daemon_log());
+        let status = daemon::spawn(&log, &config.server.control).await?;
+
+        println!("\n✅ Server started in the background (pid {})", status.pid);
+        for listener in &status.listeners {
+            println!("📡 Listening on {}", listener);
+        }
+        println!("📄 Log: {}", log.display());
+        println!("🛑 Stop with: web-terminal stop");
+        return Ok(());
     }
 
     // Start the actual server
     // Per spec-kit/003-backend-spec.md: Single-port architecture
     println!("\n🚀 Starting server...");
 
-    use crate::config::Config as ServerConfig;
     use crate::server::Server;
-    use crate::session::{SessionConfig, SessionManager};
+    use crate::session::SessionManager;
 
-    // Load server configuration
-    let server_config = ServerConfig::default();
-    let session_config = SessionConfig::default();
+    let (host, port) = (config.server.host.clone(), config.server.port);
+    let shutdown_timeout = config.server.shutdown.timeout;
 
     // Create session manager
-    let session_manager = SessionManager::new(session_config);
+    let session_manager = SessionManager::new(config.session.clone());
 
     // Create and start server
     // Per spec-kit/011-authentication-spec.md: External JWT authentication only
-    let server = Server::new(server_config, session_manager);
+    // SIGHUP or POST /api/v1/admin/config/reload re-reads the same sources
+    // Per spec-kit/005-cli-spec.md: Live Reload
+    let mut server = Server::new(config, session_manager)
+        .with_config_sources(sources)
+        .with_control_channel();
+    if let Some(log_filter) = log_filter {
+        server = server.with_log_filter(log_filter);
+    }
 
-    println!("✅ Server started on {}:{}", config.host, config.port);
-    println!(
-        "📡 WebSocket endpoint: ws://{}:{}/ws",
-        config.host, config.port
-    );
-    println!(
-        "💚 Health check: http://{}:{}/api/v1/health",
-        config.host, config.port
-    );
+    println!("✅ Server started on {}:{}", host, port);
+    println!("📡 WebSocket endpoint: ws://{}:{}/ws", host, port);
+    println!("💚 Health check: http://{}:{}/api/v1/health", host, port);
     println!("\n🔐 External JWT authentication enabled (JWKS-based)");
     println!("⚠️  Configure JWKS providers in config file");
-    println!("\n🛑 Press Ctrl+C to stop");
+    println!(
+        "\n🛑 Press Ctrl+C to stop (sessions drain for up to {}s; press again to stop at once)",
+        shutdown_timeout.as_secs()
+    );
 
-    server.run().await?;
+    // Per spec-kit/005-cli-spec.md: Graceful Shutdown
+    let summary = server.run().await?;
+    println!("\n✅ Server stopped: {}", summary);
+
+    // Same process, same arguments, configuration read afresh
+    // Per spec-kit/005-cli-spec.md: Process Management
+    if summary.restart {
+        println!("🔄 Restarting...");
+        daemon::exec_again()?;
+    }
 
     Ok(())
 }
 
-pub async fn stop(args: StopArgs) -> Result<()> {
+#[cfg(unix)]
+pub async fn stop(args: StopArgs, sources: ConfigSources) -> Result<()> {
     println!("🛑 Stopping web-terminal server...");
 
+    let config = Config::load(&sources)?.config;
     if args.force {
         println!("  Mode: force stop (no graceful shutdown)");
     } else {
-        println!("  Mode: graceful shutdown (timeout: {}s)", args.timeout);
+        let timeout = args
+            .timeout
+            .unwrap_or(config.server.shutdown.timeout.as_secs());
+        println!("  Mode: graceful shutdown (timeout: {}s)", timeout);
     }
 
-    println!("\n⚠️  Stop command not yet implemented");
-    println!("For now, use Ctrl+C or kill command");
+    let request = ControlRequest::Stop {
+        timeout_secs: args.timeout,
+        force: args.force,
+    };
+    let summary = drain(&config.server.control.socket_path(), &request).await?;
+    println!("✅ Server stopped: {}", summary);
 
     Ok(())
 }
 
-pub async fn restart(args: RestartArgs) -> Result<()> {
+#[cfg(unix)]
+pub async fn restart(args: RestartArgs, sources: ConfigSources) -> Result<()> {
     println!("🔄 Restarting web-terminal server...");
-    println!("  Timeout: {}s", args.timeout);
 
-    println!("\n⚠️  Restart command not yet implemented");
-    println!("For now, stop and start manually");
+    let config = Config::load(&sources)?.config;
+    let timeout = args
+        .timeout
+        .unwrap_or(config.server.shutdown.timeout.as_secs());
+    println!("  Timeout: {}s", timeout);
 
-    Ok(())
+    let socket = config.server.control.socket_path();
+    let before = ControlClient::status(&socket).await?;
+    let request = ControlRequest::Restart {
+        timeout_secs: args.timeout,
+    };
+    let summary = drain(&socket, &request).await?;
+    println!("✅ Server stopped: {}", summary);
+
+    // The new server has a new start time (and the same pid)
+    let deadline = tokio::time::Instant::now() + daemon::READY_TIMEOUT;
+    loop {
+        match ControlClient::status(&socket).await {
+            Ok(status) if status.started_at != before.started_at => {
+                println!("✅ Server restarted (pid {})", status.pid);
+                for listener in &status.listeners {
+                    println!("📡 Listening on {}", listener);
+                }
+                return Ok(());
+            }
+            _ if tokio::time::Instant::now() >= deadline => {
+                anyhow::bail!(
+                    "Server not running: no answer on {} {}s after restart",
+                    socket.display(),
+                    daemon::READY_TIMEOUT.as_secs()
+                );
+            }
+            _ => tokio::time::sleep(std::time::Duration::from_millis(200)).await,
+        }
+    }
 }
 
-pub async fn status(args: StatusArgs) -> Result<()> {
-    if args.watch {
-        println!("👀 Watching server status (Ctrl+C to exit)...");
-        println!("\n⚠️  Watch mode not yet implemented");
+#[cfg(unix)]
+pub async fn status(args: StatusArgs, sources: ConfigSources) -> Result<()> {
+    let config = Config::load(&sources)?.config;
+    let socket = config.server.control.socket_path();
+
+    if !args.watch {
+        let status = ControlClient::status(&socket).await;
+        if args.json && status.is_err() {
+            println!("{}", serde_json::json!({ "running": false }));
+        }
+        print_status(&status?, args.json)?;
         return Ok(());
     }
 
-    let status = ServerStatus {
-        running: false,
-        uptime: None,
-        active_sessions: 0,
-        total_connections: 0,
-        memory_usage_mb: 0,
-    };
-
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&status)?);
-    } else {
-        println!("📊 Server Status");
-        println!(
-            "  Running: {}",
-            if status.running { "✅ yes" } else { "❌ no" }
-        );
-        if let Some(uptime) = status.uptime {
-            println!("  Uptime: {}", format_duration(uptime));
+    if !args.json {
+        println!("👀 Watching server status (Ctrl+C to exit)...");
+    }
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(args.interval.max(1)));
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+        match ControlClient::status(&socket).await {
+            Ok(status) => {
+                if !args.json {
+                    println!();
+                }
+                print_status(&status, args.json)?;
+            }
+            Err(ControlError::NotRunning(_)) if args.json => {
+                println!("{}", serde_json::json!({ "running": false }));
+            }
+            Err(ControlError::NotRunning(_)) => println!("\n📊 Server Status\n  Running: ❌ no"),
+            Err(e) => return Err(e.into()),
         }
-        println!("  Active sessions: {}", status.active_sessions);
-        println!("  Total connections: {}", status.total_connections);
-        println!("  Memory usage: {} MB", status.memory_usage_mb);
     }
+}
 
-    Ok(())
+#[cfg(not(unix))]
+pub async fn stop(_args: StopArgs, _sources: ConfigSources) -> Result<()> {
+    anyhow::bail!("web-terminal stop is only supported on Unix; stop the server with Ctrl+C")
+}
+
+#[cfg(not(unix))]
+pub async fn restart(_args: RestartArgs, _sources: ConfigSources) -> Result<()> {
+    anyhow::bail!("web-terminal restart is only supported on Unix")
+}
+
+#[cfg(not(unix))]
+pub async fn status(_args: StatusArgs, _sources: ConfigSources) -> Result<()> {
+    anyhow::bail!("web-terminal status is only supported on Unix")
 }
 
 // Helper types and functions
 
-#[derive(Debug, serde::Serialize)]
-struct ServerStatus {
-    running: bool,
-    uptime: Option<u64>,
-    active_sessions: usize,
-    total_connections: usize,
-    memory_usage_mb: usize,
+/// Send `stop` or `restart` and wait for the drain summary
+#[cfg(unix)]
+async fn drain(socket: &Path, request: &ControlRequest) -> Result<ShutdownSummary> {
+    let mut client = ControlClient::connect(socket).await?;
+    client.send(request).await?;
+    loop {
+        match client.receive().await? {
+            Some(ControlResponse::Draining { seconds }) if seconds > 0 => {
+                println!("⏳ Draining sessions (up to {}s)...", seconds)
+            }
+            Some(ControlResponse::Draining { .. }) => println!("⏳ Stopping terminals..."),
+            Some(ControlResponse::Stopped(summary)) => return Ok(summary),
+            Some(other) => anyhow::bail!("Unexpected reply from server: {:?}", other),
+            None => anyhow::bail!("Server closed the control connection before stopping"),
+        }
+    }
 }
 
-struct ServerConfig {
-    host: String,
-    port: u16,
-    workers: usize,
-    tls_cert: Option<PathBuf>,
-    tls_key: Option<PathBuf>,
+#[cfg(unix)]
+fn print_status(status: &ServerStatus, json: bool) -> Result<()> {
+    if json {
+        let mut value = serde_json::to_value(status)?;
+        value["running"] = true.into();
+        println!("{}", serde_json::to_string(&value)?);
+        return Ok(());
+    }
+
+    println!("📊 Server Status");
+    println!(
+        "  Running: ✅ yes (pid {}, version {})",
+        status.pid, status.version
+    );
+    if status.draining {
+        println!("  State: draining");
+    }
+    println!(
+        "  Started: {} (uptime {})",
+        status.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        format_duration(status.uptime_secs)
+    );
+    for listener in &status.listeners {
+        println!("  Listening: {}", listener);
+    }
+    println!("  Active sessions: {}", status.sessions);
+    println!("  Connected clients: {}", status.clients);
+
+    Ok(())
 }
 
-fn load_config(config_path: Option<PathBuf>, args: &StartArgs) -> Result<ServerConfig> {
-    // Default configuration
-    let config = ServerConfig {
-        host: args.host.clone().unwrap_or_else(|| "0.0.0.0".to_string()),
-        port: args.port.unwrap_or(8080),
-        workers: args.workers.unwrap_or_else(|| num_cpus::get()),
-        tls_cert: args.tls_cert.clone(),
-        tls_key: args.tls_key.clone(),
-    };
+/// `sources` with the `start` flags as the last configuration layer
+fn start_overrides(mut sources: ConfigSources, args: &StartArgs) -> Result<ConfigSources> {
+    if args.tls_cert.is_some() != args.tls_key.is_some() {
+        anyhow::bail!("Invalid configuration: --tls-cert and --tls-key must be given together");
+    }
 
-    // TODO: Load from config file if provided
-    if let Some(path) = config_path {
-        println!("📄 Loading config from: {}", path.display());
-        // config::Config::builder()
-        //     .add_source(config::File::from(path))
-        //     .build()?;
+    if let Some(host) = &args.host {
+        sources = sources.with_override("server.host", host);
+    }
+    if let Some(port) = args.port {
+        sources = sources.with_override("server.port", port);
+    }
+    if let Some(workers) = args.workers {
+        sources = sources.with_override("server.worker_threads", workers);
+    }
+    if let Some(pid_file) = &args.pid_file {
+        sources = sources.with_override("server.control.pid_file", pid_file.display());
+    }
+    if let (Some(cert), Some(key)) = (&args.tls_cert, &args.tls_key) {
+        sources = sources
+            .with_override("server.tls.cert_path", cert.display())
+            .with_override("server.tls.key_path", key.display());
     }
 
-    Ok(config)
+    Ok(sources)
 }
 
 fn format_duration(seconds: u64) -> String {
@@ -180,3 +340,123 @@ fn format_duration(seconds: u64) -> String {
         format!("{}s", secs)
     }
 }
+
+/// Detaching into the background and starting again after `restart`
+/// Per spec-kit/005-cli-spec.md: Process Management
+#[cfg(unix)]
+mod daemon {
+    use crate::config::ControlConfig;
+    use crate::server::control::{ControlClient, ControlError, ServerStatus};
+    use anyhow::{Context, Result};
+    use std::os::unix::fs::DirBuilderExt;
+    use std::os::unix::process::CommandExt;
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+
+    /// Set in the detached server so it runs in the foreground
+    const DETACHED_ENV: &str = "WEB_TERMINAL_DAEMONIZED";
+
+    /// How long a new server gets to answer on the control socket
+    pub const READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn is_detached() -> bool {
+        std::env::var_os(DETACHED_ENV).is_some()
+    }
+
+    /// Run this command again in a new session with output appended to
+    /// `log`, and wait until it answers on the control socket
+    pub async fn spawn(log: &Path, control: &ControlConfig) -> Result<ServerStatus> {
+        if let Some(dir) = log.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            // Usually the runtime directory, which only we may enter
+            std::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(dir)
+                .with_context(|| format!("Failed to create log directory {}", dir.display()))?;
+        }
+        let output = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log)
+            .with_context(|| format!("Failed to open daemon log {}", log.display()))?;
+
+        let mut command = Command::new(std::env::current_exe()?);
+        command
+            .args(std::env::args_os().skip(1))
+            .env(DETACHED_ENV, "1")
+            .stdin(Stdio::null())
+            .stdout(output.try_clone()?)
+            .stderr(output);
+        // SAFETY: setsid is async-signal-safe and only detaches the child
+        // from our session and controlling terminal
+        unsafe {
+            command.pre_exec(|| {
+                if libc::setsid() < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let mut child = command.spawn().context("Failed to start daemon")?;
+
+        let socket = control.socket_path();
+        let deadline = tokio::time::Instant::now() + READY_TIMEOUT;
+        loop {
+            if let Some(exit) = child.try_wait()? {
+                anyhow::bail!(
+                    "Daemon exited during startup ({}); see {}",
+                    exit,
+                    log.display()
+                );
+            }
+            match ControlClient::status(&socket).await {
+                Ok(status) if status.pid == child.id() => return Ok(status),
+                Ok(_) | Err(ControlError::NotRunning(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+            if tokio::time::Instant::now() >= deadline {
+                anyhow::bail!(
+                    "Daemon (pid {}) did not answer on {} within {}s; see {}",
+                    child.id(),
+                    socket.display(),
+                    READY_TIMEOUT.as_secs(),
+                    log.display()
+                );
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    /// Replace this process with a fresh run of the same command
+    pub fn exec_again() -> Result<()> {
+        let error = Command::new(std::env::current_exe()?)
+            .args(std::env::args_os().skip(1))
+            .exec();
+        Err(error).context("Failed to restart server")
+    }
+}
+
+#[cfg(not(unix))]
+mod daemon {
+    use crate::config::ControlConfig;
+    use anyhow::{bail, Result};
+    use std::path::Path;
+
+    pub struct Detached {
+        pub pid: u32,
+        pub listeners: Vec<String>,
+    }
+
+    pub fn is_detached() -> bool {
+        false
+    }
+
+    pub async fn spawn(_log: &Path, _control: &ControlConfig) -> Result<Detached> {
+        bail!("Daemon mode is only supported on Unix; run in the foreground instead")
+    }
+
+    pub fn exec_again() -> Result<()> {
+        bail!("Restart is only supported on Unix")
+    }
+}
@@ -3,55 +3,70 @@
 
 use crate::cli::args::{
     OutputFormat, SessionCleanupArgs, SessionCommands, SessionKillArgs, SessionListArgs,
+    SessionSortField,
 };
+use crate::config::ConfigSources;
 use anyhow::Result;
 
-pub async fn execute(cmd: SessionCommands) -> Result<()> {
+#[cfg(unix)]
+use super::control::{self, unexpected_reply};
+#[cfg(unix)]
+use crate::server::control::{ControlRequest, ControlResponse, SessionInfo};
+
+pub async fn execute(cmd: SessionCommands, sources: ConfigSources) -> Result<()> {
     match cmd {
-        SessionCommands::List(args) => list(args).await,
-        SessionCommands::Kill(args) => kill(args).await,
-        SessionCommands::Cleanup(args) => cleanup(args).await,
+        SessionCommands::List(args) => list(args, sources).await,
+        SessionCommands::Kill(args) => kill(args, sources).await,
+        SessionCommands::Cleanup(args) => cleanup(args, sources).await,
     }
 }
 
-async fn list(args: SessionListArgs) -> Result<()> {
-    println!("📋 Active Sessions");
-
-    if let Some(user) = &args.user {
-        println!("  Filter: user = {}", user);
+#[cfg(unix)]
+async fn list(args: SessionListArgs, sources: ConfigSources) -> Result<()> {
+    let request = ControlRequest::ListSessions {
+        user: args.user.clone(),
+        tenant: args.tenant.clone(),
+    };
+    let mut sessions = match control::request(&sources, &request).await? {
+        ControlResponse::Sessions { sessions } => sessions,
+        other => return Err(unexpected_reply(&other)),
+    };
+    if args.expired {
+        sessions.retain(|session| session.expired);
     }
-
-    // TODO: Get actual sessions
-    let sessions = vec![Session {
-        id: "abc123".to_string(),
-        user: "alice".to_string(),
-        created: "2025-09-29 10:00:00".to_string(),
-        last_activity: "2025-09-29 10:05:00".to_string(),
-    }];
+    sort_sessions(&mut sessions, args.sort);
 
     match args.format {
         OutputFormat::Table => {
-            println!(
-                "\n{:<12} {:<12} {:<20} {:<20}",
-                "SESSION_ID", "USER", "CREATED", "LAST_ACTIVITY"
-            );
-            println!("{}", "-".repeat(64));
-            for session in sessions {
-                println!(
-                    "{:<12} {:<12} {:<20} {:<20}",
-                    session.id, session.user, session.created, session.last_activity
-                );
+            println!("📋 Active Sessions");
+            if let Some(user) = &args.user {
+                println!("  Filter: user = {}", user);
+            }
+            if let Some(tenant) = &args.tenant {
+                println!("  Filter: tenant = {}", tenant);
             }
+            print_table(&sessions);
+            println!("\n{} session(s)", sessions.len());
         }
         OutputFormat::Json => {
             println!("{}", serde_json::to_string_pretty(&sessions)?);
         }
         OutputFormat::Csv => {
-            println!("session_id,user,created,last_activity");
-            for session in sessions {
+            println!(
+                "session_id,user,tenant,created,last_activity,idle_secs,expired,terminal_running,clients"
+            );
+            for s in &sessions {
                 println!(
-                    "{},{},{},{}",
-                    session.id, session.user, session.created, session.last_activity
+                    "{},{},{},{},{},{},{},{},{}",
+                    s.id,
+                    s.user,
+                    s.tenant.as_deref().unwrap_or(""),
+                    s.created_at.to_rfc3339(),
+                    s.last_activity.to_rfc3339(),
+                    s.idle_secs,
+                    s.expired,
+                    s.terminal_running,
+                    s.clients
                 );
             }
         }
@@ -60,19 +75,32 @@ async fn list(args: SessionListArgs) -> Result<()> {
     Ok(())
 }
 
-async fn kill(args: SessionKillArgs) -> Result<()> {
+#[cfg(unix)]
+async fn kill(args: SessionKillArgs, sources: ConfigSources) -> Result<()> {
     println!("🔪 Killing session: {}", args.session_id);
 
     if args.force {
-        println!("  Mode: force (no cleanup)");
+        println!("  Mode: force (terminal killed at once)");
     }
 
-    println!("\n⚠️  Session kill not yet implemented");
-
-    Ok(())
+    let request = ControlRequest::KillSession {
+        session_id: args.session_id,
+        force: args.force,
+    };
+    match control::request(&sources, &request).await? {
+        ControlResponse::Killed { session } => {
+            println!(
+                "✅ Killed session {} (user {}, {} client(s) attached)",
+                session.id, session.user, session.clients
+            );
+            Ok(())
+        }
+        other => Err(unexpected_reply(&other)),
+    }
 }
 
-async fn cleanup(args: SessionCleanupArgs) -> Result<()> {
+#[cfg(unix)]
+async fn cleanup(args: SessionCleanupArgs, sources: ConfigSources) -> Result<()> {
     println!("🧹 Cleaning up expired sessions");
 
     if args.dry_run {
@@ -83,15 +111,78 @@ async fn cleanup(args: SessionCleanupArgs) -> Result<()> {
         println!("  Mode: force (clean all)");
     }
 
-    println!("\n⚠️  Session cleanup not yet implemented");
+    let request = ControlRequest::CleanupSessions {
+        dry_run: args.dry_run,
+        all: args.force,
+    };
+    let (sessions, dry_run) = match control::request(&sources, &request).await? {
+        ControlResponse::CleanedUp { sessions, dry_run } => (sessions, dry_run),
+        other => return Err(unexpected_reply(&other)),
+    };
+
+    if sessions.is_empty() {
+        println!("\n✅ Nothing to clean up");
+        return Ok(());
+    }
+    print_table(&sessions);
+    if dry_run {
+        println!("\n{} session(s) would be killed", sessions.len());
+    } else {
+        println!("\n✅ Killed {} session(s)", sessions.len());
+    }
 
     Ok(())
 }
 
-#[derive(Debug, serde::Serialize)]
-struct Session {
-    id: String,
-    user: String,
-    created: String,
-    last_activity: String,
+#[cfg(not(unix))]
+async fn list(_args: SessionListArgs, _sources: ConfigSources) -> Result<()> {
+    anyhow::bail!("web-terminal sessions is only supported on Unix")
+}
+
+#[cfg(not(unix))]
+async fn kill(_args: SessionKillArgs, _sources: ConfigSources) -> Result<()> {
+    anyhow::bail!("web-terminal sessions is only supported on Unix")
+}
+
+#[cfg(not(unix))]
+async fn cleanup(_args: SessionCleanupArgs, _sources: ConfigSources) -> Result<()> {
+    anyhow::bail!("web-terminal sessions is only supported on Unix")
+}
+
+#[cfg(unix)]
+fn sort_sessions(sessions: &mut [SessionInfo], sort: SessionSortField) {
+    match sort {
+        SessionSortField::Id => sessions.sort_by(|a, b| a.id.cmp(&b.id)),
+        SessionSortField::User => {
+            sessions.sort_by(|a, b| a.user.cmp(&b.user).then(a.created_at.cmp(&b.created_at)))
+        }
+        SessionSortField::Created => sessions.sort_by_key(|s| s.created_at),
+        SessionSortField::Activity => sessions.sort_by_key(|s| std::cmp::Reverse(s.last_activity)),
+    }
+}
+
+#[cfg(unix)]
+fn print_table(sessions: &[SessionInfo]) {
+    println!(
+        "\n{:<38} {:<16} {:<12} {:<20} {:<8} {:<8} {:<7}",
+        "SESSION_ID", "USER", "TENANT", "CREATED", "IDLE", "TERMINAL", "CLIENTS"
+    );
+    println!("{}", "-".repeat(115));
+    for s in sessions {
+        let idle = if s.expired {
+            format!("{}s!", s.idle_secs)
+        } else {
+            format!("{}s", s.idle_secs)
+        };
+        println!(
+            "{:<38} {:<16} {:<12} {:<20} {:<8} {:<8} {:<7}",
+            s.id,
+            s.user,
+            s.tenant.as_deref().unwrap_or("-"),
+            s.created_at.format("%Y-%m-%d %H:%M:%S"),
+            idle,
+            if s.terminal_running { "running" } else { "-" },
+            s.clients
+        );
+    }
 }
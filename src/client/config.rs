@@ -0,0 +1,179 @@
+// Terminal client configuration
+// Per spec-kit/007-websocket-spec.md: Connection lifecycle, Reconnection
+
+use std::time::Duration;
+
+use crate::protocol::CompressionAlgorithm;
+
+use super::ClientError;
+
+/// Connection settings for a [`TerminalClient`](super::TerminalClient)
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Server URL: a WebSocket endpoint (`ws://host:8080/ws`) or the server
+    /// base URL (`https://host`), which is mapped to its `/ws` endpoint
+    pub url: String,
+
+    /// JWT sent in the `authenticate` message
+    pub token: String,
+
+    /// Existing session to attach to instead of creating a new one
+    pub session: Option<String>,
+
+    /// Compression algorithms to offer, in order of preference
+    pub compression: Vec<CompressionAlgorithm>,
+
+    /// Reconnection behaviour after an unexpected disconnect
+    pub reconnect: ReconnectPolicy,
+
+    /// Timeout for connecting, authenticating and attaching
+    pub connect_timeout: Duration,
+}
+
+impl ClientConfig {
+    /// Create a configuration that opens a new session
+    pub fn new(url: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            token: token.into(),
+            session: None,
+            compression: vec![CompressionAlgorithm::Zstd, CompressionAlgorithm::Deflate],
+            reconnect: ReconnectPolicy::default(),
+            connect_timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Attach to an existing session
+    pub fn attach(mut self, session_id: impl Into<String>) -> Self {
+        self.session = Some(session_id.into());
+        self
+    }
+
+    /// Set the offered compression algorithms (empty disables compression)
+    pub fn with_compression(mut self, algorithms: Vec<CompressionAlgorithm>) -> Self {
+        self.compression = algorithms;
+        self
+    }
+
+    /// Set the reconnection policy
+    pub fn with_reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect = policy;
+        self
+    }
+
+    /// Set the connect/authenticate timeout
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// WebSocket URL for this configuration, attaching to `session` if given
+    pub fn websocket_url(&self, session: Option<&str>) -> Result<String, ClientError> {
+        let (scheme, rest) = self
+            .url
+            .split_once("://")
+            .ok_or_else(|| ClientError::InvalidUrl(self.url.clone()))?;
+
+        let scheme = match scheme {
+            "ws" | "http" => "ws",
+            "wss" | "https" => "wss",
+            _ => return Err(ClientError::InvalidUrl(self.url.clone())),
+        };
+
+        let rest = rest.trim_end_matches('/');
+        if rest.is_empty() {
+            return Err(ClientError::InvalidUrl(self.url.clone()));
+        }
+
+        // A bare host (no path) means the server's default endpoint
+        let mut url = if rest.contains('/') {
+            format!("{}://{}", scheme, rest)
+        } else {
+            format!("{}://{}/ws", scheme, rest)
+        };
+
+        if let Some(session) = session {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str("session=");
+            url.push_str(session);
+        }
+
+        Ok(url)
+    }
+}
+
+/// Exponential backoff for reconnecting after an unexpected disconnect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Attempts before giving up (0 disables reconnection)
+    pub max_attempts: u32,
+
+    /// Delay before the first attempt
+    pub initial_delay: Duration,
+
+    /// Upper bound on the delay between attempts
+    pub max_delay: Duration,
+}
+
+impl ReconnectPolicy {
+    /// Never reconnect
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before the given attempt (1-based), doubling each time
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_websocket_url_mapping() {
+        let config = ClientConfig::new("http://localhost:8080", "token");
+        assert_eq!(
+            config.websocket_url(None).unwrap(),
+            "ws://localhost:8080/ws"
+        );
+
+        let config = ClientConfig::new("wss://term.example.com/ws", "token");
+        assert_eq!(
+            config.websocket_url(Some("abc")).unwrap(),
+            "wss://term.example.com/ws?session=abc"
+        );
+
+        let config = ClientConfig::new("localhost:8080", "token");
+        assert!(matches!(
+            config.websocket_url(None),
+            Err(ClientError::InvalidUrl(_))
+        ));
+    }
+
+    #[test]
+    fn test_reconnect_backoff() {
+        let policy = ReconnectPolicy::default();
+        assert_eq!(policy.delay(1), Duration::from_millis(500));
+        assert_eq!(policy.delay(3), Duration::from_secs(2));
+        assert_eq!(policy.delay(20), Duration::from_secs(30));
+        assert_eq!(ReconnectPolicy::disabled().max_attempts, 0);
+    }
+}
@@ -0,0 +1,11 @@
+// Terminal client library
+// Per spec-kit/007-websocket-spec.md
+//
+// Typed client for the `/ws` endpoint, built on the same protocol messages
+// the server uses. Used by `web-terminal attach` and the integration tests.
+
+pub mod config;
+pub mod terminal;
+
+pub use config::{ClientConfig, ReconnectPolicy};
+pub use terminal::{ClientError, ClientEvent, ClientHandle, TerminalClient};
@@ -0,0 +1,557 @@
+// Typed WebSocket terminal client
+// Per spec-kit/007-websocket-spec.md: Connection lifecycle, Reconnection
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures_util::{SinkExt, Stream, StreamExt};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::protocol::{
+    decode_frame, error_codes, ClientMessage, ConnectionStatus, ServerMessage, Signal,
+    MAX_MESSAGE_SIZE,
+};
+
+use super::config::ClientConfig;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Terminal client errors
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] Box<tungstenite::Error>),
+
+    #[error("Invalid server URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("Authentication failed: {0}")]
+    AuthenticationFailed(String),
+
+    #[error("Server error {code}: {message}")]
+    Server { code: String, message: String },
+
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+
+    #[error("Timed out connecting to server")]
+    Timeout,
+
+    #[error("Connection closed")]
+    Closed,
+}
+
+impl From<tungstenite::Error> for ClientError {
+    fn from(e: tungstenite::Error) -> Self {
+        Self::WebSocket(Box::new(e))
+    }
+}
+
+impl ClientError {
+    /// Errors the server will keep returning; reconnecting cannot help
+    fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            Self::AuthenticationFailed(_) | Self::Server { .. } | Self::InvalidUrl(_)
+        )
+    }
+}
+
+/// Events produced by a connected client
+#[derive(Debug)]
+pub enum ClientEvent {
+    /// Terminal output, already decompressed
+    Output(Vec<u8>),
+
+    /// Any other server message
+    Message(ServerMessage),
+
+    /// The connection dropped; reconnecting after `delay`
+    Reconnecting { attempt: u32, delay: Duration },
+
+    /// Re-attached to the session after a dropped connection
+    Reconnected { session_id: String },
+
+    /// The connection is gone for good; `error` is set unless it closed cleanly
+    Closed { error: Option<ClientError> },
+}
+
+/// Frames queued for the connection task
+enum Outgoing {
+    Message(ClientMessage),
+    Raw(Vec<u8>),
+    Close,
+}
+
+/// Cloneable sender for input and control messages
+///
+/// Messages sent while the client is reconnecting are delivered once the
+/// session is re-attached.
+#[derive(Debug, Clone)]
+pub struct ClientHandle {
+    tx: mpsc::UnboundedSender<Outgoing>,
+}
+
+impl std::fmt::Debug for Outgoing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Message(msg) => f.debug_tuple("Message").field(msg).finish(),
+            Self::Raw(data) => f.debug_tuple("Raw").field(&data.len()).finish(),
+            Self::Close => f.write_str("Close"),
+        }
+    }
+}
+
+impl ClientHandle {
+    fn push(&self, outgoing: Outgoing) -> Result<(), ClientError> {
+        self.tx.send(outgoing).map_err(|_| ClientError::Closed)
+    }
+
+    /// Send a protocol message
+    pub fn send(&self, msg: ClientMessage) -> Result<(), ClientError> {
+        self.push(Outgoing::Message(msg))
+    }
+
+    /// Send terminal input (keystrokes or a command line)
+    pub fn send_input(&self, data: impl Into<String>) -> Result<(), ClientError> {
+        self.send(ClientMessage::Command { data: data.into() })
+    }
+
+    /// Send raw bytes to the PTY as a binary frame
+    pub fn send_raw(&self, data: Vec<u8>) -> Result<(), ClientError> {
+        self.push(Outgoing::Raw(data))
+    }
+
+    /// Resize the terminal
+    pub fn resize(&self, cols: u16, rows: u16) -> Result<(), ClientError> {
+        self.send(ClientMessage::Resize { cols, rows })
+    }
+
+    /// Send a signal to the session's process
+    pub fn signal(&self, signal: Signal) -> Result<(), ClientError> {
+        self.send(ClientMessage::Signal { signal })
+    }
+
+    /// Re-authenticate with a fresh token for the same user
+    ///
+    /// Answers [`ServerMessage::TokenExpiring`]; the new token is also used
+    /// for any later reconnect.
+    pub fn reauthenticate(&self, token: impl Into<String>) -> Result<(), ClientError> {
+        self.send(ClientMessage::Authenticate {
+            token: token.into(),
+        })
+    }
+
+    /// Send an application-level ping; the pong echoes the timestamp
+    pub fn ping(&self) -> Result<(), ClientError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        self.send(ClientMessage::Ping {
+            timestamp: Some(timestamp),
+        })
+    }
+}
+
+/// A WebSocket connection to a terminal session
+///
+/// Output and other server messages are consumed as a [`Stream`] of
+/// [`ClientEvent`]s. Unexpected disconnects are retried according to the
+/// configured [`ReconnectPolicy`](super::ReconnectPolicy), re-attaching to
+/// the same session.
+pub struct TerminalClient {
+    handle: ClientHandle,
+    events: mpsc::UnboundedReceiver<ClientEvent>,
+    session_id: watch::Receiver<String>,
+    user_id: String,
+    task: JoinHandle<()>,
+}
+
+impl TerminalClient {
+    /// Connect, authenticate and create or attach to a session
+    pub async fn connect(config: ClientConfig) -> Result<Self, ClientError> {
+        let (ws, attached) = handshake(&config, config.session.as_deref()).await?;
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (events_tx, events) = mpsc::unbounded_channel();
+        let (session_tx, session_id) = watch::channel(attached.session_id);
+        for msg in attached.early {
+            let _ = events_tx.send(ClientEvent::Message(msg));
+        }
+
+        let connection = Connection {
+            config,
+            commands: rx,
+            pending: VecDeque::new(),
+            events: events_tx,
+            session_id: session_tx,
+        };
+        let task = tokio::spawn(connection.run(ws));
+
+        Ok(Self {
+            handle: ClientHandle { tx },
+            events,
+            session_id,
+            user_id: attached.user_id,
+            task,
+        })
+    }
+
+    /// Session this client is attached to
+    pub fn session_id(&self) -> String {
+        self.session_id.borrow().clone()
+    }
+
+    /// Authenticated user
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Sender usable from other tasks while this client is being read
+    pub fn handle(&self) -> ClientHandle {
+        self.handle.clone()
+    }
+
+    /// Send a protocol message
+    pub fn send(&self, msg: ClientMessage) -> Result<(), ClientError> {
+        self.handle.send(msg)
+    }
+
+    /// Send terminal input (keystrokes or a command line)
+    pub fn send_input(&self, data: impl Into<String>) -> Result<(), ClientError> {
+        self.handle.send_input(data)
+    }
+
+    /// Resize the terminal
+    pub fn resize(&self, cols: u16, rows: u16) -> Result<(), ClientError> {
+        self.handle.resize(cols, rows)
+    }
+
+    /// Wait for the next event; `None` once the connection is closed
+    pub async fn next_event(&mut self) -> Option<ClientEvent> {
+        self.events.recv().await
+    }
+
+    /// Close the connection, leaving the session running for reattachment
+    pub async fn close(self) {
+        let _ = self.handle.push(Outgoing::Close);
+        let _ = self.task.await;
+    }
+}
+
+impl Stream for TerminalClient {
+    type Item = ClientEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ClientEvent>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+/// Identity confirmed by the server during the handshake
+struct Attached {
+    user_id: String,
+    session_id: String,
+    /// Token expiry warnings received before the session was attached,
+    /// delivered as the first events
+    early: Vec<ServerMessage>,
+}
+
+/// Open the socket, authenticate, and wait until the session is attached
+async fn handshake(
+    config: &ClientConfig,
+    session: Option<&str>,
+) -> Result<(WsStream, Attached), ClientError> {
+    let url = config.websocket_url(session)?;
+
+    let attach = async {
+        let (mut ws, _) = tokio_tungstenite::connect_async(url.as_str()).await?;
+
+        send_message(
+            &mut ws,
+            &ClientMessage::Authenticate {
+                token: config.token.clone(),
+            },
+        )
+        .await?;
+        if !config.compression.is_empty() {
+            send_message(
+                &mut ws,
+                &ClientMessage::NegotiateCompression {
+                    algorithms: config.compression.clone(),
+                },
+            )
+            .await?;
+        }
+
+        let mut user_id = None;
+        let mut early = Vec::new();
+        while let Some(frame) = ws.next().await {
+            let text = match frame? {
+                Message::Text(text) => text,
+                Message::Close(_) => return Err(ClientError::Closed),
+                _ => continue,
+            };
+            match parse_server_message(&text)? {
+                ServerMessage::Authenticated { user_id: id, .. } => user_id = Some(id),
+                ServerMessage::ConnectionStatus {
+                    status: ConnectionStatus::Connected,
+                    session_id: Some(session_id),
+                } => {
+                    if let Some(user_id) = user_id.take() {
+                        return Ok((
+                            ws,
+                            Attached {
+                                user_id,
+                                session_id,
+                                early,
+                            },
+                        ));
+                    }
+                }
+                ServerMessage::Error { code, message, .. } => {
+                    return Err(if code == error_codes::AUTHENTICATION_FAILED {
+                        ClientError::AuthenticationFailed(message)
+                    } else {
+                        ClientError::Server { code, message }
+                    });
+                }
+                msg @ ServerMessage::TokenExpiring { .. } => early.push(msg),
+                _ => {}
+            }
+        }
+        Err(ClientError::Closed)
+    };
+
+    tokio::time::timeout(config.connect_timeout, attach)
+        .await
+        .map_err(|_| ClientError::Timeout)?
+}
+
+async fn send_message(ws: &mut WsStream, msg: &ClientMessage) -> Result<(), ClientError> {
+    let json = serde_json::to_string(msg).map_err(|e| ClientError::Protocol(e.to_string()))?;
+    ws.send(Message::text(json)).await?;
+    Ok(())
+}
+
+fn parse_server_message(text: &str) -> Result<ServerMessage, ClientError> {
+    serde_json::from_str(text).map_err(|e| ClientError::Protocol(e.to_string()))
+}
+
+/// How a connection ended
+enum Ended {
+    /// The client asked to close
+    Requested,
+    /// The server closed the connection on purpose
+    ByServer,
+    /// The connection dropped and may be retried
+    Dropped(ClientError),
+}
+
+/// Background task owning the socket
+struct Connection {
+    config: ClientConfig,
+    commands: mpsc::UnboundedReceiver<Outgoing>,
+    /// Commands received while reconnecting
+    pending: VecDeque<Outgoing>,
+    events: mpsc::UnboundedSender<ClientEvent>,
+    session_id: watch::Sender<String>,
+}
+
+impl Connection {
+    async fn run(mut self, mut ws: WsStream) {
+        loop {
+            let error = match self.pump(&mut ws).await {
+                Ended::Requested => {
+                    let _ = ws
+                        .close(Some(CloseFrame {
+                            code: CloseCode::Normal,
+                            reason: "client closed".into(),
+                        }))
+                        .await;
+                    let _ = self.events.send(ClientEvent::Closed { error: None });
+                    return;
+                }
+                Ended::ByServer => {
+                    let _ = self.events.send(ClientEvent::Closed { error: None });
+                    return;
+                }
+                Ended::Dropped(error) => error,
+            };
+
+            tracing::warn!("Terminal connection dropped: {}", error);
+            match self.reconnect(error).await {
+                Ok(Some(new_ws)) => ws = new_ws,
+                Ok(None) => {
+                    let _ = self.events.send(ClientEvent::Closed { error: None });
+                    return;
+                }
+                Err(error) => {
+                    let _ = self.events.send(ClientEvent::Closed { error: Some(error) });
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Relay frames until the connection ends
+    async fn pump(&mut self, ws: &mut WsStream) -> Ended {
+        while let Some(outgoing) = self.pending.pop_front() {
+            if let Err(e) = self.write(ws, outgoing).await {
+                return e;
+            }
+        }
+
+        loop {
+            tokio::select! {
+                command = self.commands.recv() => {
+                    let Some(outgoing) = command else {
+                        return Ended::Requested;
+                    };
+                    if let Err(e) = self.write(ws, outgoing).await {
+                        return e;
+                    }
+                }
+                frame = ws.next() => {
+                    let event = match frame {
+                        Some(Ok(Message::Text(text))) => match parse_server_message(&text) {
+                            Ok(ServerMessage::Output { data, .. }) => {
+                                ClientEvent::Output(data.into_bytes())
+                            }
+                            Ok(msg) => ClientEvent::Message(msg),
+                            Err(e) => {
+                                tracing::warn!("Ignoring unparseable server message: {}", e);
+                                continue;
+                            }
+                        },
+                        Some(Ok(Message::Binary(frame))) => {
+                            match decode_frame(&frame, MAX_MESSAGE_SIZE) {
+                                Ok(data) => ClientEvent::Output(data),
+                                Err(e) => return Ended::Dropped(ClientError::Protocol(e.to_string())),
+                            }
+                        }
+                        Some(Ok(Message::Close(frame))) => {
+                            return match frame.map(|f| f.code) {
+                                Some(CloseCode::Normal) | Some(CloseCode::Policy) => Ended::ByServer,
+                                _ => Ended::Dropped(ClientError::Closed),
+                            };
+                        }
+                        Some(Ok(_)) => continue,
+                        Some(Err(e)) => return Ended::Dropped(e.into()),
+                        None => return Ended::Dropped(ClientError::Closed),
+                    };
+                    if self.events.send(event).is_err() {
+                        // Nobody is listening any more
+                        return Ended::Requested;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn write(&mut self, ws: &mut WsStream, outgoing: Outgoing) -> Result<(), Ended> {
+        if let Outgoing::Message(ClientMessage::Authenticate { token }) = &outgoing {
+            self.config.token = token.clone();
+        }
+
+        let result = match &outgoing {
+            Outgoing::Close => return Err(Ended::Requested),
+            Outgoing::Message(msg) => send_message(ws, msg).await,
+            Outgoing::Raw(data) => ws
+                .send(Message::binary(data.clone()))
+                .await
+                .map_err(ClientError::from),
+        };
+        result.map_err(|e| {
+            // Deliver it after reconnecting
+            self.pending.push_front(outgoing);
+            Ended::Dropped(e)
+        })
+    }
+
+    /// Re-attach to the session with backoff
+    ///
+    /// Returns `Ok(None)` if the client asked to close while waiting.
+    async fn reconnect(
+        &mut self,
+        mut last_error: ClientError,
+    ) -> Result<Option<WsStream>, ClientError> {
+        let policy = self.config.reconnect;
+        for attempt in 1..=policy.max_attempts {
+            if last_error.is_fatal() {
+                break;
+            }
+
+            let delay = policy.delay(attempt);
+            let _ = self
+                .events
+                .send(ClientEvent::Reconnecting { attempt, delay });
+
+            let sleep = tokio::time::sleep(delay);
+            tokio::pin!(sleep);
+            loop {
+                tokio::select! {
+                    _ = &mut sleep => break,
+                    command = self.commands.recv() => match command {
+                        None | Some(Outgoing::Close) => return Ok(None),
+                        Some(outgoing) => self.pending.push_back(outgoing),
+                    },
+                }
+            }
+
+            let session_id = self.session_id.borrow().clone();
+            match handshake(&self.config, Some(&session_id)).await {
+                Ok((ws, attached)) => {
+                    tracing::info!("Reconnected to session {}", attached.session_id);
+                    self.session_id.send_replace(attached.session_id.clone());
+                    let _ = self.events.send(ClientEvent::Reconnected {
+                        session_id: attached.session_id,
+                    });
+                    for msg in attached.early {
+                        let _ = self.events.send(ClientEvent::Message(msg));
+                    }
+                    return Ok(Some(ws));
+                }
+                Err(e) => {
+                    tracing::warn!("Reconnect attempt {} failed: {}", attempt, e);
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fatal_errors_are_not_retried() {
+        assert!(ClientError::AuthenticationFailed("expired".to_string()).is_fatal());
+        assert!(ClientError::Server {
+            code: error_codes::SESSION_EXPIRED.to_string(),
+            message: "gone".to_string(),
+        }
+        .is_fatal());
+        assert!(!ClientError::Closed.is_fatal());
+        assert!(!ClientError::Timeout.is_fatal());
+    }
+
+    #[tokio::test]
+    async fn test_handle_fails_after_client_task_exits() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handle = ClientHandle { tx };
+        assert!(handle.send_input("ls\n").is_ok());
+        drop(rx);
+        assert!(matches!(handle.resize(80, 24), Err(ClientError::Closed)));
+    }
+}
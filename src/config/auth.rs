@@ -2,6 +2,8 @@
 // Authentication configuration structures
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// Authentication configuration
@@ -25,6 +27,26 @@ pub struct AuthConfig {
     /// Security settings
     #[serde(default)]
     pub security: SecurityConfig,
+
+    /// Server-issued API keys
+    #[serde(default)]
+    pub api_keys: ApiKeyConfig,
+
+    /// Reloading providers and authorization settings without a restart
+    #[serde(default)]
+    pub reload: ReloadConfig,
+
+    /// Token revocation list and single-use tickets
+    #[serde(default)]
+    pub revocation: RevocationConfig,
+
+    /// Tenants derived from the token issuer or a claim
+    #[serde(default)]
+    pub tenancy: TenancyConfig,
+
+    /// Just-in-time access to other users' sessions, approved by the owner
+    #[serde(default)]
+    pub access_requests: AccessRequestConfig,
 }
 
 impl Default for AuthConfig {
@@ -35,6 +57,11 @@ impl Default for AuthConfig {
             authorization: AuthorizationConfig::default(),
             validation: ValidationConfig::default(),
             security: SecurityConfig::default(),
+            api_keys: ApiKeyConfig::default(),
+            reload: ReloadConfig::default(),
+            revocation: RevocationConfig::default(),
+            tenancy: TenancyConfig::default(),
+            access_requests: AccessRequestConfig::default(),
         }
     }
 }
@@ -44,12 +71,33 @@ impl Default for AuthConfig {
 pub struct JwksConfig {
     /// List of JWKS providers
     pub providers: Vec<JwksProvider>,
+
+    /// Keep using expired keys this long while the provider cannot be
+    /// reached
+    #[serde(default = "default_jwks_max_stale", with = "humantime_serde")]
+    pub max_stale: Duration,
+
+    /// Least time between two fetches of a provider's keys triggered by
+    /// tokens with an unknown `kid`, or by an expired cache
+    #[serde(
+        default = "default_jwks_min_refresh_interval",
+        with = "humantime_serde"
+    )]
+    pub min_refresh_interval: Duration,
+
+    /// How long a `kid` still unknown after a refresh is rejected without
+    /// fetching again
+    #[serde(default = "default_jwks_negative_cache_ttl", with = "humantime_serde")]
+    pub negative_cache_ttl: Duration,
 }
 
 impl Default for JwksConfig {
     fn default() -> Self {
         Self {
             providers: Vec::new(),
+            max_stale: default_jwks_max_stale(),
+            min_refresh_interval: default_jwks_min_refresh_interval(),
+            negative_cache_ttl: default_jwks_negative_cache_ttl(),
         }
     }
 }
@@ -62,15 +110,29 @@ pub struct JwksProvider {
     pub name: String,
 
     /// JWKS endpoint URL
-    pub url: String,
+    ///
+    /// When omitted, the endpoint, issuer and signing algorithms are
+    /// discovered from `<issuer>/.well-known/openid-configuration`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Local key material used instead of `url` (offline deployments),
+    /// written as a single-entry map such as `secret_file: <path>`
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_yaml::with::singleton_map"
+    )]
+    pub keys: Option<StaticKeySource>,
 
-    /// Expected token issuer
+    /// Expected token issuer (the discovery base URL when `url` is omitted)
     pub issuer: String,
 
     /// Expected token audience
     pub audience: String,
 
-    /// Allowed signing algorithms
+    /// Allowed signing algorithms (narrowed to those the provider
+    /// advertises when discovered)
     #[serde(default = "default_algorithms")]
     pub algorithms: Vec<String>,
 
@@ -99,6 +161,28 @@ pub struct JwksProvider {
     pub timeout: Duration,
 }
 
+/// Key material loaded from disk for a provider, re-read when the file changes
+/// Per 011-authentication-spec.md section 3.1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StaticKeySource {
+    /// JWKS document (RSA, EC, OKP or oct keys)
+    JwksFile(PathBuf),
+    /// PEM-encoded RSA, EC or Ed25519 public key
+    PemFile(PathBuf),
+    /// Shared secret for HS256/HS384/HS512 (surrounding whitespace is ignored)
+    SecretFile(PathBuf),
+}
+
+impl StaticKeySource {
+    /// File the keys are read from
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::JwksFile(path) | Self::PemFile(path) | Self::SecretFile(path) => path,
+        }
+    }
+}
+
 /// Authorization configuration
 /// Per 011-authentication-spec.md section 5
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,6 +222,20 @@ pub struct AuthorizationConfig {
     /// Enable wildcard patterns
     #[serde(default)]
     pub enable_wildcards: bool,
+
+    /// Roles granted to matching users or groups (role -> entries, matched
+    /// like the allow lists), in addition to roles from the roles claim
+    #[serde(default)]
+    pub role_mappings: HashMap<String, Vec<String>>,
+
+    /// Role of users with no role from claims or mappings
+    #[serde(default = "default_role")]
+    pub default_role: String,
+
+    /// Role permissions file (see config/permissions.yaml); built-in
+    /// defaults apply when unset
+    #[serde(default)]
+    pub permissions_file: Option<PathBuf>,
 }
 
 impl Default for AuthorizationConfig {
@@ -152,6 +250,9 @@ impl Default for AuthorizationConfig {
             deny_by_default: true,
             allow_empty_groups: false,
             enable_wildcards: false,
+            role_mappings: HashMap::new(),
+            default_role: default_role(),
+            permissions_file: None,
         }
     }
 }
@@ -179,6 +280,10 @@ pub struct ClaimMappings {
     /// Display name claim path (default: "name")
     #[serde(default = "default_name_claim")]
     pub name: String,
+
+    /// Roles claim path (default: "roles")
+    #[serde(default = "default_roles_claim")]
+    pub roles: String,
 }
 
 impl Default for ClaimMappings {
@@ -189,6 +294,7 @@ impl Default for ClaimMappings {
             groups: "groups".to_string(),
             entity_ref: "ent".to_string(),
             name: "name".to_string(),
+            roles: "roles".to_string(),
         }
     }
 }
@@ -228,6 +334,18 @@ pub struct ValidationConfig {
         rename = "max_lifetime"
     )]
     pub max_lifetime: Duration,
+
+    /// Warn WebSocket clients this long before their token expires
+    #[serde(
+        default = "default_expiry_warning",
+        with = "humantime_serde",
+        rename = "expiry_warning"
+    )]
+    pub expiry_warning: Duration,
+
+    /// What happens to a WebSocket connection whose token expires
+    #[serde(default)]
+    pub on_expiry: TokenExpiryAction,
 }
 
 impl Default for ValidationConfig {
@@ -238,10 +356,23 @@ impl Default for ValidationConfig {
             allowed_algorithms: default_algorithms(),
             min_lifetime: default_min_lifetime(),
             max_lifetime: default_max_lifetime(),
+            expiry_warning: default_expiry_warning(),
+            on_expiry: TokenExpiryAction::default(),
         }
     }
 }
 
+/// Handling of a connection whose token expires mid-session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenExpiryAction {
+    /// Close the connection (the session stays available for reattach)
+    #[default]
+    Close,
+    /// Keep the connection but block input and hold output until re-authentication
+    Freeze,
+}
+
 impl ValidationConfig {
     /// Get cache TTL as Duration (from first provider or default 1 hour)
     pub fn cache_ttl(&self) -> Duration {
@@ -275,9 +406,24 @@ pub struct SecurityConfig {
     #[serde(default = "default_true")]
     pub require_tls: bool,
 
-    /// Allowed token sources for WebSocket
+    /// Where bearer tokens are accepted from, in lookup order
     #[serde(default = "default_token_sources")]
-    pub allowed_token_sources: Vec<String>,
+    pub allowed_token_sources: Vec<TokenSource>,
+}
+
+/// Places a bearer token may be presented
+/// Per 011-authentication-spec.md section 7
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenSource {
+    /// `Authorization: Bearer <token>` header
+    Header,
+    /// `?access_token=<token>` (or `?token=`) query parameter
+    Query,
+    /// `bearer.<token>` entry in `Sec-WebSocket-Protocol`
+    Subprotocol,
+    /// In-band `Authenticate` message (or ttyd handshake `AuthToken`)
+    Message,
 }
 
 impl Default for SecurityConfig {
@@ -352,6 +498,25 @@ pub struct AuditConfig {
     /// Log token details (security risk)
     #[serde(default)]
     pub log_token_details: bool,
+
+    /// Hash-chained JSON Lines file events are appended to (the `audit`
+    /// tracing target only when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+
+    /// Secret keying the record hashes (HMAC-SHA256); required with `path`,
+    /// and created with a random key when missing. Keep it out of reach of
+    /// whoever can write the log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hmac_key_file: Option<PathBuf>,
+
+    /// Rotate the file once it grows past this many bytes (0 never rotates)
+    #[serde(default = "default_audit_max_file_size")]
+    pub max_file_size: u64,
+
+    /// Rotated files kept next to the live one (`<path>.1` is the newest)
+    #[serde(default = "default_audit_max_files")]
+    pub max_files: usize,
 }
 
 impl Default for AuditConfig {
@@ -362,6 +527,198 @@ impl Default for AuditConfig {
             log_failed_auth: true,
             log_authorization_denials: true,
             log_token_details: false,
+            path: None,
+            hmac_key_file: None,
+            max_file_size: default_audit_max_file_size(),
+            max_files: default_audit_max_files(),
+        }
+    }
+}
+
+/// API key (personal access token) settings
+/// Per 011-authentication-spec.md section 7.4
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyConfig {
+    /// Accept API keys and allow users to create them
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// File the hashed keys are persisted to (in memory only when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store_path: Option<PathBuf>,
+
+    /// Longest lifetime a key may be created with (keys may not be
+    /// created without an expiry when set)
+    #[serde(
+        default,
+        with = "humantime_serde",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_lifetime: Option<Duration>,
+
+    /// Maximum number of active keys per user
+    #[serde(default = "default_max_keys_per_user")]
+    pub max_keys_per_user: usize,
+}
+
+impl Default for ApiKeyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            store_path: None,
+            max_lifetime: None,
+            max_keys_per_user: default_max_keys_per_user(),
+        }
+    }
+}
+
+/// Hot reload settings
+/// Per 011-authentication-spec.md: Configuration Reload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadConfig {
+    /// YAML file with an `auth:` section (as in config/auth.yaml) whose
+    /// `jwks` and `authorization` settings are applied at startup and on
+    /// every reload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_file: Option<PathBuf>,
+
+    /// Reload when the config file or the permissions file changes
+    #[serde(default)]
+    pub watch: bool,
+
+    /// How often watched files are checked for changes
+    #[serde(default = "default_watch_interval", with = "humantime_serde")]
+    pub watch_interval: Duration,
+
+    /// Reload when the server receives SIGHUP
+    #[serde(default = "default_true")]
+    pub on_sighup: bool,
+}
+
+impl Default for ReloadConfig {
+    fn default() -> Self {
+        Self {
+            config_file: None,
+            watch: false,
+            watch_interval: default_watch_interval(),
+            on_sighup: true,
+        }
+    }
+}
+
+/// Token revocation settings
+/// Per 011-authentication-spec.md: Token Revocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevocationConfig {
+    /// File revocations are persisted to (in memory only when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store_path: Option<PathBuf>,
+
+    /// Accept short-lived tokens (tickets) only once for WebSocket
+    /// authentication; tickets must carry a `jti`
+    #[serde(default)]
+    pub one_time_tickets: bool,
+
+    /// Tokens valid for at most this long (`exp - iat`) count as tickets
+    #[serde(default = "default_ticket_lifetime", with = "humantime_serde")]
+    pub ticket_lifetime: Duration,
+}
+
+impl Default for RevocationConfig {
+    fn default() -> Self {
+        Self {
+            store_path: None,
+            one_time_tickets: false,
+            ticket_lifetime: default_ticket_lifetime(),
+        }
+    }
+}
+
+/// Tenant isolation settings
+/// Per 011-authentication-spec.md: Multi-Tenancy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TenancyConfig {
+    /// Qualify users, sessions and admin scope by tenant
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Claim naming the tenant (which must list the provider that issued
+    /// the token); when unset the tenant is the one listing that provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claim: Option<String>,
+
+    /// Tenant of tokens no tenant claims (refused when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_tenant: Option<String>,
+
+    /// Configured tenants
+    #[serde(default)]
+    pub tenants: Vec<TenantConfig>,
+}
+
+/// One tenant
+/// Per 011-authentication-spec.md: Multi-Tenancy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantConfig {
+    /// Tenant name, prefixed to its user IDs (`<name>/<sub>`)
+    pub name: String,
+
+    /// JWKS providers whose tokens belong to this tenant
+    #[serde(default)]
+    pub providers: Vec<String>,
+
+    /// Directory holding the tenant's workspaces (default:
+    /// `/workspace/<name>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_root: Option<PathBuf>,
+
+    /// Sessions per user, overriding `session.max_sessions_per_user`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_sessions_per_user: Option<usize>,
+
+    /// Sessions across all of the tenant's users (unlimited when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_sessions: Option<usize>,
+
+    /// Role permissions file for the tenant's users, replacing
+    /// `authorization.permissions_file`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions_file: Option<PathBuf>,
+
+    /// Administrators of this tenant manage every tenant and server-wide
+    /// state such as lockouts
+    #[serde(default)]
+    pub operator: bool,
+}
+
+/// Just-in-time session access settings
+/// Per 011-authentication-spec.md: Just-in-Time Access
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessRequestConfig {
+    /// Let users ask for access to sessions they do not own
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Access granted when a request names no duration
+    #[serde(default = "default_access_duration", with = "humantime_serde")]
+    pub default_duration: Duration,
+
+    /// Longest access a request may ask for
+    #[serde(default = "default_access_max_duration", with = "humantime_serde")]
+    pub max_duration: Duration,
+
+    /// How long a request waits for the owner's answer before it lapses
+    #[serde(default = "default_access_pending_timeout", with = "humantime_serde")]
+    pub pending_timeout: Duration,
+}
+
+impl Default for AccessRequestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_duration: default_access_duration(),
+            max_duration: default_access_max_duration(),
+            pending_timeout: default_access_pending_timeout(),
         }
     }
 }
@@ -386,6 +743,18 @@ fn default_refresh_interval() -> Duration {
     Duration::from_secs(900) // 15 minutes
 }
 
+fn default_jwks_max_stale() -> Duration {
+    Duration::from_secs(24 * 3600)
+}
+
+fn default_jwks_min_refresh_interval() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_jwks_negative_cache_ttl() -> Duration {
+    Duration::from_secs(300)
+}
+
 fn default_timeout() -> Duration {
     Duration::from_secs(30)
 }
@@ -414,6 +783,18 @@ fn default_name_claim() -> String {
     "name".to_string()
 }
 
+fn default_roles_claim() -> String {
+    "roles".to_string()
+}
+
+fn default_role() -> String {
+    "user".to_string()
+}
+
+fn default_expiry_warning() -> Duration {
+    Duration::from_secs(60)
+}
+
 fn default_required_claims() -> Vec<String> {
     vec![
         "sub".to_string(),
@@ -452,12 +833,44 @@ fn default_lockout_duration() -> Duration {
     Duration::from_secs(900) // 15 minutes
 }
 
-fn default_token_sources() -> Vec<String> {
+fn default_audit_max_file_size() -> u64 {
+    100 * 1024 * 1024
+}
+
+fn default_audit_max_files() -> usize {
+    10
+}
+
+fn default_max_keys_per_user() -> usize {
+    20
+}
+
+fn default_watch_interval() -> Duration {
+    Duration::from_secs(5)
+}
+
+fn default_ticket_lifetime() -> Duration {
+    Duration::from_secs(120) // 2 minutes
+}
+
+fn default_access_duration() -> Duration {
+    Duration::from_secs(900) // 15 minutes
+}
+
+fn default_access_max_duration() -> Duration {
+    Duration::from_secs(4 * 3600)
+}
+
+fn default_access_pending_timeout() -> Duration {
+    Duration::from_secs(300) // 5 minutes
+}
+
+fn default_token_sources() -> Vec<TokenSource> {
     vec![
-        "header".to_string(),
-        "query".to_string(),
-        "subprotocol".to_string(),
-        "message".to_string(),
+        TokenSource::Header,
+        TokenSource::Query,
+        TokenSource::Subprotocol,
+        TokenSource::Message,
     ]
 }
 
@@ -477,7 +890,8 @@ mod tests {
     fn test_jwks_provider_defaults() {
         let provider = JwksProvider {
             name: "test".to_string(),
-            url: "https://example.com/.well-known/jwks.json".to_string(),
+            url: Some("https://example.com/.well-known/jwks.json".to_string()),
+            keys: None,
             issuer: "https://example.com".to_string(),
             audience: "test-audience".to_string(),
             algorithms: default_algorithms(),
@@ -491,6 +905,38 @@ mod tests {
         assert_eq!(provider.timeout, Duration::from_secs(30));
     }
 
+    #[test]
+    fn test_issuer_only_provider() {
+        let provider: JwksProvider = serde_json::from_value(serde_json::json!({
+            "name": "keycloak",
+            "issuer": "https://sso.example.com/realms/main",
+            "audience": "web-terminal",
+        }))
+        .unwrap();
+
+        assert!(provider.url.is_none());
+        assert_eq!(provider.algorithms, default_algorithms());
+    }
+
+    #[test]
+    fn test_static_key_provider() {
+        let provider: JwksProvider = serde_json::from_value(serde_json::json!({
+            "name": "lab",
+            "keys": { "secret_file": "/etc/web-terminal/hmac.key" },
+            "issuer": "web-terminal-lab",
+            "audience": "web-terminal",
+            "algorithms": ["HS512"],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            provider.keys,
+            Some(StaticKeySource::SecretFile(PathBuf::from(
+                "/etc/web-terminal/hmac.key"
+            )))
+        );
+    }
+
     #[test]
     fn test_claim_mappings_defaults() {
         let mappings = ClaimMappings::default();
@@ -512,13 +958,36 @@ mod tests {
             deny_by_default: true,
             allow_empty_groups: false,
             enable_wildcards: false,
+            ..AuthorizationConfig::default()
         };
 
         assert_eq!(config.allowed_users.len(), 1);
+        assert_eq!(config.default_role, "user");
         assert_eq!(config.allowed_groups.len(), 1);
         assert!(config.deny_by_default);
     }
 
+    #[test]
+    fn test_token_expiry_defaults() {
+        let config = ValidationConfig::default();
+        assert_eq!(config.expiry_warning, Duration::from_secs(60));
+        assert_eq!(config.on_expiry, TokenExpiryAction::Close);
+
+        let action: TokenExpiryAction = serde_json::from_str("\"freeze\"").unwrap();
+        assert_eq!(action, TokenExpiryAction::Freeze);
+    }
+
+    #[test]
+    fn test_token_sources() {
+        let config = SecurityConfig::default();
+        assert_eq!(config.allowed_token_sources.len(), 4);
+
+        let sources: Vec<TokenSource> =
+            serde_json::from_str(r#"["subprotocol", "header"]"#).unwrap();
+        assert_eq!(sources, vec![TokenSource::Subprotocol, TokenSource::Header]);
+        assert!(serde_json::from_str::<TokenSource>(r#""cookie""#).is_err());
+    }
+
     #[test]
     fn test_rate_limit_config() {
         let config = RateLimitConfig::default();
@@ -528,6 +997,36 @@ mod tests {
         assert_eq!(config.lockout, Duration::from_secs(900));
     }
 
+    #[test]
+    fn test_api_key_config() {
+        let config = ApiKeyConfig::default();
+        assert!(!config.enabled);
+        assert_eq!(config.max_lifetime, None);
+
+        let config: ApiKeyConfig = serde_yaml::from_str(
+            "enabled: true\nstore_path: /var/lib/web-terminal/api-keys.json\nmax_lifetime: 90d\n",
+        )
+        .unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.max_lifetime, Some(Duration::from_secs(90 * 86400)));
+        assert_eq!(config.max_keys_per_user, 20);
+    }
+
+    #[test]
+    fn test_reload_config() {
+        let config = ReloadConfig::default();
+        assert!(config.config_file.is_none());
+        assert!(!config.watch);
+        assert!(config.on_sighup);
+
+        let config: ReloadConfig = serde_yaml::from_str(
+            "config_file: /etc/web-terminal/auth.yaml\nwatch: true\nwatch_interval: 30s\n",
+        )
+        .unwrap();
+        assert!(config.watch);
+        assert_eq!(config.watch_interval, Duration::from_secs(30));
+    }
+
     #[test]
     fn test_audit_config() {
         let config = AuditConfig::default();
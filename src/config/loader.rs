@@ -0,0 +1,454 @@
+// Layered configuration loading
+// Per spec-kit/005-cli-spec.md: Configuration Layers
+// Responsibilities:
+// - Merge built-in defaults, the config file, a profile overlay,
+//   WEB_TERMINAL__* environment variables and command-line overrides, each
+//   layer winning over the ones before it
+// - Report keys no setting reads, so typos and stale keys don't pass silently
+// - Produce the single Config the server runs with
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use super::Config;
+
+/// Prefix of environment variables read as configuration, e.g.
+/// `WEB_TERMINAL__SERVER__PORT=9000` sets `server.port`
+pub const ENV_PREFIX: &str = "WEB_TERMINAL";
+
+/// Separator between the prefix and each nested key in environment variables
+pub const ENV_SEPARATOR: &str = "__";
+
+/// Config file read when none is given and it exists
+pub const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Configuration loading error types
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Missing configuration file: {0}")]
+    FileNotFound(PathBuf),
+
+    #[error("Missing configuration profile '{name}': {path}")]
+    ProfileNotFound { name: String, path: PathBuf },
+
+    #[error("Unreadable configuration file {path}: {reason}")]
+    Unreadable { path: PathBuf, reason: String },
+
+    #[error("Invalid configuration: {0}")]
+    Invalid(String),
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Invalid(e.to_string())
+    }
+}
+
+/// Where configuration is read from, on top of the built-in defaults
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSources {
+    /// Config file; `config.toml` is read when unset and present
+    pub file: Option<PathBuf>,
+
+    /// Profile overlay: a name such as `production`, read from
+    /// `<name>.toml` next to the config file, or a path to the overlay
+    pub profile: Option<String>,
+
+    /// Variables used instead of the process environment
+    pub env: Option<HashMap<String, String>>,
+
+    /// Dotted keys set last, e.g. `server.port` from `--port`
+    pub overrides: Vec<(String, String)>,
+}
+
+impl ConfigSources {
+    /// Read this config file (it must exist)
+    pub fn with_file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// Apply this profile overlay (it must exist)
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// Read environment variables from `env` instead of the process
+    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env = Some(env);
+        self
+    }
+
+    /// Set a dotted key after every other layer
+    pub fn with_override(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.overrides.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// Overlay file for the profile, if one was requested
+    pub fn profile_path(&self) -> Option<PathBuf> {
+        let profile = self.profile.as_deref()?;
+        if profile.ends_with(".toml") || profile.contains(std::path::MAIN_SEPARATOR) {
+            return Some(PathBuf::from(profile));
+        }
+
+        let dir = self
+            .file
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or_else(|| Path::new(""));
+        Some(dir.join(format!("{}.toml", profile)))
+    }
+}
+
+/// The merged configuration and how it was put together
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    /// Configuration the server runs with
+    pub config: Config,
+
+    /// Files read, in the order they were applied
+    pub files: Vec<PathBuf>,
+
+    /// Dotted keys set by some layer that no setting reads
+    pub unknown_keys: Vec<String>,
+}
+
+/// Merge every configuration layer in `sources` over the built-in defaults
+/// Per spec-kit/005-cli-spec.md: Configuration Layers
+pub fn load(sources: &ConfigSources) -> Result<LoadedConfig, ConfigError> {
+    let mut files = Vec::new();
+    match &sources.file {
+        Some(path) if !path.is_file() => return Err(ConfigError::FileNotFound(path.clone())),
+        Some(path) => files.push(path.clone()),
+        None if Path::new(DEFAULT_CONFIG_FILE).is_file() => {
+            files.push(PathBuf::from(DEFAULT_CONFIG_FILE))
+        }
+        None => {}
+    }
+
+    if let Some(path) = sources.profile_path() {
+        if !path.is_file() {
+            return Err(ConfigError::ProfileNotFound {
+                name: sources.profile.clone().unwrap_or_default(),
+                path,
+            });
+        }
+        files.push(path);
+    }
+
+    let mut layers = serde_json::Value::Object(Default::default());
+    for path in &files {
+        merge(&mut layers, &read_file(path)?);
+    }
+
+    let defaults = serde_json::to_value(Config::default())?;
+    let env: Vec<(String, String)> = match &sources.env {
+        Some(env) => env.clone().into_iter().collect(),
+        None => std::env::vars().collect(),
+    };
+    let prefix = format!("{}{}", ENV_PREFIX, ENV_SEPARATOR);
+    for (name, value) in env {
+        if let Some(key) = name.strip_prefix(&prefix) {
+            let path: Vec<String> = key
+                .split(ENV_SEPARATOR)
+                .map(|part| part.to_lowercase())
+                .collect();
+            let value = parse_value(&defaults, &path, &value);
+            set_path(&mut layers, &path, value);
+        }
+    }
+
+    for (key, value) in &sources.overrides {
+        let path: Vec<String> = key.split('.').map(str::to_string).collect();
+        let value = parse_value(&defaults, &path, value);
+        set_path(&mut layers, &path, value);
+    }
+
+    let mut merged = defaults;
+    merge(&mut merged, &layers);
+
+    let mut config: Config = serde_json::from_value(merged)?;
+    config.normalize();
+
+    let mut unknown_keys = Vec::new();
+    collect_unknown(
+        "",
+        &layers,
+        &serde_json::to_value(&config)?,
+        &mut unknown_keys,
+    );
+
+    Ok(LoadedConfig {
+        config,
+        files,
+        unknown_keys,
+    })
+}
+
+/// Parse a config file into a tree of settings
+fn read_file(path: &Path) -> Result<serde_json::Value, ConfigError> {
+    let unreadable = |reason: String| ConfigError::Unreadable {
+        path: path.to_path_buf(),
+        reason,
+    };
+    let content = std::fs::read_to_string(path).map_err(|e| unreadable(e.to_string()))?;
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml" | "yml") => {
+            serde_yaml::from_str(&content).map_err(|e| unreadable(e.to_string()))
+        }
+        Some("json") => serde_json::from_str(&content).map_err(|e| unreadable(e.to_string())),
+        _ => toml::from_str(&content).map_err(|e| unreadable(e.to_string())),
+    }
+}
+
+/// An environment or command-line value for the setting at `path`, typed
+/// the way it would be written in a config file when the setting accepts
+/// that, otherwise kept as given (a numeric secret, or `0123` for a string)
+fn parse_value(defaults: &serde_json::Value, path: &[String], value: &str) -> serde_json::Value {
+    let typed = if let Ok(value) = value.parse::<bool>() {
+        serde_json::Value::Bool(value)
+    } else if let Ok(value) = value.parse::<i64>() {
+        value.into()
+    } else if let Some(value) = value.parse::<f64>().ok().filter(|v| v.is_finite()) {
+        value.into()
+    } else {
+        return value.into();
+    };
+
+    let mut probe = defaults.clone();
+    set_path(&mut probe, path, typed.clone());
+    if serde_json::from_value::<Config>(probe).is_ok() {
+        typed
+    } else {
+        value.into()
+    }
+}
+
+/// Set the setting at `path`, creating the tables leading to it
+fn set_path(tree: &mut serde_json::Value, path: &[String], value: serde_json::Value) {
+    let Some((key, rest)) = path.split_first() else {
+        *tree = value;
+        return;
+    };
+
+    if !tree.is_object() {
+        *tree = serde_json::Value::Object(Default::default());
+    }
+    if let serde_json::Value::Object(table) = tree {
+        let child = table.entry(key.clone()).or_insert(serde_json::Value::Null);
+        set_path(child, rest, value);
+    }
+}
+
+/// Overlay `layer` onto `base`, table by table; anything else in `layer`
+/// replaces what `base` had
+fn merge(base: &mut serde_json::Value, layer: &serde_json::Value) {
+    match (base, layer) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer.clone(),
+    }
+}
+
+/// Push the dotted paths set in `raw` that are missing from `known`, the
+/// parsed configuration serialized back
+fn collect_unknown(
+    prefix: &str,
+    raw: &serde_json::Value,
+    known: &serde_json::Value,
+    unknown: &mut Vec<String>,
+) {
+    let (Some(raw), Some(known)) = (raw.as_object(), known.as_object()) else {
+        return;
+    };
+
+    for (key, value) in raw {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match known.get(key) {
+            Some(known) => collect_unknown(&path, value, known, unknown),
+            // Unset optional settings are left out when serialized
+            None if value.is_null() => {}
+            None => unknown.push(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::time::Duration;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+        path
+    }
+
+    fn no_env() -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = write(
+            dir.path(),
+            "config.toml",
+            "[server]\nport = 9000\nhost = \"127.0.0.1\"\nmax_connections = 50\n\n\
+             [session]\ntimeout = \"10m\"\n\n\
+             [session.profiles.restricted]\nshell = \"/bin/rbash\"\n\
+             env = { HISTFILE = \"/dev/null\" }\n",
+        );
+        write(
+            dir.path(),
+            "production.toml",
+            "[server]\nport = 9100\nmax_connections = 500\n",
+        );
+        let env = HashMap::from([
+            ("WEB_TERMINAL__SERVER__PORT".to_string(), "9200".to_string()),
+            (
+                "WEB_TERMINAL__LOGGING__JSON".to_string(),
+                "true".to_string(),
+            ),
+            // Single-underscore variables are command-line flags, not config
+            ("WEB_TERMINAL_PORT".to_string(), "1".to_string()),
+        ]);
+
+        let loaded = load(
+            &ConfigSources::default()
+                .with_file(&file)
+                .with_profile("production")
+                .with_env(env)
+                .with_override("server.host", "10.0.0.1"),
+        )
+        .unwrap();
+
+        let config = loaded.config;
+        assert_eq!(config.server.port, 9200);
+        assert_eq!(config.server.host, "10.0.0.1");
+        assert_eq!(config.server.max_connections, 500);
+        assert_eq!(config.session.timeout, Duration::from_secs(600));
+        assert!(config.logging.json);
+        assert_eq!(
+            config.session.profiles["restricted"].env["HISTFILE"],
+            "/dev/null"
+        );
+        // Untouched settings keep their defaults
+        assert_eq!(config.session.max_sessions_per_user, 10);
+        assert_eq!(loaded.files.len(), 2);
+        assert!(loaded.unknown_keys.is_empty());
+    }
+
+    #[test]
+    fn test_values_stay_strings_where_settings_expect_them() {
+        let env = HashMap::from([
+            ("WEB_TERMINAL__SERVER__HOST".to_string(), "0123".to_string()),
+            ("WEB_TERMINAL__SERVER__PORT".to_string(), "0123".to_string()),
+            (
+                "WEB_TERMINAL__SECURITY__JWT_SECRET".to_string(),
+                "12345".to_string(),
+            ),
+        ]);
+
+        let config = load(
+            &ConfigSources::default()
+                .with_env(env)
+                .with_override("auth.tenancy.enabled", "true")
+                .with_override("auth.tenancy.default_tenant", "42")
+                .with_override("auth.tenancy.claim", "false"),
+        )
+        .unwrap()
+        .config;
+
+        assert_eq!(config.server.host, "0123");
+        assert_eq!(config.server.port, 123);
+        assert_eq!(config.security.jwt_secret, "12345");
+        assert!(config.auth.tenancy.enabled);
+        assert_eq!(config.auth.tenancy.default_tenant.as_deref(), Some("42"));
+        assert_eq!(config.auth.tenancy.claim.as_deref(), Some("false"));
+    }
+
+    #[test]
+    fn test_unknown_keys_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = write(
+            dir.path(),
+            "config.toml",
+            "[server]\nprot = 9000\n\n[resources]\nmax_cpu_percent = 80\n",
+        );
+
+        let loaded = load(
+            &ConfigSources::default()
+                .with_file(&file)
+                .with_env(HashMap::from([(
+                    "WEB_TERMINAL__SESSION__TIMOUT".to_string(),
+                    "5m".to_string(),
+                )])),
+        )
+        .unwrap();
+
+        let mut unknown = loaded.unknown_keys;
+        unknown.sort();
+        assert_eq!(unknown, vec!["resources", "server.prot", "session.timout"]);
+        assert_eq!(loaded.config.server.port, 8080);
+    }
+
+    #[test]
+    fn test_missing_sources_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = load(
+            &ConfigSources::default()
+                .with_file(dir.path().join("absent.toml"))
+                .with_env(no_env()),
+        );
+        assert!(matches!(result, Err(ConfigError::FileNotFound(_))));
+
+        let file = write(dir.path(), "config.toml", "");
+        let result = load(
+            &ConfigSources::default()
+                .with_file(&file)
+                .with_profile("staging")
+                .with_env(no_env()),
+        );
+        assert!(matches!(result, Err(ConfigError::ProfileNotFound { .. })));
+
+        let file = write(dir.path(), "bad.toml", "[session]\ntimeout = \"soon\"\n");
+        let result = load(&ConfigSources::default().with_file(&file).with_env(no_env()));
+        assert!(matches!(result, Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_shipped_profiles_load_cleanly() {
+        for profile in ["production", "staging"] {
+            let loaded = load(
+                &ConfigSources::default()
+                    .with_file("config/config.toml.example")
+                    .with_profile(profile)
+                    .with_env(no_env()),
+            )
+            .unwrap();
+            assert!(loaded.unknown_keys.is_empty(), "{:?}", loaded.unknown_keys);
+            assert!(loaded.config.server.worker_threads > 0);
+        }
+    }
+}
@@ -2,10 +2,15 @@
 // Per spec-kit/003-backend-spec.md section 6
 
 pub mod auth;
+pub mod loader;
 pub mod server;
 
 pub use auth::AuthConfig;
-pub use server::{LoggingConfig, SecurityConfig, ServerConfig};
+pub use loader::{ConfigError, ConfigSources, LoadedConfig};
+pub use server::{
+    CompressionAlgorithm, CompressionConfig, ControlConfig, LatencyConfig, LoggingConfig,
+    SecurityConfig, ServerConfig, ShutdownConfig,
+};
 
 use crate::error::Result;
 use crate::session::SessionConfig;
@@ -24,16 +29,24 @@ pub struct Config {
 }
 
 impl Config {
-    /// Load configuration from environment and default values
+    /// Load configuration from default values, `config.toml` when present
+    /// and `WEB_TERMINAL__*` environment variables
     /// Per spec-kit/003-backend-spec.md: Configuration from environment
     pub fn from_env() -> Result<Self> {
-        Ok(Self {
-            server: ServerConfig::default(),
-            session: SessionConfig::default(),
-            security: SecurityConfig::default(),
-            logging: LoggingConfig::default(),
-            auth: AuthConfig::default(),
-        })
+        Ok(Self::load(&ConfigSources::default())?.config)
+    }
+
+    /// Load configuration by merging `sources` over the default values
+    /// Per spec-kit/005-cli-spec.md: Configuration Layers
+    pub fn load(sources: &ConfigSources) -> std::result::Result<LoadedConfig, ConfigError> {
+        loader::load(sources)
+    }
+
+    /// Resolve settings whose zero value means "pick automatically"
+    fn normalize(&mut self) {
+        if self.server.worker_threads == 0 {
+            self.server.worker_threads = num_cpus::get();
+        }
     }
 }
 
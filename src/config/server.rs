@@ -3,6 +3,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Server configuration
 /// Per spec-kit/003-backend-spec.md: Single-port deployment (default 8080)
@@ -35,6 +36,22 @@ pub struct ServerConfig {
     /// Number of worker threads
     #[serde(default = "default_worker_threads")]
     pub worker_threads: usize,
+
+    /// Terminal output compression
+    #[serde(default)]
+    pub compression: CompressionConfig,
+
+    /// Heartbeat latency reporting
+    #[serde(default)]
+    pub latency: LatencyConfig,
+
+    /// Session draining on shutdown
+    #[serde(default)]
+    pub shutdown: ShutdownConfig,
+
+    /// Pidfile and local control socket
+    #[serde(default)]
+    pub control: ControlConfig,
 }
 
 impl Default for ServerConfig {
@@ -47,6 +64,10 @@ impl Default for ServerConfig {
             security_headers: SecurityHeadersConfig::default(),
             max_connections: default_max_connections(),
             worker_threads: default_worker_threads(),
+            compression: CompressionConfig::default(),
+            latency: LatencyConfig::default(),
+            shutdown: ShutdownConfig::default(),
+            control: ControlConfig::default(),
         }
     }
 }
@@ -286,6 +307,194 @@ fn default_frame_options() -> String {
     "DENY".to_string()
 }
 
+/// Compression algorithms for terminal output
+/// Per spec-kit/007-websocket-spec.md: Binary output framing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionAlgorithm {
+    /// Raw DEFLATE (RFC 1951)
+    Deflate,
+    /// Zstandard
+    Zstd,
+}
+
+/// Terminal output compression configuration
+/// Per spec-kit/007-websocket-spec.md: Binary output framing
+///
+/// Compression is negotiated per connection with a `negotiate_compression`
+/// message. WebSocket permessage-deflate is not available (actix-web-actors
+/// does not support extensions), so framing is done at the application level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Allow clients to negotiate compression
+    #[serde(default = "default_compression_enabled")]
+    pub enabled: bool,
+
+    /// Supported algorithms, in server preference order
+    #[serde(default = "default_compression_algorithms")]
+    pub algorithms: Vec<CompressionAlgorithm>,
+
+    /// Output chunks smaller than this (bytes) are sent uncompressed
+    #[serde(default = "default_compression_min_size")]
+    pub min_size: usize,
+
+    /// Compression level (0-9 for deflate, 1-22 for zstd)
+    #[serde(default = "default_compression_level")]
+    pub level: i32,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_compression_enabled(),
+            algorithms: default_compression_algorithms(),
+            min_size: default_compression_min_size(),
+            level: default_compression_level(),
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Pick the first server-supported algorithm the client offered
+    pub fn negotiate(&self, offered: &[CompressionAlgorithm]) -> Option<CompressionAlgorithm> {
+        if !self.enabled {
+            return None;
+        }
+
+        self.algorithms
+            .iter()
+            .find(|algorithm| offered.contains(algorithm))
+            .copied()
+    }
+}
+
+fn default_compression_enabled() -> bool {
+    true
+}
+
+fn default_compression_algorithms() -> Vec<CompressionAlgorithm> {
+    vec![CompressionAlgorithm::Zstd, CompressionAlgorithm::Deflate]
+}
+
+fn default_compression_min_size() -> usize {
+    256
+}
+
+fn default_compression_level() -> i32 {
+    3
+}
+
+/// Heartbeat latency configuration
+/// Per spec-kit/007-websocket-spec.md: Heartbeat mechanism
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyConfig {
+    /// Round-trip time (ms) above which a heartbeat counts as slow
+    #[serde(default = "default_high_latency_ms")]
+    pub high_latency_ms: u64,
+
+    /// Consecutive slow (or fast) heartbeats before a connection is flagged (or cleared)
+    #[serde(default = "default_sustained_samples")]
+    pub sustained_samples: u32,
+}
+
+impl Default for LatencyConfig {
+    fn default() -> Self {
+        Self {
+            high_latency_ms: default_high_latency_ms(),
+            sustained_samples: default_sustained_samples(),
+        }
+    }
+}
+
+fn default_high_latency_ms() -> u64 {
+    250
+}
+
+fn default_sustained_samples() -> u32 {
+    3
+}
+
+/// Graceful shutdown configuration
+/// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownConfig {
+    /// How long attached clients get to finish or detach after SIGTERM or
+    /// `stop` before their terminals are hung up
+    #[serde(default = "default_shutdown_timeout", with = "humantime_serde")]
+    pub timeout: Duration,
+
+    /// How long hung-up terminals get to exit before they are killed
+    #[serde(default = "default_shutdown_kill_after", with = "humantime_serde")]
+    pub kill_after: Duration,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            timeout: default_shutdown_timeout(),
+            kill_after: default_shutdown_kill_after(),
+        }
+    }
+}
+
+fn default_shutdown_timeout() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_shutdown_kill_after() -> Duration {
+    Duration::from_secs(5)
+}
+
+/// Pidfile and local control socket configuration
+/// Per spec-kit/005-cli-spec.md: Process Management
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ControlConfig {
+    /// Directory for the pidfile, control socket and daemon log (default:
+    /// `$XDG_RUNTIME_DIR/web-terminal`, else `/tmp/web-terminal-<uid>`)
+    pub runtime_dir: Option<PathBuf>,
+
+    /// Pidfile path (default: `<runtime_dir>/web-terminal.pid`)
+    pub pid_file: Option<PathBuf>,
+}
+
+impl ControlConfig {
+    pub fn runtime_dir(&self) -> PathBuf {
+        if let Some(dir) = &self.runtime_dir {
+            return dir.clone();
+        }
+        match std::env::var_os("XDG_RUNTIME_DIR") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("web-terminal"),
+            _ => std::env::temp_dir().join(format!("web-terminal-{}", current_uid())),
+        }
+    }
+
+    pub fn pid_file(&self) -> PathBuf {
+        self.pid_file
+            .clone()
+            .unwrap_or_else(|| self.runtime_dir().join("web-terminal.pid"))
+    }
+
+    pub fn socket_path(&self) -> PathBuf {
+        self.runtime_dir().join("control.sock")
+    }
+
+    /// Where a daemon's output goes when `logging.file` is not set
+    pub fn daemon_log(&self) -> PathBuf {
+        self.runtime_dir().join("web-terminal.log")
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn current_uid() -> u32 {
+    // SAFETY: getuid has no preconditions and cannot fail
+    unsafe { libc::getuid() }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn current_uid() -> u32 {
+    0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,6 +508,28 @@ mod tests {
         assert!(config.tls.is_none());
     }
 
+    #[test]
+    fn test_control_paths_follow_runtime_dir() {
+        let config = ControlConfig {
+            runtime_dir: Some(PathBuf::from("/run/web-terminal")),
+            pid_file: None,
+        };
+        assert_eq!(
+            config.pid_file(),
+            PathBuf::from("/run/web-terminal/web-terminal.pid")
+        );
+        assert_eq!(
+            config.socket_path(),
+            PathBuf::from("/run/web-terminal/control.sock")
+        );
+
+        let config = ControlConfig {
+            pid_file: Some(PathBuf::from("/var/run/wt.pid")),
+            ..config
+        };
+        assert_eq!(config.pid_file(), PathBuf::from("/var/run/wt.pid"));
+    }
+
     #[test]
     fn test_cors_config_defaults() {
         let config = CorsConfig::default();
@@ -316,6 +547,26 @@ mod tests {
         assert!(config.enable_frame_options);
     }
 
+    #[test]
+    fn test_compression_negotiation() {
+        let config = CompressionConfig::default();
+        assert_eq!(
+            config.negotiate(&[CompressionAlgorithm::Deflate, CompressionAlgorithm::Zstd]),
+            Some(CompressionAlgorithm::Zstd)
+        );
+        assert_eq!(
+            config.negotiate(&[CompressionAlgorithm::Deflate]),
+            Some(CompressionAlgorithm::Deflate)
+        );
+        assert_eq!(config.negotiate(&[]), None);
+
+        let disabled = CompressionConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(disabled.negotiate(&[CompressionAlgorithm::Zstd]), None);
+    }
+
     #[test]
     fn test_security_config_defaults() {
         let config = SecurityConfig::default();
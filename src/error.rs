@@ -16,6 +16,9 @@ pub enum Error {
     #[error("Session expired: {0}")]
     SessionExpired(String),
 
+    #[error("Server is shutting down")]
+    ShuttingDown,
+
     // Command execution errors
     #[error("Invalid command: {0}")]
     InvalidCommand(String),
@@ -112,6 +115,7 @@ impl Error {
             Error::SessionNotFound(_) | Error::NotFound(_) | Error::ProcessNotFound(_) => 404,
             Error::SessionLimitExceeded(_) | Error::ResourceLimitExceeded(_) => 429,
             Error::SessionExpired(_) => 410,
+            Error::ShuttingDown => 503,
             Error::InvalidCommand(_) | Error::CommandNotAllowed(_) | Error::EmptyCommand => 400,
             Error::InvalidPath(_) | Error::Serialization(_) => 400,
             Error::AuthenticationFailed | Error::InvalidToken => 401,
@@ -146,12 +150,86 @@ impl Error {
     }
 }
 
+impl From<crate::config::ConfigError> for Error {
+    fn from(e: crate::config::ConfigError) -> Self {
+        Error::Internal(e.to_string())
+    }
+}
+
+impl From<crate::server::reload::ConfigReloadError> for Error {
+    fn from(e: crate::server::reload::ConfigReloadError) -> Self {
+        Error::ValidationError(e.to_string())
+    }
+}
+
 impl From<crate::pty::PtyError> for Error {
     fn from(e: crate::pty::PtyError) -> Self {
         Error::PtyError(e.to_string())
     }
 }
 
+impl From<crate::security::api_keys::ApiKeyError> for Error {
+    fn from(e: crate::security::api_keys::ApiKeyError) -> Self {
+        use crate::security::api_keys::ApiKeyError;
+
+        match e {
+            ApiKeyError::Disabled | ApiKeyError::NotFound(_) => Error::NotFound(e.to_string()),
+            ApiKeyError::InvalidCidr(_) | ApiKeyError::InvalidRequest(_) => {
+                Error::ValidationError(e.to_string())
+            }
+            ApiKeyError::InvalidKey
+            | ApiKeyError::Revoked(_)
+            | ApiKeyError::Expired { .. }
+            | ApiKeyError::AddressNotAllowed { .. } => Error::AuthenticationFailed,
+            ApiKeyError::Hash(_) | ApiKeyError::Store(_) => Error::Internal(e.to_string()),
+        }
+    }
+}
+
+impl From<crate::security::revocation::RevocationError> for Error {
+    fn from(e: crate::security::revocation::RevocationError) -> Self {
+        use crate::security::revocation::RevocationError;
+
+        match e {
+            RevocationError::NotFound(_) => Error::NotFound(e.to_string()),
+            RevocationError::InvalidRequest(_) => Error::ValidationError(e.to_string()),
+            RevocationError::Revoked(_)
+            | RevocationError::TicketReused(_)
+            | RevocationError::TicketWithoutJti => Error::AuthenticationFailed,
+            RevocationError::Unavailable(_) | RevocationError::Store(_) => {
+                Error::Internal(e.to_string())
+            }
+        }
+    }
+}
+
+impl From<crate::security::lockout::LockoutError> for Error {
+    fn from(e: crate::security::lockout::LockoutError) -> Self {
+        use crate::security::lockout::LockoutError;
+
+        match e {
+            LockoutError::InvalidKey(_) => Error::ValidationError(e.to_string()),
+            LockoutError::NotFound(_) => Error::NotFound(e.to_string()),
+        }
+    }
+}
+
+impl From<crate::security::access_grants::AccessGrantError> for Error {
+    fn from(e: crate::security::access_grants::AccessGrantError) -> Self {
+        use crate::security::access_grants::AccessGrantError;
+
+        match e {
+            AccessGrantError::Disabled | AccessGrantError::NotFound(_) => {
+                Error::NotFound(e.to_string())
+            }
+            AccessGrantError::SessionNotFound(id) => Error::SessionNotFound(id),
+            AccessGrantError::NotPending(..) | AccessGrantError::InvalidRequest(_) => {
+                Error::ValidationError(e.to_string())
+            }
+        }
+    }
+}
+
 /// Implement ResponseError for Actix-Web integration
 /// Per spec-kit/006-api-spec.md: Structured error responses
 impl actix_web::ResponseError for Error {
@@ -162,6 +240,7 @@ impl actix_web::ResponseError for Error {
             Error::SessionNotFound(id) => ErrorResponse::session_not_found(id),
             Error::SessionLimitExceeded(_) => ErrorResponse::rate_limit_exceeded(),
             Error::SessionExpired(_) => ErrorResponse::session_expired(),
+            Error::ShuttingDown => ErrorResponse::shutting_down(),
             Error::InvalidCommand(_) | Error::CommandNotAllowed(_) | Error::EmptyCommand => {
                 ErrorResponse::validation_error(format!("Command error: {}", self))
             }
@@ -202,5 +281,6 @@ mod tests {
         );
         assert_eq!(Error::AuthenticationFailed.status_code(), 401);
         assert_eq!(Error::InvalidCommand("test".to_string()).status_code(), 400);
+        assert_eq!(Error::ShuttingDown.status_code(), 503);
     }
 }
@@ -0,0 +1,131 @@
+// REST just-in-time access handlers
+// Per docs/spec-kit/006-api-spec.md - Access Requests
+// Per docs/spec-kit/011-authentication-spec.md - Just-in-Time Access
+
+use actix_web::{web, HttpResponse};
+use std::sync::Arc;
+use std::time::Duration;
+use validator::Validate;
+
+use crate::error::{Error, Result};
+use crate::handlers::api_types::*;
+use crate::security::access_grants::{AccessGrantStore, NewAccessRequest};
+use crate::security::authorization::Permission;
+use crate::server::middleware::auth::UserContext;
+use crate::session::manager::SessionManager;
+use crate::session::state::SessionId;
+
+/// POST /api/v1/sessions/{id}/access-requests - Ask for access to a session
+///
+/// Per docs/spec-kit/006-api-spec.md - Request Session Access
+/// The owner is prompted in their terminal; access starts once they approve
+pub async fn request_access(
+    grants: web::Data<Arc<AccessGrantStore>>,
+    session_manager: web::Data<Arc<SessionManager>>,
+    user_ctx: web::ReqData<UserContext>,
+    path: web::Path<String>,
+    req: web::Json<CreateAccessRequest>,
+) -> Result<HttpResponse> {
+    user_ctx.require_scope(Permission::ViewSession)?;
+    req.validate()
+        .map_err(|e| Error::validation(format!("Invalid request: {}", e)))?;
+
+    let session_id = SessionId::new(path.into_inner());
+    let session = session_manager.get_session(&session_id).await?;
+    let req = req.into_inner();
+    let grant = grants.request(
+        &user_ctx.user_id,
+        user_ctx.tenant_name(),
+        &session,
+        NewAccessRequest {
+            level: req.level,
+            duration: req.duration_secs.map(Duration::from_secs),
+            reason: req.reason,
+        },
+    )?;
+
+    tracing::info!(
+        user = %user_ctx.user_id,
+        session_id = %session_id,
+        request_id = %grant.id,
+        "Requested {} access",
+        grant.level.as_str()
+    );
+
+    Ok(HttpResponse::Created().json(grant))
+}
+
+/// GET /api/v1/access-requests - List requests the caller made or decides on
+///
+/// Per docs/spec-kit/006-api-spec.md - List Access Requests
+pub async fn list_access_requests(
+    grants: web::Data<Arc<AccessGrantStore>>,
+    user_ctx: web::ReqData<UserContext>,
+) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ListAccessRequestsResponse {
+        requests: grants.list_for(&user_ctx.user_id),
+    }))
+}
+
+/// POST /api/v1/access-requests/{id}/approve - Grant a pending request
+///
+/// Per docs/spec-kit/006-api-spec.md - Decide Access Request
+/// Only the session owner may decide
+pub async fn approve_access_request(
+    grants: web::Data<Arc<AccessGrantStore>>,
+    user_ctx: web::ReqData<UserContext>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    decide(&grants, &user_ctx, &path.into_inner(), true)
+}
+
+/// POST /api/v1/access-requests/{id}/deny - Refuse a pending request
+///
+/// Per docs/spec-kit/006-api-spec.md - Decide Access Request
+/// Only the session owner may decide
+pub async fn deny_access_request(
+    grants: web::Data<Arc<AccessGrantStore>>,
+    user_ctx: web::ReqData<UserContext>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    decide(&grants, &user_ctx, &path.into_inner(), false)
+}
+
+fn decide(
+    grants: &AccessGrantStore,
+    user_ctx: &UserContext,
+    id: &str,
+    approve: bool,
+) -> Result<HttpResponse> {
+    let grant = grants.decide(id, &user_ctx.user_id, approve)?;
+
+    tracing::info!(
+        user = %user_ctx.user_id,
+        request_id = %grant.id,
+        "Access request {}",
+        grant.status
+    );
+
+    Ok(HttpResponse::Ok().json(grant))
+}
+
+/// DELETE /api/v1/access-requests/{id} - Withdraw a request or end access
+///
+/// Per docs/spec-kit/006-api-spec.md - Revoke Access Request
+/// Either the requester or the session owner may revoke; connections
+/// relying on the access are closed
+pub async fn revoke_access_request(
+    grants: web::Data<Arc<AccessGrantStore>>,
+    user_ctx: web::ReqData<UserContext>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let grant = grants.revoke(&path.into_inner(), &user_ctx.user_id)?;
+
+    tracing::info!(
+        user = %user_ctx.user_id,
+        request_id = %grant.id,
+        "Revoked access request"
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
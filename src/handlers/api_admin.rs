@@ -0,0 +1,221 @@
+// REST admin handlers
+// Per docs/spec-kit/006-api-spec.md - Admin API
+// Per docs/spec-kit/011-authentication-spec.md - Token Revocation,
+// Brute-Force Protection
+// Per docs/spec-kit/005-cli-spec.md - Live Reload
+
+use actix_web::{web, HttpResponse};
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::error::{Error, Result};
+use crate::handlers::api_types::*;
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::security::authorization::{AuthorizationService, Permission};
+use crate::security::lockout::{LockoutKey, LockoutTracker};
+use crate::security::reload::ReloadTrigger;
+use crate::security::revocation::{NewRevocation, RevocationError, RevocationList};
+use crate::server::middleware::auth::UserContext;
+use crate::server::reload::ConfigReloader;
+
+/// GET /api/v1/admin/revocations - List active revocations
+///
+/// Per docs/spec-kit/006-api-spec.md - List Revocations
+/// Requires the administer permission; tenant administrators see their
+/// tenant's revocations only
+pub async fn list_revocations(
+    revocations: web::Data<Arc<RevocationList>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    user_ctx: web::ReqData<UserContext>,
+) -> Result<HttpResponse> {
+    user_ctx.authorize(&authz, Permission::Administer, None)?;
+
+    Ok(HttpResponse::Ok().json(ListRevocationsResponse {
+        revocations: revocations
+            .list()
+            .into_iter()
+            .filter(|revocation| user_ctx.manages(revocation.tenant.as_deref()))
+            .collect(),
+    }))
+}
+
+/// POST /api/v1/admin/revocations - Revoke a token, subject or issuing period
+///
+/// Per docs/spec-kit/006-api-spec.md - Create Revocation
+/// Requires the administer permission; matching WebSocket connections are
+/// closed on their next heartbeat. Tenant administrators revoke within
+/// their tenant only.
+pub async fn create_revocation(
+    revocations: web::Data<Arc<RevocationList>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    audit: web::Data<Arc<AuditLogger>>,
+    user_ctx: web::ReqData<UserContext>,
+    req: web::Json<CreateRevocationRequest>,
+) -> Result<HttpResponse> {
+    user_ctx.authorize(&authz, Permission::Administer, None)?;
+    req.validate()
+        .map_err(|e| Error::validation(format!("Invalid request: {}", e)))?;
+
+    let req = req.into_inner();
+    let tenant = req.tenant.or_else(|| {
+        user_ctx
+            .tenant
+            .as_ref()
+            .filter(|tenant| !tenant.operator)
+            .map(|tenant| tenant.name.clone())
+    });
+    user_ctx.require_manages(tenant.as_deref())?;
+
+    let revocation = revocations.revoke(
+        user_ctx.user_id.as_str(),
+        NewRevocation {
+            jti: req.jti,
+            sub: req.sub,
+            issued_before: req.issued_before,
+            tenant,
+            reason: req.reason,
+            expires_at: req.expires_at,
+        },
+    )?;
+
+    tracing::info!(
+        user = %user_ctx.user_id,
+        revocation_id = %revocation.id,
+        "Revoked {}",
+        revocation.describe()
+    );
+    audit.record(
+        AuditEvent::new(AuditAction::TokenRevoked)
+            .user(user_ctx.user_id.as_str())
+            .detail(format!("{} ({})", revocation.describe(), revocation.id)),
+    );
+
+    Ok(HttpResponse::Created().json(revocation))
+}
+
+/// DELETE /api/v1/admin/revocations/{id} - Lift a revocation
+///
+/// Per docs/spec-kit/006-api-spec.md - Delete Revocation
+/// Requires the administer permission over the revocation's tenant
+pub async fn delete_revocation(
+    revocations: web::Data<Arc<RevocationList>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    audit: web::Data<Arc<AuditLogger>>,
+    user_ctx: web::ReqData<UserContext>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    user_ctx.authorize(&authz, Permission::Administer, None)?;
+
+    let id = path.into_inner();
+    if !revocations
+        .list()
+        .iter()
+        .any(|revocation| revocation.id == id && user_ctx.manages(revocation.tenant.as_deref()))
+    {
+        return Err(RevocationError::NotFound(id).into());
+    }
+    let revocation = revocations.remove(&id)?;
+
+    tracing::info!(
+        user = %user_ctx.user_id,
+        revocation_id = %revocation.id,
+        "Removed revocation"
+    );
+    audit.record(
+        AuditEvent::new(AuditAction::RevocationRemoved)
+            .user(user_ctx.user_id.as_str())
+            .detail(format!("{} ({})", revocation.describe(), revocation.id)),
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// GET /api/v1/admin/lockouts - List addresses and subjects locked out
+///
+/// Per docs/spec-kit/006-api-spec.md - List Lockouts
+/// Requires the administer permission (in an operator tenant with tenancy)
+pub async fn list_lockouts(
+    lockouts: web::Data<Arc<LockoutTracker>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    user_ctx: web::ReqData<UserContext>,
+) -> Result<HttpResponse> {
+    user_ctx.authorize(&authz, Permission::Administer, None)?;
+    user_ctx.require_manages(None)?;
+
+    Ok(HttpResponse::Ok().json(ListLockoutsResponse {
+        lockouts: lockouts.lockouts(),
+    }))
+}
+
+/// DELETE /api/v1/admin/lockouts/{key} - Lift one lockout
+///
+/// Per docs/spec-kit/006-api-spec.md - Clear Lockout
+/// Requires the administer permission (in an operator tenant with
+/// tenancy); `key` is `ip:<address>` or `sub:<subject>`
+pub async fn clear_lockout(
+    lockouts: web::Data<Arc<LockoutTracker>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    audit: web::Data<Arc<AuditLogger>>,
+    user_ctx: web::ReqData<UserContext>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    user_ctx.authorize(&authz, Permission::Administer, None)?;
+    user_ctx.require_manages(None)?;
+
+    let key: LockoutKey = path.into_inner().parse()?;
+    let lockout = lockouts.clear(&key)?;
+
+    tracing::info!(user = %user_ctx.user_id, "Cleared lockout of {}", lockout.key);
+    audit.record(
+        AuditEvent::new(AuditAction::LockoutCleared)
+            .user(user_ctx.user_id.as_str())
+            .detail(lockout.key.to_string()),
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// DELETE /api/v1/admin/lockouts - Lift every lockout
+///
+/// Per docs/spec-kit/006-api-spec.md - Clear Lockouts
+/// Requires the administer permission (in an operator tenant with tenancy)
+pub async fn clear_lockouts(
+    lockouts: web::Data<Arc<LockoutTracker>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    audit: web::Data<Arc<AuditLogger>>,
+    user_ctx: web::ReqData<UserContext>,
+) -> Result<HttpResponse> {
+    user_ctx.authorize(&authz, Permission::Administer, None)?;
+    user_ctx.require_manages(None)?;
+
+    let cleared = lockouts.clear_all();
+
+    tracing::info!(user = %user_ctx.user_id, cleared, "Cleared all lockouts");
+    audit.record(
+        AuditEvent::new(AuditAction::LockoutCleared)
+            .user(user_ctx.user_id.as_str())
+            .detail(format!("all ({} in effect)", cleared)),
+    );
+
+    Ok(HttpResponse::Ok().json(ClearLockoutsResponse { cleared }))
+}
+
+/// POST /api/v1/admin/config/reload - Re-read and apply the configuration
+///
+/// Per docs/spec-kit/006-api-spec.md - Reload Configuration
+/// Requires the administer permission (in an operator tenant with
+/// tenancy); an invalid configuration is refused with 422 and the running
+/// one kept
+pub async fn reload_config(
+    reloader: web::Data<Arc<ConfigReloader>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    user_ctx: web::ReqData<UserContext>,
+) -> Result<HttpResponse> {
+    user_ctx.authorize(&authz, Permission::Administer, None)?;
+    user_ctx.require_manages(None)?;
+
+    tracing::info!(user = %user_ctx.user_id, "Configuration reload requested");
+    let report = reloader.reload(ReloadTrigger::Manual).await?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
@@ -0,0 +1,93 @@
+// REST authorization handlers
+// Per docs/spec-kit/006-api-spec.md - Authorization API
+// Per docs/spec-kit/011-authentication-spec.md - Policy Rules
+
+use actix_web::{web, HttpResponse};
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::error::{Error, Result};
+use crate::handlers::api_types::*;
+use crate::security::authorization::{AuthorizationService, Permission};
+use crate::security::policy::Resource;
+use crate::server::middleware::auth::UserContext;
+use crate::session::manager::SessionManager;
+use crate::session::state::SessionId;
+
+/// POST /api/v1/authz/explain - Explain an authorization decision
+///
+/// Per docs/spec-kit/006-api-spec.md - Explain Authorization
+/// Dry run for the authenticated caller: evaluates the policy rules and
+/// role permissions as if the action were attempted, optionally from
+/// another address, at another time or on an existing session, and reports
+/// which rule or role decided. Nothing is performed. Sessions the caller
+/// may not view, and that are not in a tenant they administer, are
+/// reported as not found.
+pub async fn explain(
+    session_manager: web::Data<Arc<SessionManager>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    user_ctx: web::ReqData<UserContext>,
+    req: web::Json<ExplainRequest>,
+) -> Result<HttpResponse> {
+    req.validate()
+        .map_err(|e| Error::validation(format!("Invalid request: {}", e)))?;
+
+    let session = match &req.session_id {
+        Some(id) => {
+            let session = session_manager
+                .get_session(&SessionId::new(id.clone()))
+                .await?;
+            let visible = user_ctx
+                .decide(
+                    &authz,
+                    Permission::ViewSession,
+                    &user_ctx.access_request(Resource::session(&session)),
+                )
+                .allowed
+                || (user_ctx
+                    .decide(
+                        &authz,
+                        Permission::ListAllSessions,
+                        &user_ctx.access_request(Resource::default()),
+                    )
+                    .allowed
+                    && user_ctx.manages(session.tenant.as_deref()));
+            if !visible {
+                return Err(Error::SessionNotFound(id.clone()));
+            }
+            Some(session)
+        }
+        None => None,
+    };
+    let mut resource = session
+        .as_deref()
+        .map(Resource::session)
+        .unwrap_or_default();
+    if req.profile.is_some() {
+        resource = resource.with_profile(req.profile.as_deref());
+    }
+
+    let mut request = user_ctx.access_request(resource);
+    if let Some(source_ip) = req.source_ip {
+        request.source_ip = Some(source_ip);
+    }
+    if let Some(at) = req.at {
+        request.at = at;
+    }
+    let decision = user_ctx.decide(&authz, req.permission, &request);
+
+    tracing::debug!(
+        user = %user_ctx.user_id,
+        permission = req.permission.as_str(),
+        allowed = decision.allowed,
+        "Explained authorization decision"
+    );
+
+    Ok(HttpResponse::Ok().json(ExplainResponse {
+        user_id: user_ctx.user_id.to_string(),
+        roles: user_ctx.roles.clone(),
+        source_ip: request.source_ip.map(|ip| ip.to_string()),
+        at: request.at.to_rfc3339(),
+        decision,
+    }))
+}
@@ -7,6 +7,7 @@ use std::time::Instant;
 
 use crate::error::Result;
 use crate::handlers::api_types::*;
+use crate::security::jwks_client::JwksClient;
 use crate::session::manager::SessionManager;
 
 /// Server start time for uptime calculation
@@ -49,6 +50,19 @@ pub async fn health_check(session_manager: web::Data<Arc<SessionManager>>) -> Re
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// GET /api/v1/metrics - Prometheus metrics
+///
+/// Per docs/spec-kit/006-api-spec.md - Metrics (Prometheus Format)
+/// Requires JWT authentication
+pub async fn metrics(jwks_client: web::Data<Arc<JwksClient>>) -> HttpResponse {
+    // JWKS cache gauges are sampled when scraped
+    jwks_client.update_metrics();
+
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(crate::monitoring::metrics::gather_text())
+}
+
 /// Check if session manager is healthy
 async fn check_sessions_health(session_manager: &SessionManager) -> Result<()> {
     // Try to list sessions to verify manager is responsive
@@ -8,6 +8,9 @@ use validator::Validate;
 
 use crate::error::{Error, Result};
 use crate::handlers::api_types::*;
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::security::authorization::{AuthorizationService, Permission};
+use crate::security::policy::Resource;
 use crate::server::middleware::auth::UserContext;
 use crate::session::manager::SessionManager;
 use crate::session::state::SessionId;
@@ -18,9 +21,17 @@ use crate::session::state::SessionId;
 /// Requires JWT authentication (extracted from middleware)
 pub async fn create_session(
     session_manager: web::Data<Arc<SessionManager>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    audit: web::Data<Arc<AuditLogger>>,
     user_ctx: web::ReqData<UserContext>,
     req: web::Json<CreateSessionRequest>,
 ) -> Result<HttpResponse> {
+    user_ctx.authorize_resource(
+        &authz,
+        Permission::CreateSession,
+        Resource::default().with_profile(req.profile.as_deref()),
+    )?;
+
     // Validate input
     req.validate()
         .map_err(|e| Error::validation(format!("Invalid request: {}", e)))?;
@@ -28,17 +39,28 @@ pub async fn create_session(
     tracing::info!(
         user = %user_ctx.user_id,
         initial_dir = ?req.initial_dir,
+        profile = ?req.profile,
         "Creating new terminal session"
     );
 
     // Create session
     let session = session_manager
-        .create_session(user_ctx.user_id.clone())
+        .create_tenant_session(
+            user_ctx.user_id.clone(),
+            user_ctx.tenant_name(),
+            req.into_inner().profile,
+        )
         .await?;
+    audit.record(
+        AuditEvent::new(AuditAction::SessionCreated)
+            .user(user_ctx.user_id.as_str())
+            .session(&session.id),
+    );
 
     let response = CreateSessionResponse {
         id: session.id.to_string(),
         user_id: session.user_id.to_string(),
+        profile: session.profile.clone(),
         created_at: chrono::DateTime::<Utc>::from(
             std::time::SystemTime::UNIX_EPOCH + session.created_at.elapsed(),
         )
@@ -60,9 +82,10 @@ pub async fn create_session(
 /// GET /api/v1/sessions/{id} - Get session details
 ///
 /// Per docs/spec-kit/006-api-spec.md - Get Session
-/// Requires JWT authentication
+/// Requires JWT authentication; other users' sessions need list_all_sessions
 pub async fn get_session(
     session_manager: web::Data<Arc<SessionManager>>,
+    authz: web::Data<Arc<AuthorizationService>>,
     user_ctx: web::ReqData<UserContext>,
     path: web::Path<String>,
 ) -> Result<HttpResponse> {
@@ -76,17 +99,12 @@ pub async fn get_session(
 
     // Get session
     let session = session_manager.get_session(&session_id).await?;
-
-    // Authorization check: user can only access their own sessions
-    if session.user_id != user_ctx.user_id {
-        return Err(Error::forbidden(
-            "You are not authorized to access this session",
-        ));
-    }
+    user_ctx.authorize_resource(&authz, Permission::ViewSession, Resource::session(&session))?;
 
     let response = GetSessionResponse {
         id: session.id.to_string(),
         user_id: session.user_id.to_string(),
+        profile: session.profile.clone(),
         created_at: chrono::DateTime::<Utc>::from(
             std::time::SystemTime::UNIX_EPOCH + session.created_at.elapsed(),
         )
@@ -113,12 +131,22 @@ pub async fn get_session(
 ///
 /// Per docs/spec-kit/006-api-spec.md - List Sessions
 /// Requires JWT authentication
-/// Lists only the authenticated user's sessions
+/// Lists the authenticated user's sessions, or every user's sessions for
+/// holders of list_all_sessions (within their tenant, unless it is an
+/// operator tenant)
 pub async fn list_sessions(
     session_manager: web::Data<Arc<SessionManager>>,
+    authz: web::Data<Arc<AuthorizationService>>,
     user_ctx: web::ReqData<UserContext>,
     query: web::Query<ListSessionsQuery>,
 ) -> Result<HttpResponse> {
+    let list_all = user_ctx
+        .authorize(&authz, Permission::ListAllSessions, None)
+        .is_ok();
+    if !list_all {
+        user_ctx.authorize(&authz, Permission::ViewSession, None)?;
+    }
+
     // Validate query parameters
     query
         .validate()
@@ -131,17 +159,32 @@ pub async fn list_sessions(
         user = %user_ctx.user_id,
         limit = limit,
         offset = offset,
-        "Listing user sessions"
+        list_all = list_all,
+        "Listing sessions"
     );
 
-    // Get all sessions for the user
     let all_sessions = session_manager.list_sessions().await;
 
-    // Filter to user's sessions only
-    let user_sessions: Vec<_> = all_sessions
-        .into_iter()
-        .filter(|s| s.user_id == user_ctx.user_id)
-        .collect();
+    // Filter to the user's sessions unless they may see all (of the tenants
+    // they manage), optionally by latency flag
+    let mut user_sessions = Vec::new();
+    for s in all_sessions.into_iter().filter(|s| {
+        if list_all {
+            user_ctx.manages(s.tenant.as_deref())
+        } else {
+            s.user_id == user_ctx.user_id
+        }
+    }) {
+        let latency = s.get_latency().await;
+        let high_latency = latency.as_ref().is_some_and(|l| l.high_latency);
+        if query
+            .high_latency
+            .is_some_and(|wanted| wanted != high_latency)
+        {
+            continue;
+        }
+        user_sessions.push((s, latency, high_latency));
+    }
 
     let total = user_sessions.len();
 
@@ -150,7 +193,7 @@ pub async fn list_sessions(
         .into_iter()
         .skip(offset as usize)
         .take(limit as usize)
-        .map(|s| SessionSummary {
+        .map(|(s, latency, high_latency)| SessionSummary {
             id: s.id.to_string(),
             user_id: s.user_id.to_string(),
             created_at: chrono::DateTime::<Utc>::from(
@@ -161,6 +204,8 @@ pub async fn list_sessions(
                 std::time::SystemTime::UNIX_EPOCH + s.last_activity.elapsed(),
             )
             .to_rfc3339(),
+            latency,
+            high_latency,
         })
         .collect();
 
@@ -177,9 +222,11 @@ pub async fn list_sessions(
 /// DELETE /api/v1/sessions/{id} - Delete session
 ///
 /// Per docs/spec-kit/006-api-spec.md - Delete Session
-/// Requires JWT authentication
+/// Requires JWT authentication; other users' sessions need kill_any_session
 pub async fn delete_session(
     session_manager: web::Data<Arc<SessionManager>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    audit: web::Data<Arc<AuditLogger>>,
     user_ctx: web::ReqData<UserContext>,
     path: web::Path<String>,
 ) -> Result<HttpResponse> {
@@ -193,16 +240,16 @@ pub async fn delete_session(
 
     // Get session first to check authorization
     let session = session_manager.get_session(&session_id).await?;
-
-    // Authorization check: user can only delete their own sessions
-    if session.user_id != user_ctx.user_id {
-        return Err(Error::forbidden(
-            "You are not authorized to delete this session",
-        ));
-    }
+    user_ctx.authorize_resource(&authz, Permission::KillSession, Resource::session(&session))?;
 
     // Delete the session
     session_manager.destroy_session(&session_id).await?;
+    audit.record(
+        AuditEvent::new(AuditAction::SessionKilled)
+            .user(user_ctx.user_id.as_str())
+            .session(&session_id)
+            .detail(format!("owned by {}", session.user_id)),
+    );
 
     Ok(HttpResponse::NoContent().finish())
 }
@@ -210,9 +257,10 @@ pub async fn delete_session(
 /// GET /api/v1/sessions/{id}/history - Get command history
 ///
 /// Per docs/spec-kit/006-api-spec.md - Get Session History
-/// Requires JWT authentication
+/// Requires JWT authentication; other users' sessions need list_all_sessions
 pub async fn get_session_history(
     session_manager: web::Data<Arc<SessionManager>>,
+    authz: web::Data<Arc<AuthorizationService>>,
     user_ctx: web::ReqData<UserContext>,
     path: web::Path<String>,
     query: web::Query<SessionHistoryQuery>,
@@ -235,13 +283,7 @@ pub async fn get_session_history(
 
     // Get session first to check authorization
     let session = session_manager.get_session(&session_id).await?;
-
-    // Authorization check
-    if session.user_id != user_ctx.user_id {
-        return Err(Error::forbidden(
-            "You are not authorized to access this session history",
-        ));
-    }
+    user_ctx.authorize_resource(&authz, Permission::ViewSession, Resource::session(&session))?;
 
     // Get command history
     let history = session.get_history().await;
@@ -280,6 +322,7 @@ mod tests {
         let req = CreateSessionRequest {
             initial_dir: Some("/workspace".to_string()),
             environment: None,
+            profile: None,
         };
         assert!(req.validate().is_ok());
 
@@ -287,6 +330,7 @@ mod tests {
         let bad_req = CreateSessionRequest {
             initial_dir: Some("../../../etc/passwd".to_string()),
             environment: None,
+            profile: None,
         };
         // Validation passes (path traversal is blocked at execution layer)
         assert!(bad_req.validate().is_ok());
@@ -297,12 +341,14 @@ mod tests {
         let query = ListSessionsQuery {
             limit: Some(50),
             offset: Some(0),
+            high_latency: None,
         };
         assert!(query.validate().is_ok());
 
         let bad_query = ListSessionsQuery {
             limit: Some(200), // Over max of 100
             offset: Some(0),
+            high_latency: None,
         };
         assert!(bad_query.validate().is_err());
     }
@@ -0,0 +1,137 @@
+// REST API key handlers
+// Per docs/spec-kit/006-api-spec.md - API Keys
+// Per docs/spec-kit/011-authentication-spec.md - API Keys
+
+use actix_web::{web, HttpResponse};
+use std::sync::Arc;
+use std::time::Duration;
+use validator::Validate;
+
+use crate::error::{Error, Result};
+use crate::handlers::api_types::*;
+use crate::security::api_keys::{ApiKeyStore, CidrBlock, NewApiKey};
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::server::middleware::auth::UserContext;
+
+/// API keys are managed with an identity provider token only, so a leaked
+/// key cannot be used to mint or revoke others
+fn require_jwt(user_ctx: &UserContext) -> Result<()> {
+    if user_ctx.is_api_key() {
+        return Err(Error::forbidden(
+            "API keys cannot be used to manage API keys",
+        ));
+    }
+    Ok(())
+}
+
+/// POST /api/v1/tokens - Create an API key
+///
+/// Per docs/spec-kit/006-api-spec.md - Create API Key
+/// Requires JWT authentication; the key is returned once and only its hash
+/// is stored
+pub async fn create_token(
+    store: web::Data<Arc<ApiKeyStore>>,
+    audit: web::Data<Arc<AuditLogger>>,
+    user_ctx: web::ReqData<UserContext>,
+    req: web::Json<CreateApiKeyRequest>,
+) -> Result<HttpResponse> {
+    require_jwt(&user_ctx)?;
+    req.validate()
+        .map_err(|e| Error::validation(format!("Invalid request: {}", e)))?;
+
+    let req = req.into_inner();
+    let allowed_cidrs = req
+        .allowed_cidrs
+        .iter()
+        .map(|cidr| cidr.parse::<CidrBlock>())
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let (key, token) = store.create(
+        &user_ctx.claims,
+        NewApiKey {
+            name: req.name,
+            scopes: req.scopes,
+            lifetime: req.expires_in.map(Duration::from_secs),
+            allowed_cidrs,
+            tenant: user_ctx.tenant_name().map(str::to_string),
+        },
+    )?;
+
+    tracing::info!(
+        user = %user_ctx.user_id,
+        key_id = %key.id,
+        "Created API key"
+    );
+    audit.record(
+        AuditEvent::new(AuditAction::ApiKeyCreated)
+            .user(user_ctx.user_id.as_str())
+            .detail(format!(
+                "key {} ({}) scopes [{}]",
+                key.id,
+                key.name,
+                key.scopes
+                    .iter()
+                    .map(|s| s.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+    );
+
+    Ok(HttpResponse::Created().json(CreateApiKeyResponse {
+        key: ApiKeySummary::from(&key),
+        token,
+    }))
+}
+
+/// GET /api/v1/tokens - List the caller's API keys
+///
+/// Per docs/spec-kit/006-api-spec.md - List API Keys
+/// Requires JWT authentication
+pub async fn list_tokens(
+    store: web::Data<Arc<ApiKeyStore>>,
+    user_ctx: web::ReqData<UserContext>,
+) -> Result<HttpResponse> {
+    require_jwt(&user_ctx)?;
+    if !store.is_enabled() {
+        return Err(Error::not_found("API keys are disabled"));
+    }
+
+    let tokens = store
+        .list(user_ctx.user_id.as_str())
+        .iter()
+        .map(ApiKeySummary::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(ListApiKeysResponse { tokens }))
+}
+
+/// DELETE /api/v1/tokens/{id} - Revoke an API key
+///
+/// Per docs/spec-kit/006-api-spec.md - Revoke API Key
+/// Requires JWT authentication; users can only revoke their own keys
+pub async fn revoke_token(
+    store: web::Data<Arc<ApiKeyStore>>,
+    audit: web::Data<Arc<AuditLogger>>,
+    user_ctx: web::ReqData<UserContext>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    require_jwt(&user_ctx)?;
+    if !store.is_enabled() {
+        return Err(Error::not_found("API keys are disabled"));
+    }
+
+    let key = store.revoke(user_ctx.user_id.as_str(), &path.into_inner())?;
+
+    tracing::info!(
+        user = %user_ctx.user_id,
+        key_id = %key.id,
+        "Revoked API key"
+    );
+    audit.record(
+        AuditEvent::new(AuditAction::ApiKeyRevoked)
+            .user(user_ctx.user_id.as_str())
+            .detail(format!("key {} ({})", key.id, key.name)),
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
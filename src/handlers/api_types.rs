@@ -5,6 +5,13 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use validator::Validate;
 
+use crate::monitoring::LatencySnapshot;
+use crate::security::access_grants::{AccessGrant, AccessLevel};
+use crate::security::api_keys::ApiKey;
+use crate::security::authorization::{Decision, Permission};
+use crate::security::lockout::Lockout;
+use crate::security::revocation::Revocation;
+
 // ===== Session API Types =====
 
 /// Request to create a new terminal session
@@ -16,6 +23,10 @@ pub struct CreateSessionRequest {
 
     /// Environment variables (optional)
     pub environment: Option<HashMap<String, String>>,
+
+    /// Session profile to run (optional, default shell when omitted)
+    #[validate(length(min = 1, max = 64))]
+    pub profile: Option<String>,
 }
 
 /// Response for session creation
@@ -23,6 +34,8 @@ pub struct CreateSessionRequest {
 pub struct CreateSessionResponse {
     pub id: String,
     pub user_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
     pub created_at: String,
     pub state: SessionState,
 }
@@ -48,6 +61,8 @@ pub struct ProcessInfo {
 pub struct GetSessionResponse {
     pub id: String,
     pub user_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
     pub created_at: String,
     pub last_activity: String,
     pub state: SessionState,
@@ -60,6 +75,9 @@ pub struct ListSessionsQuery {
     pub limit: Option<u32>,
 
     pub offset: Option<u32>,
+
+    /// Only list sessions with (or without) sustained high latency
+    pub high_latency: Option<bool>,
 }
 
 /// Response for listing sessions
@@ -78,6 +96,11 @@ pub struct SessionSummary {
     pub user_id: String,
     pub created_at: String,
     pub last_activity: String,
+    /// Heartbeat latency of the attached connection, if measured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencySnapshot>,
+    /// Connection has had sustained high latency
+    pub high_latency: bool,
 }
 
 /// Query parameters for session history
@@ -101,6 +124,181 @@ pub struct HistoryEntry {
     pub exit_code: Option<i32>,
 }
 
+// ===== API Key Types =====
+
+/// Request to create an API key
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateApiKeyRequest {
+    /// Label for the key
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+
+    /// Permissions the key is limited to
+    #[validate(length(min = 1))]
+    pub scopes: Vec<Permission>,
+
+    /// Seconds until the key expires (never, when omitted)
+    #[validate(range(min = 1))]
+    pub expires_in: Option<u64>,
+
+    /// Networks the key may be used from, e.g. "10.0.0.0/8"
+    #[serde(default)]
+    pub allowed_cidrs: Vec<String>,
+}
+
+/// API key details (never includes the secret)
+#[derive(Debug, Serialize)]
+pub struct ApiKeySummary {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<Permission>,
+    pub allowed_cidrs: Vec<String>,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+    pub revoked_at: Option<String>,
+    pub last_used_at: Option<String>,
+    pub active: bool,
+}
+
+impl From<&ApiKey> for ApiKeySummary {
+    fn from(key: &ApiKey) -> Self {
+        Self {
+            id: key.id.clone(),
+            name: key.name.clone(),
+            scopes: key.scopes.clone(),
+            allowed_cidrs: key.allowed_cidrs.iter().map(ToString::to_string).collect(),
+            created_at: key.created_at.to_rfc3339(),
+            expires_at: key.expires_at.map(|t| t.to_rfc3339()),
+            revoked_at: key.revoked_at.map(|t| t.to_rfc3339()),
+            last_used_at: key.last_used_at.map(|t| t.to_rfc3339()),
+            active: key.is_active(chrono::Utc::now()),
+        }
+    }
+}
+
+/// Response for API key creation: the only time the key itself is returned
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    #[serde(flatten)]
+    pub key: ApiKeySummary,
+    pub token: String,
+}
+
+/// Response for listing API keys
+#[derive(Debug, Serialize)]
+pub struct ListApiKeysResponse {
+    pub tokens: Vec<ApiKeySummary>,
+}
+
+// ===== Authorization API Types =====
+
+/// Request to explain an authorization decision without acting on it
+///
+/// Unset fields take the values of the explaining request itself (its
+/// source address, the current time).
+#[derive(Debug, Deserialize, Validate)]
+pub struct ExplainRequest {
+    /// Permission to evaluate
+    pub permission: Permission,
+
+    /// Existing session the action targets (its owner and profile apply)
+    #[validate(length(min = 1, max = 128))]
+    pub session_id: Option<String>,
+
+    /// Session profile, overriding the target session's
+    #[validate(length(min = 1, max = 64))]
+    pub profile: Option<String>,
+
+    /// Source address to evaluate from
+    pub source_ip: Option<std::net::IpAddr>,
+
+    /// Time to evaluate at
+    pub at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Response for an explained decision
+#[derive(Debug, Serialize)]
+pub struct ExplainResponse {
+    pub user_id: String,
+    pub roles: Vec<String>,
+    pub source_ip: Option<String>,
+    pub at: String,
+    #[serde(flatten)]
+    pub decision: Decision,
+}
+
+// ===== Admin API Types =====
+
+/// Request to revoke a token, a subject or everything issued before a time
+///
+/// At least one of `jti`, `sub` and `issued_before` must be set; every field
+/// that is set must match for a credential to be refused.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateRevocationRequest {
+    /// Token ID (`jti` claim)
+    #[validate(length(min = 1, max = 256))]
+    pub jti: Option<String>,
+
+    /// Subject (`sub` claim)
+    #[validate(length(min = 1, max = 256))]
+    pub sub: Option<String>,
+
+    /// Refuse tokens issued before this time
+    pub issued_before: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Only credentials of this tenant (default: the administrator's own,
+    /// or every tenant for operators)
+    #[validate(length(min = 1, max = 256))]
+    pub tenant: Option<String>,
+
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
+
+    /// Drop the entry after this time (e.g. when the revoked token expires)
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Response for listing revocations
+#[derive(Debug, Serialize)]
+pub struct ListRevocationsResponse {
+    pub revocations: Vec<Revocation>,
+}
+
+/// Response for listing lockouts
+#[derive(Debug, Serialize)]
+pub struct ListLockoutsResponse {
+    pub lockouts: Vec<Lockout>,
+}
+
+/// Response for clearing every lockout
+#[derive(Debug, Serialize)]
+pub struct ClearLockoutsResponse {
+    pub cleared: usize,
+}
+
+// ===== Access Request Types =====
+
+/// Request for access to another user's session
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAccessRequest {
+    /// "view" or "control"
+    pub level: AccessLevel,
+
+    /// Seconds of access wanted (the server default when omitted)
+    #[validate(range(min = 1))]
+    pub duration_secs: Option<u64>,
+
+    /// Why access is needed, shown to the owner
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
+}
+
+/// Response for listing access requests
+#[derive(Debug, Serialize)]
+pub struct ListAccessRequestsResponse {
+    pub requests: Vec<AccessGrant>,
+}
+
 // ===== Health API Types =====
 
 /// Health check response
@@ -214,6 +412,14 @@ impl ErrorResponse {
         Self::new("SESSION_EXPIRED", "Session has expired")
     }
 
+    /// Server shutting down, no new sessions (503)
+    pub fn shutting_down() -> Self {
+        Self::new(
+            "SERVER_SHUTTING_DOWN",
+            "Server is shutting down and not accepting new sessions",
+        )
+    }
+
     /// Not found error (404)
     pub fn not_found(message: impl Into<String>) -> Self {
         Self::new("NOT_FOUND", message)
@@ -249,6 +455,15 @@ impl ErrorResponse {
         )
     }
 
+    /// Authentication lockout error (429)
+    pub fn auth_locked_out(retry_after: u64) -> Self {
+        Self::with_details(
+            "AUTH_LOCKED_OUT",
+            "Too many failed authentication attempts. Please try again later.",
+            serde_json::json!({ "retry_after": retry_after }),
+        )
+    }
+
     /// Internal server error (500)
     pub fn internal_error() -> Self {
         Self::new("INTERNAL_ERROR", "An internal server error occurred")
@@ -279,6 +494,7 @@ mod tests {
         let req = CreateSessionRequest {
             initial_dir: Some("/workspace".to_string()),
             environment: Some(HashMap::new()),
+            profile: None,
         };
         assert!(req.validate().is_ok());
     }
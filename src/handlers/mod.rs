@@ -2,16 +2,30 @@
 //!
 //! Per spec-kit/006-api-spec.md
 
+pub mod api_access;
+pub mod api_admin;
+pub mod api_authz;
 pub mod api_health;
 pub mod api_sessions;
+pub mod api_tokens;
 pub mod api_types;
 pub mod sessions;
 
 // Re-export REST API handlers
-pub use api_health::health_check;
+pub use api_access::{
+    approve_access_request, deny_access_request, list_access_requests, request_access,
+    revoke_access_request,
+};
+pub use api_admin::{
+    clear_lockout, clear_lockouts, create_revocation, delete_revocation, list_lockouts,
+    list_revocations, reload_config,
+};
+pub use api_authz::explain;
+pub use api_health::{health_check, metrics};
 pub use api_sessions::{
     create_session, delete_session, get_session, get_session_history, list_sessions,
 };
+pub use api_tokens::{create_token, list_tokens, revoke_token};
 pub use api_types::*;
 
 // Legacy handlers (will be removed)
@@ -2,6 +2,7 @@
 // Per spec-kit/003-backend-spec.md
 
 pub mod cli;
+pub mod client;
 pub mod config;
 pub mod error;
 pub mod execution;
@@ -0,0 +1,230 @@
+// Per-connection heartbeat round-trip latency tracking
+// Per spec-kit/007-websocket-spec.md: Heartbeat mechanism, Latency test
+//
+// The server stamps each heartbeat ping frame with the time it was sent; the
+// matching pong yields one RTT sample. Samples feed a small per-connection
+// histogram plus the global `web_terminal_websocket_rtt_seconds` metric.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::config::LatencyConfig;
+
+/// Upper bounds (ms) of the per-connection RTT histogram buckets
+pub const RTT_BUCKETS_MS: [u64; 10] = [5, 10, 20, 50, 100, 250, 500, 1000, 2500, 5000];
+
+/// Point-in-time view of a connection's latency
+/// Per spec-kit/006-api-spec.md: Session listing
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencySnapshot {
+    /// Most recent RTT sample
+    pub last_ms: u64,
+    /// Mean RTT over all samples
+    pub avg_ms: u64,
+    /// Smallest RTT seen
+    pub min_ms: u64,
+    /// Largest RTT seen
+    pub max_ms: u64,
+    /// 95th percentile (upper bound of the bucket it falls in)
+    pub p95_ms: u64,
+    /// Number of RTT samples recorded
+    pub samples: u64,
+    /// Latency has stayed above the configured threshold
+    pub high_latency: bool,
+}
+
+/// Heartbeat RTT tracker for a single connection
+#[derive(Debug, Clone)]
+pub struct LatencyTracker {
+    config: LatencyConfig,
+    epoch: Instant,
+    /// Counts per bucket in `RTT_BUCKETS_MS`, plus one overflow bucket
+    buckets: [u64; RTT_BUCKETS_MS.len() + 1],
+    samples: u64,
+    sum_ms: u64,
+    last_ms: u64,
+    min_ms: u64,
+    max_ms: u64,
+    consecutive_high: u32,
+    consecutive_normal: u32,
+    high_latency: bool,
+}
+
+impl LatencyTracker {
+    /// Create a tracker for a new connection
+    pub fn new(config: LatencyConfig) -> Self {
+        Self {
+            config,
+            epoch: Instant::now(),
+            buckets: [0; RTT_BUCKETS_MS.len() + 1],
+            samples: 0,
+            sum_ms: 0,
+            last_ms: 0,
+            min_ms: u64::MAX,
+            max_ms: 0,
+            consecutive_high: 0,
+            consecutive_normal: 0,
+            high_latency: false,
+        }
+    }
+
+    /// Payload for the next heartbeat ping frame
+    ///
+    /// Encodes microseconds since the tracker was created, so the matching
+    /// pong can be turned back into an RTT by [`LatencyTracker::rtt_from_pong`].
+    pub fn ping_payload(&self) -> [u8; 8] {
+        (self.epoch.elapsed().as_micros() as u64).to_be_bytes()
+    }
+
+    /// Decode a pong payload produced by [`LatencyTracker::ping_payload`]
+    ///
+    /// Returns `None` for empty or foreign payloads (e.g. unsolicited pongs).
+    pub fn rtt_from_pong(&self, payload: &[u8]) -> Option<Duration> {
+        let sent: [u8; 8] = payload.try_into().ok()?;
+        let sent = Duration::from_micros(u64::from_be_bytes(sent));
+        self.epoch.elapsed().checked_sub(sent)
+    }
+
+    /// Record one RTT sample
+    ///
+    /// Returns `true` when the sample flips the sustained high-latency flag.
+    pub fn record(&mut self, rtt: Duration) -> bool {
+        let ms = rtt.as_millis() as u64;
+
+        let bucket = RTT_BUCKETS_MS
+            .iter()
+            .position(|&bound| ms <= bound)
+            .unwrap_or(RTT_BUCKETS_MS.len());
+        self.buckets[bucket] += 1;
+        self.samples += 1;
+        self.sum_ms = self.sum_ms.saturating_add(ms);
+        self.last_ms = ms;
+        self.min_ms = self.min_ms.min(ms);
+        self.max_ms = self.max_ms.max(ms);
+
+        // Require several consecutive samples either side of the threshold so
+        // a single slow pong doesn't flap the flag
+        if ms > self.config.high_latency_ms {
+            self.consecutive_high += 1;
+            self.consecutive_normal = 0;
+        } else {
+            self.consecutive_normal += 1;
+            self.consecutive_high = 0;
+        }
+
+        let sustained = self.config.sustained_samples.max(1);
+        let was_high = self.high_latency;
+        if self.consecutive_high >= sustained {
+            self.high_latency = true;
+        } else if self.consecutive_normal >= sustained {
+            self.high_latency = false;
+        }
+
+        was_high != self.high_latency
+    }
+
+    /// Most recent RTT, if any sample has been recorded
+    pub fn last_ms(&self) -> Option<u64> {
+        (self.samples > 0).then_some(self.last_ms)
+    }
+
+    /// Whether latency is currently flagged as high
+    pub fn is_high_latency(&self) -> bool {
+        self.high_latency
+    }
+
+    /// Summarise the recorded samples
+    pub fn snapshot(&self) -> LatencySnapshot {
+        if self.samples == 0 {
+            return LatencySnapshot::default();
+        }
+
+        LatencySnapshot {
+            last_ms: self.last_ms,
+            avg_ms: self.sum_ms / self.samples,
+            min_ms: self.min_ms,
+            max_ms: self.max_ms,
+            p95_ms: self.percentile(0.95),
+            samples: self.samples,
+            high_latency: self.high_latency,
+        }
+    }
+
+    fn percentile(&self, quantile: f64) -> u64 {
+        let rank = ((self.samples as f64) * quantile).ceil() as u64;
+        let mut seen = 0;
+        for (i, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return RTT_BUCKETS_MS.get(i).copied().unwrap_or(self.max_ms);
+            }
+        }
+        self.max_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LatencyConfig {
+        LatencyConfig {
+            high_latency_ms: 200,
+            sustained_samples: 3,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_statistics() {
+        let mut tracker = LatencyTracker::new(config());
+        assert_eq!(tracker.snapshot(), LatencySnapshot::default());
+        assert_eq!(tracker.last_ms(), None);
+
+        for ms in [4, 8, 15, 40, 90] {
+            tracker.record(Duration::from_millis(ms));
+        }
+
+        let snapshot = tracker.snapshot();
+        assert_eq!(snapshot.samples, 5);
+        assert_eq!(snapshot.last_ms, 90);
+        assert_eq!(snapshot.min_ms, 4);
+        assert_eq!(snapshot.max_ms, 90);
+        assert_eq!(snapshot.avg_ms, 31);
+        assert_eq!(snapshot.p95_ms, 100);
+        assert!(!snapshot.high_latency);
+    }
+
+    #[test]
+    fn test_high_latency_requires_sustained_samples() {
+        let mut tracker = LatencyTracker::new(config());
+
+        // A single spike does not flag the connection
+        assert!(!tracker.record(Duration::from_millis(900)));
+        assert!(!tracker.record(Duration::from_millis(10)));
+        assert!(!tracker.is_high_latency());
+
+        assert!(!tracker.record(Duration::from_millis(300)));
+        assert!(!tracker.record(Duration::from_millis(300)));
+        assert!(tracker.record(Duration::from_millis(300)));
+        assert!(tracker.is_high_latency());
+
+        // Recovery also needs sustained normal samples
+        assert!(!tracker.record(Duration::from_millis(10)));
+        assert!(!tracker.record(Duration::from_millis(10)));
+        assert!(tracker.record(Duration::from_millis(10)));
+        assert!(!tracker.is_high_latency());
+    }
+
+    #[test]
+    fn test_ping_payload_round_trip() {
+        let tracker = LatencyTracker::new(config());
+        let payload = tracker.ping_payload();
+        std::thread::sleep(Duration::from_millis(5));
+
+        let rtt = tracker.rtt_from_pong(&payload).unwrap();
+        assert!(rtt >= Duration::from_millis(5));
+
+        assert!(tracker.rtt_from_pong(b"").is_none());
+        assert!(tracker.rtt_from_pong(&u64::MAX.to_be_bytes()).is_none());
+    }
+}
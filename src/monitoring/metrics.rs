@@ -0,0 +1,155 @@
+// Prometheus metrics
+// Per docs/spec-kit/006-api-spec.md - Metrics (Prometheus Format)
+
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, Encoder, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, TextEncoder,
+};
+
+/// WebSocket heartbeat round-trip time, labelled by wire protocol ("json" or "ttyd")
+pub static WEBSOCKET_RTT_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "web_terminal_websocket_rtt_seconds",
+        "WebSocket heartbeat round-trip time",
+        &["protocol"],
+        vec![0.005, 0.01, 0.02, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0]
+    )
+    .expect("register web_terminal_websocket_rtt_seconds")
+});
+
+/// Connections currently flagged with sustained high latency
+pub static WEBSOCKET_HIGH_LATENCY_CONNECTIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "web_terminal_websocket_high_latency_connections",
+        "WebSocket connections with sustained high heartbeat latency"
+    )
+    .expect("register web_terminal_websocket_high_latency_connections")
+});
+
+/// Failed authentications counted towards lockouts
+pub static AUTH_FAILURES_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "web_terminal_auth_failures_total",
+        "Failed authentications counted towards lockouts"
+    )
+    .expect("register web_terminal_auth_failures_total")
+});
+
+/// Lockouts imposed, labelled by scope ("ip" or "subject")
+pub static AUTH_LOCKOUTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "web_terminal_auth_lockouts_total",
+        "Lockouts imposed after repeated failed authentications",
+        &["scope"]
+    )
+    .expect("register web_terminal_auth_lockouts_total")
+});
+
+/// Lockouts currently in effect, labelled by scope ("ip" or "subject")
+pub static AUTH_LOCKED_OUT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "web_terminal_auth_locked_out",
+        "Addresses and subjects currently locked out",
+        &["scope"]
+    )
+    .expect("register web_terminal_auth_locked_out")
+});
+
+/// Authentication attempts refused because of a lockout, labelled by scope
+pub static AUTH_LOCKOUT_REJECTIONS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "web_terminal_auth_lockout_rejections_total",
+        "Authentication attempts refused during a lockout",
+        &["scope"]
+    )
+    .expect("register web_terminal_auth_lockout_rejections_total")
+});
+
+/// JWKS fetches, labelled by provider and result ("success" or "failure")
+pub static JWKS_REFRESH_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "web_terminal_jwks_refresh_total",
+        "JWKS fetches from identity providers",
+        &["provider", "result"]
+    )
+    .expect("register web_terminal_jwks_refresh_total")
+});
+
+/// Expired JWKS keys served because the provider could not be reached
+pub static JWKS_STALE_SERVED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "web_terminal_jwks_stale_served_total",
+        "Lookups answered with expired JWKS keys while refreshes fail",
+        &["provider"]
+    )
+    .expect("register web_terminal_jwks_stale_served_total")
+});
+
+/// Tokens with a kid missing from the cache, labelled by provider and
+/// outcome ("found", "not_found", "refresh_failed", "rate_limited",
+/// "negative_cached")
+pub static JWKS_UNKNOWN_KID_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "web_terminal_jwks_unknown_kid_total",
+        "Token kids missing from the JWKS cache",
+        &["provider", "outcome"]
+    )
+    .expect("register web_terminal_jwks_unknown_kid_total")
+});
+
+/// Age of each provider's cached keys
+pub static JWKS_CACHE_AGE_SECONDS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "web_terminal_jwks_cache_age_seconds",
+        "Seconds since each provider's JWKS keys were fetched",
+        &["provider"]
+    )
+    .expect("register web_terminal_jwks_cache_age_seconds")
+});
+
+/// Keys cached per provider
+pub static JWKS_CACHE_KEYS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "web_terminal_jwks_cache_keys",
+        "JWKS keys cached per provider",
+        &["provider"]
+    )
+    .expect("register web_terminal_jwks_cache_keys")
+});
+
+/// 1 while a provider's keys are expired but still served
+pub static JWKS_CACHE_STALE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "web_terminal_jwks_cache_stale",
+        "Whether each provider's JWKS keys are expired and served stale",
+        &["provider"]
+    )
+    .expect("register web_terminal_jwks_cache_stale")
+});
+
+/// Render all registered metrics in the Prometheus text exposition format
+pub fn gather_text() -> String {
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        tracing::error!("Failed to encode metrics: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rtt_histogram_exported() {
+        WEBSOCKET_RTT_SECONDS
+            .with_label_values(&["json"])
+            .observe(0.015);
+
+        let text = gather_text();
+        assert!(text.contains("web_terminal_websocket_rtt_seconds_bucket"));
+        assert!(text.contains("protocol=\"json\""));
+    }
+}
@@ -1,2 +1,7 @@
 // Monitoring and logging module
 // Per spec-kit/003-backend-spec.md section 2.8
+
+pub mod latency;
+pub mod metrics;
+
+pub use latency::{LatencySnapshot, LatencyTracker};
@@ -0,0 +1,205 @@
+// Output compression for binary WebSocket frames
+// Per spec-kit/007-websocket-spec.md: Binary output framing
+//
+// Once a client negotiates compression, terminal output is sent as binary
+// frames instead of JSON `output` messages. Each frame starts with a one-byte
+// codec tag followed by the (possibly compressed) raw PTY bytes:
+//
+//   [codec: u8][payload...]
+//
+// Small chunks are sent uncompressed (codec 0) so latency-sensitive
+// keystroke echo is not penalised.
+
+use crate::config::CompressionAlgorithm;
+use std::io::{Read, Write};
+use thiserror::Error;
+
+/// Codec tag values for binary output frames
+pub mod codec_tags {
+    pub const RAW: u8 = 0;
+    pub const DEFLATE: u8 = 1;
+    pub const ZSTD: u8 = 2;
+}
+
+impl CompressionAlgorithm {
+    /// Codec tag written in front of frames compressed with this algorithm
+    pub fn tag(&self) -> u8 {
+        match self {
+            Self::Deflate => codec_tags::DEFLATE,
+            Self::Zstd => codec_tags::ZSTD,
+        }
+    }
+}
+
+/// Compression errors
+#[derive(Debug, Error)]
+pub enum CompressionError {
+    #[error("Empty output frame")]
+    EmptyFrame,
+
+    #[error("Unknown codec tag: {0}")]
+    UnknownCodec(u8),
+
+    #[error("Decompressed frame exceeds maximum of {0} bytes")]
+    FrameTooLarge(usize),
+
+    #[error("Compression I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Encodes PTY output into binary frames
+#[derive(Debug, Clone)]
+pub struct OutputEncoder {
+    algorithm: CompressionAlgorithm,
+    min_size: usize,
+    level: i32,
+}
+
+impl OutputEncoder {
+    /// Create an encoder
+    ///
+    /// `min_size` is the smallest chunk worth compressing; `level` is passed to
+    /// the codec (0-9 for deflate, 1-22 for zstd).
+    pub fn new(algorithm: CompressionAlgorithm, min_size: usize, level: i32) -> Self {
+        Self {
+            algorithm,
+            min_size,
+            level,
+        }
+    }
+
+    /// Negotiated algorithm
+    pub fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
+
+    /// Encode a chunk of output as a binary frame
+    ///
+    /// Falls back to a raw frame when the chunk is below the threshold or
+    /// compression does not make it smaller.
+    pub fn encode(&self, data: &[u8]) -> Vec<u8> {
+        if data.len() >= self.min_size {
+            if let Ok(compressed) = self.compress(data) {
+                if compressed.len() < data.len() {
+                    return frame(self.algorithm.tag(), &compressed);
+                }
+            }
+        }
+
+        frame(codec_tags::RAW, data)
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        match self.algorithm {
+            CompressionAlgorithm::Deflate => {
+                let level = flate2::Compression::new(self.level.clamp(0, 9) as u32);
+                let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), level);
+                encoder.write_all(data)?;
+                Ok(encoder.finish()?)
+            }
+            CompressionAlgorithm::Zstd => Ok(zstd::bulk::compress(data, self.level)?),
+        }
+    }
+}
+
+/// Decode a binary output frame back into raw PTY bytes
+///
+/// `max_size` bounds the decompressed size to guard against compression bombs.
+pub fn decode_frame(frame: &[u8], max_size: usize) -> Result<Vec<u8>, CompressionError> {
+    let (&tag, payload) = frame.split_first().ok_or(CompressionError::EmptyFrame)?;
+
+    let data = match tag {
+        codec_tags::RAW => payload.to_vec(),
+        codec_tags::DEFLATE => {
+            let mut decoded = Vec::new();
+            flate2::read::DeflateDecoder::new(payload)
+                .take(max_size as u64 + 1)
+                .read_to_end(&mut decoded)?;
+            decoded
+        }
+        codec_tags::ZSTD => {
+            let mut decoded = Vec::new();
+            zstd::stream::read::Decoder::new(payload)?
+                .take(max_size as u64 + 1)
+                .read_to_end(&mut decoded)?;
+            decoded
+        }
+        other => return Err(CompressionError::UnknownCodec(other)),
+    };
+
+    if data.len() > max_size {
+        return Err(CompressionError::FrameTooLarge(max_size));
+    }
+
+    Ok(data)
+}
+
+fn frame(tag: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 1);
+    frame.push(tag);
+    frame.extend_from_slice(payload);
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX: usize = 1024 * 1024;
+
+    fn build_log() -> Vec<u8> {
+        (0..200)
+            .map(|i| {
+                format!(
+                    "   Compiling crate-{} v0.1.{} (/src/crate-{})\n",
+                    i % 7,
+                    i,
+                    i % 7
+                )
+            })
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_small_chunks_sent_raw() {
+        let encoder = OutputEncoder::new(CompressionAlgorithm::Zstd, 512, 3);
+        let frame = encoder.encode(b"ls\r\n");
+        assert_eq!(frame[0], codec_tags::RAW);
+        assert_eq!(decode_frame(&frame, MAX).unwrap(), b"ls\r\n");
+    }
+
+    #[test]
+    fn test_round_trip_all_algorithms() {
+        let data = build_log();
+        for algorithm in [CompressionAlgorithm::Deflate, CompressionAlgorithm::Zstd] {
+            let encoder = OutputEncoder::new(algorithm, 64, 3);
+            let frame = encoder.encode(&data);
+            assert_eq!(frame[0], algorithm.tag());
+            assert!(frame.len() < data.len() / 2);
+            assert_eq!(decode_frame(&frame, MAX).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_oversized_frames() {
+        let data = vec![b'x'; 4096];
+        let frame = OutputEncoder::new(CompressionAlgorithm::Zstd, 0, 3).encode(&data);
+        assert!(matches!(
+            decode_frame(&frame, 1024),
+            Err(CompressionError::FrameTooLarge(1024))
+        ));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(matches!(
+            decode_frame(&[], MAX),
+            Err(CompressionError::EmptyFrame)
+        ));
+        assert!(matches!(
+            decode_frame(&[9, 1, 2], MAX),
+            Err(CompressionError::UnknownCodec(9))
+        ));
+    }
+}
@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::CompressionAlgorithm;
+
 /// Maximum message size: 1 MB
 pub const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
 
@@ -52,13 +54,27 @@ pub enum ClientMessage {
     /// Per spec-kit/007-websocket-spec.md: File transfer protocol
     FileDownload { path: String },
 
-    /// Ping message for heartbeat
-    /// Per spec-kit/007-websocket-spec.md: Heartbeat mechanism
-    Ping,
+    /// Ping message for heartbeat and latency measurement
+    /// `timestamp` (client clock, ms) is echoed back in the pong
+    /// Per spec-kit/007-websocket-spec.md: Heartbeat mechanism, Latency test
+    Ping {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timestamp: Option<u64>,
+    },
 
     /// Echo test message
     /// Per spec-kit/007-websocket-spec.md: Testing protocol
     Echo { data: String },
+
+    /// Request compressed binary output, in order of preference
+    /// Per spec-kit/007-websocket-spec.md: Binary output framing
+    NegotiateCompression {
+        algorithms: Vec<CompressionAlgorithm>,
+    },
+
+    /// Approve or deny a request for access to this session (owner only)
+    /// Per spec-kit/011-authentication-spec.md: Just-in-Time Access
+    AccessDecision { request_id: String, approve: bool },
 }
 
 impl ClientMessage {
@@ -102,6 +118,16 @@ impl ClientMessage {
                     return Err("Path length must be between 1 and 4096 characters".to_string());
                 }
             }
+            ClientMessage::NegotiateCompression { algorithms } => {
+                if algorithms.len() > 8 {
+                    return Err("At most 8 compression algorithms may be offered".to_string());
+                }
+            }
+            ClientMessage::AccessDecision { request_id, .. }
+                if request_id.is_empty() || request_id.len() > 64 =>
+            {
+                return Err("Request ID length must be between 1 and 64 characters".to_string());
+            }
             _ => {}
         }
         Ok(())
@@ -202,6 +228,8 @@ pub enum ServerMessage {
     FlowControl { action: FlowControlAction },
 
     /// Pong response to ping
+    /// `timestamp` echoes the client's ping timestamp; `latency_ms` is the
+    /// server-measured heartbeat round-trip time for this connection
     /// Per spec-kit/007-websocket-spec.md: Heartbeat
     Pong {
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -213,6 +241,60 @@ pub enum ServerMessage {
     /// Echo response
     /// Per spec-kit/007-websocket-spec.md: Testing protocol
     Echo { data: String },
+
+    /// The connection's token expires soon; send `authenticate` with a fresh
+    /// token for the same user to keep the connection
+    /// Per spec-kit/011-authentication-spec.md: Token expiry
+    TokenExpiring {
+        /// Expiry time (Unix seconds)
+        expires_at: i64,
+        /// Seconds remaining
+        expires_in: u64,
+    },
+
+    /// Result of compression negotiation
+    /// When `algorithm` is set, output is sent as binary frames from now on
+    /// Per spec-kit/007-websocket-spec.md: Binary output framing
+    CompressionNegotiated {
+        algorithm: Option<CompressionAlgorithm>,
+        min_size: usize,
+    },
+
+    /// Another user asks for access to this session; answer with
+    /// `access_decision` before `expires_at` (Unix seconds)
+    /// Per spec-kit/011-authentication-spec.md: Just-in-Time Access
+    AccessRequested {
+        request_id: String,
+        requester: String,
+        /// "view" or "control"
+        level: String,
+        duration_secs: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+        expires_at: i64,
+    },
+
+    /// An access request this user made or decides on changed status
+    /// (`expires_at`, Unix seconds, is set once approved)
+    /// Per spec-kit/011-authentication-spec.md: Just-in-Time Access
+    AccessUpdated {
+        request_id: String,
+        session_id: String,
+        status: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        expires_at: Option<i64>,
+    },
+
+    /// The server is shutting down; the terminal is hung up at `deadline`
+    /// (Unix seconds) unless the client detaches first. Repeated as the
+    /// deadline approaches, and with `seconds_remaining` 0 just before the
+    /// connection closes
+    /// Per spec-kit/007-websocket-spec.md: Server shutdown
+    ServerShutdown {
+        reason: String,
+        deadline: i64,
+        seconds_remaining: u64,
+    },
 }
 
 /// Signal types for process control
@@ -267,6 +349,11 @@ pub mod error_codes {
     pub const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
     pub const AUTHENTICATION_REQUIRED: &str = "AUTHENTICATION_REQUIRED";
     pub const AUTHENTICATION_FAILED: &str = "AUTHENTICATION_FAILED";
+    pub const TOKEN_EXPIRED: &str = "TOKEN_EXPIRED";
+    pub const TOKEN_REVOKED: &str = "TOKEN_REVOKED";
+    pub const AUTHENTICATION_LOCKED: &str = "AUTHENTICATION_LOCKED";
+    pub const ACCESS_ENDED: &str = "ACCESS_ENDED";
+    pub const SERVER_SHUTTING_DOWN: &str = "SERVER_SHUTTING_DOWN";
 }
 
 #[cfg(test)]
@@ -308,4 +395,35 @@ mod tests {
             _ => panic!("Wrong message type"),
         }
     }
+
+    #[test]
+    fn test_negotiate_compression_message() {
+        let json = r#"{"type":"negotiate_compression","algorithms":["zstd","deflate"]}"#;
+        let parsed: ClientMessage = serde_json::from_str(json).unwrap();
+
+        match parsed {
+            ClientMessage::NegotiateCompression { algorithms } => {
+                assert_eq!(
+                    algorithms,
+                    vec![CompressionAlgorithm::Zstd, CompressionAlgorithm::Deflate]
+                );
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_ping_timestamp_optional() {
+        let parsed: ClientMessage = serde_json::from_str(r#"{"type":"ping"}"#).unwrap();
+        assert!(matches!(parsed, ClientMessage::Ping { timestamp: None }));
+
+        let parsed: ClientMessage =
+            serde_json::from_str(r#"{"type":"ping","timestamp":1633024800000}"#).unwrap();
+        assert!(matches!(
+            parsed,
+            ClientMessage::Ping {
+                timestamp: Some(1633024800000)
+            }
+        ));
+    }
 }
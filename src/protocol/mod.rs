@@ -3,9 +3,14 @@
 //! Implements message types and protocol handling
 //! Per spec-kit/007-websocket-spec.md
 
+pub mod compression;
 pub mod messages;
+pub mod ttyd;
 
+pub use crate::config::CompressionAlgorithm;
+pub use compression::{decode_frame, CompressionError, OutputEncoder};
 pub use messages::{
     error_codes, ClientMessage, ConnectionStatus, FlowControlAction, ServerMessage, Signal,
     MAX_MESSAGE_SIZE,
 };
+pub use ttyd::{TtydClientMessage, TtydHandshake, TtydProtocolError, TtydServerMessage};
@@ -0,0 +1,211 @@
+// ttyd-compatible wire protocol
+// Per spec-kit/007-websocket-spec.md: Alternative client protocols
+//
+// ttyd frames every WebSocket message with a single ASCII command byte
+// followed by the payload. This lets off-the-shelf ttyd clients (and
+// xterm.js frontends built for ttyd) attach to a web-terminal session.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// WebSocket subprotocol negotiated by ttyd clients
+pub const SUBPROTOCOL: &str = "tty";
+
+/// Client -> server command bytes
+pub mod client_commands {
+    pub const INPUT: u8 = b'0';
+    pub const RESIZE_TERMINAL: u8 = b'1';
+    pub const PAUSE: u8 = b'2';
+    pub const RESUME: u8 = b'3';
+    pub const JSON_DATA: u8 = b'{';
+}
+
+/// Server -> client command bytes
+pub mod server_commands {
+    pub const OUTPUT: u8 = b'0';
+    pub const SET_WINDOW_TITLE: u8 = b'1';
+    pub const SET_PREFERENCES: u8 = b'2';
+}
+
+/// ttyd protocol errors
+#[derive(Debug, Error)]
+pub enum TtydProtocolError {
+    #[error("Empty ttyd frame")]
+    EmptyFrame,
+
+    #[error("Unknown ttyd command: {0:#04x}")]
+    UnknownCommand(u8),
+
+    #[error("Invalid ttyd JSON payload: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+}
+
+/// Initial handshake sent by ttyd clients as a bare JSON object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TtydHandshake {
+    /// Credential (a JWT for web-terminal)
+    #[serde(rename = "AuthToken", default)]
+    pub auth_token: String,
+
+    /// Initial terminal width
+    #[serde(default = "default_columns")]
+    pub columns: u16,
+
+    /// Initial terminal height
+    #[serde(default = "default_rows")]
+    pub rows: u16,
+}
+
+fn default_columns() -> u16 {
+    80
+}
+
+fn default_rows() -> u16 {
+    24
+}
+
+/// Resize payload for `RESIZE_TERMINAL`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TtydResize {
+    pub columns: u16,
+    pub rows: u16,
+}
+
+/// Messages sent from a ttyd client to the server
+#[derive(Debug, Clone)]
+pub enum TtydClientMessage {
+    /// Raw keyboard input for the PTY
+    Input(Vec<u8>),
+    /// Terminal resize
+    Resize { cols: u16, rows: u16 },
+    /// Stop sending output (client-side backpressure)
+    Pause,
+    /// Resume sending output
+    Resume,
+    /// Initial handshake carrying the auth token and terminal size
+    Handshake(TtydHandshake),
+}
+
+impl TtydClientMessage {
+    /// Decode a ttyd frame (text and binary frames share the same layout)
+    pub fn decode(frame: &[u8]) -> Result<Self, TtydProtocolError> {
+        let (&command, payload) = frame.split_first().ok_or(TtydProtocolError::EmptyFrame)?;
+
+        match command {
+            client_commands::INPUT => Ok(Self::Input(payload.to_vec())),
+            client_commands::RESIZE_TERMINAL => {
+                let resize: TtydResize = serde_json::from_slice(payload)?;
+                Ok(Self::Resize {
+                    cols: resize.columns,
+                    rows: resize.rows,
+                })
+            }
+            client_commands::PAUSE => Ok(Self::Pause),
+            client_commands::RESUME => Ok(Self::Resume),
+            // The handshake is a whole JSON object, so the command byte is part of the payload
+            client_commands::JSON_DATA => Ok(Self::Handshake(serde_json::from_slice(frame)?)),
+            other => Err(TtydProtocolError::UnknownCommand(other)),
+        }
+    }
+}
+
+/// Messages sent from the server to a ttyd client
+#[derive(Debug, Clone)]
+pub enum TtydServerMessage {
+    /// Raw PTY output
+    Output(Vec<u8>),
+    /// Window title shown by the client
+    SetWindowTitle(String),
+    /// Client preferences (xterm.js options)
+    SetPreferences(serde_json::Value),
+}
+
+impl TtydServerMessage {
+    /// Encode as a ttyd frame (sent as a binary WebSocket message)
+    pub fn encode(&self) -> Vec<u8> {
+        let (command, payload) = match self {
+            Self::Output(data) => (server_commands::OUTPUT, data.clone()),
+            Self::SetWindowTitle(title) => {
+                (server_commands::SET_WINDOW_TITLE, title.as_bytes().to_vec())
+            }
+            Self::SetPreferences(prefs) => (
+                server_commands::SET_PREFERENCES,
+                serde_json::to_vec(prefs).unwrap_or_else(|_| b"{}".to_vec()),
+            ),
+        };
+
+        let mut frame = Vec::with_capacity(payload.len() + 1);
+        frame.push(command);
+        frame.extend_from_slice(&payload);
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_input() {
+        match TtydClientMessage::decode(b"0ls -la\r").unwrap() {
+            TtydClientMessage::Input(data) => assert_eq!(data, b"ls -la\r"),
+            other => panic!("Wrong message type: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_resize() {
+        match TtydClientMessage::decode(br#"1{"columns":120,"rows":40}"#).unwrap() {
+            TtydClientMessage::Resize { cols, rows } => {
+                assert_eq!(cols, 120);
+                assert_eq!(rows, 40);
+            }
+            other => panic!("Wrong message type: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_handshake() {
+        let frame = br#"{"AuthToken":"abc.def.ghi","columns":100,"rows":30}"#;
+        match TtydClientMessage::decode(frame).unwrap() {
+            TtydClientMessage::Handshake(handshake) => {
+                assert_eq!(handshake.auth_token, "abc.def.ghi");
+                assert_eq!(handshake.columns, 100);
+                assert_eq!(handshake.rows, 30);
+            }
+            other => panic!("Wrong message type: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(matches!(
+            TtydClientMessage::decode(b""),
+            Err(TtydProtocolError::EmptyFrame)
+        ));
+        assert!(matches!(
+            TtydClientMessage::decode(b"9"),
+            Err(TtydProtocolError::UnknownCommand(b'9'))
+        ));
+        assert!(matches!(
+            TtydClientMessage::decode(b"1not-json"),
+            Err(TtydProtocolError::InvalidJson(_))
+        ));
+    }
+
+    #[test]
+    fn test_encode_server_messages() {
+        assert_eq!(
+            TtydServerMessage::Output(b"hello".to_vec()).encode(),
+            b"0hello"
+        );
+        assert_eq!(
+            TtydServerMessage::SetWindowTitle("web-terminal".to_string()).encode(),
+            b"1web-terminal"
+        );
+        assert_eq!(
+            TtydServerMessage::SetPreferences(serde_json::json!({})).encode(),
+            b"2{}"
+        );
+    }
+}
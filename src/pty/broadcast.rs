@@ -0,0 +1,99 @@
+// PTY output fan-out
+// Per spec-kit/003-backend-spec.md section 3
+//
+// A PTY has exactly one reader, but several WebSocket connections may be
+// attached to the same session (a browser tab plus `web-terminal attach`, or
+// a client reconnecting). The reader publishes into a broadcast channel and
+// keeps a bounded scrollback that is replayed to clients as they attach.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Recent output replayed to newly attached clients: 64 KB
+pub const SCROLLBACK_BYTES: usize = 64 * 1024;
+
+/// Output chunks buffered per subscriber before it starts lagging
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// A client's view of a PTY's output
+pub struct PtySubscription {
+    /// Output produced before the client attached (most recent bytes)
+    pub scrollback: Vec<u8>,
+    /// Output produced from now on; closed when the PTY exits
+    pub receiver: broadcast::Receiver<Vec<u8>>,
+}
+
+/// Broadcast hub for one PTY's output
+pub(crate) struct OutputHub {
+    tx: broadcast::Sender<Vec<u8>>,
+    scrollback: Mutex<VecDeque<u8>>,
+}
+
+impl OutputHub {
+    pub(crate) fn new() -> Self {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            tx,
+            scrollback: Mutex::new(VecDeque::with_capacity(SCROLLBACK_BYTES)),
+        }
+    }
+
+    /// Record a chunk in the scrollback and deliver it to all subscribers
+    pub(crate) fn publish(&self, data: Vec<u8>) {
+        // Hold the scrollback lock while sending so a concurrent subscribe()
+        // sees each chunk exactly once: either in its scrollback or its receiver
+        let mut scrollback = self.scrollback.lock().unwrap_or_else(|e| e.into_inner());
+        scrollback.extend(data.iter().copied());
+        let excess = scrollback.len().saturating_sub(SCROLLBACK_BYTES);
+        scrollback.drain(..excess);
+
+        let _ = self.tx.send(data);
+    }
+
+    /// Attach a new subscriber
+    pub(crate) fn subscribe(&self) -> PtySubscription {
+        let scrollback = self.scrollback.lock().unwrap_or_else(|e| e.into_inner());
+        PtySubscription {
+            scrollback: scrollback.iter().copied().collect(),
+            receiver: self.tx.subscribe(),
+        }
+    }
+
+    /// Number of attached subscribers
+    pub(crate) fn subscriber_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_late_subscriber_gets_scrollback() {
+        let hub = OutputHub::new();
+        let mut early = hub.subscribe();
+        assert!(early.scrollback.is_empty());
+
+        hub.publish(b"$ ".to_vec());
+        let late = hub.subscribe();
+        hub.publish(b"ls\r\n".to_vec());
+
+        assert_eq!(early.receiver.recv().await.unwrap(), b"$ ");
+        assert_eq!(early.receiver.recv().await.unwrap(), b"ls\r\n");
+        assert_eq!(late.scrollback, b"$ ");
+        assert_eq!(hub.subscriber_count(), 2);
+    }
+
+    #[test]
+    fn test_scrollback_is_bounded() {
+        let hub = OutputHub::new();
+        hub.publish(vec![b'a'; SCROLLBACK_BYTES]);
+        hub.publish(b"tail".to_vec());
+
+        let sub = hub.subscribe();
+        assert_eq!(sub.scrollback.len(), SCROLLBACK_BYTES);
+        assert!(sub.scrollback.ends_with(b"tail"));
+    }
+}
@@ -34,7 +34,7 @@ impl PtyReader {
         // Get reader outside of async context
         let reader = {
             let inner = self.handle.get_master().await;
-            let mut inner = inner.write().await;
+            let mut inner = inner.lock().await;
             inner.get_reader()?
         };
 
@@ -89,7 +89,7 @@ impl PtyReader {
         // Get reader outside of async context
         let reader = {
             let inner = self.handle.get_master().await;
-            let mut inner = inner.write().await;
+            let mut inner = inner.lock().await;
             inner.get_reader()?
         };
 
@@ -143,7 +143,7 @@ impl PtyReader {
     /// Read output synchronously (blocking)
     pub fn read_blocking(&mut self, buf: &mut [u8]) -> PtyResult<usize> {
         let inner = self.handle.get_master_blocking();
-        let mut inner = inner.blocking_write();
+        let mut inner = inner.blocking_lock();
 
         if inner.is_closed() {
             return Err(PtyError::AlreadyClosed);
@@ -173,9 +173,9 @@ impl PtyWriter {
     /// Per FR-2.2.1: Capture keyboard input in real-time
     pub async fn write(&self, data: &[u8]) -> PtyResult<usize> {
         // Get writer outside of async context
-        let mut writer = {
+        let writer = {
             let inner = self.handle.get_master().await;
-            let mut inner = inner.write().await;
+            let mut inner = inner.lock().await;
 
             if inner.is_closed() {
                 return Err(PtyError::AlreadyClosed);
@@ -187,6 +187,9 @@ impl PtyWriter {
         // Spawn blocking task for writing
         let data = data.to_vec();
         let result = tokio::task::spawn_blocking(move || -> PtyResult<usize> {
+            let mut writer = writer
+                .lock()
+                .map_err(|_| PtyError::IoError(std::io::Error::other("PTY writer poisoned")))?;
             let n = writer.write(&data).map_err(|e| PtyError::IoError(e))?;
 
             writer.flush().map_err(|e| PtyError::IoError(e))?;
@@ -212,13 +215,16 @@ impl PtyWriter {
     /// Write data synchronously (blocking)
     pub fn write_blocking(&mut self, data: &[u8]) -> PtyResult<usize> {
         let inner = self.handle.get_master_blocking();
-        let mut inner = inner.blocking_write();
+        let mut inner = inner.blocking_lock();
 
         if inner.is_closed() {
             return Err(PtyError::AlreadyClosed);
         }
 
-        let mut writer = inner.get_writer().map_err(|e| PtyError::IoError(e))?;
+        let writer = inner.get_writer().map_err(|e| PtyError::IoError(e))?;
+        let mut writer = writer
+            .lock()
+            .map_err(|_| PtyError::IoError(std::io::Error::other("PTY writer poisoned")))?;
 
         let n = writer.write(data).map_err(|e| PtyError::IoError(e))?;
 
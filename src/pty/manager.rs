@@ -1,7 +1,12 @@
 // PTY manager for lifecycle management
 // Per spec-kit/003-backend-spec.md
 
-use super::{PtyConfig, PtyError, PtyProcess, PtyProcessHandle, PtyReader, PtyResult, PtyWriter};
+use super::broadcast::OutputHub;
+use super::{
+    PtyConfig, PtyError, PtyProcess, PtyProcessHandle, PtyReader, PtyResult, PtySubscription,
+    PtyWriter,
+};
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -14,6 +19,9 @@ pub struct PtyManager {
     /// Active PTY processes by ID
     processes: Arc<DashMap<String, PtyProcessHandle>>,
 
+    /// Output fan-out for PTYs that have attached clients
+    outputs: Arc<DashMap<String, Arc<OutputHub>>>,
+
     /// Default configuration for new PTY processes
     default_config: PtyConfig,
 }
@@ -23,6 +31,7 @@ impl PtyManager {
     pub fn new(default_config: PtyConfig) -> Self {
         Self {
             processes: Arc::new(DashMap::new()),
+            outputs: Arc::new(DashMap::new()),
             default_config,
         }
     }
@@ -32,6 +41,11 @@ impl PtyManager {
         Self::new(PtyConfig::default())
     }
 
+    /// Configuration used for processes spawned without one
+    pub fn default_config(&self) -> &PtyConfig {
+        &self.default_config
+    }
+
     /// Spawn a new PTY process
     ///
     /// Per FR-1.2.1: Start processes for executed commands
@@ -147,6 +161,51 @@ impl PtyManager {
         reader.stream_output_bounded(tx).await
     }
 
+    /// Attach to a PTY's output
+    ///
+    /// The first subscriber starts the PTY's reader; later subscribers share
+    /// it and receive the recent scrollback first. The subscription closes when
+    /// the PTY exits.
+    /// Per FR-3.3: Real-time streaming
+    pub fn subscribe(&self, id: &str) -> PtyResult<PtySubscription> {
+        let handle = self.get(id)?;
+
+        let hub = match self.outputs.entry(id.to_string()) {
+            Entry::Occupied(entry) => return Ok(entry.get().subscribe()),
+            Entry::Vacant(entry) => entry.insert(Arc::new(OutputHub::new())).clone(),
+        };
+        let subscription = hub.subscribe();
+
+        let reader = PtyReader::new(handle, 4096);
+        let outputs = self.outputs.clone();
+        let id = id.to_string();
+        tokio::spawn(async move {
+            let (tx, mut rx) = mpsc::unbounded_channel();
+            match reader.stream_output(tx).await {
+                Ok(()) => {
+                    while let Some(data) = rx.recv().await {
+                        hub.publish(data);
+                    }
+                }
+                Err(e) => tracing::error!("Failed to start PTY {} output stream: {}", id, e),
+            }
+
+            // Dropping the last hub reference closes every subscription
+            outputs.remove(&id);
+            tracing::debug!("PTY {} output fan-out finished", id);
+        });
+
+        Ok(subscription)
+    }
+
+    /// Number of clients attached to a PTY's output
+    pub fn subscriber_count(&self, id: &str) -> usize {
+        self.outputs
+            .get(id)
+            .map(|hub| hub.subscriber_count())
+            .unwrap_or(0)
+    }
+
     /// Send signal to PTY process
     ///
     /// Per FR-1.2.4: Support process termination (Ctrl+C / SIGINT)
@@ -220,6 +279,33 @@ impl PtyManager {
         Ok(count)
     }
 
+    /// Hang up one PTY process (SIGHUP), letting its shell exit cleanly
+    /// Per spec-kit/005-cli-spec.md: Session Management
+    pub async fn hangup(&self, id: &str) -> PtyResult<()> {
+        self.get(id)?.hangup().await
+    }
+
+    /// Hang up every running PTY process, returning the IDs signalled
+    /// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+    pub async fn hangup_all(&self) -> Vec<String> {
+        let mut hung_up = Vec::new();
+
+        for id in self.list() {
+            let Ok(handle) = self.get(&id) else {
+                continue;
+            };
+            if !handle.is_alive().await {
+                continue;
+            }
+            match handle.hangup().await {
+                Ok(()) => hung_up.push(id),
+                Err(e) => tracing::error!("Failed to hang up PTY process {}: {}", id, e),
+            }
+        }
+
+        hung_up
+    }
+
     /// Wait for a PTY process to exit
     pub async fn wait(&self, id: &str) -> PtyResult<Option<i32>> {
         let handle = self.get(id)?;
@@ -275,6 +361,23 @@ mod tests {
         manager.kill(handle.id()).await.expect("Failed to kill PTY");
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_pty_manager_hangup_all() {
+        let manager = PtyManager::with_defaults();
+        let handle = manager.spawn(None).expect("Failed to spawn PTY");
+
+        assert_eq!(manager.hangup_all().await, vec![handle.id().to_string()]);
+
+        for _ in 0..50 {
+            if !handle.is_alive().await {
+                break;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+        assert!(!handle.is_alive().await);
+    }
+
     #[tokio::test]
     async fn test_pty_manager_cleanup() {
         let manager = PtyManager::with_defaults();
@@ -295,4 +398,31 @@ mod tests {
         assert_eq!(count, 1);
         assert_eq!(manager.count(), 0);
     }
+
+    #[tokio::test]
+    async fn test_pty_manager_subscribers_share_output() {
+        let manager = PtyManager::with_defaults();
+        let handle = manager.spawn(None).expect("Failed to spawn PTY");
+
+        let mut first = manager.subscribe(handle.id()).expect("Failed to subscribe");
+        let mut second = manager.subscribe(handle.id()).expect("Failed to subscribe");
+        assert_eq!(manager.subscriber_count(handle.id()), 2);
+
+        let writer = manager.create_writer(handle.id()).unwrap();
+        writer.write(b"echo fanout-marker\n").await.unwrap();
+
+        for sub in [&mut first, &mut second] {
+            let mut seen = String::from_utf8_lossy(&sub.scrollback).into_owned();
+            let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(5);
+            while !seen.contains("fanout-marker") {
+                let chunk = tokio::time::timeout_at(deadline, sub.receiver.recv())
+                    .await
+                    .expect("Timed out waiting for PTY output")
+                    .expect("PTY output closed");
+                seen.push_str(&String::from_utf8_lossy(&chunk));
+            }
+        }
+
+        manager.kill(handle.id()).await.expect("Failed to kill PTY");
+    }
 }
@@ -6,11 +6,13 @@
 // - FR-3.3: Real-time streaming (<20ms latency)
 // - NFR-1.1: Command execution latency < 100ms (p95)
 
+mod broadcast;
 mod config;
 mod io_handler;
 mod manager;
 mod process;
 
+pub use broadcast::{PtySubscription, SCROLLBACK_BYTES};
 pub use config::{PtyConfig, ShellConfig};
 pub use io_handler::{PtyReader, PtyWriter};
 pub use manager::PtyManager;
@@ -3,19 +3,26 @@
 
 use super::{PtyConfig, PtyError, PtyResult};
 use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Mutex as AsyncMutex;
 
 /// Handle to a running PTY process
+///
+/// The master PTY is `Send` but not `Sync`, so it sits behind a mutex rather
+/// than a read/write lock; this keeps handles shareable across worker threads.
 #[derive(Clone)]
 pub struct PtyProcessHandle {
     id: String,
-    inner: Arc<RwLock<PtyProcessInner>>,
+    inner: Arc<AsyncMutex<PtyProcessInner>>,
 }
 
+/// Shared PTY input handle (portable-pty only hands out one writer per PTY)
+pub(crate) type SharedPtyWriter = Arc<Mutex<Box<dyn std::io::Write + Send>>>;
+
 pub(crate) struct PtyProcessInner {
     master: Box<dyn MasterPty + Send>,
     child: Box<dyn Child + Send + Sync>,
+    writer: Option<SharedPtyWriter>,
     config: PtyConfig,
     closed: bool,
 }
@@ -30,7 +37,7 @@ impl PtyProcessHandle {
     ///
     /// Per FR-2.1.5: Support terminal dimensions (rows, columns)
     pub async fn resize(&self, cols: u16, rows: u16) -> PtyResult<()> {
-        let mut inner = self.inner.write().await;
+        let mut inner = self.inner.lock().await;
 
         if inner.closed {
             return Err(PtyError::AlreadyClosed);
@@ -59,7 +66,7 @@ impl PtyProcessHandle {
 
     /// Check if the PTY process is still running
     pub async fn is_alive(&self) -> bool {
-        let mut inner = self.inner.write().await;
+        let mut inner = self.inner.lock().await;
         if inner.closed {
             return false;
         }
@@ -70,7 +77,7 @@ impl PtyProcessHandle {
 
     /// Wait for the process to exit and get exit status
     pub async fn wait(&self) -> PtyResult<Option<i32>> {
-        let mut inner = self.inner.write().await;
+        let mut inner = self.inner.lock().await;
 
         if inner.closed {
             return Err(PtyError::AlreadyClosed);
@@ -89,7 +96,7 @@ impl PtyProcessHandle {
     ///
     /// Per FR-1.2.4: Support process termination (Ctrl+C / SIGINT)
     pub async fn kill(&self) -> PtyResult<()> {
-        let mut inner = self.inner.write().await;
+        let mut inner = self.inner.lock().await;
 
         if inner.closed {
             return Err(PtyError::AlreadyClosed);
@@ -107,19 +114,49 @@ impl PtyProcessHandle {
         Ok(())
     }
 
+    /// Hang up the terminal: send SIGHUP to the shell, which passes it on to
+    /// its jobs and exits. Elsewhere than Unix the process is killed
+    /// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+    pub async fn hangup(&self) -> PtyResult<()> {
+        #[cfg(unix)]
+        {
+            let inner = self.inner.lock().await;
+            if inner.closed {
+                return Err(PtyError::AlreadyClosed);
+            }
+            let pid = inner
+                .child
+                .process_id()
+                .ok_or_else(|| PtyError::SignalFailed("process has exited".to_string()))?;
+
+            // SAFETY: kill only sends a signal to the given process ID
+            if unsafe { libc::kill(pid as libc::pid_t, libc::SIGHUP) } != 0 {
+                return Err(PtyError::SignalFailed(
+                    std::io::Error::last_os_error().to_string(),
+                ));
+            }
+
+            tracing::info!("Hung up PTY process {}", self.id);
+            Ok(())
+        }
+
+        #[cfg(not(unix))]
+        self.kill().await
+    }
+
     /// Get the master PTY for I/O operations (async)
-    pub(crate) async fn get_master(&self) -> Arc<RwLock<PtyProcessInner>> {
+    pub(crate) async fn get_master(&self) -> Arc<AsyncMutex<PtyProcessInner>> {
         self.inner.clone()
     }
 
     /// Get the master PTY for I/O operations (blocking)
-    pub(crate) fn get_master_blocking(&self) -> Arc<RwLock<PtyProcessInner>> {
+    pub(crate) fn get_master_blocking(&self) -> Arc<AsyncMutex<PtyProcessInner>> {
         self.inner.clone()
     }
 
     /// Get current PTY configuration
     pub async fn config(&self) -> PtyConfig {
-        let inner = self.inner.read().await;
+        let inner = self.inner.lock().await;
         inner.config.clone()
     }
 }
@@ -169,6 +206,7 @@ impl PtyProcess {
         let inner = PtyProcessInner {
             master: pair.master,
             child,
+            writer: None,
             config: config.clone(),
             closed: false,
         };
@@ -182,7 +220,7 @@ impl PtyProcess {
 
         Ok(PtyProcessHandle {
             id,
-            inner: Arc::new(RwLock::new(inner)),
+            inner: Arc::new(AsyncMutex::new(inner)),
         })
     }
 }
@@ -195,10 +233,18 @@ impl PtyProcessInner {
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
     }
 
-    pub(crate) fn get_writer(&mut self) -> std::io::Result<Box<dyn std::io::Write + Send>> {
-        self.master
+    pub(crate) fn get_writer(&mut self) -> std::io::Result<SharedPtyWriter> {
+        if let Some(writer) = &self.writer {
+            return Ok(writer.clone());
+        }
+
+        let writer = self
+            .master
             .take_writer()
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let writer = Arc::new(Mutex::new(writer));
+        self.writer = Some(writer.clone());
+        Ok(writer)
     }
 
     pub(crate) fn is_closed(&self) -> bool {
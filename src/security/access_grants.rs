@@ -0,0 +1,555 @@
+// Just-in-time session access
+// Per 011-authentication-spec.md: Just-in-Time Access
+// Responsibilities:
+// - Let a user ask for view or control access to someone else's session
+// - Let the session owner approve or deny the request
+// - Grant approved access for a limited time, revocable by either side
+// - Publish request changes so the owner's terminal can prompt for a decision
+// - Audit every request, decision and revocation
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::config::auth::AccessRequestConfig;
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::security::authorization::Permission;
+use crate::session::state::{Session, SessionId, UserId};
+
+/// How long finished requests stay listed
+const RETENTION: Duration = Duration::from_secs(3600);
+
+/// Request changes buffered for slow subscribers
+const EVENT_CAPACITY: usize = 64;
+
+/// Access request error types
+#[derive(Error, Debug)]
+pub enum AccessGrantError {
+    #[error("Access requests are disabled")]
+    Disabled,
+
+    #[error("Access request not found: {0}")]
+    NotFound(String),
+
+    #[error("Session not found: {0}")]
+    SessionNotFound(String),
+
+    #[error("Access request {0} is already {1}")]
+    NotPending(String, GrantStatus),
+
+    #[error("Invalid access request: {0}")]
+    InvalidRequest(String),
+}
+
+/// What an approved request lets the requester do on the session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessLevel {
+    /// Watch the terminal
+    View,
+    /// Watch and type into the terminal
+    Control,
+}
+
+impl AccessLevel {
+    /// Whether this level covers `permission` on the session
+    pub fn permits(&self, permission: Permission) -> bool {
+        match self {
+            Self::View => permission == Permission::ViewSession,
+            Self::Control => matches!(permission, Permission::ViewSession | Permission::SendInput),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::View => "view",
+            Self::Control => "control",
+        }
+    }
+}
+
+/// Where a request stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GrantStatus {
+    /// Waiting for the owner
+    Pending,
+    /// Approved; access lasts until `expires_at`
+    Approved,
+    /// Refused by the owner
+    Denied,
+    /// Unanswered in time, or the granted access ran out
+    Expired,
+    /// Withdrawn by the requester or ended by the owner
+    Revoked,
+}
+
+impl GrantStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Denied => "denied",
+            Self::Expired => "expired",
+            Self::Revoked => "revoked",
+        }
+    }
+}
+
+impl std::fmt::Display for GrantStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A request for access to another user's session, and the access it grants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessGrant {
+    pub id: String,
+    pub session_id: SessionId,
+    /// Owner of the session, who decides
+    pub owner: UserId,
+    pub requester: UserId,
+    pub level: AccessLevel,
+    /// How long access lasts once approved
+    pub duration_secs: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub status: GrantStatus,
+    pub requested_at: DateTime<Utc>,
+    /// When an unanswered request lapses
+    pub pending_until: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_at: Option<DateTime<Utc>>,
+    /// When approved access ends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl AccessGrant {
+    /// Whether the request gives access right now
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.status == GrantStatus::Approved && self.expires_at.is_some_and(|at| at > now)
+    }
+
+    /// Whether `user` asked for or decides on this request
+    pub fn involves(&self, user: &UserId) -> bool {
+        self.requester == *user || self.owner == *user
+    }
+
+    /// Mark a request whose time ran out as expired
+    fn refresh(&mut self, now: DateTime<Utc>) {
+        let lapsed = match self.status {
+            GrantStatus::Pending => self.pending_until <= now,
+            GrantStatus::Approved => !self.is_active(now),
+            _ => false,
+        };
+        if lapsed {
+            self.status = GrantStatus::Expired;
+        }
+    }
+
+    /// When the request stopped mattering (`None` while pending or active)
+    fn ended_at(&self) -> Option<DateTime<Utc>> {
+        match self.status {
+            GrantStatus::Pending | GrantStatus::Approved => None,
+            GrantStatus::Expired => Some(self.expires_at.unwrap_or(self.pending_until)),
+            _ => self.decided_at,
+        }
+    }
+
+    /// What was requested, for the audit log
+    pub fn describe(&self) -> String {
+        format!(
+            "{} access to session {} for {}s, owned by {} ({})",
+            self.level.as_str(),
+            self.session_id,
+            self.duration_secs,
+            self.owner,
+            self.id
+        )
+    }
+}
+
+/// Parameters for a new access request
+#[derive(Debug, Clone)]
+pub struct NewAccessRequest {
+    pub level: AccessLevel,
+    /// Access duration (the configured default when unset)
+    pub duration: Option<Duration>,
+    pub reason: Option<String>,
+}
+
+/// Access requests and the grants they turn into
+/// Per 011-authentication-spec.md: Just-in-Time Access
+pub struct AccessGrantStore {
+    config: AccessRequestConfig,
+    grants: DashMap<String, AccessGrant>,
+    events: broadcast::Sender<AccessGrant>,
+    audit: Arc<AuditLogger>,
+}
+
+impl AccessGrantStore {
+    /// Create an empty store
+    pub fn new(config: AccessRequestConfig) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        Self {
+            config,
+            grants: DashMap::new(),
+            events,
+            audit: Arc::new(AuditLogger::default()),
+        }
+    }
+
+    /// Record requests, decisions and revocations in `audit`
+    pub fn with_audit(mut self, audit: Arc<AuditLogger>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Whether users may request access
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Changes to every request, as they happen
+    pub fn subscribe(&self) -> broadcast::Receiver<AccessGrant> {
+        self.events.subscribe()
+    }
+
+    /// Ask the owner of `session` for access on behalf of `requester`, a
+    /// user of `tenant`
+    ///
+    /// Sessions of other tenants are reported as not found.
+    pub fn request(
+        &self,
+        requester: &UserId,
+        tenant: Option<&str>,
+        session: &Session,
+        new: NewAccessRequest,
+    ) -> Result<AccessGrant, AccessGrantError> {
+        if !self.is_enabled() {
+            return Err(AccessGrantError::Disabled);
+        }
+        if session.tenant.as_deref() != tenant {
+            return Err(AccessGrantError::SessionNotFound(session.id.to_string()));
+        }
+        if session.user_id == *requester {
+            return Err(AccessGrantError::InvalidRequest(format!(
+                "session {} is already yours",
+                session.id
+            )));
+        }
+        let duration = new.duration.unwrap_or(self.config.default_duration);
+        if duration.is_zero() || duration > self.config.max_duration {
+            return Err(AccessGrantError::InvalidRequest(format!(
+                "duration must be between 1s and {}s",
+                self.config.max_duration.as_secs()
+            )));
+        }
+
+        let now = Utc::now();
+        self.prune(now);
+        if let Some(pending) = self.grants.iter_mut().find_map(|mut grant| {
+            grant.refresh(now);
+            (grant.status == GrantStatus::Pending
+                && grant.requester == *requester
+                && grant.session_id == session.id)
+                .then(|| grant.id.clone())
+        }) {
+            return Err(AccessGrantError::InvalidRequest(format!(
+                "request {} for this session is still pending",
+                pending
+            )));
+        }
+
+        let grant = AccessGrant {
+            id: Uuid::new_v4().to_string(),
+            session_id: session.id.clone(),
+            owner: session.user_id.clone(),
+            requester: requester.clone(),
+            level: new.level,
+            duration_secs: duration.as_secs(),
+            reason: new.reason,
+            status: GrantStatus::Pending,
+            requested_at: now,
+            pending_until: now + self.config.pending_timeout,
+            decided_at: None,
+            expires_at: None,
+        };
+        self.grants.insert(grant.id.clone(), grant.clone());
+
+        let mut detail = grant.describe();
+        if let Some(reason) = &grant.reason {
+            detail.push_str(&format!(": {}", reason));
+        }
+        self.audit.record(
+            AuditEvent::new(AuditAction::AccessRequested)
+                .user(requester.as_str())
+                .session(&grant.session_id)
+                .detail(detail),
+        );
+        self.publish(&grant);
+        Ok(grant)
+    }
+
+    /// Approve or deny a pending request as `owner` of its session
+    pub fn decide(
+        &self,
+        id: &str,
+        owner: &UserId,
+        approve: bool,
+    ) -> Result<AccessGrant, AccessGrantError> {
+        let now = Utc::now();
+        let grant = {
+            let mut grant = self
+                .grants
+                .get_mut(id)
+                .filter(|grant| grant.owner == *owner)
+                .ok_or_else(|| AccessGrantError::NotFound(id.to_string()))?;
+            grant.refresh(now);
+            if grant.status != GrantStatus::Pending {
+                return Err(AccessGrantError::NotPending(id.to_string(), grant.status));
+            }
+            grant.decided_at = Some(now);
+            if approve {
+                grant.status = GrantStatus::Approved;
+                grant.expires_at = Some(now + Duration::from_secs(grant.duration_secs));
+            } else {
+                grant.status = GrantStatus::Denied;
+            }
+            grant.clone()
+        };
+
+        let (action, detail) = match grant.expires_at {
+            Some(until) => (
+                AuditAction::AccessApproved,
+                format!(
+                    "{} granted to {} until {}",
+                    grant.describe(),
+                    grant.requester,
+                    until.to_rfc3339()
+                ),
+            ),
+            None => (
+                AuditAction::AccessRequestDenied,
+                format!("{} refused to {}", grant.describe(), grant.requester),
+            ),
+        };
+        self.audit.record(
+            AuditEvent::new(action)
+                .user(owner.as_str())
+                .session(&grant.session_id)
+                .detail(detail),
+        );
+        self.publish(&grant);
+        Ok(grant)
+    }
+
+    /// Withdraw a pending request or end granted access, as its requester
+    /// or the session owner
+    pub fn revoke(&self, id: &str, user: &UserId) -> Result<AccessGrant, AccessGrantError> {
+        let now = Utc::now();
+        let grant = {
+            let mut grant = self
+                .grants
+                .get_mut(id)
+                .filter(|grant| grant.involves(user))
+                .ok_or_else(|| AccessGrantError::NotFound(id.to_string()))?;
+            grant.refresh(now);
+            if !matches!(grant.status, GrantStatus::Pending | GrantStatus::Approved) {
+                return Err(AccessGrantError::NotPending(id.to_string(), grant.status));
+            }
+            grant.status = GrantStatus::Revoked;
+            grant.decided_at = Some(now);
+            grant.clone()
+        };
+
+        self.audit.record(
+            AuditEvent::new(AuditAction::AccessRevoked)
+                .user(user.as_str())
+                .session(&grant.session_id)
+                .detail(format!("{} for {}", grant.describe(), grant.requester)),
+        );
+        self.publish(&grant);
+        Ok(grant)
+    }
+
+    /// Highest level of access `user` currently holds on `session_id`, with
+    /// the request that granted it
+    pub fn active_level(
+        &self,
+        user: &UserId,
+        session_id: &SessionId,
+    ) -> Option<(String, AccessLevel)> {
+        let now = Utc::now();
+        self.grants
+            .iter()
+            .filter(|grant| {
+                grant.requester == *user && grant.session_id == *session_id && grant.is_active(now)
+            })
+            .map(|grant| (grant.id.clone(), grant.level))
+            .max_by_key(|(_, level)| *level)
+    }
+
+    /// The request `id`, if `user` asked for or decides on it
+    pub fn get(&self, id: &str, user: &UserId) -> Result<AccessGrant, AccessGrantError> {
+        let mut grant = self
+            .grants
+            .get_mut(id)
+            .filter(|grant| grant.involves(user))
+            .ok_or_else(|| AccessGrantError::NotFound(id.to_string()))?;
+        grant.refresh(Utc::now());
+        Ok(grant.clone())
+    }
+
+    /// Requests `user` made or decides on, newest first
+    pub fn list_for(&self, user: &UserId) -> Vec<AccessGrant> {
+        let now = Utc::now();
+        self.prune(now);
+        let mut grants: Vec<AccessGrant> = self
+            .grants
+            .iter_mut()
+            .filter(|grant| grant.involves(user))
+            .map(|mut grant| {
+                grant.refresh(now);
+                grant.clone()
+            })
+            .collect();
+        grants.sort_by_key(|grant| std::cmp::Reverse(grant.requested_at));
+        grants
+    }
+
+    /// Drop requests that ended more than [`RETENTION`] ago
+    fn prune(&self, now: DateTime<Utc>) {
+        self.grants.retain(|_, grant| {
+            grant.refresh(now);
+            grant.ended_at().is_none_or(|ended| ended + RETENTION > now)
+        });
+    }
+
+    fn publish(&self, grant: &AccessGrant) {
+        // No subscribers just means no terminal is open to show it
+        let _ = self.events.send(grant.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::testing::configured;
+    use std::path::PathBuf;
+
+    fn store(configure: impl FnOnce(&mut AccessRequestConfig)) -> AccessGrantStore {
+        AccessGrantStore::new(configured(configure))
+    }
+
+    fn session(owner: &str) -> Session {
+        Session::new(UserId::new(owner.to_string()), PathBuf::from("/tmp"))
+    }
+
+    fn view() -> NewAccessRequest {
+        NewAccessRequest {
+            level: AccessLevel::View,
+            duration: None,
+            reason: Some("debugging".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_approve_grants_access_until_revoked() {
+        let store = store(|_| {});
+        let alice = UserId::new("alice".to_string());
+        let bob = UserId::new("bob".to_string());
+        let session = session("alice");
+        let mut events = store.subscribe();
+
+        let request = store.request(&bob, None, &session, view()).unwrap();
+        assert_eq!(request.status, GrantStatus::Pending);
+        assert_eq!(events.try_recv().unwrap().id, request.id);
+        assert!(store.active_level(&bob, &session.id).is_none());
+
+        // Only the owner decides
+        assert!(matches!(
+            store.decide(&request.id, &bob, true),
+            Err(AccessGrantError::NotFound(_))
+        ));
+        let approved = store.decide(&request.id, &alice, true).unwrap();
+        assert_eq!(approved.status, GrantStatus::Approved);
+        assert_eq!(events.try_recv().unwrap().status, GrantStatus::Approved);
+        assert!(matches!(
+            store.decide(&request.id, &alice, false),
+            Err(AccessGrantError::NotPending(_, GrantStatus::Approved))
+        ));
+
+        let (id, level) = store.active_level(&bob, &session.id).unwrap();
+        assert_eq!(id, request.id);
+        assert!(level.permits(Permission::ViewSession));
+        assert!(!level.permits(Permission::SendInput));
+        assert!(store
+            .active_level(&bob, &SessionId::new("other".to_string()))
+            .is_none());
+
+        store.revoke(&request.id, &alice).unwrap();
+        assert!(store.active_level(&bob, &session.id).is_none());
+        assert_eq!(store.list_for(&bob)[0].status, GrantStatus::Revoked);
+    }
+
+    #[test]
+    fn test_denied_and_lapsed_requests_grant_nothing() {
+        let store = store(|config| config.pending_timeout = Duration::ZERO);
+        let alice = UserId::new("alice".to_string());
+        let bob = UserId::new("bob".to_string());
+        let session = session("alice");
+
+        let request = store.request(&bob, None, &session, view()).unwrap();
+        assert!(matches!(
+            store.decide(&request.id, &alice, true),
+            Err(AccessGrantError::NotPending(_, GrantStatus::Expired))
+        ));
+
+        let store = self::store(|_| {});
+        let request = store.request(&bob, None, &session, view()).unwrap();
+        assert!(store.request(&bob, None, &session, view()).is_err());
+        let denied = store.decide(&request.id, &alice, false).unwrap();
+        assert_eq!(denied.status, GrantStatus::Denied);
+        assert!(store.active_level(&bob, &session.id).is_none());
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let store = store(|config| config.max_duration = Duration::from_secs(600));
+        let bob = UserId::new("bob".to_string());
+        let session = session("alice");
+
+        assert!(store
+            .request(&UserId::new("alice".to_string()), None, &session, view())
+            .is_err());
+        assert!(store
+            .request(
+                &bob,
+                None,
+                &session,
+                NewAccessRequest {
+                    duration: Some(Duration::from_secs(3600)),
+                    ..view()
+                }
+            )
+            .is_err());
+        // Another tenant's session does not exist for the requester
+        assert!(matches!(
+            store.request(&bob, Some("globex"), &session, view()),
+            Err(AccessGrantError::SessionNotFound(_))
+        ));
+        assert!(matches!(
+            self::store(|config| config.enabled = false).request(&bob, None, &session, view()),
+            Err(AccessGrantError::Disabled)
+        ));
+    }
+}
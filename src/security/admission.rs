@@ -0,0 +1,582 @@
+// Claim-based admission control
+// Per 011-authentication-spec.md: Authorization Model
+// Responsibilities:
+// - Decide, after a credential is validated, whether its holder may use this
+//   server at all (before any per-action permission checks)
+// - Match users and groups from configurable claim paths and Backstage `ent`
+//   entity references against the allow/deny lists
+// - Explain denials so they can be audited
+// - Resolve the roles a token holder acts with
+
+use std::sync::{Arc, RwLock};
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::config::auth::{AuthorizationConfig, ClaimMappings};
+use crate::security::jwt_validator::Claims;
+
+/// Why a validated identity was refused
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AdmissionDenied {
+    #[error("{subject} is denied by deny_users entry '{pattern}'")]
+    DeniedUser { subject: String, pattern: String },
+
+    #[error("{subject} is denied by deny_groups entry '{pattern}' (member of {group})")]
+    DeniedGroup {
+        subject: String,
+        group: String,
+        pattern: String,
+    },
+
+    #[error("{subject} has no group memberships")]
+    NoGroups { subject: String },
+
+    #[error("{subject} matches no allowed user or group")]
+    NotAllowed { subject: String },
+}
+
+impl AdmissionDenied {
+    /// The identity that was refused
+    pub fn subject(&self) -> &str {
+        match self {
+            Self::DeniedUser { subject, .. }
+            | Self::DeniedGroup { subject, .. }
+            | Self::NoGroups { subject }
+            | Self::NotAllowed { subject } => subject,
+        }
+    }
+}
+
+/// Users and groups a token speaks for, as normalized references
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Identity {
+    pub users: Vec<String>,
+    pub groups: Vec<String>,
+}
+
+impl Identity {
+    /// Collect the identity from `claims` using the configured claim paths
+    ///
+    /// Users come from the `user_id` claim and `user:` entity references,
+    /// groups from the `groups` claim and `group:` entity references.
+    pub fn from_claims(claims: &Claims, mappings: &ClaimMappings) -> Self {
+        let document = serde_json::to_value(claims).unwrap_or(Value::Null);
+        let entities = claim_values(&document, &mappings.entity_ref);
+
+        let mut identity = Self::default();
+        for user in claim_values(&document, &mappings.user_id) {
+            push_unique(&mut identity.users, normalize_ref(&user));
+        }
+        for group in claim_values(&document, &mappings.groups) {
+            push_unique(&mut identity.groups, normalize_ref(&group));
+        }
+        for entity in entities.iter().map(|e| normalize_ref(e)) {
+            if entity.starts_with("user:") {
+                push_unique(&mut identity.users, entity);
+            } else if entity.starts_with("group:") {
+                push_unique(&mut identity.groups, entity);
+            }
+        }
+
+        identity
+    }
+}
+
+fn push_unique(values: &mut Vec<String>, value: String) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+/// An allow/deny list entry
+#[derive(Debug, Clone)]
+pub(crate) struct Pattern {
+    /// The entry as configured, reported in denials
+    source: String,
+    /// Normalized form used for matching; globs are kept as written and
+    /// matched against whole references, so `user:*` covers every namespace
+    normalized: String,
+    glob: bool,
+}
+
+impl Pattern {
+    pub(crate) fn new(source: &str, enable_wildcards: bool) -> Self {
+        let glob = enable_wildcards && source.contains(['*', '?']);
+        Self {
+            source: source.to_string(),
+            normalized: if glob {
+                source.to_string()
+            } else {
+                normalize_ref(source)
+            },
+            glob,
+        }
+    }
+
+    pub(crate) fn matches(&self, value: &str) -> bool {
+        if self.glob {
+            glob_match(&self.normalized, value)
+        } else {
+            self.normalized == value
+        }
+    }
+
+    pub(crate) fn first_match<'a>(
+        patterns: &'a [Pattern],
+        values: &'a [String],
+    ) -> Option<(&'a Pattern, &'a str)> {
+        patterns.iter().find_map(|pattern| {
+            values
+                .iter()
+                .find(|value| pattern.matches(value))
+                .map(|value| (pattern, value.as_str()))
+        })
+    }
+}
+
+/// Allow/deny admission policy built from [`AuthorizationConfig`]
+///
+/// Evaluation order:
+/// 1. `deny_users` / `deny_groups` — an explicit deny always wins
+/// 2. `allowed_users` / `allowed_groups` — any match admits
+/// 3. identities with no groups are refused unless `allow_empty_groups`
+/// 4. everyone else is refused when `deny_by_default`, admitted otherwise
+///
+/// The lists can be replaced while the server runs (see [`Self::replace`]).
+///
+/// Per 011-authentication-spec.md: Combined Authorization
+#[derive(Debug)]
+pub struct AdmissionPolicy {
+    lists: RwLock<Arc<AdmissionLists>>,
+}
+
+/// The compiled allow/deny lists of an [`AdmissionPolicy`]
+#[derive(Debug)]
+struct AdmissionLists {
+    allowed_users: Vec<Pattern>,
+    allowed_groups: Vec<Pattern>,
+    deny_users: Vec<Pattern>,
+    deny_groups: Vec<Pattern>,
+    claims: ClaimMappings,
+    deny_by_default: bool,
+    allow_empty_groups: bool,
+}
+
+impl AdmissionPolicy {
+    /// Build the policy from configuration
+    pub fn new(config: &AuthorizationConfig) -> Self {
+        Self {
+            lists: RwLock::new(Arc::new(AdmissionLists::new(config))),
+        }
+    }
+
+    /// Swap in the lists of `config`; checks already running finish with
+    /// the previous lists
+    /// Per 011-authentication-spec.md: Configuration Reload
+    pub fn replace(&self, config: &AuthorizationConfig) {
+        *self.lists.write().expect("admission lock poisoned") =
+            Arc::new(AdmissionLists::new(config));
+    }
+
+    fn lists(&self) -> Arc<AdmissionLists> {
+        self.lists.read().expect("admission lock poisoned").clone()
+    }
+
+    /// Whether the policy can never admit anyone (deny by default with
+    /// nothing allowed), which is almost certainly a misconfiguration
+    pub fn admits_nobody(&self) -> bool {
+        let lists = self.lists();
+        lists.deny_by_default && lists.allowed_users.is_empty() && lists.allowed_groups.is_empty()
+    }
+
+    /// Decide whether the holder of `claims` may use the server
+    pub fn check(&self, claims: &Claims) -> Result<(), AdmissionDenied> {
+        let lists = self.lists();
+        lists.check_identity(&Identity::from_claims(claims, &lists.claims), &claims.sub)
+    }
+
+    /// Decide for an already extracted identity; `fallback_subject` names
+    /// the caller in denials when the identity has no user reference
+    pub fn check_identity(
+        &self,
+        identity: &Identity,
+        fallback_subject: &str,
+    ) -> Result<(), AdmissionDenied> {
+        self.lists().check_identity(identity, fallback_subject)
+    }
+}
+
+impl AdmissionLists {
+    fn new(config: &AuthorizationConfig) -> Self {
+        let patterns = |entries: &[String]| {
+            entries
+                .iter()
+                .map(|entry| Pattern::new(entry, config.enable_wildcards))
+                .collect()
+        };
+
+        Self {
+            allowed_users: patterns(&config.allowed_users),
+            allowed_groups: patterns(&config.allowed_groups),
+            deny_users: patterns(&config.deny_users),
+            deny_groups: patterns(&config.deny_groups),
+            claims: config.claims.clone(),
+            deny_by_default: config.deny_by_default,
+            allow_empty_groups: config.allow_empty_groups,
+        }
+    }
+
+    fn check_identity(
+        &self,
+        identity: &Identity,
+        fallback_subject: &str,
+    ) -> Result<(), AdmissionDenied> {
+        let subject = identity
+            .users
+            .first()
+            .map(String::as_str)
+            .unwrap_or(fallback_subject)
+            .to_string();
+
+        if let Some((pattern, _)) = Pattern::first_match(&self.deny_users, &identity.users) {
+            return Err(AdmissionDenied::DeniedUser {
+                subject,
+                pattern: pattern.source.clone(),
+            });
+        }
+        if let Some((pattern, group)) = Pattern::first_match(&self.deny_groups, &identity.groups) {
+            return Err(AdmissionDenied::DeniedGroup {
+                subject,
+                group: group.to_string(),
+                pattern: pattern.source.clone(),
+            });
+        }
+
+        if Pattern::first_match(&self.allowed_users, &identity.users).is_some()
+            || Pattern::first_match(&self.allowed_groups, &identity.groups).is_some()
+        {
+            return Ok(());
+        }
+
+        if identity.groups.is_empty() && !self.allow_empty_groups {
+            return Err(AdmissionDenied::NoGroups { subject });
+        }
+        if self.deny_by_default {
+            return Err(AdmissionDenied::NotAllowed { subject });
+        }
+        Ok(())
+    }
+}
+
+/// Resolves roles from the configured roles claim and `role_mappings`
+/// Per 011-authentication-spec.md: Authorization Model
+#[derive(Debug, Clone)]
+pub struct RoleResolver {
+    claims: ClaimMappings,
+    mappings: Vec<(String, Vec<Pattern>)>,
+    default_role: String,
+}
+
+impl RoleResolver {
+    /// Build the resolver from configuration
+    pub fn new(config: &AuthorizationConfig) -> Self {
+        let mut mappings: Vec<(String, Vec<Pattern>)> = config
+            .role_mappings
+            .iter()
+            .map(|(role, entries)| {
+                let patterns = entries
+                    .iter()
+                    .map(|entry| Pattern::new(entry, config.enable_wildcards))
+                    .collect();
+                (role.to_lowercase(), patterns)
+            })
+            .collect();
+        mappings.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Self {
+            claims: config.claims.clone(),
+            mappings,
+            default_role: config.default_role.to_lowercase(),
+        }
+    }
+
+    /// Roles of the holder of `claims`: those named in the roles claim plus
+    /// those mapped to one of its users or groups, or the default role
+    pub fn resolve(&self, claims: &Claims) -> Vec<String> {
+        let document = serde_json::to_value(claims).unwrap_or(Value::Null);
+        let mut roles = Vec::new();
+        for role in claim_values(&document, &self.claims.roles) {
+            push_unique(&mut roles, role.trim().to_lowercase());
+        }
+
+        let identity = Identity::from_claims(claims, &self.claims);
+        for (role, patterns) in &self.mappings {
+            if Pattern::first_match(patterns, &identity.users).is_some()
+                || Pattern::first_match(patterns, &identity.groups).is_some()
+            {
+                push_unique(&mut roles, role.clone());
+            }
+        }
+
+        if roles.is_empty() {
+            roles.push(self.default_role.clone());
+        }
+        roles
+    }
+}
+
+impl Default for RoleResolver {
+    fn default() -> Self {
+        Self::new(&AuthorizationConfig::default())
+    }
+}
+
+/// String values at a claim path
+///
+/// The path is first looked up as a literal claim name (namespaced claims
+/// such as `https://example.com/groups` contain dots), then as a
+/// dot-separated path into nested objects. Strings and arrays of strings
+/// are returned; other values are ignored.
+pub(crate) fn claim_values(document: &Value, path: &str) -> Vec<String> {
+    let value = document.get(path).or_else(|| {
+        path.split('.')
+            .try_fold(document, |value, segment| value.get(segment))
+    });
+
+    match value {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Normalize a Backstage entity reference (`kind:[namespace/]name`) to
+/// lowercase with the default namespace filled in; other values are
+/// returned unchanged
+fn normalize_ref(value: &str) -> String {
+    let Some((kind, rest)) = value.split_once(':') else {
+        return value.to_string();
+    };
+    let valid_kind = !kind.is_empty()
+        && kind
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let (namespace, name) = rest.split_once('/').unwrap_or(("default", rest));
+    if !valid_kind || namespace.is_empty() || name.is_empty() || name.contains('/') {
+        return value.to_string();
+    }
+
+    format!("{}:{}/{}", kind, namespace, name).to_lowercase()
+}
+
+/// Match `text` against a glob where `*` matches any run of characters and
+/// `?` any single character, ignoring ASCII case
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c.eq_ignore_ascii_case(&text[t]) => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::testing::{claims, configured};
+
+    fn policy(configure: impl FnOnce(&mut AuthorizationConfig)) -> AdmissionPolicy {
+        AdmissionPolicy::new(&configured(configure))
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_allow_by_user_or_group() {
+        let policy = policy(|c| {
+            c.allowed_users = strings(&["user:default/alice"]);
+            c.allowed_groups = strings(&["group:default/platform-team"]);
+        });
+
+        assert!(policy.check(&claims("user:default/alice", &[])).is_ok());
+        assert!(policy
+            .check(&claims(
+                "user:default/bob",
+                &["user:default/bob", "group:default/platform-team"]
+            ))
+            .is_ok());
+        assert_eq!(
+            policy.check(&claims("user:default/carol", &["group:default/sales"])),
+            Err(AdmissionDenied::NotAllowed {
+                subject: "user:default/carol".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_deny_takes_precedence() {
+        let policy = policy(|c| {
+            c.allowed_users = strings(&["user:default/alice"]);
+            c.allowed_groups = strings(&["group:default/developers"]);
+            c.deny_users = strings(&["user:default/alice"]);
+            c.deny_groups = strings(&["group:default/contractors"]);
+        });
+
+        assert!(matches!(
+            policy.check(&claims("user:default/alice", &[])),
+            Err(AdmissionDenied::DeniedUser { .. })
+        ));
+        assert_eq!(
+            policy.check(&claims(
+                "user:default/dan",
+                &["group:default/developers", "group:default/contractors"]
+            )),
+            Err(AdmissionDenied::DeniedGroup {
+                subject: "user:default/dan".to_string(),
+                group: "group:default/contractors".to_string(),
+                pattern: "group:default/contractors".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_entity_refs_are_normalized() {
+        let policy = policy(|c| {
+            c.allowed_groups = strings(&["group:Platform-Team"]);
+        });
+
+        assert!(policy
+            .check(&claims(
+                "00u1abc",
+                &["user:default/erin", "group:default/platform-team"]
+            ))
+            .is_ok());
+        assert_eq!(normalize_ref("User:alice"), "user:default/alice");
+        assert_eq!(normalize_ref("alice@example.com"), "alice@example.com");
+        assert_eq!(normalize_ref("https://idp/x"), "https://idp/x");
+    }
+
+    #[test]
+    fn test_wildcards_require_opt_in() {
+        let literal = policy(|c| c.allowed_users = strings(&["user:default/*"]));
+        assert!(literal.check(&claims("user:default/alice", &[])).is_err());
+
+        let globbed = policy(|c| {
+            c.enable_wildcards = true;
+            c.allowed_users = strings(&["user:default/*"]);
+            c.deny_groups = strings(&["group:*/contract?rs"]);
+        });
+        assert!(globbed.check(&claims("user:default/alice", &[])).is_ok());
+        assert!(globbed.check(&claims("user:prod/alice", &[])).is_err());
+        assert!(globbed.check(&claims("user:Default/Alice", &[])).is_ok());
+        assert!(matches!(
+            globbed.check(&claims("user:default/bob", &["group:external/contractors"])),
+            Err(AdmissionDenied::DeniedGroup { .. })
+        ));
+    }
+
+    #[test]
+    fn test_empty_groups_without_default_deny() {
+        let open = policy(|c| c.deny_by_default = false);
+        assert!(matches!(
+            open.check(&claims("user:default/alice", &[])),
+            Err(AdmissionDenied::NoGroups { .. })
+        ));
+        assert!(open
+            .check(&claims("user:default/alice", &["group:default/any"]))
+            .is_ok());
+
+        let anyone = policy(|c| {
+            c.deny_by_default = false;
+            c.allow_empty_groups = true;
+        });
+        assert!(anyone.check(&claims("user:default/alice", &[])).is_ok());
+        assert!(policy(|_| {}).admits_nobody());
+    }
+
+    #[test]
+    fn test_configurable_claim_paths() {
+        let policy = policy(|c| {
+            c.claims.user_id = "preferred_username".to_string();
+            c.claims.groups = "realm_access.roles".to_string();
+            c.allowed_users = strings(&["alice"]);
+            c.allowed_groups = strings(&["terminal-users"]);
+        });
+
+        let mut token = claims("f81d4fae", &[]);
+        token
+            .custom
+            .insert("preferred_username".to_string(), "alice".into());
+        assert!(policy.check(&token).is_ok());
+
+        let mut token = claims("f81d4fae", &[]);
+        token.custom.insert(
+            "realm_access".to_string(),
+            serde_json::json!({ "roles": ["terminal-users"] }),
+        );
+        assert!(policy.check(&token).is_ok());
+    }
+
+    #[test]
+    fn test_role_resolution() {
+        let mut config = AuthorizationConfig::default();
+        config
+            .role_mappings
+            .insert("admin".to_string(), strings(&["group:default/sre"]));
+        let resolver = RoleResolver::new(&config);
+
+        assert_eq!(
+            resolver.resolve(&claims("user:default/alice", &[])),
+            ["user"]
+        );
+        assert_eq!(
+            resolver.resolve(&claims("user:default/bob", &["group:default/sre"])),
+            ["admin"]
+        );
+
+        let mut token = claims("user:default/carol", &[]);
+        token
+            .custom
+            .insert("roles".to_string(), serde_json::json!(["ReadOnly"]));
+        assert_eq!(resolver.resolve(&token), ["readonly"]);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "user:default/alice"));
+        assert!(glob_match("user:*/a*e", "user:prod/alice"));
+        assert!(glob_match("user:*", "user:prod/alice"));
+        assert!(glob_match("a?c", "abc"));
+        assert!(!glob_match("a?c", "ac"));
+        assert!(!glob_match("user:default/*", "group:default/x"));
+    }
+}
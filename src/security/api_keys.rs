@@ -0,0 +1,745 @@
+// Per 011-authentication-spec.md: API Keys
+// Responsibilities:
+// - Issue API keys (personal access tokens) for automation
+// - Store only argon2 hashes of key secrets, optionally persisted to disk
+// - Verify presented keys against revocation, expiry and address bindings
+// - Describe key holders by their owner's user reference so they flow
+//   through the same user pipeline as JWT callers
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::config::auth::ApiKeyConfig;
+use crate::security::authorization::Permission;
+use crate::security::jwt_validator::{Audience, Claims};
+
+/// Prefix of every API key, so keys are recognizable (and never mistaken
+/// for JWTs, which start with `eyJ`)
+pub const API_KEY_PREFIX: &str = "wtk_";
+
+/// Issuer of the claims synthesized for API key callers
+pub const API_KEY_ISSUER: &str = "web-terminal";
+
+/// Provider name reported for API key callers
+pub const API_KEY_PROVIDER: &str = "api-key";
+
+/// API key error types
+#[derive(Error, Debug)]
+pub enum ApiKeyError {
+    #[error("API keys are disabled")]
+    Disabled,
+
+    #[error("Invalid API key")]
+    InvalidKey,
+
+    #[error("API key {0} has been revoked")]
+    Revoked(String),
+
+    #[error("API key {id} expired at {expired_at}")]
+    Expired {
+        id: String,
+        expired_at: DateTime<Utc>,
+    },
+
+    #[error("API key {id} may not be used from {address}")]
+    AddressNotAllowed { id: String, address: String },
+
+    #[error("API key not found: {0}")]
+    NotFound(String),
+
+    #[error("Invalid CIDR block: {0}")]
+    InvalidCidr(String),
+
+    #[error("Invalid API key request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Failed to hash API key: {0}")]
+    Hash(String),
+
+    #[error("API key store error: {0}")]
+    Store(String),
+}
+
+/// An IP network a key may be used from, e.g. `10.0.0.0/8` or `2001:db8::/32`
+/// (a bare address matches only itself)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CidrBlock {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl CidrBlock {
+    /// Whether `addr` lies in this network (IPv4-mapped IPv6 addresses
+    /// match IPv4 blocks)
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        match (self.network, addr.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(addr)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(addr)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for CidrBlock {
+    type Err = ApiKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ApiKeyError::InvalidCidr(s.to_string());
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+
+        let network: IpAddr = addr.trim().parse().map_err(|_| invalid())?;
+        let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.trim().parse::<u8>().map_err(|_| invalid())?,
+            None => max_prefix,
+        };
+        if prefix > max_prefix {
+            return Err(invalid());
+        }
+
+        Ok(Self { network, prefix })
+    }
+}
+
+impl fmt::Display for CidrBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+impl Serialize for CidrBlock {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CidrBlock {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A stored API key (the secret itself is never kept, only its hash)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    /// Public identifier, embedded in the key
+    pub id: String,
+    /// Label chosen by the owner
+    pub name: String,
+    /// Subject the key acts as
+    pub owner: String,
+    /// Owner's tenant (`None` without tenancy)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    /// Owner's email when the key was created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Permissions the key is limited to
+    pub scopes: Vec<Permission>,
+    /// Networks the key may be used from (any when empty)
+    #[serde(default)]
+    pub allowed_cidrs: Vec<CidrBlock>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// argon2 PHC string of the key secret
+    hash: String,
+}
+
+impl ApiKey {
+    /// Owner's user ID, qualified by tenant like the user IDs of signed-in
+    /// users
+    pub fn owner_id(&self) -> String {
+        match &self.tenant {
+            Some(tenant) => format!("{}/{}", tenant, self.owner),
+            None => self.owner.clone(),
+        }
+    }
+
+    /// Neither revoked nor expired at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|exp| exp > now)
+    }
+
+    /// Claims describing the key holder (a key without expiry gets `exp`
+    /// at the end of time)
+    ///
+    /// Only the owner's user reference is carried, not the groups of the
+    /// token the key was created with: admission and roles are resolved for
+    /// the owner as a user, and the key's scopes cap what that allows.
+    pub fn claims(&self) -> Claims {
+        let mut custom = HashMap::new();
+        custom.insert(
+            "api_key_id".to_string(),
+            serde_json::Value::String(self.id.clone()),
+        );
+
+        Claims {
+            sub: self.owner.clone(),
+            iss: API_KEY_ISSUER.to_string(),
+            aud: Audience::default(),
+            exp: self.expires_at.map_or(i64::MAX, |exp| exp.timestamp()),
+            iat: self.created_at.timestamp(),
+            nbf: None,
+            ent: Some(vec![self.owner.clone()]),
+            usc: None,
+            email: self.email.clone(),
+            groups: None,
+            custom,
+        }
+    }
+}
+
+/// Parameters for a new key
+#[derive(Debug, Clone)]
+pub struct NewApiKey {
+    pub name: String,
+    pub scopes: Vec<Permission>,
+    /// Time until the key expires (never, when unset)
+    pub lifetime: Option<Duration>,
+    pub allowed_cidrs: Vec<CidrBlock>,
+    /// Tenant of the caller creating the key
+    pub tenant: Option<String>,
+}
+
+/// On-disk layout of the key store
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreFile {
+    keys: Vec<ApiKey>,
+}
+
+/// Server-managed API keys
+/// Per 011-authentication-spec.md: API Keys
+pub struct ApiKeyStore {
+    config: ApiKeyConfig,
+    keys: RwLock<HashMap<String, ApiKey>>,
+}
+
+impl ApiKeyStore {
+    /// Open the store, loading persisted keys from `store_path` if it exists
+    pub fn open(config: ApiKeyConfig) -> Result<Self, ApiKeyError> {
+        let keys = match &config.store_path {
+            Some(path) if config.enabled && path.exists() => load_store(path)?,
+            _ => HashMap::new(),
+        };
+
+        if config.enabled {
+            tracing::info!(keys_count = keys.len(), "API keys enabled");
+        }
+
+        Ok(Self {
+            config,
+            keys: RwLock::new(keys),
+        })
+    }
+
+    /// A store that accepts no keys
+    pub fn disabled() -> Self {
+        Self {
+            config: ApiKeyConfig::default(),
+            keys: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Whether API keys are accepted and may be created
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Whether a bearer credential is an API key rather than a JWT
+    pub fn is_api_key(token: &str) -> bool {
+        token.starts_with(API_KEY_PREFIX)
+    }
+
+    /// Owner of the key `token` names, without checking its secret
+    ///
+    /// Only charge failures to this owner once the secret has been verified
+    /// (a revoked, expired or out-of-range key).
+    pub fn claimed_owner(&self, token: &str) -> Option<String> {
+        let (id, _) = split_key(token)?;
+        self.keys
+            .read()
+            .expect("API key lock poisoned")
+            .get(id)
+            .map(|key| key.owner.clone())
+    }
+
+    /// Create a key for the caller described by `owner`
+    ///
+    /// Returns the stored key and the full key string, which is shown to
+    /// the caller once and cannot be recovered afterwards.
+    pub fn create(
+        &self,
+        owner: &Claims,
+        request: NewApiKey,
+    ) -> Result<(ApiKey, String), ApiKeyError> {
+        if !self.is_enabled() {
+            return Err(ApiKeyError::Disabled);
+        }
+
+        let name = request.name.trim();
+        if name.is_empty() {
+            return Err(ApiKeyError::InvalidRequest("name must not be empty".into()));
+        }
+
+        let mut scopes = Vec::new();
+        for scope in request.scopes {
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+        if scopes.is_empty() {
+            return Err(ApiKeyError::InvalidRequest(
+                "at least one scope is required".into(),
+            ));
+        }
+
+        if let Some(max) = self.config.max_lifetime {
+            match request.lifetime {
+                Some(lifetime) if lifetime <= max => {}
+                _ => {
+                    return Err(ApiKeyError::InvalidRequest(format!(
+                        "keys must expire within {}",
+                        humantime_serde::re::humantime::format_duration(max)
+                    )))
+                }
+            }
+        }
+
+        let now = Utc::now();
+        let expires_at = request
+            .lifetime
+            .map(|lifetime| {
+                chrono::Duration::from_std(lifetime)
+                    .ok()
+                    .and_then(|lifetime| now.checked_add_signed(lifetime))
+                    .ok_or_else(|| ApiKeyError::InvalidRequest("lifetime is too long".into()))
+            })
+            .transpose()?;
+
+        let id = Uuid::new_v4().simple().to_string()[..16].to_string();
+        // Two v4 UUIDs: 244 random bits
+        let secret = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let salt = SaltString::encode_b64(Uuid::new_v4().as_bytes())
+            .map_err(|e| ApiKeyError::Hash(e.to_string()))?;
+        let hash = Argon2::default()
+            .hash_password(secret.as_bytes(), &salt)
+            .map_err(|e| ApiKeyError::Hash(e.to_string()))?
+            .to_string();
+
+        let key = ApiKey {
+            id: id.clone(),
+            name: name.to_string(),
+            owner: owner.sub.clone(),
+            tenant: request.tenant,
+            email: owner.email.clone(),
+            scopes,
+            allowed_cidrs: request.allowed_cidrs,
+            created_at: now,
+            expires_at,
+            revoked_at: None,
+            last_used_at: None,
+            hash,
+        };
+
+        let mut keys = self.keys.write().expect("API key lock poisoned");
+        let active = keys
+            .values()
+            .filter(|k| k.owner_id() == key.owner_id() && k.is_active(now))
+            .count();
+        if active >= self.config.max_keys_per_user {
+            return Err(ApiKeyError::InvalidRequest(format!(
+                "at most {} active keys are allowed per user",
+                self.config.max_keys_per_user
+            )));
+        }
+
+        keys.insert(id.clone(), key.clone());
+        if let Err(e) = self.persist(&keys) {
+            keys.remove(&id);
+            return Err(e);
+        }
+
+        Ok((key, format!("{}{}_{}", API_KEY_PREFIX, id, secret)))
+    }
+
+    /// Keys owned by `owner` (a user ID, see [`ApiKey::owner_id`]), oldest
+    /// first
+    pub fn list(&self, owner: &str) -> Vec<ApiKey> {
+        let keys = self.keys.read().expect("API key lock poisoned");
+        let mut owned: Vec<ApiKey> = keys
+            .values()
+            .filter(|k| k.owner_id() == owner)
+            .cloned()
+            .collect();
+        owned.sort_by_key(|k| k.created_at);
+        owned
+    }
+
+    /// Revoke one of `owner`'s keys (revoking twice is a no-op)
+    pub fn revoke(&self, owner: &str, id: &str) -> Result<ApiKey, ApiKeyError> {
+        let mut keys = self.keys.write().expect("API key lock poisoned");
+        let key = keys
+            .get_mut(id)
+            .filter(|k| k.owner_id() == owner)
+            .ok_or_else(|| ApiKeyError::NotFound(id.to_string()))?;
+        if key.revoked_at.is_some() {
+            return Ok(key.clone());
+        }
+
+        key.revoked_at = Some(Utc::now());
+        let revoked = key.clone();
+        if let Err(e) = self.persist(&keys) {
+            if let Some(key) = keys.get_mut(id) {
+                key.revoked_at = None;
+            }
+            return Err(e);
+        }
+
+        Ok(revoked)
+    }
+
+    /// Verify a presented key from `client_ip`
+    ///
+    /// The secret is checked before anything else so that revocation,
+    /// expiry and address errors are only reported to the key's holder.
+    pub async fn authenticate(
+        &self,
+        token: &str,
+        client_ip: Option<IpAddr>,
+    ) -> Result<ApiKey, ApiKeyError> {
+        if !self.is_enabled() {
+            return Err(ApiKeyError::Disabled);
+        }
+
+        let (id, secret) = split_key(token).ok_or(ApiKeyError::InvalidKey)?;
+        let key = self
+            .keys
+            .read()
+            .expect("API key lock poisoned")
+            .get(id)
+            .cloned()
+            .ok_or(ApiKeyError::InvalidKey)?;
+
+        // argon2 is deliberately slow; keep it off the async workers
+        let hash = key.hash.clone();
+        let secret = secret.to_string();
+        let verified = tokio::task::spawn_blocking(move || {
+            PasswordHash::new(&hash)
+                .map(|hash| {
+                    Argon2::default()
+                        .verify_password(secret.as_bytes(), &hash)
+                        .is_ok()
+                })
+                .unwrap_or(false)
+        })
+        .await
+        .map_err(|e| ApiKeyError::Hash(e.to_string()))?;
+        if !verified {
+            return Err(ApiKeyError::InvalidKey);
+        }
+
+        let now = Utc::now();
+        if key.revoked_at.is_some() {
+            return Err(ApiKeyError::Revoked(key.id));
+        }
+        if let Some(expired_at) = key.expires_at.filter(|exp| *exp <= now) {
+            return Err(ApiKeyError::Expired {
+                id: key.id,
+                expired_at,
+            });
+        }
+        if !key.allowed_cidrs.is_empty()
+            && !client_ip.is_some_and(|ip| key.allowed_cidrs.iter().any(|c| c.contains(ip)))
+        {
+            return Err(ApiKeyError::AddressNotAllowed {
+                id: key.id,
+                address: client_ip.map_or_else(|| "an unknown address".into(), |ip| ip.to_string()),
+            });
+        }
+
+        // Last use is tracked in memory and persisted with the next change
+        let mut keys = self.keys.write().expect("API key lock poisoned");
+        if let Some(stored) = keys.get_mut(&key.id) {
+            stored.last_used_at = Some(now);
+        }
+
+        Ok(ApiKey {
+            last_used_at: Some(now),
+            ..key
+        })
+    }
+
+    /// Write all keys to `store_path` (no-op for in-memory stores)
+    fn persist(&self, keys: &HashMap<String, ApiKey>) -> Result<(), ApiKeyError> {
+        let Some(path) = &self.config.store_path else {
+            return Ok(());
+        };
+
+        let mut file = StoreFile {
+            keys: keys.values().cloned().collect(),
+        };
+        file.keys.sort_by_key(|k| k.created_at);
+        let json =
+            serde_json::to_vec_pretty(&file).map_err(|e| ApiKeyError::Store(e.to_string()))?;
+
+        // Write a sibling file and rename it over the store so readers never
+        // see a partial file
+        let tmp = path.with_extension("tmp");
+        write_private(&tmp, &json)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| ApiKeyError::Store(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// Split a key into its id and secret
+fn split_key(token: &str) -> Option<(&str, &str)> {
+    token
+        .strip_prefix(API_KEY_PREFIX)
+        .and_then(|rest| rest.split_once('_'))
+}
+
+/// Read a persisted key store
+fn load_store(path: &Path) -> Result<HashMap<String, ApiKey>, ApiKeyError> {
+    let content = std::fs::read(path)
+        .map_err(|e| ApiKeyError::Store(format!("{}: {}", path.display(), e)))?;
+    let file: StoreFile = serde_json::from_slice(&content)
+        .map_err(|e| ApiKeyError::Store(format!("{}: {}", path.display(), e)))?;
+    Ok(file.keys.into_iter().map(|k| (k.id.clone(), k)).collect())
+}
+
+/// Create (or truncate) a file readable only by its owner
+pub(crate) fn write_private(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    file.write_all(content)?;
+    file.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::testing::claims_with;
+
+    fn owner(sub: &str) -> Claims {
+        claims_with(
+            sub,
+            serde_json::json!({
+                "email": format!("{}@example.com", sub),
+                "ent": ["group:default/ci"],
+            }),
+        )
+    }
+
+    fn enabled_store() -> ApiKeyStore {
+        ApiKeyStore::open(ApiKeyConfig {
+            enabled: true,
+            ..ApiKeyConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn request(name: &str) -> NewApiKey {
+        NewApiKey {
+            name: name.to_string(),
+            scopes: vec![Permission::CreateSession, Permission::SendInput],
+            lifetime: None,
+            allowed_cidrs: Vec::new(),
+            tenant: None,
+        }
+    }
+
+    #[test]
+    fn test_cidr_block() {
+        let block: CidrBlock = "10.1.0.0/16".parse().unwrap();
+        assert!(block.contains("10.1.200.3".parse().unwrap()));
+        assert!(block.contains("::ffff:10.1.0.9".parse().unwrap()));
+        assert!(!block.contains("10.2.0.1".parse().unwrap()));
+        assert_eq!(block.to_string(), "10.1.0.0/16");
+
+        let single: CidrBlock = "2001:db8::1".parse().unwrap();
+        assert!(single.contains("2001:db8::1".parse().unwrap()));
+        assert!(!single.contains("2001:db8::2".parse().unwrap()));
+
+        let any: CidrBlock = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains("192.0.2.1".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<CidrBlock>().is_err());
+        assert!("not-an-ip/8".parse::<CidrBlock>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_authenticate_and_revoke() {
+        let store = enabled_store();
+        let (key, token) = store.create(&owner("alice"), request("ci")).unwrap();
+
+        assert!(ApiKeyStore::is_api_key(&token));
+        assert!(!token.contains(&key.hash));
+        assert!(key.hash.starts_with("$argon2"));
+
+        let authenticated = store.authenticate(&token, None).await.unwrap();
+        assert_eq!(authenticated.owner, "alice");
+        assert!(authenticated.last_used_at.is_some());
+
+        let claims = authenticated.claims();
+        assert_eq!(claims.sub, "alice");
+        assert_eq!(claims.exp, i64::MAX);
+        // The owner's groups at creation time are not carried
+        assert_eq!(claims.ent, Some(vec!["alice".to_string()]));
+
+        // Wrong secret for a known id
+        let forged = format!("{}{}_{}", API_KEY_PREFIX, key.id, "0".repeat(64));
+        assert!(matches!(
+            store.authenticate(&forged, None).await,
+            Err(ApiKeyError::InvalidKey)
+        ));
+
+        // Only the owner can revoke
+        assert!(matches!(
+            store.revoke("bob", &key.id),
+            Err(ApiKeyError::NotFound(_))
+        ));
+        store.revoke("alice", &key.id).unwrap();
+        assert!(matches!(
+            store.authenticate(&token, None).await,
+            Err(ApiKeyError::Revoked(_))
+        ));
+        assert_eq!(store.list("alice").len(), 1);
+        assert!(store.list("bob").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_address_binding() {
+        let store = enabled_store();
+        let (_, token) = store
+            .create(
+                &owner("alice"),
+                NewApiKey {
+                    allowed_cidrs: vec!["192.0.2.0/24".parse().unwrap()],
+                    ..request("runner")
+                },
+            )
+            .unwrap();
+
+        assert!(store
+            .authenticate(&token, Some("192.0.2.10".parse().unwrap()))
+            .await
+            .is_ok());
+        assert!(matches!(
+            store
+                .authenticate(&token, Some("198.51.100.1".parse().unwrap()))
+                .await,
+            Err(ApiKeyError::AddressNotAllowed { .. })
+        ));
+        assert!(matches!(
+            store.authenticate(&token, None).await,
+            Err(ApiKeyError::AddressNotAllowed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_create_limits() {
+        let store = ApiKeyStore::open(ApiKeyConfig {
+            enabled: true,
+            max_lifetime: Some(Duration::from_secs(3600)),
+            max_keys_per_user: 1,
+            ..ApiKeyConfig::default()
+        })
+        .unwrap();
+
+        // Keys must expire within max_lifetime
+        assert!(store.create(&owner("alice"), request("forever")).is_err());
+        let short = NewApiKey {
+            lifetime: Some(Duration::from_secs(600)),
+            ..request("short")
+        };
+        store.create(&owner("alice"), short.clone()).unwrap();
+
+        // Per-user limit
+        assert!(store.create(&owner("alice"), short.clone()).is_err());
+        assert!(store.create(&owner("bob"), short).is_ok());
+
+        // Scopes are required
+        assert!(store
+            .create(
+                &owner("carol"),
+                NewApiKey {
+                    scopes: Vec::new(),
+                    lifetime: Some(Duration::from_secs(60)),
+                    ..request("empty")
+                },
+            )
+            .is_err());
+
+        assert!(matches!(
+            ApiKeyStore::disabled().create(&owner("alice"), request("off")),
+            Err(ApiKeyError::Disabled)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_store_persists_hashes_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api-keys.json");
+        let config = ApiKeyConfig {
+            enabled: true,
+            store_path: Some(path.clone()),
+            ..ApiKeyConfig::default()
+        };
+
+        let store = ApiKeyStore::open(config.clone()).unwrap();
+        let (key, token) = store.create(&owner("alice"), request("ci")).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let secret = token.rsplit('_').next().unwrap();
+        assert!(!content.contains(secret));
+        assert!(!content.contains("group:default/ci"));
+        assert!(content.contains(&key.id));
+
+        let reopened = ApiKeyStore::open(config).unwrap();
+        assert!(reopened.authenticate(&token, None).await.is_ok());
+        reopened.revoke("alice", &key.id).unwrap();
+        drop(reopened);
+
+        let reopened = ApiKeyStore::open(store.config.clone()).unwrap();
+        assert!(matches!(
+            reopened.authenticate(&token, None).await,
+            Err(ApiKeyError::Revoked(_))
+        ));
+    }
+}
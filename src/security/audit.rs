@@ -0,0 +1,829 @@
+//! Security audit log
+//!
+//! Per spec-kit/011-authentication-spec.md: Audit logging
+//! Records authentication, session and admin events on the `audit` tracing
+//! target, filtered by [`AuditConfig`]. With `path` set, events are also
+//! appended, by a dedicated writer thread, to a JSON Lines file in which
+//! every record carries an HMAC keyed by `hmac_key_file` over its content
+//! and its predecessor's HMAC, so edited, reordered or deleted records, and
+//! chains rewritten without the key, are detected by [`verify_chain`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use chrono::{DateTime, Utc};
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::config::auth::AuditConfig;
+
+/// `prev_hash` of the first record ever written
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// How much of the end of a file is read to find its last record
+const TAIL_READ: u64 = 64 * 1024;
+
+/// Shortest accepted record key
+const MIN_KEY_BYTES: usize = 32;
+
+/// Audit log error types
+#[derive(Error, Debug)]
+pub enum AuditError {
+    #[error("Audit log error: {0}")]
+    Store(String),
+
+    #[error("Audit log {path} line {line}: {reason}")]
+    Tampered {
+        path: String,
+        line: usize,
+        reason: String,
+    },
+
+    #[error("Audit log starts at record {0}: earlier records were rotated away or removed")]
+    Incomplete(u64),
+}
+
+/// Audited security actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A connection authenticated
+    AuthSuccess,
+    /// A token was rejected
+    AuthFailure,
+    /// A connection re-authenticated with a fresh token
+    TokenRefreshed,
+    /// A re-authentication was refused (e.g. different subject)
+    TokenRefreshRejected,
+    /// The client was warned that its token is about to expire
+    TokenExpiring,
+    /// The token expired and the connection was closed or frozen
+    TokenExpired,
+    /// An API key was created
+    ApiKeyCreated,
+    /// An API key was revoked
+    ApiKeyRevoked,
+    /// A validated identity was refused by the authorization policy
+    AuthorizationDenied,
+    /// An authenticated user was refused an action
+    PermissionDenied,
+    /// Providers and authorization settings were reloaded
+    ConfigReloaded,
+    /// A reload was rejected and the previous configuration kept
+    ConfigReloadFailed,
+    /// Tokens or a subject were added to the revocation list
+    TokenRevoked,
+    /// A revocation was lifted
+    RevocationRemoved,
+    /// A live connection was closed because its credential was revoked
+    ConnectionRevoked,
+    /// Too many failed authentications locked out an address or subject
+    AuthLockout,
+    /// An admin lifted a lockout
+    LockoutCleared,
+    /// A user asked for access to another user's session
+    AccessRequested,
+    /// A session owner approved an access request
+    AccessApproved,
+    /// A session owner refused an access request
+    AccessRequestDenied,
+    /// Requested or granted session access was withdrawn
+    AccessRevoked,
+    /// A terminal session was created
+    SessionCreated,
+    /// A connection attached to an existing session
+    SessionAttached,
+    /// A session was killed on request
+    SessionKilled,
+    /// The server drained its sessions and shut down
+    ServerShutdown,
+    /// A file upload was requested
+    FileUpload,
+    /// A file download was requested
+    FileDownload,
+}
+
+impl AuditAction {
+    /// Convert action to string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AuthSuccess => "auth_success",
+            Self::AuthFailure => "auth_failure",
+            Self::TokenRefreshed => "token_refreshed",
+            Self::TokenRefreshRejected => "token_refresh_rejected",
+            Self::TokenExpiring => "token_expiring",
+            Self::TokenExpired => "token_expired",
+            Self::ApiKeyCreated => "api_key_created",
+            Self::ApiKeyRevoked => "api_key_revoked",
+            Self::AuthorizationDenied => "authorization_denied",
+            Self::PermissionDenied => "permission_denied",
+            Self::ConfigReloaded => "config_reloaded",
+            Self::ConfigReloadFailed => "config_reload_failed",
+            Self::TokenRevoked => "token_revoked",
+            Self::RevocationRemoved => "revocation_removed",
+            Self::ConnectionRevoked => "connection_revoked",
+            Self::AuthLockout => "auth_lockout",
+            Self::LockoutCleared => "lockout_cleared",
+            Self::AccessRequested => "access_requested",
+            Self::AccessApproved => "access_approved",
+            Self::AccessRequestDenied => "access_request_denied",
+            Self::AccessRevoked => "access_revoked",
+            Self::SessionCreated => "session_created",
+            Self::SessionAttached => "session_attached",
+            Self::SessionKilled => "session_killed",
+            Self::ServerShutdown => "server_shutdown",
+            Self::FileUpload => "file_upload",
+            Self::FileDownload => "file_download",
+        }
+    }
+}
+
+/// A single audit record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub timestamp: DateTime<Utc>,
+    pub action: AuditAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl AuditEvent {
+    /// Create an event stamped with the current time
+    pub fn new(action: AuditAction) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            action,
+            user_id: None,
+            session_id: None,
+            detail: None,
+        }
+    }
+
+    /// Set the acting user
+    pub fn user(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Set the affected session
+    pub fn session(mut self, session_id: impl ToString) -> Self {
+        self.session_id = Some(session_id.to_string());
+        self
+    }
+
+    /// Set a human-readable detail
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// Audit logger
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogger {
+    config: AuditConfig,
+    /// Queue of the thread appending to the audit file
+    writer: Option<mpsc::Sender<WriterMessage>>,
+}
+
+/// Work for the audit file writer
+#[derive(Debug)]
+enum WriterMessage {
+    Record(AuditEvent),
+    /// Sync the file once everything queued before is written
+    Flush(mpsc::Sender<Result<(), AuditError>>),
+}
+
+impl AuditLogger {
+    /// Create a logger honouring the given audit settings, on the tracing
+    /// target only
+    pub fn new(config: AuditConfig) -> Self {
+        Self {
+            config,
+            writer: None,
+        }
+    }
+
+    /// Create a logger that also appends to `config.path`, continuing the
+    /// hash chain already in the file
+    pub fn open(config: AuditConfig) -> Result<Self, AuditError> {
+        let writer = match &config.path {
+            Some(path) if config.enabled => {
+                let key_file = config.hmac_key_file.as_deref().ok_or_else(|| {
+                    AuditError::Store("audit.hmac_key_file is required with audit.path".to_string())
+                })?;
+                let key = AuditKey::read_or_create(key_file)?;
+                Some(AuditFile::open(&config, path, key)?.spawn_writer()?)
+            }
+            _ => None,
+        };
+        Ok(Self { config, writer })
+    }
+
+    /// Whether events of this kind are recorded
+    pub fn is_enabled(&self, action: AuditAction) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+
+        match action {
+            AuditAction::AuthSuccess => self.config.log_successful_auth,
+            AuditAction::AuthFailure => self.config.log_failed_auth,
+            AuditAction::AuthorizationDenied | AuditAction::PermissionDenied => {
+                self.config.log_authorization_denials
+            }
+            _ => true,
+        }
+    }
+
+    /// Record an event; the file write happens on the writer thread
+    pub fn record(&self, event: AuditEvent) {
+        if !self.is_enabled(event.action) {
+            return;
+        }
+
+        match serde_json::to_string(&event) {
+            Ok(json) => tracing::info!(target: "audit", action = event.action.as_str(), "{}", json),
+            Err(e) => tracing::error!("Failed to serialize audit event: {}", e),
+        }
+
+        if let Some(writer) = &self.writer {
+            if writer.send(WriterMessage::Record(event)).is_err() {
+                tracing::error!("Failed to write audit log: writer stopped");
+            }
+        }
+    }
+
+    /// Wait until the events recorded so far are written, then sync the
+    /// audit file to disk
+    pub fn flush(&self) -> Result<(), AuditError> {
+        let Some(writer) = &self.writer else {
+            return Ok(());
+        };
+        let stopped = || AuditError::Store("audit log writer stopped".to_string());
+
+        let (done, result) = mpsc::channel();
+        writer
+            .send(WriterMessage::Flush(done))
+            .map_err(|_| stopped())?;
+        result.recv().map_err(|_| stopped())?
+    }
+}
+
+/// Secret keying the audit record hashes (HMAC-SHA256)
+pub struct AuditKey(hmac::Key);
+
+impl AuditKey {
+    /// Read the key in `path` (surrounding whitespace ignored)
+    pub fn read(path: &Path) -> Result<Self, AuditError> {
+        let contents = std::fs::read(path).map_err(|e| store_error(path, e))?;
+        let secret = contents.trim_ascii();
+        if secret.len() < MIN_KEY_BYTES {
+            return Err(AuditError::Store(format!(
+                "{}: key must be at least {} bytes, found {}",
+                path.display(),
+                MIN_KEY_BYTES,
+                secret.len()
+            )));
+        }
+        Ok(Self(hmac::Key::new(hmac::HMAC_SHA256, secret)))
+    }
+
+    /// Read the key in `path`, writing a random one there first if missing
+    fn read_or_create(path: &Path) -> Result<Self, AuditError> {
+        if !path.exists() {
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir).map_err(|e| store_error(dir, e))?;
+            }
+            // Two v4 UUIDs: 244 random bits
+            let secret = format!("{}{}\n", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            options
+                .open(path)
+                .and_then(|mut file| file.write_all(secret.as_bytes()))
+                .map_err(|e| store_error(path, e))?;
+            tracing::info!("Created audit log key {}", path.display());
+        }
+        Self::read(path)
+    }
+
+    /// Hex HMAC of a record (without its `hash`) serialized with sorted keys
+    fn sign(&self, record: &Map<String, Value>) -> String {
+        let sorted: BTreeMap<_, _> = record.iter().collect();
+        let canonical = serde_json::to_string(&sorted).unwrap_or_default();
+        hmac::sign(&self.0, canonical.as_bytes())
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+impl fmt::Debug for AuditKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AuditKey(..)")
+    }
+}
+
+/// The live audit file and the end of its hash chain
+#[derive(Debug)]
+struct AuditFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_file_size: u64,
+    max_files: usize,
+    /// Sequence number of the next record
+    seq: u64,
+    /// Hash of the last record written
+    last_hash: String,
+    key: AuditKey,
+}
+
+impl AuditFile {
+    fn open(config: &AuditConfig, path: &Path, key: AuditKey) -> Result<Self, AuditError> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| store_error(dir, e))?;
+        }
+
+        // Continue the chain from the last record, which is in the newest
+        // rotated file when the live one was just rotated
+        let last = match last_record(path)? {
+            Some(last) => Some(last),
+            None => last_record(&rotated_path(path, 1))?,
+        };
+        let (seq, last_hash) = match last {
+            Some(record) => (
+                record_seq(&record) + 1,
+                record_str(&record, "hash").to_string(),
+            ),
+            None => (0, GENESIS_HASH.to_string()),
+        };
+
+        let file = open_append(path)?;
+        let size = file.metadata().map_err(|e| store_error(path, e))?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            size,
+            max_file_size: config.max_file_size,
+            max_files: config.max_files,
+            seq,
+            last_hash,
+            key,
+        })
+    }
+
+    /// Hand the file to a dedicated thread appending what the returned
+    /// queue receives, so recording never blocks on disk
+    fn spawn_writer(mut self) -> Result<mpsc::Sender<WriterMessage>, AuditError> {
+        let (sender, receiver) = mpsc::channel();
+        let path = self.path.clone();
+        std::thread::Builder::new()
+            .name("audit-writer".to_string())
+            .spawn(move || {
+                for message in receiver {
+                    match message {
+                        WriterMessage::Record(event) => {
+                            if let Err(e) = self.append(&event) {
+                                tracing::error!("Failed to write audit log: {}", e);
+                            }
+                        }
+                        WriterMessage::Flush(done) => {
+                            let _ = done.send(self.flush());
+                        }
+                    }
+                }
+            })
+            .map_err(|e| store_error(&path, e))?;
+        Ok(sender)
+    }
+
+    /// Append `event` as the next link of the chain
+    fn append(&mut self, event: &AuditEvent) -> Result<(), AuditError> {
+        if self.max_file_size > 0 && self.size >= self.max_file_size {
+            self.rotate()?;
+        }
+
+        let (line, hash) = chain_record(&self.key, event, self.seq, &self.last_hash)?;
+        self.file
+            .write_all(line.as_bytes())
+            .map_err(|e| store_error(&self.path, e))?;
+        self.size += line.len() as u64;
+        self.seq += 1;
+        self.last_hash = hash;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), AuditError> {
+        self.file.sync_all().map_err(|e| store_error(&self.path, e))
+    }
+
+    /// Shift `<path>.N` to `<path>.N+1`, dropping the oldest, and start a
+    /// new live file; the chain carries on across files
+    fn rotate(&mut self) -> Result<(), AuditError> {
+        if self.max_files == 0 {
+            std::fs::remove_file(&self.path).map_err(|e| store_error(&self.path, e))?;
+        } else {
+            for n in (1..self.max_files).rev() {
+                let from = rotated_path(&self.path, n);
+                if from.exists() {
+                    let to = rotated_path(&self.path, n + 1);
+                    std::fs::rename(&from, &to).map_err(|e| store_error(&from, e))?;
+                }
+            }
+            let to = rotated_path(&self.path, 1);
+            std::fs::rename(&self.path, &to).map_err(|e| store_error(&self.path, e))?;
+        }
+
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// Result of checking an audit hash chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainSummary {
+    /// Files checked, oldest first
+    pub files: Vec<PathBuf>,
+    /// Records checked
+    pub records: u64,
+    pub first_seq: Option<u64>,
+    pub last_seq: Option<u64>,
+    /// Whether the chain starts at the first record ever written rather
+    /// than after files rotated away
+    pub from_genesis: bool,
+    /// Hash of the last record
+    pub last_hash: String,
+}
+
+impl ChainSummary {
+    /// Refuse a chain that does not start at the first record ever written
+    pub fn require_genesis(&self) -> Result<(), AuditError> {
+        match self.first_seq {
+            Some(seq) if seq != 0 => Err(AuditError::Incomplete(seq)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Check that `files`, oldest first, hold one unbroken hash chain signed
+/// with `key`
+pub fn verify_chain(files: &[PathBuf], key: &AuditKey) -> Result<ChainSummary, AuditError> {
+    let mut summary = ChainSummary {
+        files: files.to_vec(),
+        records: 0,
+        first_seq: None,
+        last_seq: None,
+        from_genesis: false,
+        last_hash: GENESIS_HASH.to_string(),
+    };
+
+    for path in files {
+        let file = File::open(path).map_err(|e| store_error(path, e))?;
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| store_error(path, e))?;
+            let tampered = |reason: String| AuditError::Tampered {
+                path: path.display().to_string(),
+                line: index + 1,
+                reason,
+            };
+
+            let mut record = match serde_json::from_str(&line) {
+                Ok(Value::Object(record)) => record,
+                _ => return Err(tampered("not a JSON object".to_string())),
+            };
+            let hash = match record.remove("hash") {
+                Some(Value::String(hash)) => hash,
+                _ => return Err(tampered("missing hash".to_string())),
+            };
+            let (Some(seq), Some(prev_hash)) = (
+                record.get("seq").and_then(Value::as_u64),
+                record.get("prev_hash").and_then(Value::as_str),
+            ) else {
+                return Err(tampered("missing seq or prev_hash".to_string()));
+            };
+
+            if key.sign(&record) != hash {
+                return Err(tampered(format!("record {} does not match its hash", seq)));
+            }
+            match summary.last_seq {
+                Some(last_seq) => {
+                    if seq != last_seq + 1 {
+                        return Err(tampered(format!(
+                            "record {} follows record {}",
+                            seq, last_seq
+                        )));
+                    }
+                    if prev_hash != summary.last_hash {
+                        return Err(tampered(format!(
+                            "record {} does not link to record {}",
+                            seq, last_seq
+                        )));
+                    }
+                }
+                None => {
+                    summary.first_seq = Some(seq);
+                    summary.from_genesis = seq == 0;
+                    if seq == 0 && prev_hash != GENESIS_HASH {
+                        return Err(tampered(
+                            "first record does not start the chain".to_string(),
+                        ));
+                    }
+                }
+            }
+
+            summary.records += 1;
+            summary.last_seq = Some(seq);
+            summary.last_hash = hash;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// The live audit file preceded by its rotated files, oldest first
+pub fn chain_files(path: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<_> = (1..)
+        .map(|n| rotated_path(path, n))
+        .take_while(|rotated| rotated.exists())
+        .collect();
+    files.reverse();
+    files.push(path.to_path_buf());
+    files
+}
+
+/// `<path>.<n>`
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(format!(".{}", n));
+    PathBuf::from(rotated)
+}
+
+/// Serialize `event` as record `seq` following `prev_hash`; returns the
+/// line and the record's hash
+fn chain_record(
+    key: &AuditKey,
+    event: &AuditEvent,
+    seq: u64,
+    prev_hash: &str,
+) -> Result<(String, String), AuditError> {
+    let mut record = match serde_json::to_value(event) {
+        Ok(Value::Object(record)) => record,
+        Ok(_) => return Err(AuditError::Store("event is not an object".to_string())),
+        Err(e) => return Err(AuditError::Store(e.to_string())),
+    };
+    record.insert("seq".to_string(), seq.into());
+    record.insert("prev_hash".to_string(), prev_hash.into());
+
+    let hash = key.sign(&record);
+    record.insert("hash".to_string(), hash.clone().into());
+    Ok((format!("{}\n", Value::Object(record)), hash))
+}
+
+/// The last record of `path`, if the file exists and has one
+fn last_record(path: &Path) -> Result<Option<Map<String, Value>>, AuditError> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(store_error(path, e)),
+    };
+    let len = file.metadata().map_err(|e| store_error(path, e))?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(TAIL_READ)))
+        .map_err(|e| store_error(path, e))?;
+    let mut tail = String::new();
+    file.read_to_string(&mut tail)
+        .map_err(|e| store_error(path, e))?;
+
+    let Some(line) = tail.lines().rev().find(|line| !line.trim().is_empty()) else {
+        return Ok(None);
+    };
+    match serde_json::from_str(line) {
+        Ok(Value::Object(record)) if record.get("hash").is_some_and(Value::is_string) => {
+            Ok(Some(record))
+        }
+        _ => Err(AuditError::Store(format!(
+            "{}: last record is unreadable; move the file aside to start a new chain",
+            path.display()
+        ))),
+    }
+}
+
+fn record_seq(record: &Map<String, Value>) -> u64 {
+    record
+        .get("seq")
+        .and_then(Value::as_u64)
+        .unwrap_or_default()
+}
+
+fn record_str<'a>(record: &'a Map<String, Value>, field: &str) -> &'a str {
+    record
+        .get(field)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+/// Open `path` for appending, readable by its owner only
+fn open_append(path: &Path) -> Result<File, AuditError> {
+    let mut options = OpenOptions::new();
+    options.append(true).create(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path).map_err(|e| store_error(path, e))
+}
+
+fn store_error(path: &Path, e: std::io::Error) -> AuditError {
+    AuditError::Store(format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_filters_follow_config() {
+        let logger = AuditLogger::new(AuditConfig {
+            log_successful_auth: false,
+            ..AuditConfig::default()
+        });
+        assert!(!logger.is_enabled(AuditAction::AuthSuccess));
+        assert!(logger.is_enabled(AuditAction::AuthFailure));
+        assert!(logger.is_enabled(AuditAction::TokenExpired));
+        assert!(logger.is_enabled(AuditAction::AuthorizationDenied));
+
+        let disabled = AuditLogger::new(AuditConfig {
+            enabled: false,
+            ..AuditConfig::default()
+        });
+        assert!(!disabled.is_enabled(AuditAction::TokenExpired));
+    }
+
+    #[test]
+    fn test_audit_event_serialization() {
+        let event = AuditEvent::new(AuditAction::TokenRefreshed)
+            .user("alice")
+            .session("abc");
+        let json = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(json["action"], "token_refreshed");
+        assert_eq!(json["user_id"], "alice");
+        assert_eq!(json["session_id"], "abc");
+        assert!(json.get("detail").is_none());
+    }
+
+    fn file_config(dir: &Path, max_file_size: u64) -> AuditConfig {
+        AuditConfig {
+            path: Some(dir.join("audit.log")),
+            hmac_key_file: Some(dir.join("audit.key")),
+            max_file_size,
+            max_files: 10,
+            ..AuditConfig::default()
+        }
+    }
+
+    fn key(config: &AuditConfig) -> AuditKey {
+        AuditKey::read_or_create(config.hmac_key_file.as_deref().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_hash_chain_survives_restart_and_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = file_config(dir.path(), 400);
+        let path = config.path.clone().unwrap();
+
+        let logger = AuditLogger::open(config.clone()).unwrap();
+        for i in 0..3 {
+            logger.record(AuditEvent::new(AuditAction::SessionCreated).session(i));
+        }
+        logger.flush().unwrap();
+        drop(logger);
+
+        // Reopening continues the chain, rotating once the file is full
+        let logger = AuditLogger::open(config.clone()).unwrap();
+        for i in 3..8 {
+            logger.record(
+                AuditEvent::new(AuditAction::SessionKilled)
+                    .user("alice")
+                    .session(i),
+            );
+        }
+        logger.flush().unwrap();
+
+        let files = chain_files(&path);
+        assert!(files.len() > 1);
+        assert_eq!(files.last(), Some(&path));
+
+        let summary = verify_chain(&files, &key(&config)).unwrap();
+        assert_eq!(summary.records, 8);
+        assert_eq!(summary.first_seq, Some(0));
+        assert_eq!(summary.last_seq, Some(7));
+        assert!(summary.from_genesis);
+        assert!(summary.require_genesis().is_ok());
+
+        // The live file alone is a valid tail of the chain, but not a
+        // complete one
+        let tail = verify_chain(&[path], &key(&config)).unwrap();
+        assert!(!tail.from_genesis);
+        assert_eq!(tail.last_hash, summary.last_hash);
+        assert!(matches!(
+            tail.require_genesis(),
+            Err(AuditError::Incomplete(_))
+        ));
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = file_config(dir.path(), 0);
+        let path = config.path.clone().unwrap();
+        let key = key(&config);
+
+        let logger = AuditLogger::open(config).unwrap();
+        for user in ["alice", "bob", "carol"] {
+            logger.record(AuditEvent::new(AuditAction::AuthSuccess).user(user));
+        }
+        logger.flush().unwrap();
+        let original = std::fs::read_to_string(&path).unwrap();
+        assert!(verify_chain(std::slice::from_ref(&path), &key).is_ok());
+
+        // An edited record no longer matches its hash
+        std::fs::write(&path, original.replace("bob", "eve")).unwrap();
+        let err = verify_chain(std::slice::from_ref(&path), &key).unwrap_err();
+        assert!(matches!(err, AuditError::Tampered { line: 2, .. }));
+
+        // A deleted record breaks the sequence
+        let lines: Vec<_> = original.lines().collect();
+        std::fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        let err = verify_chain(std::slice::from_ref(&path), &key).unwrap_err();
+        assert!(matches!(err, AuditError::Tampered { line: 2, .. }));
+
+        // Without its first record the file only verifies as a tail
+        std::fs::write(&path, format!("{}\n{}\n", lines[1], lines[2])).unwrap();
+        assert!(
+            !verify_chain(std::slice::from_ref(&path), &key)
+                .unwrap()
+                .from_genesis
+        );
+    }
+
+    #[test]
+    fn test_chain_rewritten_without_the_key_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = file_config(dir.path(), 0);
+        let path = config.path.clone().unwrap();
+
+        // A complete, self-consistent chain signed with another key
+        let forged = AuditConfig {
+            path: Some(dir.path().join("forged.log")),
+            hmac_key_file: Some(dir.path().join("forged.key")),
+            ..config.clone()
+        };
+        let logger = AuditLogger::open(forged.clone()).unwrap();
+        logger.record(AuditEvent::new(AuditAction::AuthSuccess).user("eve"));
+        logger.flush().unwrap();
+
+        let key = key(&config);
+        std::fs::copy(forged.path.unwrap(), &path).unwrap();
+        let err = verify_chain(&[path], &key).unwrap_err();
+        assert!(matches!(err, AuditError::Tampered { line: 1, .. }));
+    }
+
+    #[test]
+    fn test_file_requires_a_strong_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AuditConfig {
+            hmac_key_file: None,
+            ..file_config(dir.path(), 0)
+        };
+        assert!(AuditLogger::open(config.clone()).is_err());
+
+        let key_file = dir.path().join("weak.key");
+        std::fs::write(&key_file, "short\n").unwrap();
+        let weak = AuditConfig {
+            hmac_key_file: Some(key_file),
+            ..config
+        };
+        assert!(AuditLogger::open(weak).is_err());
+    }
+}
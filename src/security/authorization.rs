@@ -1,15 +1,23 @@
 //! Authorization service for role-based access control
 //!
 //! Per spec-kit/011-authentication-spec.md section 5: Authorization Model
-//! Implements permission checking and resource ownership validation
+//! Implements permission checking and resource ownership validation, with
+//! conditional policy rules evaluated ahead of the role permissions
 
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::config::auth::{AuthConfig, AuthorizationConfig, ClaimMappings};
+use crate::security::access_grants::AccessGrantStore;
+use crate::security::admission::RoleResolver;
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::security::jwt_validator::Claims;
+use crate::security::policy::{self, AccessRequest, PolicyRule, RuleTrace};
+use crate::security::tenancy::Tenant;
 use crate::session::state::{SessionId, UserId};
 
 /// Authorization errors
@@ -47,6 +55,8 @@ pub enum Permission {
     ListAllSessions,
     /// Kill any session (not just own)
     KillAnySession,
+    /// Server administration (token revocation and other admin endpoints)
+    Administer,
 }
 
 impl Permission {
@@ -59,6 +69,17 @@ impl Permission {
             Self::KillSession => "kill_session",
             Self::ListAllSessions => "list_all_sessions",
             Self::KillAnySession => "kill_any_session",
+            Self::Administer => "administer",
+        }
+    }
+
+    /// Permission needed to perform this action on another user's session;
+    /// `None` when no permission allows that (e.g. typing into it)
+    pub fn any_owner(&self) -> Option<Self> {
+        match self {
+            Self::ViewSession | Self::ListAllSessions => Some(Self::ListAllSessions),
+            Self::KillSession | Self::KillAnySession => Some(Self::KillAnySession),
+            Self::CreateSession | Self::SendInput | Self::Administer => None,
         }
     }
 }
@@ -93,6 +114,7 @@ impl Role {
                 Permission::KillSession,
                 Permission::ListAllSessions,
                 Permission::KillAnySession,
+                Permission::Administer,
             ],
             Self::User => vec![
                 Permission::CreateSession,
@@ -125,6 +147,18 @@ fn default_true() -> bool {
     true
 }
 
+impl OwnershipRules {
+    /// Whether owners may perform `permission` on their own sessions
+    fn allows(&self, permission: Permission) -> bool {
+        match permission {
+            Permission::ViewSession => self.own_sessions_view,
+            Permission::KillSession => self.own_sessions_kill,
+            Permission::SendInput => self.own_sessions_input,
+            _ => true,
+        }
+    }
+}
+
 impl Default for OwnershipRules {
     fn default() -> Self {
         Self {
@@ -146,9 +180,13 @@ pub struct PermissionRules {
     #[serde(default)]
     pub ownership_rules: OwnershipRules,
 
-    /// Default permissions for all authenticated users
+    /// Permissions of users whose role is not listed in `role_permissions`
     #[serde(default)]
     pub default_permissions: Vec<Permission>,
+
+    /// Conditional rules evaluated in order before the role permissions
+    #[serde(default)]
+    pub policies: Vec<PolicyRule>,
 }
 
 impl Default for PermissionRules {
@@ -162,6 +200,7 @@ impl Default for PermissionRules {
             role_permissions,
             ownership_rules: OwnershipRules::default(),
             default_permissions: vec![Permission::CreateSession, Permission::ViewSession],
+            policies: Vec::new(),
         }
     }
 }
@@ -179,17 +218,162 @@ impl PermissionRules {
     }
 }
 
+/// Outcome of an authorization check, as reported by the explain API
+#[derive(Debug, Clone, Serialize)]
+pub struct Decision {
+    pub permission: Permission,
+    pub allowed: bool,
+    /// Policy rule that decided, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// Role that granted the permission when no rule applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    pub reason: String,
+    /// Policy rules considered, in order
+    pub trace: Vec<RuleTrace>,
+}
+
+impl Decision {
+    /// A denial made before any rule was consulted
+    pub fn denied(permission: Permission, reason: impl Into<String>) -> Self {
+        Self {
+            permission,
+            allowed: false,
+            rule: None,
+            role: None,
+            reason: reason.into(),
+            trace: Vec::new(),
+        }
+    }
+}
+
 /// Authorization service for checking permissions
+///
+/// Rules, role mapping and claim settings are swapped as a whole on reload
+/// (see [`Self::replace`]); every check works on one consistent snapshot.
 pub struct AuthorizationService {
-    rules: Arc<PermissionRules>,
+    state: RwLock<Arc<AuthorizationState>>,
+    audit: Arc<AuditLogger>,
+    /// Access to other users' sessions approved by their owners
+    grants: Option<Arc<AccessGrantStore>>,
+}
+
+/// Everything an authorization decision depends on
+struct AuthorizationState {
+    rules: PermissionRules,
+    /// Rules of tenants with their own permissions file
+    tenant_rules: HashMap<String, PermissionRules>,
+    roles: RoleResolver,
+    claims: ClaimMappings,
 }
 
 impl AuthorizationService {
     /// Create a new authorization service with given rules
     pub fn new(rules: PermissionRules) -> Self {
+        Self::with_state(AuthorizationState {
+            rules,
+            tenant_rules: HashMap::new(),
+            roles: RoleResolver::default(),
+            claims: ClaimMappings::default(),
+        })
+    }
+
+    fn with_state(state: AuthorizationState) -> Self {
         Self {
-            rules: Arc::new(rules),
+            state: RwLock::new(Arc::new(state)),
+            audit: Arc::new(AuditLogger::default()),
+            grants: None,
+        }
+    }
+
+    /// Record refused actions in `audit`
+    /// Per spec-kit/011-authentication-spec.md: Audit logging
+    pub fn with_audit(mut self, audit: Arc<AuditLogger>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Allow what session owners granted through approved access requests
+    /// Per spec-kit/011-authentication-spec.md: Just-in-Time Access
+    pub fn with_access_grants(mut self, grants: Arc<AccessGrantStore>) -> Self {
+        self.grants = Some(grants);
+        self
+    }
+
+    /// Audit an action refused to `user_id`
+    pub fn record_denial(&self, user_id: &UserId, decision: &Decision) {
+        self.audit.record(
+            AuditEvent::new(AuditAction::PermissionDenied)
+                .user(user_id.as_str())
+                .detail(format!(
+                    "{}: {}",
+                    decision.permission.as_str(),
+                    decision.reason
+                )),
+        );
+    }
+
+    fn state(&self) -> Arc<AuthorizationState> {
+        self.state
+            .read()
+            .expect("authorization lock poisoned")
+            .clone()
+    }
+
+    /// Take over the rules and settings of `next`; decisions already being
+    /// made finish with the previous ones
+    /// Per spec-kit/011-authentication-spec.md: Configuration Reload
+    pub fn replace(&self, next: AuthorizationService) {
+        let next = next.state();
+        *self.state.write().expect("authorization lock poisoned") = next;
+    }
+
+    /// Number of role permission entries and policy rules in effect
+    pub fn rule_counts(&self) -> (usize, usize) {
+        let state = self.state();
+        (
+            state.rules.role_permissions.len(),
+            state.rules.policies.len(),
+        )
+    }
+
+    /// Create the service for `config`: rules from its permissions file (or
+    /// the defaults) and roles resolved from its claim settings
+    /// Per spec-kit/011-authentication-spec.md section 5: Authorization Model
+    pub fn from_auth_config(config: &AuthorizationConfig) -> Result<Self> {
+        Ok(Self::with_state(AuthorizationState::new(config)?))
+    }
+
+    /// Create the service for `config`'s authorization settings, with the
+    /// rules of each tenant that has its own permissions file
+    /// Per spec-kit/011-authentication-spec.md: Multi-Tenancy
+    pub fn from_config(config: &AuthConfig) -> Result<Self> {
+        let mut state = AuthorizationState::new(&config.authorization)?;
+        for tenant in &config.tenancy.tenants {
+            if let Some(path) = &tenant.permissions_file {
+                state
+                    .tenant_rules
+                    .insert(tenant.name.clone(), PermissionRules::from_yaml_file(path)?);
+            }
         }
+        Ok(Self::with_state(state))
+    }
+
+    /// A service that grants nothing, for when the configured rules cannot
+    /// be loaded
+    pub fn deny_all() -> Self {
+        Self::new(PermissionRules {
+            role_permissions: HashMap::new(),
+            ownership_rules: OwnershipRules::default(),
+            default_permissions: Vec::new(),
+            policies: Vec::new(),
+        })
+    }
+
+    /// Roles the holder of `claims` acts with
+    pub fn resolve_roles(&self, claims: &Claims) -> Vec<String> {
+        self.state().roles.resolve(claims)
     }
 
     /// Create authorization service from config file
@@ -205,6 +389,10 @@ impl AuthorizationService {
 
     /// Check if user has a specific permission
     ///
+    /// On another user's resource the permission's [`Permission::any_owner`]
+    /// counterpart is required instead; on their own resource users need the
+    /// permission itself (subject to the ownership rules) or its counterpart.
+    ///
     /// # Arguments
     /// * `user_id` - User identifier
     /// * `role` - User's role
@@ -217,42 +405,50 @@ impl AuthorizationService {
         permission: Permission,
         resource_owner: Option<&UserId>,
     ) -> Result<()> {
-        // Check role-based permissions
-        if let Some(role_perms) = self.rules.role_permissions.get(role) {
-            if role_perms.contains(&permission) {
-                return Ok(());
-            }
-        }
+        self.state()
+            .rules
+            .check_permission(user_id, role, permission, resource_owner)
+    }
 
-        // Check default permissions
-        if self.rules.default_permissions.contains(&permission) {
-            return Ok(());
+    /// Decide whether `request` may exercise `permission`
+    ///
+    /// The first policy rule that applies decides; otherwise any of the
+    /// request's roles granting the permission (see
+    /// [`Self::check_permission`]) allows it, and failing that an approved
+    /// access request for the session.
+    /// Per spec-kit/011-authentication-spec.md: Policy Rules
+    pub fn evaluate(&self, permission: Permission, request: &AccessRequest<'_>) -> Decision {
+        let decision = self.state().evaluate(permission, request);
+        if decision.allowed || decision.rule.is_some() {
+            return decision;
         }
-
-        // Check ownership-based permissions
-        if let Some(owner) = resource_owner {
-            if user_id == owner {
-                match permission {
-                    Permission::ViewSession if self.rules.ownership_rules.own_sessions_view => {
-                        return Ok(())
-                    }
-                    Permission::KillSession if self.rules.ownership_rules.own_sessions_kill => {
-                        return Ok(())
-                    }
-                    Permission::SendInput if self.rules.ownership_rules.own_sessions_input => {
-                        return Ok(())
-                    }
-                    _ => {}
-                }
-            }
+        match self.granted(permission, request) {
+            Some(reason) => Decision {
+                allowed: true,
+                reason,
+                ..decision
+            },
+            None => decision,
         }
+    }
 
-        Err(AuthorizationError::PermissionDenied(format!(
-            "User {} with role {} does not have permission {}",
-            user_id.as_str(),
-            role,
-            permission.as_str()
-        )))
+    /// Why the session owner's approval lets `request` exercise
+    /// `permission`, if it does
+    /// Per spec-kit/011-authentication-spec.md: Just-in-Time Access
+    fn granted(&self, permission: Permission, request: &AccessRequest<'_>) -> Option<String> {
+        let grants = self.grants.as_ref()?;
+        let session = request.resource.session?;
+        if request.resource.owner? == request.user_id {
+            return None;
+        }
+        let (id, level) = grants.active_level(request.user_id, session)?;
+        level.permits(permission).then(|| {
+            format!(
+                "Granted {} access by the session owner (request {})",
+                level.as_str(),
+                id
+            )
+        })
     }
 
     /// Check if user owns a session
@@ -270,11 +466,7 @@ impl AuthorizationService {
 
     /// Get all permissions for a role
     pub fn get_role_permissions(&self, role: &str) -> Vec<Permission> {
-        self.rules
-            .role_permissions
-            .get(role)
-            .cloned()
-            .unwrap_or_else(|| self.rules.default_permissions.clone())
+        self.state().rules.role_permissions(role)
     }
 
     /// Check if user can perform action on session
@@ -291,9 +483,153 @@ impl AuthorizationService {
     }
 }
 
+impl PermissionRules {
+    fn role_permissions(&self, role: &str) -> Vec<Permission> {
+        self.role_permissions
+            .get(role)
+            .cloned()
+            .unwrap_or_else(|| self.default_permissions.clone())
+    }
+
+    fn check_permission(
+        &self,
+        user_id: &UserId,
+        role: &str,
+        permission: Permission,
+        resource_owner: Option<&UserId>,
+    ) -> Result<()> {
+        let granted = self.role_permissions(role);
+        let any_owner = permission
+            .any_owner()
+            .is_some_and(|any| granted.contains(&any));
+
+        let allowed = match resource_owner {
+            Some(owner) if owner != user_id => any_owner,
+            Some(_) => {
+                any_owner
+                    || (granted.contains(&permission) && self.ownership_rules.allows(permission))
+            }
+            None => granted.contains(&permission),
+        };
+        if allowed {
+            return Ok(());
+        }
+
+        Err(AuthorizationError::PermissionDenied(match resource_owner {
+            Some(owner) if owner != user_id => format!(
+                "User {} with role {} may not {} on a session owned by {}",
+                user_id.as_str(),
+                role,
+                permission.as_str(),
+                owner.as_str()
+            ),
+            _ => format!(
+                "User {} with role {} does not have permission {}",
+                user_id.as_str(),
+                role,
+                permission.as_str()
+            ),
+        }))
+    }
+}
+
+impl AuthorizationState {
+    fn new(config: &AuthorizationConfig) -> Result<Self> {
+        let rules = match &config.permissions_file {
+            Some(path) => PermissionRules::from_yaml_file(path)?,
+            None => PermissionRules::default(),
+        };
+        Ok(Self {
+            rules,
+            tenant_rules: HashMap::new(),
+            roles: RoleResolver::new(config),
+            claims: config.claims.clone(),
+        })
+    }
+
+    /// Rules for users of `tenant`: its own when it has a permissions file
+    fn rules_for(&self, tenant: Option<&Tenant>) -> &PermissionRules {
+        tenant
+            .and_then(|tenant| self.tenant_rules.get(&tenant.name))
+            .unwrap_or(&self.rules)
+    }
+
+    fn evaluate(&self, permission: Permission, request: &AccessRequest<'_>) -> Decision {
+        // Sessions of other tenants are out of reach unless the user's
+        // tenant is an operator tenant
+        if let (Some(tenant), Some(other)) = (request.tenant, request.resource.tenant) {
+            if !tenant.manages(Some(other)) {
+                return Decision::denied(
+                    permission,
+                    format!(
+                        "User {} of tenant {} may not {} on a session of tenant {}",
+                        request.user_id.as_str(),
+                        tenant.name,
+                        permission.as_str(),
+                        other
+                    ),
+                );
+            }
+        }
+
+        let rules = self.rules_for(request.tenant);
+        let (rule, trace) = policy::first_match(&rules.policies, permission, request, &self.claims);
+        if let Some(rule) = rule {
+            let allowed = rule.effect == policy::PolicyEffect::Allow;
+            return Decision {
+                permission,
+                allowed,
+                rule: Some(rule.name.clone()),
+                role: None,
+                reason: format!(
+                    "{} by policy rule '{}'",
+                    if allowed { "Allowed" } else { "Denied" },
+                    rule.name
+                ),
+                trace,
+            };
+        }
+
+        // No roles means only the default permissions
+        let default_role = [String::new()];
+        let roles: &[String] = if request.roles.is_empty() {
+            &default_role
+        } else {
+            request.roles
+        };
+        let mut reason = String::new();
+        for role in roles {
+            match rules.check_permission(request.user_id, role, permission, request.resource.owner)
+            {
+                Ok(()) => {
+                    return Decision {
+                        permission,
+                        allowed: true,
+                        rule: None,
+                        role: Some(role.clone()).filter(|role| !role.is_empty()),
+                        reason: if role.is_empty() {
+                            "Granted by the default permissions".to_string()
+                        } else {
+                            format!("Granted by role '{}'", role)
+                        },
+                        trace,
+                    }
+                }
+                Err(e) => reason = e.to_string(),
+            }
+        }
+
+        Decision {
+            trace,
+            ..Decision::denied(permission, reason)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::security::testing::claims;
 
     fn test_user() -> UserId {
         UserId::new("user:default/alice".to_string())
@@ -410,6 +746,214 @@ mod tests {
         assert!(service.check_session_ownership(&user, &other).is_err());
     }
 
+    #[test]
+    fn test_policy_rules_precede_roles() {
+        use crate::security::policy::{AccessRequest, Resource};
+
+        let rules: PermissionRules = serde_yaml::from_str(
+            r#"
+policies:
+  - name: oncall-kill
+    effect: allow
+    permissions: [kill_session]
+    when:
+      groups: ["group:default/oncall"]
+  - name: no-readonly-views
+    effect: deny
+    permissions: [view_session]
+    when:
+      roles: [readonly]
+"#,
+        )
+        .unwrap();
+        // Unset sections keep their defaults
+        assert!(rules.ownership_rules.own_sessions_input);
+        let service = AuthorizationService::new(PermissionRules {
+            role_permissions: PermissionRules::default().role_permissions,
+            ..rules
+        });
+
+        let user = test_user();
+        let other = test_other_user();
+        let claims = claims(user.as_str(), &["group:default/oncall"]);
+        let ungrouped = Claims {
+            ent: None,
+            ..claims.clone()
+        };
+        let request = |claims, roles, owner| AccessRequest {
+            user_id: &user,
+            claims,
+            roles,
+            source_ip: None,
+            at: chrono::Utc::now(),
+            tenant: None,
+            resource: Resource::owned_by(owner),
+        };
+        let user_role = ["user".to_string()];
+        let readonly_role = ["readonly".to_string()];
+
+        let decision = service.evaluate(
+            Permission::KillSession,
+            &request(&claims, &user_role, Some(&other)),
+        );
+        assert!(decision.allowed);
+        assert_eq!(decision.rule.as_deref(), Some("oncall-kill"));
+
+        let decision = service.evaluate(
+            Permission::ViewSession,
+            &request(&claims, &readonly_role, Some(&user)),
+        );
+        assert!(!decision.allowed);
+        assert_eq!(decision.rule.as_deref(), Some("no-readonly-views"));
+        assert_eq!(decision.trace.len(), 2);
+
+        // Without a matching rule the roles decide
+        let decision = service.evaluate(
+            Permission::KillSession,
+            &request(&ungrouped, &user_role, Some(&other)),
+        );
+        assert!(!decision.allowed);
+        assert_eq!(decision.rule, None);
+        let decision = service.evaluate(
+            Permission::KillSession,
+            &request(&ungrouped, &user_role, Some(&user)),
+        );
+        assert!(decision.allowed);
+        assert_eq!(decision.role.as_deref(), Some("user"));
+    }
+
+    #[test]
+    fn test_tenant_isolation_and_rules() {
+        use crate::security::policy::{AccessRequest, Resource};
+        use crate::security::tenancy::Tenant;
+
+        let dir = tempfile::tempdir().unwrap();
+        let globex_rules = dir.path().join("globex.yaml");
+        std::fs::write(
+            &globex_rules,
+            "role_permissions:\n  admin: [view_session, list_all_sessions]\n",
+        )
+        .unwrap();
+        let config: AuthConfig = serde_json::from_value(serde_json::json!({
+            "jwks": { "providers": [] },
+            "authorization": {},
+            "tenancy": {
+                "enabled": true,
+                "tenants": [
+                    { "name": "acme" },
+                    { "name": "globex", "permissions_file": globex_rules },
+                    { "name": "ops", "operator": true },
+                ],
+            },
+        }))
+        .unwrap();
+        let service = AuthorizationService::from_config(&config).unwrap();
+
+        let tenant = |name: &str| Tenant {
+            name: name.to_string(),
+            operator: name == "ops",
+        };
+        let (acme, globex, ops) = (tenant("acme"), tenant("globex"), tenant("ops"));
+        let admin = UserId::new("acme/admin".to_string());
+        let owner = UserId::new("globex/alice".to_string());
+        let claims = claims("admin", &[]);
+        let admin_role = ["admin".to_string()];
+        let request = |tenant, resource_tenant| AccessRequest {
+            user_id: &admin,
+            claims: &claims,
+            roles: &admin_role,
+            source_ip: None,
+            at: chrono::Utc::now(),
+            tenant: Some(tenant),
+            resource: Resource {
+                tenant: resource_tenant,
+                ..Resource::owned_by(Some(&owner))
+            },
+        };
+
+        // Admins reach sessions of their own tenant, operators every tenant
+        let decision = service.evaluate(Permission::KillSession, &request(&acme, Some("globex")));
+        assert!(!decision.allowed);
+        assert!(decision.reason.contains("tenant globex"));
+        assert!(
+            service
+                .evaluate(Permission::KillSession, &request(&acme, Some("acme")))
+                .allowed
+        );
+        assert!(
+            service
+                .evaluate(Permission::KillSession, &request(&ops, Some("globex")))
+                .allowed
+        );
+
+        // Tenants with a permissions file use their own rules
+        assert!(
+            !service
+                .evaluate(Permission::KillSession, &request(&globex, Some("globex")))
+                .allowed
+        );
+        assert!(
+            service
+                .evaluate(Permission::ViewSession, &request(&globex, Some("globex")))
+                .allowed
+        );
+    }
+
+    #[test]
+    fn test_approved_access_request_grants_session() {
+        use crate::config::auth::AccessRequestConfig;
+        use crate::security::access_grants::{AccessLevel, NewAccessRequest};
+        use crate::security::policy::Resource;
+        use crate::session::state::Session;
+
+        let grants = Arc::new(AccessGrantStore::new(AccessRequestConfig::default()));
+        let service = AuthorizationService::with_defaults().with_access_grants(grants.clone());
+        let session = Session::new(test_user(), std::path::PathBuf::from("/tmp"));
+        let bob = test_other_user();
+        let claims = claims(bob.as_str(), &[]);
+        let user_role = ["user".to_string()];
+        let evaluate = |permission| {
+            service.evaluate(
+                permission,
+                &AccessRequest {
+                    user_id: &bob,
+                    claims: &claims,
+                    roles: &user_role,
+                    source_ip: None,
+                    at: chrono::Utc::now(),
+                    tenant: None,
+                    resource: Resource::session(&session),
+                },
+            )
+        };
+        assert!(!evaluate(Permission::ViewSession).allowed);
+
+        let request = grants
+            .request(
+                &bob,
+                None,
+                &session,
+                NewAccessRequest {
+                    level: AccessLevel::View,
+                    duration: None,
+                    reason: None,
+                },
+            )
+            .unwrap();
+        assert!(!evaluate(Permission::ViewSession).allowed);
+
+        grants.decide(&request.id, &test_user(), true).unwrap();
+        let decision = evaluate(Permission::ViewSession);
+        assert!(decision.allowed);
+        assert!(decision.reason.contains(&request.id));
+        // View access does not extend to typing or killing
+        assert!(!evaluate(Permission::SendInput).allowed);
+        assert!(!evaluate(Permission::KillSession).allowed);
+
+        grants.revoke(&request.id, &bob).unwrap();
+        assert!(!evaluate(Permission::ViewSession).allowed);
+    }
+
     #[test]
     fn test_get_role_permissions() {
         let service = AuthorizationService::with_defaults();
@@ -2,16 +2,21 @@
 // Responsibilities:
 // - Fetch JWKS from configured providers
 // - Cache public keys with TTL
-// - Handle JWKS endpoint failures gracefully
+// - Handle JWKS endpoint failures gracefully (serve stale keys up to max_stale)
+// - Refresh on unknown kid (rate limited) and remember kids still unknown
 // - Support multiple JWKS providers
+// - Discover JWKS endpoints via OpenID Connect discovery
 
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::time::sleep;
 
+use crate::monitoring::metrics;
+
 /// JWKS client error types
 #[derive(Error, Debug)]
 pub enum JwksError {
@@ -32,6 +37,9 @@ pub enum JwksError {
 
     #[error("Cache expired and refresh failed")]
     CacheExpired,
+
+    #[error("OIDC discovery failed for {provider}: {reason}")]
+    DiscoveryFailed { provider: String, reason: String },
 }
 
 /// JSON Web Key as defined in RFC 7517
@@ -53,6 +61,26 @@ struct JwksResponse {
     keys: Vec<JsonWebKey>,
 }
 
+/// OpenID Connect provider metadata (the fields we use)
+/// Per OpenID Connect Discovery 1.0 section 3
+#[derive(Debug, Deserialize)]
+struct OpenIdConfiguration {
+    issuer: String,
+    jwks_uri: String,
+    #[serde(default)]
+    id_token_signing_alg_values_supported: Vec<String>,
+}
+
+/// Path of the discovery document relative to the issuer
+const OPENID_CONFIGURATION_PATH: &str = "/.well-known/openid-configuration";
+
+/// Default background refresh interval
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(900); // 15 minutes
+
+/// Unknown kids remembered across providers; beyond this, expired entries
+/// are swept and then the one expiring soonest is evicted
+const MAX_UNKNOWN_KIDS: usize = 1_000;
+
 /// Cached JWKS with expiration metadata
 /// Per 011-authentication-spec.md section 3.3: Cache and Refresh Strategy
 #[derive(Debug, Clone)]
@@ -60,15 +88,34 @@ struct CachedJwks {
     keys: Vec<JsonWebKey>,
     fetched_at: Instant,
     expires_at: Instant,
+    /// Refreshes failed since the last successful fetch
+    consecutive_failures: u32,
+    last_error: Option<String>,
 }
 
 /// Provider configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct JwksProvider {
     pub name: String,
+    /// JWKS endpoint (empty until discovered)
     pub jwks_url: String,
     pub issuer: String,
+    /// Audience tokens must be issued for
+    pub audience: String,
+    /// Allowed signing algorithms
+    pub algorithms: Vec<String>,
     pub cache_ttl: Duration,
+    /// OpenID configuration URL, for providers configured by issuer only
+    pub discovery_url: Option<String>,
+}
+
+impl JwksProvider {
+    /// Whether tokens signed with `algorithm` are accepted from this provider
+    pub fn allows_algorithm(&self, algorithm: &str) -> bool {
+        self.algorithms
+            .iter()
+            .any(|a| a.eq_ignore_ascii_case(algorithm))
+    }
 }
 
 /// JWKS client with caching and background refresh
@@ -76,8 +123,18 @@ pub struct JwksProvider {
 pub struct JwksClient {
     http_client: reqwest::Client,
     cache: Arc<DashMap<String, CachedJwks>>,
-    providers: Arc<Vec<JwksProvider>>,
+    providers: Arc<RwLock<Vec<JwksProvider>>>,
+    /// Providers as configured, before discovery filled them in
+    configured: RwLock<Vec<JwksProvider>>,
     default_ttl: Duration,
+    refresh_interval: Duration,
+    /// Last fetch attempt per provider, for rate limiting on-demand refreshes
+    last_attempt: DashMap<String, Instant>,
+    /// Kids still unknown after a refresh, per (provider, kid), until when
+    unknown_kids: DashMap<(String, String), Instant>,
+    max_stale: Duration,
+    min_refresh_interval: Duration,
+    negative_cache_ttl: Duration,
 }
 
 impl JwksClient {
@@ -89,57 +146,314 @@ impl JwksClient {
             .build()
             .expect("Failed to create HTTP client");
 
-        // Convert config providers to internal providers
-        let providers: Vec<JwksProvider> = auth_config
+        // Refresh as often as the most eager provider asks for
+        let refresh_interval = auth_config
             .jwks
             .providers
-            .into_iter()
-            .map(|p| JwksProvider {
-                name: p.name,
-                jwks_url: p.url, // Config field is 'url'
-                issuer: p.issuer,
-                cache_ttl: auth_config.validation.cache_ttl(),
-            })
-            .collect();
+            .iter()
+            .filter(|p| p.keys.is_none())
+            .map(|p| p.refresh_interval)
+            .min()
+            .unwrap_or(DEFAULT_REFRESH_INTERVAL);
+
+        let providers = Self::configured_providers(&auth_config);
 
         Self {
             http_client,
             cache: Arc::new(DashMap::new()),
-            providers: Arc::new(providers),
+            providers: Arc::new(RwLock::new(providers.clone())),
+            configured: RwLock::new(providers),
             default_ttl: auth_config.validation.cache_ttl(),
+            refresh_interval,
+            last_attempt: DashMap::new(),
+            unknown_kids: DashMap::new(),
+            max_stale: auth_config.jwks.max_stale,
+            min_refresh_interval: auth_config.jwks.min_refresh_interval,
+            negative_cache_ttl: auth_config.jwks.negative_cache_ttl,
+        }
+    }
+
+    /// Convert config providers to internal providers; providers without a
+    /// JWKS URL are resolved through OIDC discovery on first use, and
+    /// providers with local keys are served by the static key store
+    fn configured_providers(auth_config: &crate::config::AuthConfig) -> Vec<JwksProvider> {
+        auth_config
+            .jwks
+            .providers
+            .iter()
+            .filter(|p| p.keys.is_none())
+            .map(|p| JwksProvider {
+                name: p.name.clone(),
+                discovery_url: p.url.is_none().then(|| {
+                    format!(
+                        "{}{}",
+                        p.issuer.trim_end_matches('/'),
+                        OPENID_CONFIGURATION_PATH
+                    )
+                }),
+                jwks_url: p.url.clone().unwrap_or_default(), // Config field is 'url'
+                issuer: p.issuer.clone(),
+                audience: p.audience.clone(),
+                algorithms: p.algorithms.clone(),
+                cache_ttl: auth_config.validation.cache_ttl(),
+            })
+            .collect()
+    }
+
+    /// Replace the provider definitions with those of `auth_config`
+    ///
+    /// Providers whose definition is unchanged keep their discovered
+    /// settings and cached keys; new or changed ones start over (discovery
+    /// and key fetch on first use), and removed ones are dropped together
+    /// with their keys. The refresh interval stays as configured at startup.
+    /// Per 011-authentication-spec.md: Configuration Reload
+    pub fn set_providers(&self, auth_config: &crate::config::AuthConfig) {
+        let next = Self::configured_providers(auth_config);
+        let mut configured = self
+            .configured
+            .write()
+            .expect("JWKS provider lock poisoned");
+        let mut providers = self.providers.write().expect("JWKS provider lock poisoned");
+
+        let live: Vec<JwksProvider> = next
+            .iter()
+            .map(|provider| {
+                let unchanged = configured.contains(provider);
+                match providers.iter().find(|p| p.name == provider.name) {
+                    Some(current) if unchanged => current.clone(),
+                    _ => {
+                        self.forget(&provider.name);
+                        provider.clone()
+                    }
+                }
+            })
+            .collect();
+        let is_live = |name: &String| live.iter().any(|provider| &provider.name == name);
+        self.cache.retain(|name, _| is_live(name));
+        self.last_attempt.retain(|name, _| is_live(name));
+        self.unknown_kids.retain(|(name, _), _| is_live(name));
+
+        tracing::info!(providers = live.len(), "JWKS provider definitions replaced");
+        *providers = live;
+        *configured = next;
+    }
+
+    /// Drop everything cached for a provider
+    fn forget(&self, provider_name: &str) {
+        self.cache.remove(provider_name);
+        self.last_attempt.remove(provider_name);
+        self.unknown_kids
+            .retain(|(name, _), _| name != provider_name);
+    }
+
+    /// Snapshot of a provider's current settings
+    fn provider(&self, provider_name: &str) -> Result<JwksProvider, JwksError> {
+        self.providers
+            .read()
+            .expect("JWKS provider lock poisoned")
+            .iter()
+            .find(|p| p.name == provider_name)
+            .cloned()
+            .ok_or_else(|| JwksError::ProviderNotConfigured(provider_name.to_string()))
+    }
+
+    /// Resolve a provider's JWKS endpoint, issuer and algorithms from its
+    /// OpenID configuration
+    /// Per OpenID Connect Discovery 1.0 section 4
+    pub async fn discover(&self, provider_name: &str) -> Result<JwksProvider, JwksError> {
+        let provider = self.provider(provider_name)?;
+        let Some(discovery_url) = provider.discovery_url.clone() else {
+            return Ok(provider);
+        };
+        let failed = |reason: String| JwksError::DiscoveryFailed {
+            provider: provider_name.to_string(),
+            reason,
+        };
+
+        tracing::info!(
+            provider = provider_name,
+            url = %discovery_url,
+            "Fetching OpenID configuration"
+        );
+
+        let response = self.http_client.get(&discovery_url).send().await?;
+        if !response.status().is_success() {
+            return Err(failed(format!(
+                "HTTP {} from {}",
+                response.status(),
+                discovery_url
+            )));
+        }
+        let metadata: OpenIdConfiguration = response.json().await?;
+
+        // The document must describe the issuer we were configured with
+        if metadata.issuer.trim_end_matches('/') != provider.issuer.trim_end_matches('/') {
+            return Err(failed(format!(
+                "issuer mismatch: configured {}, discovered {}",
+                provider.issuer, metadata.issuer
+            )));
+        }
+
+        // Never widen the configured algorithms, only narrow them
+        let algorithms = if metadata.id_token_signing_alg_values_supported.is_empty() {
+            provider.algorithms.clone()
+        } else {
+            metadata
+                .id_token_signing_alg_values_supported
+                .iter()
+                .filter(|alg| provider.allows_algorithm(alg))
+                .cloned()
+                .collect()
+        };
+        if algorithms.is_empty() {
+            return Err(failed(format!(
+                "no allowed signing algorithm among {:?}",
+                metadata.id_token_signing_alg_values_supported
+            )));
+        }
+
+        let discovered = JwksProvider {
+            jwks_url: metadata.jwks_uri,
+            issuer: metadata.issuer,
+            algorithms,
+            ..provider
+        };
+
+        tracing::info!(
+            provider = provider_name,
+            jwks_url = %discovered.jwks_url,
+            algorithms = ?discovered.algorithms,
+            "OIDC discovery successful"
+        );
+
+        let mut providers = self.providers.write().expect("JWKS provider lock poisoned");
+        if let Some(entry) = providers.iter_mut().find(|p| p.name == provider_name) {
+            *entry = discovered.clone();
+        }
+
+        Ok(discovered)
+    }
+
+    /// Run discovery for providers whose JWKS endpoint is not yet known
+    pub async fn discover_pending(&self) {
+        let pending: Vec<String> = self
+            .providers
+            .read()
+            .expect("JWKS provider lock poisoned")
+            .iter()
+            .filter(|p| p.discovery_url.is_some() && p.jwks_url.is_empty())
+            .map(|p| p.name.clone())
+            .collect();
+
+        for name in pending {
+            if let Err(e) = self.discover(&name).await {
+                tracing::warn!(provider = %name, error = %e, "OIDC discovery failed");
+            }
         }
     }
 
     /// Fetch JWKS keys for a provider (cache-first strategy)
+    ///
+    /// Expired keys are refreshed at most once per `min_refresh_interval`;
+    /// while the provider cannot be reached they are served for up to
+    /// `max_stale` past their expiry.
     /// Per 011-authentication-spec.md section 3.3: Cache and Refresh Strategy
     pub async fn fetch_keys(&self, provider_name: &str) -> Result<Vec<JsonWebKey>, JwksError> {
         // Check cache first
-        if let Some(cached) = self.cache.get(provider_name) {
-            if cached.expires_at > Instant::now() {
-                tracing::debug!(
-                    provider = provider_name,
-                    keys_count = cached.keys.len(),
-                    "JWKS cache hit"
-                );
-                return Ok(cached.keys.clone());
+        let cached = self.cache.get(provider_name).map(|entry| entry.clone());
+        let Some(cached) = cached else {
+            // Cache miss - fetch from provider
+            return self.fetch_keys_from_provider(provider_name).await;
+        };
+
+        let now = Instant::now();
+        if cached.expires_at > now {
+            tracing::debug!(
+                provider = provider_name,
+                keys_count = cached.keys.len(),
+                "JWKS cache hit"
+            );
+            return Ok(cached.keys);
+        }
+        tracing::debug!(provider = provider_name, "JWKS cache expired");
+
+        let error = if self.begin_refresh(provider_name) {
+            match self.fetch_keys_from_provider(provider_name).await {
+                Ok(keys) => return Ok(keys),
+                Err(e) => e.to_string(),
             }
-            tracing::debug!(provider = provider_name, "JWKS cache expired");
+        } else {
+            "refresh rate limited".to_string()
+        };
+
+        if now.duration_since(cached.expires_at) <= self.max_stale {
+            tracing::warn!(
+                provider = provider_name,
+                error = %error,
+                stale_seconds = now.duration_since(cached.expires_at).as_secs(),
+                "Serving stale JWKS keys"
+            );
+            metrics::JWKS_STALE_SERVED_TOTAL
+                .with_label_values(&[provider_name])
+                .inc();
+            return Ok(cached.keys);
         }
 
-        // Cache miss or expired - fetch from provider
-        self.fetch_keys_from_provider(provider_name).await
+        Err(JwksError::CacheExpired)
+    }
+
+    /// Claim the right to fetch a provider's keys now; false while the
+    /// previous attempt is less than `min_refresh_interval` old
+    fn begin_refresh(&self, provider_name: &str) -> bool {
+        let now = Instant::now();
+        match self.last_attempt.entry(provider_name.to_string()) {
+            Entry::Occupied(mut last) => {
+                if now.duration_since(*last.get()) < self.min_refresh_interval {
+                    return false;
+                }
+                last.insert(now);
+            }
+            Entry::Vacant(last) => {
+                last.insert(now);
+            }
+        }
+        true
     }
 
-    /// Fetch JWKS from provider's HTTP endpoint
+    /// Fetch JWKS from provider's HTTP endpoint, recording the outcome
     async fn fetch_keys_from_provider(
         &self,
         provider_name: &str,
     ) -> Result<Vec<JsonWebKey>, JwksError> {
-        let provider = self
-            .providers
-            .iter()
-            .find(|p| p.name == provider_name)
-            .ok_or_else(|| JwksError::ProviderNotConfigured(provider_name.to_string()))?;
+        self.last_attempt
+            .insert(provider_name.to_string(), Instant::now());
+
+        match self.download_keys(provider_name).await {
+            Ok(keys) => {
+                metrics::JWKS_REFRESH_TOTAL
+                    .with_label_values(&[provider_name, "success"])
+                    .inc();
+                Ok(keys)
+            }
+            Err(e) => {
+                metrics::JWKS_REFRESH_TOTAL
+                    .with_label_values(&[provider_name, "failure"])
+                    .inc();
+                if let Some(mut cached) = self.cache.get_mut(provider_name) {
+                    cached.consecutive_failures += 1;
+                    cached.last_error = Some(e.to_string());
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Download and cache a provider's keys
+    async fn download_keys(&self, provider_name: &str) -> Result<Vec<JsonWebKey>, JwksError> {
+        let mut provider = self.provider(provider_name)?;
+        if provider.jwks_url.is_empty() {
+            provider = self.discover(provider_name).await?;
+        }
 
         tracing::info!(
             provider = provider_name,
@@ -169,42 +483,141 @@ impl JwksClient {
             "Successfully fetched JWKS"
         );
 
-        // Cache the keys
+        // Cache the keys; kids that have appeared are no longer unknown
         let cached = CachedJwks {
             keys: jwks.keys.clone(),
             fetched_at: Instant::now(),
             expires_at: Instant::now() + provider.cache_ttl,
+            consecutive_failures: 0,
+            last_error: None,
         };
 
         self.cache.insert(provider_name.to_string(), cached);
+        self.unknown_kids.retain(|(name, kid), _| {
+            name != provider_name || !jwks.keys.iter().any(|key| &key.kid == kid)
+        });
 
         Ok(jwks.keys)
     }
 
     /// Get a specific key by kid
+    ///
+    /// A kid missing from the cached keys triggers a refresh (rate limited
+    /// by `min_refresh_interval`), so rotated keys are picked up before the
+    /// next scheduled refresh. A kid missing from the refreshed keys is
+    /// rejected without fetching for `negative_cache_ttl`; one that could not
+    /// be checked (refresh failed or rate limited) is not remembered.
     /// Per 011-authentication-spec.md section 2.1: JWKS Client
     pub async fn get_key(
         &self,
         kid: &str,
         provider_name: &str,
     ) -> Result<Option<JsonWebKey>, JwksError> {
+        let negative_key = (provider_name.to_string(), kid.to_string());
+        if let Some(until) = self.unknown_kids.get(&negative_key).map(|until| *until) {
+            if until > Instant::now() {
+                metrics::JWKS_UNKNOWN_KID_TOTAL
+                    .with_label_values(&[provider_name, "negative_cached"])
+                    .inc();
+                return Ok(None);
+            }
+            self.unknown_kids.remove(&negative_key);
+        }
+
         let keys = self.fetch_keys(provider_name).await?;
+        if let Some(key) = keys.into_iter().find(|key| key.kid == kid) {
+            return Ok(Some(key));
+        }
+
+        let outcome = if self.begin_refresh(provider_name) {
+            tracing::info!(
+                provider = provider_name,
+                kid,
+                "Unknown kid, refreshing JWKS"
+            );
+            match self.fetch_keys_from_provider(provider_name).await {
+                Ok(keys) => {
+                    if let Some(key) = keys.into_iter().find(|key| key.kid == kid) {
+                        metrics::JWKS_UNKNOWN_KID_TOTAL
+                            .with_label_values(&[provider_name, "found"])
+                            .inc();
+                        return Ok(Some(key));
+                    }
+                    // Only a fresh key set proves the kid unknown
+                    self.remember_unknown_kid(negative_key);
+                    "not_found"
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        provider = provider_name,
+                        kid,
+                        error = %e,
+                        "JWKS refresh for unknown kid failed"
+                    );
+                    "refresh_failed"
+                }
+            }
+        } else {
+            "rate_limited"
+        };
+
+        metrics::JWKS_UNKNOWN_KID_TOTAL
+            .with_label_values(&[provider_name, outcome])
+            .inc();
+        Ok(None)
+    }
 
-        Ok(keys.into_iter().find(|key| key.kid == kid))
+    /// Reject `kid` of a provider without fetching for `negative_cache_ttl`,
+    /// keeping at most `MAX_UNKNOWN_KIDS` entries
+    fn remember_unknown_kid(&self, key: (String, String)) {
+        if self.unknown_kids.len() >= MAX_UNKNOWN_KIDS {
+            let now = Instant::now();
+            self.unknown_kids.retain(|_, until| *until > now);
+        }
+        if self.unknown_kids.len() >= MAX_UNKNOWN_KIDS {
+            let soonest = self
+                .unknown_kids
+                .iter()
+                .min_by_key(|entry| *entry.value())
+                .map(|entry| entry.key().clone());
+            if let Some(soonest) = soonest {
+                self.unknown_kids.remove(&soonest);
+            }
+        }
+        self.unknown_kids
+            .insert(key, Instant::now() + self.negative_cache_ttl);
     }
 
     /// Start background refresh task
     /// Per 011-authentication-spec.md section 3.3: Key Rotation Handling
     pub fn start_refresh_task(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
-            let refresh_interval = Duration::from_secs(900); // 15 minutes
+            // Resolve discovered providers up front so issuers can be matched
+            self.discover_pending().await;
 
             loop {
-                sleep(refresh_interval).await;
+                sleep(self.refresh_interval).await;
 
                 tracing::debug!("Starting background JWKS refresh");
 
-                for provider in self.providers.iter() {
+                let providers = self
+                    .providers
+                    .read()
+                    .expect("JWKS provider lock poisoned")
+                    .clone();
+                for provider in providers {
+                    // Re-run discovery so endpoint and algorithm changes are
+                    // picked up; on failure the previous settings stay in use
+                    if provider.discovery_url.is_some() {
+                        if let Err(e) = self.discover(&provider.name).await {
+                            tracing::warn!(
+                                provider = %provider.name,
+                                error = %e,
+                                "Background OIDC discovery failed, using previous settings"
+                            );
+                        }
+                    }
+
                     match self.fetch_keys_from_provider(&provider.name).await {
                         Ok(keys) => {
                             tracing::info!(
@@ -228,28 +641,61 @@ impl JwksClient {
 
     /// Get cache statistics for monitoring
     pub fn cache_stats(&self) -> Vec<CacheStats> {
+        let now = Instant::now();
         self.cache
             .iter()
             .map(|entry| {
                 let provider = entry.key();
                 let cached = entry.value();
-                let age = Instant::now().duration_since(cached.fetched_at);
-                let ttl_remaining = cached.expires_at.saturating_duration_since(Instant::now());
+                let age = now.duration_since(cached.fetched_at);
+                let ttl_remaining = cached.expires_at.saturating_duration_since(now);
+                let stale_for = now.saturating_duration_since(cached.expires_at);
+                let is_expired = cached.expires_at <= now;
 
                 CacheStats {
                     provider: provider.clone(),
                     keys_count: cached.keys.len(),
                     age_seconds: age.as_secs(),
                     ttl_remaining_seconds: ttl_remaining.as_secs(),
-                    is_expired: cached.expires_at <= Instant::now(),
+                    is_expired,
+                    is_stale: is_expired && stale_for <= self.max_stale,
+                    stale_seconds: stale_for.as_secs(),
+                    consecutive_failures: cached.consecutive_failures,
+                    last_error: cached.last_error.clone(),
+                    unknown_kids: self
+                        .unknown_kids
+                        .iter()
+                        .filter(|entry| &entry.key().0 == provider && *entry.value() > now)
+                        .count(),
                 }
             })
             .collect()
     }
 
+    /// Publish the cache state as gauges
+    pub fn update_metrics(&self) {
+        for stats in self.cache_stats() {
+            let provider = [stats.provider.as_str()];
+            metrics::JWKS_CACHE_AGE_SECONDS
+                .with_label_values(&provider)
+                .set(stats.age_seconds as i64);
+            metrics::JWKS_CACHE_KEYS
+                .with_label_values(&provider)
+                .set(stats.keys_count as i64);
+            metrics::JWKS_CACHE_STALE
+                .with_label_values(&provider)
+                .set(stats.is_stale as i64);
+        }
+    }
+
     /// Find provider by issuer URL
-    pub fn find_provider_by_issuer(&self, issuer: &str) -> Option<&JwksProvider> {
-        self.providers.iter().find(|p| p.issuer == issuer)
+    pub fn find_provider_by_issuer(&self, issuer: &str) -> Option<JwksProvider> {
+        self.providers
+            .read()
+            .expect("JWKS provider lock poisoned")
+            .iter()
+            .find(|p| p.issuer == issuer)
+            .cloned()
     }
 }
 
@@ -261,11 +707,259 @@ pub struct CacheStats {
     pub age_seconds: u64,
     pub ttl_remaining_seconds: u64,
     pub is_expired: bool,
+    /// Expired but still served because refreshes fail (within max_stale)
+    pub is_stale: bool,
+    /// Time since expiry (0 while fresh)
+    pub stale_seconds: u64,
+    /// Refreshes failed since the last successful fetch
+    pub consecutive_failures: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Kids currently rejected without fetching
+    pub unknown_kids: usize,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::auth::JwksProvider as JwksProviderConfig;
+    use crate::config::AuthConfig;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    /// Auth config with a single issuer-only provider
+    fn discovery_config(issuer: &str, refresh_interval: Duration) -> AuthConfig {
+        let mut config = AuthConfig::default();
+        config.jwks.providers = vec![JwksProviderConfig {
+            name: "oidc".to_string(),
+            url: None,
+            keys: None,
+            issuer: issuer.to_string(),
+            audience: "web-terminal".to_string(),
+            algorithms: vec!["RS256".to_string(), "ES256".to_string()],
+            cache_ttl: Duration::from_secs(3600),
+            refresh_interval,
+            timeout: Duration::from_secs(5),
+        }];
+        config
+    }
+
+    /// Auth config with a single provider at `jwks_url`, refreshing on
+    /// demand without rate limiting
+    fn direct_config(jwks_url: String) -> AuthConfig {
+        let mut config = discovery_config("https://idp.example.com", DEFAULT_REFRESH_INTERVAL);
+        config.jwks.providers[0].url = Some(jwks_url);
+        config.jwks.min_refresh_interval = Duration::ZERO;
+        config.jwks.max_stale = Duration::from_secs(600);
+        config
+    }
+
+    fn jwks_body(kids: &[&str]) -> ResponseTemplate {
+        let keys: Vec<_> = kids
+            .iter()
+            .map(|kid| {
+                serde_json::json!({
+                    "kid": kid, "kty": "RSA", "alg": "RS256", "use": "sig",
+                    "n": "0vx7agoebGcQ", "e": "AQAB",
+                })
+            })
+            .collect();
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({ "keys": keys }))
+    }
+
+    async fn mount_openid_configuration(server: &MockServer, issuer: &str, jwks_path: &str) {
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "issuer": issuer,
+                "jwks_uri": format!("{}{}", server.uri(), jwks_path),
+                "id_token_signing_alg_values_supported": ["RS256", "HS256"],
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_discovery_resolves_provider() {
+        let server = MockServer::start().await;
+        mount_openid_configuration(&server, &server.uri(), "/keys").await;
+        Mock::given(method("GET"))
+            .and(path("/keys"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                include_str!("../../tests/fixtures/test-jwks.json"),
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+
+        // Configured with a trailing slash; the document's issuer wins
+        let client = JwksClient::new(discovery_config(
+            &format!("{}/", server.uri()),
+            DEFAULT_REFRESH_INTERVAL,
+        ));
+        let key = client.get_key("test-key", "oidc").await.unwrap();
+        assert!(key.is_some());
+
+        let provider = client.find_provider_by_issuer(&server.uri()).unwrap();
+        assert_eq!(provider.jwks_url, format!("{}/keys", server.uri()));
+        // HS256 is advertised but was never allowed; ES256 is allowed but not advertised
+        assert_eq!(provider.algorithms, vec!["RS256".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_discovery_rejects_issuer_mismatch() {
+        let server = MockServer::start().await;
+        mount_openid_configuration(&server, "https://elsewhere.example.com", "/keys").await;
+
+        let client = JwksClient::new(discovery_config(&server.uri(), DEFAULT_REFRESH_INTERVAL));
+        let result = client.fetch_keys("oidc").await;
+        assert!(matches!(result, Err(JwksError::DiscoveryFailed { .. })));
+        assert!(client
+            .find_provider_by_issuer(&server.uri())
+            .unwrap()
+            .jwks_url
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_task_reruns_discovery() {
+        let server = MockServer::start().await;
+        mount_openid_configuration(&server, &server.uri(), "/keys").await;
+
+        let client = Arc::new(JwksClient::new(discovery_config(
+            &server.uri(),
+            Duration::from_millis(100),
+        )));
+        let task = client.clone().start_refresh_task();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            client
+                .find_provider_by_issuer(&server.uri())
+                .unwrap()
+                .jwks_url,
+            format!("{}/keys", server.uri())
+        );
+
+        // The provider rotates its JWKS endpoint
+        server.reset().await;
+        mount_openid_configuration(&server, &server.uri(), "/rotated-keys").await;
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(
+            client
+                .find_provider_by_issuer(&server.uri())
+                .unwrap()
+                .jwks_url,
+            format!("{}/rotated-keys", server.uri())
+        );
+
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_refreshes_then_negative_caches() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/keys"))
+            .respond_with(jwks_body(&["old"]))
+            .mount(&server)
+            .await;
+        let client = JwksClient::new(direct_config(format!("{}/keys", server.uri())));
+        assert!(client.get_key("old", "oidc").await.unwrap().is_some());
+
+        // The provider rotates in a new key before the scheduled refresh
+        server.reset().await;
+        Mock::given(method("GET"))
+            .and(path("/keys"))
+            .respond_with(jwks_body(&["old", "new"]))
+            .mount(&server)
+            .await;
+        assert!(client.get_key("new", "oidc").await.unwrap().is_some());
+
+        // A kid the provider does not have is fetched for once
+        assert!(client.get_key("bogus", "oidc").await.unwrap().is_none());
+        assert!(client.get_key("bogus", "oidc").await.unwrap().is_none());
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+        assert_eq!(client.cache_stats()[0].unknown_kids, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_refresh_is_rate_limited() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/keys"))
+            .respond_with(jwks_body(&["a"]))
+            .mount(&server)
+            .await;
+        let mut config = direct_config(format!("{}/keys", server.uri()));
+        config.jwks.min_refresh_interval = Duration::from_secs(60);
+        let client = JwksClient::new(config);
+
+        for kid in ["a", "b", "c", "d"] {
+            client.get_key(kid, "oidc").await.unwrap();
+        }
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+        // Kids that could not be looked up are not negative cached
+        assert_eq!(client.cache_stats()[0].unknown_kids, 0);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kids_are_bounded() {
+        let client = JwksClient::new(direct_config("http://127.0.0.1:9/keys".to_string()));
+        let key = |i: usize| ("oidc".to_string(), format!("kid-{}", i));
+
+        for i in 0..MAX_UNKNOWN_KIDS {
+            client.remember_unknown_kid(key(i));
+        }
+        client.unknown_kids.insert(key(0), Instant::now());
+        client.remember_unknown_kid(key(MAX_UNKNOWN_KIDS));
+        assert_eq!(client.unknown_kids.len(), MAX_UNKNOWN_KIDS);
+        assert!(!client.unknown_kids.contains_key(&key(0)));
+
+        // Without expired entries the one expiring soonest makes room
+        client
+            .unknown_kids
+            .insert(key(1), Instant::now() + Duration::from_secs(1));
+        client.remember_unknown_kid(key(MAX_UNKNOWN_KIDS + 1));
+        assert_eq!(client.unknown_kids.len(), MAX_UNKNOWN_KIDS);
+        assert!(!client.unknown_kids.contains_key(&key(1)));
+        assert!(client.unknown_kids.contains_key(&key(MAX_UNKNOWN_KIDS + 1)));
+    }
+
+    #[tokio::test]
+    async fn test_stale_keys_served_within_max_stale() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/keys"))
+            .respond_with(jwks_body(&["a"]))
+            .mount(&server)
+            .await;
+        let client = JwksClient::new(direct_config(format!("{}/keys", server.uri())));
+        client.fetch_keys("oidc").await.unwrap();
+
+        // The provider goes down after the keys expire
+        server.reset().await;
+        let expire = |ago: Duration| {
+            client.cache.get_mut("oidc").unwrap().expires_at = Instant::now() - ago;
+        };
+        expire(Duration::from_secs(60));
+        assert_eq!(client.fetch_keys("oidc").await.unwrap().len(), 1);
+
+        let stats = &client.cache_stats()[0];
+        assert!(stats.is_expired && stats.is_stale);
+        assert_eq!(stats.consecutive_failures, 1);
+        assert!(stats.last_error.is_some());
+
+        // Past max_stale the keys are no longer trusted
+        expire(Duration::from_secs(601));
+        assert!(matches!(
+            client.fetch_keys("oidc").await,
+            Err(JwksError::CacheExpired)
+        ));
+        assert!(!client.cache_stats()[0].is_stale);
+    }
 
     #[test]
     fn test_jwk_deserialization() {
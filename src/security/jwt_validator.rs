@@ -4,12 +4,15 @@
 // - Verify token claims (iss, aud, exp, nbf)
 // - Extract user identity from claims
 // - Support multiple signing algorithms (RS256, RS384, RS512)
+// - Validate locally signed tokens against static key providers
 
 use crate::security::jwks_client::{JwksClient, JwksError, JwksProvider};
+use crate::security::static_keys::{StaticKeyError, StaticKeyStore};
+use jsonwebtoken::errors::ErrorKind;
 use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, TokenData, Validation};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use thiserror::Error;
 
 /// JWT validation error types
@@ -36,8 +39,13 @@ pub enum ValidationError {
     #[error("Invalid RSA key: {0}")]
     InvalidRsaKey(String),
 
-    #[error("Token expired at {0}")]
-    TokenExpired(i64),
+    /// Correctly signed by `provider`, but expired
+    #[error("Token expired at {exp}")]
+    TokenExpired {
+        provider: String,
+        subject: String,
+        exp: i64,
+    },
 
     #[error("Token not yet valid (nbf={0})")]
     TokenNotYetValid(i64),
@@ -143,15 +151,51 @@ pub struct ValidatedToken {
 /// Per 011-authentication-spec.md section 2.2: JWT Verifier
 pub struct JwtValidator {
     jwks_client: Arc<JwksClient>,
+    keys: RwLock<Arc<ProviderKeys>>,
     auth_config: crate::config::AuthConfig,
-    allowed_algorithms: Vec<Algorithm>,
     clock_skew_seconds: u64,
 }
 
+/// Provider-derived settings, replaced as a whole on reload
+struct ProviderKeys {
+    static_keys: StaticKeyStore,
+    allowed_algorithms: Vec<Algorithm>,
+}
+
 impl JwtValidator {
     /// Create a new JWT validator from auth configuration
     /// Per 011-authentication-spec.md section 10: Configuration
     pub fn new(jwks_client: Arc<JwksClient>, auth_config: crate::config::AuthConfig) -> Self {
+        let keys = ProviderKeys {
+            static_keys: StaticKeyStore::new(&auth_config),
+            allowed_algorithms: Self::allowed_algorithms(&auth_config),
+        };
+
+        Self {
+            jwks_client,
+            keys: RwLock::new(Arc::new(keys)),
+            auth_config: auth_config.clone(),
+            clock_skew_seconds: auth_config.validation.clock_skew_seconds(),
+        }
+    }
+
+    /// Switch to the provider definitions of `auth_config`
+    ///
+    /// Every static key file is loaded first; if one fails nothing changes.
+    /// Per 011-authentication-spec.md: Configuration Reload
+    pub fn reload(&self, auth_config: &crate::config::AuthConfig) -> Result<(), StaticKeyError> {
+        let keys = ProviderKeys {
+            static_keys: StaticKeyStore::try_new(auth_config)?,
+            allowed_algorithms: Self::allowed_algorithms(auth_config),
+        };
+
+        *self.keys.write().expect("validator key lock poisoned") = Arc::new(keys);
+        self.jwks_client.set_providers(auth_config);
+        Ok(())
+    }
+
+    /// Algorithms accepted from JWKS providers
+    fn allowed_algorithms(auth_config: &crate::config::AuthConfig) -> Vec<Algorithm> {
         // Determine allowed algorithms from config
         let allowed_algorithms = auth_config
             .jwks
@@ -168,7 +212,7 @@ impl JwtValidator {
             })
             .collect::<Vec<_>>();
 
-        let allowed_algorithms = if allowed_algorithms.is_empty() {
+        if allowed_algorithms.is_empty() {
             // Default algorithms if none specified
             vec![
                 Algorithm::RS256,
@@ -179,13 +223,6 @@ impl JwtValidator {
             ]
         } else {
             allowed_algorithms
-        };
-
-        Self {
-            jwks_client,
-            auth_config: auth_config.clone(),
-            allowed_algorithms,
-            clock_skew_seconds: auth_config.validation.clock_skew_seconds(),
         }
     }
 
@@ -195,24 +232,40 @@ impl JwtValidator {
         // Step 1: Decode header to extract kid and alg
         let header = decode_header(token)?;
 
+        // Step 2: Decode without verification to get issuer
+        let unverified = decode_unverified(token)?;
+        let issuer = &unverified.iss;
+
+        // Step 3: Providers with local keys (each key limits its own algorithms)
+        let keys = self
+            .keys
+            .read()
+            .expect("validator key lock poisoned")
+            .clone();
+        if let Some(provider) = keys.static_keys.find_provider_by_issuer(issuer) {
+            let decoding_key = provider.decoding_key(header.kid.as_deref(), header.alg)?;
+            return self.verify(
+                token,
+                &decoding_key,
+                header.alg,
+                issuer,
+                &provider.audience,
+                &provider.name,
+            );
+        }
+
         let kid = header.kid.as_ref().ok_or(ValidationError::MissingKid)?;
 
-        // Step 2: Verify algorithm is allowed
-        if !self.allowed_algorithms.contains(&header.alg) {
+        // Verify algorithm is allowed
+        if !keys.allowed_algorithms.contains(&header.alg) {
             return Err(ValidationError::UnsupportedAlgorithm(header.alg));
         }
 
-        // Step 3: Decode without verification to get issuer (using unsafe validation)
-        let mut validation_unsafe = Validation::default();
-        validation_unsafe.insecure_disable_signature_validation();
-        validation_unsafe.validate_exp = false;
-
-        let unverified: TokenData<Claims> =
-            decode(token, &DecodingKey::from_secret(&[]), &validation_unsafe)?;
-        let issuer = &unverified.claims.iss;
-
-        // Step 4: Find provider by issuer
-        let provider = self.find_provider_by_issuer(issuer)?;
+        // Step 4: Find provider by issuer, and check it signs with this algorithm
+        let provider = self.find_provider_by_issuer(issuer).await?;
+        if !provider.allows_algorithm(&format!("{:?}", header.alg)) {
+            return Err(ValidationError::UnsupportedAlgorithm(header.alg));
+        }
 
         // Step 5: Fetch public key via JWKS client
         let jwk = self
@@ -224,21 +277,52 @@ impl JwtValidator {
         // Step 6: Convert JWK to RSA public key
         let decoding_key = self.jwk_to_decoding_key(&jwk)?;
 
-        // Step 7: Build validation parameters
-        let mut validation = Validation::new(header.alg);
+        self.verify(
+            token,
+            &decoding_key,
+            header.alg,
+            issuer,
+            &provider.audience,
+            &provider.name,
+        )
+    }
+
+    /// Verify the signature and standard claims with the selected key
+    fn verify(
+        &self,
+        token: &str,
+        decoding_key: &DecodingKey,
+        algorithm: Algorithm,
+        issuer: &str,
+        audience: &str,
+        provider_name: &str,
+    ) -> Result<ValidatedToken, ValidationError> {
+        // Step 7: Build validation parameters (`aud` may be a string or an
+        // array; one entry must be the provider's audience)
+        let mut validation = Validation::new(algorithm);
         validation.set_issuer(&[issuer]);
+        validation.set_audience(&[audience]);
         validation.leeway = self.clock_skew_seconds;
 
-        // Allow flexible audience validation (checked separately)
-        validation.validate_aud = false;
-
-        // Step 8: Verify token signature and decode claims
-        let token_data = decode::<Claims>(token, &decoding_key, &validation)?;
+        // Step 8: Verify token signature and decode claims. The signature is
+        // checked before the lifetime, so an expired token's subject is real.
+        let token_data = match decode::<Claims>(token, decoding_key, &validation) {
+            Ok(token_data) => token_data,
+            Err(e) if *e.kind() == ErrorKind::ExpiredSignature => {
+                let claims = decode_unverified(token)?;
+                return Err(ValidationError::TokenExpired {
+                    provider: provider_name.to_string(),
+                    subject: claims.sub,
+                    exp: claims.exp,
+                });
+            }
+            Err(e) => return Err(e.into()),
+        };
 
         Ok(ValidatedToken {
             claims: token_data.claims,
-            provider: provider.name.clone(),
-            algorithm: header.alg,
+            provider: provider_name.to_string(),
+            algorithm,
         })
     }
 
@@ -262,17 +346,36 @@ impl JwtValidator {
             .map_err(|e| ValidationError::InvalidRsaKey(format!("Failed to create key: {}", e)))
     }
 
-    /// Find provider by issuer URL
-    fn find_provider_by_issuer(&self, issuer: &str) -> Result<&JwksProvider, ValidationError> {
+    /// Find provider by issuer URL, running any pending OIDC discovery
+    /// before giving up (discovered issuers are only known afterwards)
+    async fn find_provider_by_issuer(&self, issuer: &str) -> Result<JwksProvider, ValidationError> {
+        if let Some(provider) = self.jwks_client.find_provider_by_issuer(issuer) {
+            return Ok(provider);
+        }
+
+        self.jwks_client.discover_pending().await;
         self.jwks_client
             .find_provider_by_issuer(issuer)
             .ok_or_else(|| ValidationError::ProviderNotFound(issuer.to_string()))
     }
 }
 
+/// Decode the claims of a JWT without verifying its signature or lifetime
+fn decode_unverified(token: &str) -> Result<Claims, ValidationError> {
+    let mut validation_unsafe = Validation::default();
+    validation_unsafe.insecure_disable_signature_validation();
+    validation_unsafe.validate_exp = false;
+    validation_unsafe.validate_aud = false;
+
+    let unverified: TokenData<Claims> =
+        decode(token, &DecodingKey::from_secret(&[]), &validation_unsafe)?;
+    Ok(unverified.claims)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::security::testing::{forge, lab_token, lab_validator};
 
     #[test]
     fn test_claims_deserialization_standard() {
@@ -331,4 +434,47 @@ mod tests {
         assert!(aud.contains("backstage"));
         assert!(!aud.contains("other"));
     }
+
+    #[tokio::test]
+    async fn test_audience_must_match_provider() {
+        let (_dir, validator) = lab_validator();
+        let token = |aud: serde_json::Value| lab_token("alice", serde_json::json!({ "aud": aud }));
+
+        assert!(validator
+            .validate(&token("web-terminal".into()))
+            .await
+            .is_ok());
+        assert!(validator
+            .validate(&token(serde_json::json!(["backstage", "web-terminal"])))
+            .await
+            .is_ok());
+        assert!(validator
+            .validate(&token("backstage".into()))
+            .await
+            .is_err());
+        assert!(validator
+            .validate(&token(serde_json::json!([])))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_names_its_verified_subject() {
+        let (_dir, validator) = lab_validator();
+        let exp = chrono::Utc::now().timestamp() - 3600;
+
+        let expired = lab_token("alice", serde_json::json!({ "exp": exp }));
+        match validator.validate(&expired).await {
+            Err(ValidationError::TokenExpired {
+                provider, subject, ..
+            }) => assert_eq!((provider.as_str(), subject.as_str()), ("lab", "alice")),
+            other => panic!("expected TokenExpired, got {:?}", other.map(|_| ())),
+        }
+
+        // A forged signature is refused before the lifetime is looked at
+        assert!(matches!(
+            validator.validate(&forge(&expired)).await,
+            Err(ValidationError::JwtError(_))
+        ));
+    }
 }
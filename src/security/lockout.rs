@@ -0,0 +1,449 @@
+// Brute-force protection for failed authentications
+// Per 011-authentication-spec.md: Brute-Force Protection
+// Responsibilities:
+// - Count failed authentications per client address, and per provider and
+//   subject for credentials whose signature or secret was verified
+// - Lock an address or subject out for a while after too many failures
+// - Refuse attempts during a lockout with the time left
+// - Export lockouts as metrics and let admins lift them
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+use crate::config::auth::RateLimitConfig;
+use crate::monitoring::metrics;
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+
+/// Tracked addresses and subjects before idle records are swept early
+const MAX_TRACKED: usize = 10_000;
+
+/// How often expired lockouts and idle records are swept
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Lockout error types
+#[derive(Error, Debug)]
+pub enum LockoutError {
+    #[error("Invalid lockout key '{0}': expected ip:<address> or sub:<subject>")]
+    InvalidKey(String),
+
+    #[error("Lockout not found: {0}")]
+    NotFound(String),
+}
+
+/// Lockout subject of a verified credential: `<provider>/<sub>`, so a
+/// subject issued by one provider cannot lock out another provider's
+pub fn subject_key(provider: &str, sub: &str) -> String {
+    format!("{}/{}", provider, sub)
+}
+
+/// What failures are counted against: a client address or a subject
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LockoutKey {
+    Ip(IpAddr),
+    Subject(String),
+}
+
+impl LockoutKey {
+    /// Metric label for this kind of key
+    pub fn scope(&self) -> &'static str {
+        match self {
+            Self::Ip(_) => "ip",
+            Self::Subject(_) => "subject",
+        }
+    }
+}
+
+impl fmt::Display for LockoutKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ip(ip) => write!(f, "ip:{}", ip),
+            Self::Subject(sub) => write!(f, "sub:{}", sub),
+        }
+    }
+}
+
+impl FromStr for LockoutKey {
+    type Err = LockoutError;
+
+    /// Parse `ip:<address>` or `sub:<subject>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("ip", ip)) => ip
+                .parse()
+                .map(Self::Ip)
+                .map_err(|_| LockoutError::InvalidKey(s.to_string())),
+            Some(("sub", sub)) if !sub.is_empty() => Ok(Self::Subject(sub.to_string())),
+            _ => Err(LockoutError::InvalidKey(s.to_string())),
+        }
+    }
+}
+
+impl Serialize for LockoutKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// An address or subject refused until `until`
+#[derive(Error, Debug, Clone, Serialize)]
+#[error("Too many failed authentications for {key}; retry after {until}")]
+pub struct Lockout {
+    pub key: LockoutKey,
+    pub until: DateTime<Utc>,
+}
+
+impl Lockout {
+    /// Whole seconds until the lockout ends (at least 1), for Retry-After
+    pub fn retry_after_secs(&self) -> u64 {
+        let millis = (self.until - Utc::now()).num_milliseconds().max(0) as u64;
+        millis.div_ceil(1000).max(1)
+    }
+}
+
+/// Recent failures of one address or subject
+#[derive(Debug, Default)]
+struct FailureRecord {
+    /// Failures within the window, oldest first
+    failures: VecDeque<DateTime<Utc>>,
+    locked_until: Option<DateTime<Utc>>,
+}
+
+impl FailureRecord {
+    fn is_locked(&self, now: DateTime<Utc>) -> bool {
+        self.locked_until.is_some_and(|until| until > now)
+    }
+
+    /// Nothing left worth remembering
+    fn is_idle(&self, now: DateTime<Utc>, window: Duration) -> bool {
+        !self.is_locked(now)
+            && self
+                .failures
+                .back()
+                .is_none_or(|last| now - *last > window_delta(window))
+    }
+}
+
+fn window_delta(window: Duration) -> chrono::Duration {
+    chrono::Duration::from_std(window).unwrap_or(chrono::Duration::MAX)
+}
+
+/// Failed authentication accounting, consulted before and after every
+/// credential verification
+/// Per 011-authentication-spec.md: Brute-Force Protection
+pub struct LockoutTracker {
+    config: RwLock<Arc<RateLimitConfig>>,
+    records: DashMap<LockoutKey, FailureRecord>,
+    audit: Arc<AuditLogger>,
+}
+
+impl LockoutTracker {
+    /// Track failures with the limits from `config`
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config: RwLock::new(Arc::new(config)),
+            records: DashMap::new(),
+            audit: Arc::new(AuditLogger::default()),
+        }
+    }
+
+    /// Record lockouts in `audit`
+    pub fn with_audit(mut self, audit: Arc<AuditLogger>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Swap in new limits; failures already counted and lockouts in effect
+    /// are kept
+    /// Per spec-kit/005-cli-spec.md: Live Reload
+    pub fn replace_config(&self, config: RateLimitConfig) {
+        *self.config.write().expect("lockout config lock poisoned") = Arc::new(config);
+    }
+
+    fn config(&self) -> Arc<RateLimitConfig> {
+        self.config
+            .read()
+            .expect("lockout config lock poisoned")
+            .clone()
+    }
+
+    /// Whether failures are tracked at all
+    pub fn is_enabled(&self) -> bool {
+        let config = self.config();
+        config.enabled && config.max_failed_attempts > 0
+    }
+
+    /// Refuse an attempt from `ip`, or by the verified `subject`, while
+    /// either is locked out
+    pub fn check(&self, ip: Option<IpAddr>, subject: Option<&str>) -> Result<(), Lockout> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let lockout = keys(ip, subject)
+            .filter_map(|key| {
+                let until = self.records.get(&key)?.locked_until?;
+                (until > now).then_some(Lockout { key, until })
+            })
+            .max_by_key(|lockout| lockout.until);
+
+        match lockout {
+            Some(lockout) => {
+                metrics::AUTH_LOCKOUT_REJECTIONS_TOTAL
+                    .with_label_values(&[lockout.key.scope()])
+                    .inc();
+                Err(lockout)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Count a failed attempt; returns the lockouts it triggered
+    pub fn record_failure(&self, ip: Option<IpAddr>, subject: Option<&str>) -> Vec<Lockout> {
+        if !self.is_enabled() {
+            return Vec::new();
+        }
+        metrics::AUTH_FAILURES_TOTAL.inc();
+
+        let config = self.config();
+        let now = Utc::now();
+        let window = window_delta(config.window);
+        let mut imposed = Vec::new();
+        for key in keys(ip, subject) {
+            let mut record = self.records.entry(key.clone()).or_default();
+            if record.is_locked(now) {
+                continue;
+            }
+            while record.failures.front().is_some_and(|at| now - *at > window) {
+                record.failures.pop_front();
+            }
+            record.failures.push_back(now);
+
+            if record.failures.len() >= config.max_failed_attempts as usize {
+                let until = now + window_delta(config.lockout);
+                record.failures.clear();
+                record.locked_until = Some(until);
+                imposed.push(Lockout { key, until });
+            }
+        }
+
+        for lockout in &imposed {
+            tracing::warn!(
+                "{} locked out until {} after {} failed authentications",
+                lockout.key,
+                lockout.until,
+                config.max_failed_attempts
+            );
+            metrics::AUTH_LOCKOUTS_TOTAL
+                .with_label_values(&[lockout.key.scope()])
+                .inc();
+            self.audit
+                .record(AuditEvent::new(AuditAction::AuthLockout).detail(format!(
+                    "{} until {}",
+                    lockout.key,
+                    lockout.until.to_rfc3339()
+                )));
+        }
+        if !imposed.is_empty() || self.records.len() > MAX_TRACKED {
+            self.sweep();
+        }
+        imposed
+    }
+
+    /// Forget the failures of `subject` once it authenticates; the address
+    /// keeps its count, so one valid account cannot reset it
+    pub fn record_success(&self, subject: &str) {
+        if self.is_enabled() {
+            self.records
+                .remove_if(&LockoutKey::Subject(subject.to_string()), |_, record| {
+                    !record.is_locked(Utc::now())
+                });
+        }
+    }
+
+    /// Lockouts in effect, ending soonest first
+    pub fn lockouts(&self) -> Vec<Lockout> {
+        let now = Utc::now();
+        let mut lockouts: Vec<_> = self
+            .records
+            .iter()
+            .filter_map(|entry| {
+                let until = entry.locked_until.filter(|until| *until > now)?;
+                Some(Lockout {
+                    key: entry.key().clone(),
+                    until,
+                })
+            })
+            .collect();
+        lockouts.sort_by_key(|lockout| lockout.until);
+        lockouts
+    }
+
+    /// Lift the lockout of `key` and forget its failures
+    pub fn clear(&self, key: &LockoutKey) -> Result<Lockout, LockoutError> {
+        let now = Utc::now();
+        let (key, record) = self
+            .records
+            .remove_if(key, |_, record| record.is_locked(now))
+            .ok_or_else(|| LockoutError::NotFound(key.to_string()))?;
+        self.update_gauges();
+        Ok(Lockout {
+            key,
+            until: record.locked_until.unwrap_or(now),
+        })
+    }
+
+    /// Lift every lockout; returns how many were in effect
+    pub fn clear_all(&self) -> usize {
+        let cleared = self.lockouts().len();
+        self.records.clear();
+        self.update_gauges();
+        cleared
+    }
+
+    /// Drop expired lockouts and failures outside the window
+    fn sweep(&self) {
+        let now = Utc::now();
+        let window = self.config().window;
+        self.records
+            .retain(|_, record| !record.is_idle(now, window));
+        self.update_gauges();
+    }
+
+    fn update_gauges(&self) {
+        let now = Utc::now();
+        let (mut ips, mut subjects) = (0, 0);
+        for entry in self.records.iter() {
+            if entry.is_locked(now) {
+                match entry.key() {
+                    LockoutKey::Ip(_) => ips += 1,
+                    LockoutKey::Subject(_) => subjects += 1,
+                }
+            }
+        }
+        metrics::AUTH_LOCKED_OUT.with_label_values(&["ip"]).set(ips);
+        metrics::AUTH_LOCKED_OUT
+            .with_label_values(&["subject"])
+            .set(subjects);
+    }
+
+    /// Periodically sweep expired lockouts so the gauges stay current
+    pub fn start_sweeper(self: Arc<Self>) {
+        if !self.is_enabled() {
+            return;
+        }
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                self.sweep();
+            }
+        });
+    }
+}
+
+/// Keys an attempt is counted against
+fn keys(ip: Option<IpAddr>, subject: Option<&str>) -> impl Iterator<Item = LockoutKey> {
+    ip.map(LockoutKey::Ip).into_iter().chain(
+        subject
+            .filter(|sub| !sub.is_empty())
+            .map(|sub| LockoutKey::Subject(sub.to_string())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(max_failed_attempts: u32) -> LockoutTracker {
+        LockoutTracker::new(RateLimitConfig {
+            enabled: true,
+            max_failed_attempts,
+            window: Duration::from_secs(300),
+            lockout: Duration::from_secs(900),
+        })
+    }
+
+    #[test]
+    fn test_lockout_after_max_failures() {
+        let tracker = tracker(3);
+        let ip: IpAddr = "192.0.2.1".parse().unwrap();
+
+        assert!(tracker.record_failure(Some(ip), Some("alice")).is_empty());
+        assert!(tracker.record_failure(Some(ip), Some("bob")).is_empty());
+        assert!(tracker.check(Some(ip), Some("alice")).is_ok());
+
+        // The third failure from the address locks it, not the subjects
+        let imposed = tracker.record_failure(Some(ip), Some("carol"));
+        assert_eq!(imposed.len(), 1);
+        assert_eq!(imposed[0].key, LockoutKey::Ip(ip));
+
+        let lockout = tracker.check(Some(ip), Some("dave")).unwrap_err();
+        assert_eq!(lockout.key.to_string(), "ip:192.0.2.1");
+        assert!(lockout.retry_after_secs() > 890 && lockout.retry_after_secs() <= 900);
+        assert!(tracker.check(None, Some("alice")).is_ok());
+
+        // Subjects are tracked across addresses
+        let other: IpAddr = "192.0.2.2".parse().unwrap();
+        tracker.record_failure(Some(other), Some("alice"));
+        tracker.record_failure(None, Some("alice"));
+        assert!(tracker.check(None, Some("alice")).is_err());
+        assert_eq!(tracker.lockouts().len(), 2);
+    }
+
+    #[test]
+    fn test_success_resets_subject_only() {
+        let tracker = tracker(2);
+        let ip: IpAddr = "192.0.2.1".parse().unwrap();
+
+        tracker.record_failure(Some(ip), Some("alice"));
+        tracker.record_success("alice");
+        assert!(tracker.record_failure(None, Some("alice")).is_empty());
+
+        // The address kept its count
+        let imposed = tracker.record_failure(Some(ip), None);
+        assert_eq!(imposed.len(), 1);
+    }
+
+    #[test]
+    fn test_clear_lockouts() {
+        let tracker = tracker(1);
+        let ip: IpAddr = "2001:db8::1".parse().unwrap();
+        tracker.record_failure(Some(ip), Some("alice"));
+
+        let key: LockoutKey = "ip:2001:db8::1".parse().unwrap();
+        assert_eq!(key, LockoutKey::Ip(ip));
+        assert!(tracker.clear(&key).is_ok());
+        assert!(matches!(
+            tracker.clear(&key),
+            Err(LockoutError::NotFound(_))
+        ));
+        assert!(tracker.check(Some(ip), None).is_ok());
+        assert!(tracker.check(None, Some("alice")).is_err());
+
+        assert_eq!(tracker.clear_all(), 1);
+        assert!(tracker.lockouts().is_empty());
+        assert!("user:alice".parse::<LockoutKey>().is_err());
+    }
+
+    #[test]
+    fn test_disabled_tracker_never_locks() {
+        let tracker = LockoutTracker::new(RateLimitConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        for _ in 0..10 {
+            assert!(tracker.record_failure(None, Some("alice")).is_empty());
+        }
+        assert!(tracker.check(None, Some("alice")).is_ok());
+    }
+}
@@ -2,15 +2,37 @@
 // Per spec-kit/003-backend-spec.md section 2.6
 // Per 011-authentication-spec.md section 2
 
+pub mod access_grants;
+pub mod admission;
+pub mod api_keys;
+pub mod audit;
 pub mod auth;
 pub mod authorization;
 pub mod jwks_client;
 pub mod jwt_validator;
+pub mod lockout;
+pub mod policy;
+pub mod reload;
+pub mod revocation;
+pub mod static_keys;
+pub mod tenancy;
+#[cfg(test)]
+pub(crate) mod testing;
 
 // External JWT validation only - NO internal token generation
+pub use access_grants::{AccessGrant, AccessGrantError, AccessGrantStore, AccessLevel};
+pub use admission::{AdmissionDenied, AdmissionPolicy, RoleResolver};
+pub use api_keys::{ApiKey, ApiKeyError, ApiKeyStore};
+pub use audit::{AuditAction, AuditError, AuditEvent, AuditLogger, ChainSummary};
 pub use auth::{Claims, JwtValidator, ValidatedToken, ValidationError};
 pub use authorization::{
-    AuthorizationError, AuthorizationService, Permission, PermissionRules, Role,
+    AuthorizationError, AuthorizationService, Decision, Permission, PermissionRules, Role,
 };
 pub use jwks_client::{JsonWebKey, JwksClient, JwksError, JwksProvider};
 pub use jwt_validator::UserSignInContext;
+pub use lockout::{Lockout, LockoutError, LockoutKey, LockoutTracker};
+pub use policy::{AccessRequest, PolicyRule, Resource};
+pub use reload::{AuthReloader, ReloadError, ReloadTrigger};
+pub use revocation::{Revocation, RevocationError, RevocationList};
+pub use static_keys::{StaticKeyError, StaticKeyStore};
+pub use tenancy::{Tenant, TenantError, TenantResolver};
@@ -0,0 +1,563 @@
+// Attribute-based authorization policies
+// Per 011-authentication-spec.md: Authorization Model
+// Responsibilities:
+// - Describe conditional allow/deny rules in the permissions file
+// - Match rules on who is asking (users, groups, roles, claims), from where
+//   (source address), when (time window) and on what (session profile,
+//   resource owner)
+// - Record why each rule did or did not apply, for the explain API
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveTime, Offset, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::auth::ClaimMappings;
+use crate::security::admission::{claim_values, glob_match, Identity, Pattern};
+use crate::security::api_keys::CidrBlock;
+use crate::security::authorization::Permission;
+use crate::security::jwt_validator::Claims;
+use crate::security::tenancy::Tenant;
+use crate::session::state::{Session, SessionId, UserId};
+
+/// What a matching rule does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyEffect {
+    Allow,
+    Deny,
+}
+
+/// Whose session a rule covers, relative to the caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OwnerCondition {
+    /// The caller's own sessions
+    #[serde(rename = "self")]
+    Own,
+    /// Sessions owned by someone else
+    Other,
+    /// Actions not on an existing session (creating or listing)
+    None,
+}
+
+/// A conditional rule from the `policies` list of the permissions file
+///
+/// Rules are evaluated in order; the first one that covers the requested
+/// permission and whose conditions all hold decides. When none does, the
+/// caller's role permissions apply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRule {
+    /// Name reported by the explain API and in denials
+    pub name: String,
+
+    pub effect: PolicyEffect,
+
+    /// Permissions the rule covers (all when empty)
+    #[serde(default)]
+    pub permissions: Vec<Permission>,
+
+    /// Conditions that must all hold for the rule to apply
+    #[serde(default)]
+    pub when: PolicyConditions,
+}
+
+/// Conditions of a [`PolicyRule`]; an empty list or missing entry places no
+/// restriction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyConditions {
+    /// User references or globs (e.g. `user:default/alice`, `user:contractors/*`)
+    #[serde(default)]
+    pub users: Vec<String>,
+
+    /// Group references or globs (e.g. `group:default/oncall`)
+    #[serde(default)]
+    pub groups: Vec<String>,
+
+    /// Resolved roles
+    #[serde(default)]
+    pub roles: Vec<String>,
+
+    /// Claim path -> accepted values or globs; every listed claim must match
+    #[serde(default)]
+    pub claims: HashMap<String, Vec<String>>,
+
+    /// Networks the request must come from
+    #[serde(default)]
+    pub source_cidrs: Vec<CidrBlock>,
+
+    /// When the request must be made
+    #[serde(default)]
+    pub time: Option<TimeWindow>,
+
+    /// Session profiles the session must use
+    #[serde(default)]
+    pub profiles: Vec<String>,
+
+    /// Whose session it must be
+    #[serde(default)]
+    pub owner: Option<OwnerCondition>,
+}
+
+/// Daily time window, e.g. 08:00-18:00 on weekdays
+///
+/// A window whose end is before its start spans midnight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeWindow {
+    /// Start of the window (inclusive), `HH:MM`
+    pub start: NaiveTime,
+
+    /// End of the window (exclusive), `HH:MM`
+    pub end: NaiveTime,
+
+    /// Days the window applies on (every day when empty)
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+
+    /// Offset the times are given in, e.g. `+02:00` (UTC when unset)
+    #[serde(default, with = "utc_offset")]
+    pub utc_offset: Option<FixedOffset>,
+}
+
+impl TimeWindow {
+    /// Whether `at` falls inside the window
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let local = at.with_timezone(&self.utc_offset.unwrap_or(Utc.fix()));
+        if !self.days.is_empty() && !self.days.contains(&local.weekday()) {
+            return false;
+        }
+        let time = local.time();
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// `utc_offset` as a `+HH:MM` string
+mod utc_offset {
+    use chrono::FixedOffset;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        offset: &Option<FixedOffset>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match offset {
+            Some(offset) => serializer.collect_str(offset),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<FixedOffset>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| s.parse().map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+/// The session an action is performed on
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Resource<'a> {
+    /// Owner of the session (`None` when creating or listing)
+    pub owner: Option<&'a UserId>,
+    /// Profile the session runs (or is requested) with
+    pub profile: Option<&'a str>,
+    /// Tenant the session belongs to (`None` without tenancy)
+    pub tenant: Option<&'a str>,
+    /// ID of the session, checked against just-in-time access grants
+    pub session: Option<&'a SessionId>,
+}
+
+impl<'a> Resource<'a> {
+    /// A session owned by `owner`
+    pub fn owned_by(owner: Option<&'a UserId>) -> Self {
+        Self {
+            owner,
+            profile: None,
+            tenant: None,
+            session: None,
+        }
+    }
+
+    /// An existing session
+    pub fn session(session: &'a Session) -> Self {
+        Self {
+            tenant: session.tenant.as_deref(),
+            ..Self::owned_by(Some(&session.user_id))
+                .with_profile(session.profile.as_deref())
+                .with_session(&session.id)
+        }
+    }
+
+    /// The same resource running `profile`
+    pub fn with_profile(mut self, profile: Option<&'a str>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// The same resource, identified as session `id`
+    pub fn with_session(mut self, id: &'a SessionId) -> Self {
+        self.session = Some(id);
+        self
+    }
+}
+
+/// Everything a policy decision depends on
+#[derive(Debug, Clone)]
+pub struct AccessRequest<'a> {
+    pub user_id: &'a UserId,
+    pub claims: &'a Claims,
+    pub roles: &'a [String],
+    pub source_ip: Option<IpAddr>,
+    pub at: DateTime<Utc>,
+    /// Tenant of the requesting user (`None` without tenancy)
+    pub tenant: Option<&'a Tenant>,
+    pub resource: Resource<'a>,
+}
+
+/// How one rule fared during evaluation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleTrace {
+    pub rule: String,
+    pub effect: PolicyEffect,
+    pub matched: bool,
+    /// First condition that did not hold
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// The first rule in `rules` that applies to `permission` for `request`,
+/// with a trace of every rule considered up to and including it
+pub fn first_match<'r>(
+    rules: &'r [PolicyRule],
+    permission: Permission,
+    request: &AccessRequest<'_>,
+    mappings: &ClaimMappings,
+) -> (Option<&'r PolicyRule>, Vec<RuleTrace>) {
+    let subject = Subject::new(request, mappings);
+    let mut trace = Vec::new();
+    for rule in rules {
+        let reason = rule.mismatch(permission, &subject);
+        trace.push(RuleTrace {
+            rule: rule.name.clone(),
+            effect: rule.effect,
+            matched: reason.is_none(),
+            reason,
+        });
+        if trace.last().is_some_and(|t| t.matched) {
+            return (Some(rule), trace);
+        }
+    }
+    (None, trace)
+}
+
+/// The request with its claims parsed once for all rules
+struct Subject<'a> {
+    request: &'a AccessRequest<'a>,
+    identity: Identity,
+    document: Value,
+}
+
+impl<'a> Subject<'a> {
+    fn new(request: &'a AccessRequest<'a>, mappings: &ClaimMappings) -> Self {
+        Self {
+            request,
+            identity: Identity::from_claims(request.claims, mappings),
+            document: serde_json::to_value(request.claims).unwrap_or(Value::Null),
+        }
+    }
+}
+
+impl PolicyRule {
+    /// Why the rule does not apply, or `None` when it does
+    fn mismatch(&self, permission: Permission, subject: &Subject<'_>) -> Option<String> {
+        if !self.permissions.is_empty() && !self.permissions.contains(&permission) {
+            return Some(format!("does not cover {}", permission.as_str()));
+        }
+
+        let when = &self.when;
+        let request = subject.request;
+        if !when.users.is_empty() && !any_pattern(&when.users, &subject.identity.users) {
+            return Some("user not listed".to_string());
+        }
+        if !when.groups.is_empty() && !any_pattern(&when.groups, &subject.identity.groups) {
+            return Some("not in a listed group".to_string());
+        }
+        if !when.roles.is_empty()
+            && !request
+                .roles
+                .iter()
+                .any(|role| when.roles.iter().any(|r| r.eq_ignore_ascii_case(role)))
+        {
+            return Some("no listed role".to_string());
+        }
+        let mut claims: Vec<_> = when.claims.iter().collect();
+        claims.sort_by(|a, b| a.0.cmp(b.0));
+        for (path, accepted) in claims {
+            let values = claim_values(&subject.document, path);
+            let matched = values
+                .iter()
+                .any(|value| accepted.iter().any(|pattern| glob_match(pattern, value)));
+            if !matched {
+                return Some(format!("claim {} does not match", path));
+            }
+        }
+        if !when.source_cidrs.is_empty() {
+            match request.source_ip {
+                Some(ip) if when.source_cidrs.iter().any(|cidr| cidr.contains(ip)) => {}
+                Some(ip) => return Some(format!("source address {} not allowed", ip)),
+                None => return Some("source address unknown".to_string()),
+            }
+        }
+        if let Some(window) = &when.time {
+            if !window.contains(request.at) {
+                return Some("outside time window".to_string());
+            }
+        }
+        if !when.profiles.is_empty() {
+            match request.resource.profile {
+                Some(profile) if when.profiles.iter().any(|p| p == profile) => {}
+                Some(profile) => return Some(format!("profile {} not allowed", profile)),
+                None => return Some("no session profile".to_string()),
+            }
+        }
+        if let Some(owner) = when.owner {
+            let actual = match request.resource.owner {
+                None => OwnerCondition::None,
+                Some(owner) if owner == request.user_id => OwnerCondition::Own,
+                Some(_) => OwnerCondition::Other,
+            };
+            if actual != owner {
+                return Some("session owner does not match".to_string());
+            }
+        }
+
+        None
+    }
+}
+
+/// Whether any of `values` matches one of the reference `patterns`
+fn any_pattern(patterns: &[String], values: &[String]) -> bool {
+    let patterns: Vec<Pattern> = patterns
+        .iter()
+        .map(|pattern| Pattern::new(pattern, true))
+        .collect();
+    Pattern::first_match(&patterns, values).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::testing::claims;
+    use chrono::TimeZone;
+
+    fn rules(yaml: &str) -> Vec<PolicyRule> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    const CONTRACTORS: &str = r#"
+- name: contractors-office-hours
+  effect: allow
+  permissions: [create_session]
+  when:
+    groups: ["group:default/contractors"]
+    source_cidrs: ["10.8.0.0/16"]
+    time: { start: "08:00", end: "18:00", days: [mon, tue, wed, thu, fri] }
+    profiles: [restricted]
+- name: contractors-otherwise
+  effect: deny
+  permissions: [create_session]
+  when:
+    groups: ["group:default/contractors"]
+- name: oncall-kill
+  effect: allow
+  permissions: [kill_session]
+  when:
+    groups: ["group:default/oncall"]
+    owner: other
+"#;
+
+    fn evaluate(
+        rules: &[PolicyRule],
+        permission: Permission,
+        claims: &Claims,
+        source_ip: &str,
+        at: DateTime<Utc>,
+        resource: Resource<'_>,
+    ) -> (Option<String>, Vec<RuleTrace>) {
+        let user_id = UserId::new(claims.sub.clone());
+        let request = AccessRequest {
+            user_id: &user_id,
+            claims,
+            roles: &[],
+            source_ip: Some(source_ip.parse().unwrap()),
+            at,
+            tenant: None,
+            resource,
+        };
+        let (rule, trace) = first_match(rules, permission, &request, &ClaimMappings::default());
+        (rule.map(|r| r.name.clone()), trace)
+    }
+
+    #[test]
+    fn test_conditions_must_all_hold() {
+        let rules = rules(CONTRACTORS);
+        let contractor = claims("user:default/carol", &["group:default/contractors"]);
+        // Wednesday 10:00 UTC
+        let office = Utc.with_ymd_and_hms(2026, 10, 14, 10, 0, 0).unwrap();
+        let restricted = Resource::default().with_profile(Some("restricted"));
+
+        let (rule, trace) = evaluate(
+            &rules,
+            Permission::CreateSession,
+            &contractor,
+            "10.8.1.2",
+            office,
+            restricted,
+        );
+        assert_eq!(rule.as_deref(), Some("contractors-office-hours"));
+        assert_eq!(trace.len(), 1);
+
+        // Off the VPN, at night, on a weekend or with another profile the
+        // deny rule applies instead
+        let cases = [
+            ("192.168.1.2", office, restricted, "source address"),
+            (
+                "10.8.1.2",
+                Utc.with_ymd_and_hms(2026, 10, 14, 19, 0, 0).unwrap(),
+                restricted,
+                "time window",
+            ),
+            (
+                "10.8.1.2",
+                Utc.with_ymd_and_hms(2026, 10, 17, 10, 0, 0).unwrap(),
+                restricted,
+                "time window",
+            ),
+            (
+                "10.8.1.2",
+                office,
+                Resource::default(),
+                "no session profile",
+            ),
+        ];
+        for (ip, at, resource, reason) in cases {
+            let (rule, trace) = evaluate(
+                &rules,
+                Permission::CreateSession,
+                &contractor,
+                ip,
+                at,
+                resource,
+            );
+            assert_eq!(rule.as_deref(), Some("contractors-otherwise"));
+            assert!(trace[0].reason.as_deref().unwrap().contains(reason));
+        }
+
+        // Other users fall through to their role permissions
+        let other = claims("user:default/alice", &["group:default/developers"]);
+        let (rule, trace) = evaluate(
+            &rules,
+            Permission::CreateSession,
+            &other,
+            "192.168.1.2",
+            office,
+            Resource::default(),
+        );
+        assert_eq!(rule, None);
+        assert!(trace.iter().all(|t| !t.matched));
+    }
+
+    #[test]
+    fn test_owner_condition() {
+        let rules = rules(CONTRACTORS);
+        let oncall = claims("user:default/olly", &["group:default/oncall"]);
+        let now = Utc::now();
+        let bob = UserId::new("user:default/bob".to_string());
+        let olly = UserId::new("user:default/olly".to_string());
+
+        let (rule, _) = evaluate(
+            &rules,
+            Permission::KillSession,
+            &oncall,
+            "10.0.0.1",
+            now,
+            Resource::owned_by(Some(&bob)),
+        );
+        assert_eq!(rule.as_deref(), Some("oncall-kill"));
+
+        let (rule, trace) = evaluate(
+            &rules,
+            Permission::KillSession,
+            &oncall,
+            "10.0.0.1",
+            now,
+            Resource::owned_by(Some(&olly)),
+        );
+        assert_eq!(rule, None);
+        assert_eq!(
+            trace[2].reason.as_deref(),
+            Some("session owner does not match")
+        );
+    }
+
+    #[test]
+    fn test_claim_conditions_and_windows_over_midnight() {
+        let rules = rules(
+            r#"
+- name: night-shift
+  effect: allow
+  when:
+    claims: { department: ["ops*"] }
+    time: { start: "22:00", end: "06:00", utc_offset: "+02:00" }
+"#,
+        );
+        let mut ops = claims("user:default/nina", &[]);
+        ops.custom.insert(
+            "department".to_string(),
+            Value::String("ops-emea".to_string()),
+        );
+        let late = Utc.with_ymd_and_hms(2026, 10, 14, 23, 30, 0).unwrap(); // 01:30 local
+        let noon = Utc.with_ymd_and_hms(2026, 10, 14, 12, 0, 0).unwrap();
+
+        let (rule, _) = evaluate(
+            &rules,
+            Permission::SendInput,
+            &ops,
+            "10.0.0.1",
+            late,
+            Resource::default(),
+        );
+        assert_eq!(rule.as_deref(), Some("night-shift"));
+
+        let (rule, _) = evaluate(
+            &rules,
+            Permission::SendInput,
+            &ops,
+            "10.0.0.1",
+            noon,
+            Resource::default(),
+        );
+        assert_eq!(rule, None);
+
+        let dev = claims("user:default/dave", &[]);
+        let (_, trace) = evaluate(
+            &rules,
+            Permission::SendInput,
+            &dev,
+            "10.0.0.1",
+            late,
+            Resource::default(),
+        );
+        assert_eq!(
+            trace[0].reason.as_deref(),
+            Some("claim department does not match")
+        );
+    }
+}
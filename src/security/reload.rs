@@ -0,0 +1,469 @@
+// Authentication configuration hot reload
+// Per 011-authentication-spec.md: Configuration Reload
+// Responsibilities:
+// - Re-read provider definitions, allow/deny lists and permission rules
+// - Validate everything before swapping anything, so a bad file leaves the
+//   running configuration untouched
+// - Reload when the config or permissions file changes (SIGHUP reloads the
+//   whole server configuration, see server::reload)
+// - Record every reload attempt in the audit log
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use thiserror::Error;
+
+use crate::config::auth::{AuthConfig, AuthorizationConfig, JwksConfig};
+use crate::security::admission::AdmissionPolicy;
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::security::authorization::{AuthorizationError, AuthorizationService};
+use crate::security::jwks_client::JwksClient;
+use crate::security::jwt_validator::JwtValidator;
+use crate::security::static_keys::StaticKeyError;
+
+/// Reload error types
+#[derive(Error, Debug)]
+pub enum ReloadError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid auth configuration {path}: {reason}")]
+    InvalidFile { path: PathBuf, reason: String },
+
+    #[error("Invalid provider configuration: {0}")]
+    InvalidProviders(String),
+
+    #[error(transparent)]
+    Keys(#[from] StaticKeyError),
+
+    #[error(transparent)]
+    Rules(#[from] AuthorizationError),
+}
+
+/// What caused a reload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReloadTrigger {
+    /// SIGHUP
+    Signal,
+    /// A watched file changed
+    FileChange,
+    /// Requested explicitly
+    Manual,
+}
+
+impl ReloadTrigger {
+    /// Convert trigger to string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Signal => "sighup",
+            Self::FileChange => "file_change",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+/// What a successful reload put in place
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReloadSummary {
+    /// Configured identity providers (JWKS and static keys)
+    pub providers: usize,
+    /// Roles with explicit permissions
+    pub roles: usize,
+    /// Policy rules
+    pub policies: usize,
+}
+
+/// The reloadable sections of an auth config file (everything else in the
+/// file is ignored)
+#[derive(Deserialize)]
+struct AuthFile {
+    auth: ReloadableAuth,
+}
+
+#[derive(Deserialize)]
+struct ReloadableAuth {
+    jwks: JwksConfig,
+    authorization: AuthorizationConfig,
+}
+
+/// `base` with the `jwks` and `authorization` sections of its reload config
+/// file applied (unchanged when no file is configured)
+/// Per 011-authentication-spec.md: Configuration Reload
+pub fn apply_config_file(base: &AuthConfig) -> Result<AuthConfig, ReloadError> {
+    let mut config = base.clone();
+    if let Some(path) = &base.reload.config_file {
+        let content = std::fs::read_to_string(path).map_err(|source| ReloadError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let file: AuthFile =
+            serde_yaml::from_str(&content).map_err(|e| ReloadError::InvalidFile {
+                path: path.clone(),
+                reason: e.to_string(),
+            })?;
+        config.jwks = file.auth.jwks;
+        config.authorization = file.auth.authorization;
+    }
+
+    check_providers(&config.jwks)?;
+    Ok(config)
+}
+
+/// Provider names identify cached keys, so they must be present and unique
+fn check_providers(jwks: &JwksConfig) -> Result<(), ReloadError> {
+    let mut names = HashSet::new();
+    for provider in &jwks.providers {
+        if provider.name.is_empty() {
+            return Err(ReloadError::InvalidProviders(
+                "provider without a name".to_string(),
+            ));
+        }
+        if !names.insert(provider.name.as_str()) {
+            return Err(ReloadError::InvalidProviders(format!(
+                "duplicate provider name '{}'",
+                provider.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Modification time of a file, `None` when it cannot be read
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Swaps the live authentication and authorization services to a freshly
+/// loaded configuration
+/// Per 011-authentication-spec.md: Configuration Reload
+pub struct AuthReloader {
+    /// Main configuration's auth section; sections that are not reloadable
+    /// come from here
+    base: Mutex<AuthConfig>,
+    validator: Arc<JwtValidator>,
+    jwks_client: Arc<JwksClient>,
+    admission: Arc<AdmissionPolicy>,
+    authz: Arc<AuthorizationService>,
+    audit: Arc<AuditLogger>,
+    /// Configuration in effect (also serializes reloads)
+    current: Mutex<AuthConfig>,
+    /// Watched files and their modification times when last read
+    seen: Mutex<Vec<(PathBuf, Option<SystemTime>)>>,
+}
+
+impl AuthReloader {
+    /// Create a reloader for services built from `current`
+    pub fn new(
+        current: AuthConfig,
+        validator: Arc<JwtValidator>,
+        jwks_client: Arc<JwksClient>,
+        admission: Arc<AdmissionPolicy>,
+        authz: Arc<AuthorizationService>,
+        audit: Arc<AuditLogger>,
+    ) -> Self {
+        let reloader = Self {
+            base: Mutex::new(current.clone()),
+            validator,
+            jwks_client,
+            admission,
+            authz,
+            audit,
+            current: Mutex::new(current),
+            seen: Mutex::new(Vec::new()),
+        };
+        reloader.remember_files();
+        reloader
+    }
+
+    /// Configuration currently in effect
+    pub fn current(&self) -> AuthConfig {
+        self.current.lock().expect("reload lock poisoned").clone()
+    }
+
+    /// Swap in `base` (a freshly loaded main configuration's auth section)
+    /// with the reload config file applied, or keep the running
+    /// configuration if anything fails to load; the caller reports the
+    /// outcome
+    /// Per spec-kit/005-cli-spec.md: Live Reload
+    pub async fn apply(&self, base: AuthConfig) -> Result<ReloadSummary, ReloadError> {
+        let previous = std::mem::replace(&mut *self.base(), base);
+        let result = self.swap();
+        self.remember_files();
+
+        match &result {
+            // Issuer-only providers that are new need their endpoints
+            Ok(_) => self.jwks_client.discover_pending().await,
+            Err(_) => *self.base() = previous,
+        }
+        result
+    }
+
+    fn base(&self) -> std::sync::MutexGuard<'_, AuthConfig> {
+        self.base.lock().expect("reload lock poisoned")
+    }
+
+    /// Load the configuration again and swap it in, or keep the running one
+    /// if anything fails to load; either outcome is audited
+    pub async fn reload(&self, trigger: ReloadTrigger) -> Result<ReloadSummary, ReloadError> {
+        let result = self.swap();
+        self.remember_files();
+
+        match &result {
+            Ok(summary) => {
+                tracing::info!(
+                    trigger = trigger.as_str(),
+                    providers = summary.providers,
+                    roles = summary.roles,
+                    policies = summary.policies,
+                    "Authentication configuration reloaded"
+                );
+                self.audit
+                    .record(AuditEvent::new(AuditAction::ConfigReloaded).detail(format!(
+                        "{}: {} providers, {} roles, {} policy rules",
+                        trigger.as_str(),
+                        summary.providers,
+                        summary.roles,
+                        summary.policies
+                    )));
+
+                // Issuer-only providers that are new need their endpoints
+                self.jwks_client.discover_pending().await;
+            }
+            Err(e) => {
+                tracing::error!(
+                    trigger = trigger.as_str(),
+                    error = %e,
+                    "Authentication configuration reload failed, keeping previous configuration"
+                );
+                self.audit.record(
+                    AuditEvent::new(AuditAction::ConfigReloadFailed).detail(format!(
+                        "{}: {}",
+                        trigger.as_str(),
+                        e
+                    )),
+                );
+            }
+        }
+
+        result
+    }
+
+    /// Build every service from the new configuration first, then replace
+    /// them one after another
+    fn swap(&self) -> Result<ReloadSummary, ReloadError> {
+        let mut current = self.current.lock().expect("reload lock poisoned");
+
+        let config = apply_config_file(&self.base())?;
+        let authz = AuthorizationService::from_config(&config)?;
+        self.validator.reload(&config)?;
+        self.admission.replace(&config.authorization);
+        self.authz.replace(authz);
+
+        let (roles, policies) = self.authz.rule_counts();
+        let summary = ReloadSummary {
+            providers: config.jwks.providers.len(),
+            roles,
+            policies,
+        };
+        *current = config;
+        Ok(summary)
+    }
+
+    /// Files whose changes trigger a reload
+    fn watched_files(&self) -> Vec<PathBuf> {
+        let current = self.current.lock().expect("reload lock poisoned");
+        self.base()
+            .reload
+            .config_file
+            .iter()
+            .chain(&current.authorization.permissions_file)
+            .cloned()
+            .collect()
+    }
+
+    /// Record the watched files' modification times; a file that failed to
+    /// load is not retried until it changes again
+    fn remember_files(&self) {
+        let seen = self
+            .watched_files()
+            .into_iter()
+            .map(|path| {
+                let modified = modified(&path);
+                (path, modified)
+            })
+            .collect();
+        *self.seen.lock().expect("reload lock poisoned") = seen;
+    }
+
+    /// Whether a watched file changed since it was last read
+    fn files_changed(&self) -> bool {
+        let watched = self.watched_files();
+        let seen = self.seen.lock().expect("reload lock poisoned");
+        watched.len() != seen.len()
+            || seen
+                .iter()
+                .zip(&watched)
+                .any(|((path, at), watched)| path != watched || modified(path) != *at)
+    }
+
+    /// Start watching the config and permissions files, if enabled
+    pub fn start(self: Arc<Self>) {
+        let settings = self.base().reload.clone();
+        if !settings.watch {
+            return;
+        }
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(settings.watch_interval);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if self.files_changed() {
+                    let _ = self.reload(ReloadTrigger::FileChange).await;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::authorization::Permission;
+    use crate::security::testing::claims;
+    use crate::session::state::UserId;
+    use std::io::Write;
+
+    fn write(path: &Path, content: &str) {
+        let mut file = std::fs::File::create(path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    fn auth_file(allowed_user: &str, permissions_file: &Path) -> String {
+        format!(
+            "auth:\n  jwks:\n    providers: []\n  authorization:\n    allowed_users: [{}]\n    permissions_file: {}\n",
+            allowed_user,
+            permissions_file.display()
+        )
+    }
+
+    fn reloader(config: &AuthConfig) -> AuthReloader {
+        let jwks_client = Arc::new(JwksClient::new(config.clone()));
+        AuthReloader::new(
+            config.clone(),
+            Arc::new(JwtValidator::new(jwks_client.clone(), config.clone())),
+            jwks_client,
+            Arc::new(AdmissionPolicy::new(&config.authorization)),
+            Arc::new(AuthorizationService::from_auth_config(&config.authorization).unwrap()),
+            Arc::new(AuditLogger::default()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_reload_swaps_and_keeps_previous_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("auth.yaml");
+        let permissions_path = dir.path().join("permissions.yaml");
+        write(
+            &permissions_path,
+            "role_permissions:\n  user: [create_session]\n",
+        );
+        write(
+            &config_path,
+            &auth_file("user:default/alice", &permissions_path),
+        );
+
+        let mut base = AuthConfig::default();
+        base.reload.config_file = Some(config_path.clone());
+        let base = apply_config_file(&base).unwrap();
+        let reloader = reloader(&base);
+        let (alice, bob) = (
+            claims("user:default/alice", &[]),
+            claims("user:default/bob", &[]),
+        );
+        let user = UserId::new("user:default/alice".to_string());
+        assert!(reloader.admission.check(&alice).is_ok());
+        assert!(reloader.admission.check(&bob).is_err());
+        assert!(!reloader.files_changed());
+
+        // Allow lists and permission rules are replaced together
+        write(
+            &config_path,
+            &auth_file("user:default/bob", &permissions_path),
+        );
+        write(
+            &permissions_path,
+            "role_permissions:\n  user: [view_session]\n",
+        );
+        let summary = reloader.reload(ReloadTrigger::Manual).await.unwrap();
+        assert_eq!(summary.roles, 1);
+        assert!(reloader.admission.check(&alice).is_err());
+        assert!(reloader.admission.check(&bob).is_ok());
+        assert!(reloader
+            .authz
+            .check_permission(&user, "user", Permission::CreateSession, None)
+            .is_err());
+
+        // Broken files are rejected and the running configuration stays
+        write(&permissions_path, "role_permissions: [not a map\n");
+        assert!(matches!(
+            reloader.reload(ReloadTrigger::Manual).await,
+            Err(ReloadError::Rules(_))
+        ));
+        write(&config_path, "auth: {}\n");
+        assert!(matches!(
+            reloader.reload(ReloadTrigger::Manual).await,
+            Err(ReloadError::InvalidFile { .. })
+        ));
+        assert!(reloader.admission.check(&bob).is_ok());
+        assert!(reloader
+            .authz
+            .check_permission(&user, "user", Permission::ViewSession, None)
+            .is_ok());
+        assert!(!reloader.files_changed());
+    }
+
+    #[test]
+    fn test_example_auth_file_loads() {
+        let mut base = AuthConfig::default();
+        base.reload.config_file = Some(PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/config/auth.yaml"
+        )));
+        let config = apply_config_file(&base).unwrap();
+        assert_eq!(config.jwks.providers.len(), 4);
+        assert!(config.jwks.providers[3].keys.is_some());
+        assert!(config.authorization.deny_by_default);
+    }
+
+    #[test]
+    fn test_duplicate_provider_names_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("hmac.key");
+        let config_path = dir.path().join("auth.yaml");
+        let provider = format!(
+            "      - name: lab\n        keys:\n          secret_file: {}\n        issuer: lab\n        audience: web-terminal\n",
+            key_path.display()
+        );
+        write(
+            &config_path,
+            &format!(
+                "auth:\n  jwks:\n    providers:\n{}{}  authorization: {{}}\n",
+                provider, provider
+            ),
+        );
+
+        let mut base = AuthConfig::default();
+        base.reload.config_file = Some(config_path);
+        assert!(matches!(
+            apply_config_file(&base),
+            Err(ReloadError::InvalidProviders(_))
+        ));
+    }
+}
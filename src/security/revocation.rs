@@ -0,0 +1,468 @@
+// Token revocation list
+// Per 011-authentication-spec.md: Token Revocation
+// Responsibilities:
+// - Revoke single tokens (by jti), subjects, or everything issued before a time
+// - Persist revocations to disk so they survive restarts
+// - Check every validated credential against the list
+// - Accept short-lived WebSocket tickets only once (replay protection)
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::config::auth::RevocationConfig;
+use crate::security::api_keys::write_private;
+use crate::security::jwt_validator::Claims;
+
+/// Revocation error types
+#[derive(Error, Debug)]
+pub enum RevocationError {
+    #[error("Token revoked: {0}")]
+    Revoked(String),
+
+    #[error("Ticket {0} has already been used")]
+    TicketReused(String),
+
+    #[error("Ticket has no jti and cannot be checked for reuse")]
+    TicketWithoutJti,
+
+    #[error("Revocation list unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Revocation not found: {0}")]
+    NotFound(String),
+
+    #[error("Invalid revocation request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Revocation store error: {0}")]
+    Store(String),
+}
+
+/// A revoked token, subject or issuing period
+///
+/// Every field that is set must match, so `sub` together with
+/// `issued_before` revokes a subject's current tokens while letting them
+/// sign in again, and `sub` alone shuts them out until the entry is removed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Revocation {
+    pub id: String,
+    /// Token ID (`jti` claim)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    /// Subject (`sub` claim)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    /// Tokens issued (`iat`) before this time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issued_before: Option<DateTime<Utc>>,
+    /// Only credentials of this tenant (every tenant when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    /// When the entry is dropped (e.g. once the revoked token has expired)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Revocation {
+    /// Whether the holder of `claims`, a user of `tenant`, is revoked by
+    /// this entry
+    pub fn matches(&self, claims: &Claims, tenant: Option<&str>) -> bool {
+        self.tenant
+            .as_deref()
+            .is_none_or(|revoked| tenant == Some(revoked))
+            && self
+                .jti
+                .as_deref()
+                .is_none_or(|jti| token_id(claims) == Some(jti))
+            && self.sub.as_deref().is_none_or(|sub| claims.sub == sub)
+            && self
+                .issued_before
+                .is_none_or(|before| claims.iat < before.timestamp())
+    }
+
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+
+    /// What the entry revokes, for error messages and the audit log
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(jti) = &self.jti {
+            parts.push(format!("token {}", jti));
+        }
+        if let Some(sub) = &self.sub {
+            parts.push(format!("subject {}", sub));
+        }
+        if let Some(before) = &self.issued_before {
+            parts.push(format!("issued before {}", before.to_rfc3339()));
+        }
+        if let Some(tenant) = &self.tenant {
+            parts.push(format!("in tenant {}", tenant));
+        }
+        parts.join(", ")
+    }
+}
+
+/// The `jti` claim, if present
+fn token_id(claims: &Claims) -> Option<&str> {
+    claims.custom.get("jti").and_then(|jti| jti.as_str())
+}
+
+/// Parameters for a new revocation
+#[derive(Debug, Clone, Default)]
+pub struct NewRevocation {
+    pub jti: Option<String>,
+    pub sub: Option<String>,
+    pub issued_before: Option<DateTime<Utc>>,
+    pub tenant: Option<String>,
+    pub reason: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// On-disk layout of the revocation list
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreFile {
+    revocations: Vec<Revocation>,
+}
+
+/// Revoked credentials, checked after every successful validation
+/// Per 011-authentication-spec.md: Token Revocation
+pub struct RevocationList {
+    config: RevocationConfig,
+    entries: RwLock<Vec<Revocation>>,
+    /// Used ticket IDs and when each ticket expires
+    used_tickets: Mutex<HashMap<String, i64>>,
+    /// Why the persisted list could not be loaded; every credential is
+    /// refused rather than silently forgetting revocations
+    unavailable: Option<String>,
+}
+
+impl RevocationList {
+    /// Open the list, loading persisted revocations from `store_path` if it
+    /// exists
+    pub fn open(config: RevocationConfig) -> Result<Self, RevocationError> {
+        let mut entries = match &config.store_path {
+            Some(path) if path.exists() => load_store(path)?,
+            _ => Vec::new(),
+        };
+        entries.retain(|entry| !entry.is_expired(Utc::now()));
+
+        if !entries.is_empty() {
+            tracing::info!(revocations = entries.len(), "Loaded token revocations");
+        }
+
+        Ok(Self {
+            config,
+            entries: RwLock::new(entries),
+            used_tickets: Mutex::new(HashMap::new()),
+            unavailable: None,
+        })
+    }
+
+    /// A list that refuses every credential, for when the persisted list
+    /// cannot be read
+    pub fn unavailable(reason: impl Into<String>) -> Self {
+        Self {
+            config: RevocationConfig::default(),
+            entries: RwLock::new(Vec::new()),
+            used_tickets: Mutex::new(HashMap::new()),
+            unavailable: Some(reason.into()),
+        }
+    }
+
+    /// Current revocations, oldest first
+    pub fn list(&self) -> Vec<Revocation> {
+        let now = Utc::now();
+        self.entries
+            .read()
+            .expect("revocation lock poisoned")
+            .iter()
+            .filter(|entry| !entry.is_expired(now))
+            .cloned()
+            .collect()
+    }
+
+    /// Add a revocation on behalf of `created_by`
+    pub fn revoke(
+        &self,
+        created_by: &str,
+        request: NewRevocation,
+    ) -> Result<Revocation, RevocationError> {
+        if let Some(reason) = &self.unavailable {
+            return Err(RevocationError::Unavailable(reason.clone()));
+        }
+        if request.jti.is_none() && request.sub.is_none() && request.issued_before.is_none() {
+            return Err(RevocationError::InvalidRequest(
+                "one of jti, sub or issued_before is required".to_string(),
+            ));
+        }
+        if [&request.jti, &request.sub]
+            .iter()
+            .any(|value| value.as_deref().is_some_and(str::is_empty))
+        {
+            return Err(RevocationError::InvalidRequest(
+                "jti and sub must not be empty".to_string(),
+            ));
+        }
+
+        let revocation = Revocation {
+            id: Uuid::new_v4().simple().to_string()[..16].to_string(),
+            jti: request.jti,
+            sub: request.sub,
+            issued_before: request.issued_before,
+            tenant: request.tenant,
+            reason: request.reason,
+            created_by: created_by.to_string(),
+            created_at: Utc::now(),
+            expires_at: request.expires_at,
+        };
+
+        let mut entries = self.entries.write().expect("revocation lock poisoned");
+        let now = Utc::now();
+        entries.retain(|entry| !entry.is_expired(now));
+        entries.push(revocation.clone());
+        if let Err(e) = self.persist(&entries) {
+            entries.pop();
+            return Err(e);
+        }
+
+        Ok(revocation)
+    }
+
+    /// Remove a revocation, letting the credentials it matched in again
+    pub fn remove(&self, id: &str) -> Result<Revocation, RevocationError> {
+        let mut entries = self.entries.write().expect("revocation lock poisoned");
+        let index = entries
+            .iter()
+            .position(|entry| entry.id == id)
+            .ok_or_else(|| RevocationError::NotFound(id.to_string()))?;
+
+        let removed = entries.remove(index);
+        if let Err(e) = self.persist(&entries) {
+            entries.insert(index, removed);
+            return Err(e);
+        }
+
+        Ok(removed)
+    }
+
+    /// Refuse the holder of `claims`, a user of `tenant`, if a revocation
+    /// matches
+    pub fn check(&self, claims: &Claims, tenant: Option<&str>) -> Result<(), RevocationError> {
+        if let Some(reason) = &self.unavailable {
+            return Err(RevocationError::Unavailable(reason.clone()));
+        }
+
+        let now = Utc::now();
+        let entries = self.entries.read().expect("revocation lock poisoned");
+        match entries
+            .iter()
+            .find(|entry| !entry.is_expired(now) && entry.matches(claims, tenant))
+        {
+            Some(entry) => Err(RevocationError::Revoked(match &entry.reason {
+                Some(reason) => format!("{} ({})", entry.describe(), reason),
+                None => entry.describe(),
+            })),
+            None => Ok(()),
+        }
+    }
+
+    /// Record the use of a WebSocket ticket, refusing it the second time
+    ///
+    /// Only applies when `one_time_tickets` is enabled and the token lives no
+    /// longer than `ticket_lifetime`; longer-lived tokens may be reused.
+    pub fn consume_ticket(&self, claims: &Claims) -> Result<(), RevocationError> {
+        let lifetime = claims.exp.saturating_sub(claims.iat);
+        if !self.config.one_time_tickets || lifetime > self.config.ticket_lifetime.as_secs() as i64
+        {
+            return Ok(());
+        }
+        let jti = token_id(claims).ok_or(RevocationError::TicketWithoutJti)?;
+
+        let now = Utc::now().timestamp();
+        let mut used = self.used_tickets.lock().expect("ticket lock poisoned");
+        used.retain(|_, exp| *exp > now);
+        if used.contains_key(jti) {
+            return Err(RevocationError::TicketReused(jti.to_string()));
+        }
+        used.insert(jti.to_string(), claims.exp);
+        Ok(())
+    }
+
+    fn persist(&self, entries: &[Revocation]) -> Result<(), RevocationError> {
+        let Some(path) = &self.config.store_path else {
+            return Ok(());
+        };
+
+        let file = StoreFile {
+            revocations: entries.to_vec(),
+        };
+        let json =
+            serde_json::to_vec_pretty(&file).map_err(|e| RevocationError::Store(e.to_string()))?;
+
+        // Write a sibling file and rename it over the store so readers never
+        // see a partial file
+        let tmp = path.with_extension("tmp");
+        write_private(&tmp, &json)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| RevocationError::Store(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// Read a persisted revocation list
+fn load_store(path: &Path) -> Result<Vec<Revocation>, RevocationError> {
+    let content = std::fs::read(path)
+        .map_err(|e| RevocationError::Store(format!("{}: {}", path.display(), e)))?;
+    let file: StoreFile = serde_json::from_slice(&content)
+        .map_err(|e| RevocationError::Store(format!("{}: {}", path.display(), e)))?;
+    Ok(file.revocations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::testing::claims_with;
+    use std::time::Duration;
+
+    fn claims(sub: &str, jti: Option<&str>, iat: i64, lifetime: i64) -> Claims {
+        claims_with(
+            sub,
+            serde_json::json!({ "jti": jti, "iat": iat, "exp": iat + lifetime }),
+        )
+    }
+
+    #[test]
+    fn test_revocation_matching() {
+        let list = RevocationList::open(RevocationConfig::default()).unwrap();
+        let now = Utc::now().timestamp();
+        let alice = claims("user:default/alice", Some("t1"), now - 60, 3600);
+        let bob = claims("user:default/bob", Some("t2"), now - 60, 3600);
+
+        list.revoke(
+            "admin",
+            NewRevocation {
+                jti: Some("t1".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(matches!(
+            list.check(&alice, None),
+            Err(RevocationError::Revoked(_))
+        ));
+        assert!(list.check(&bob, None).is_ok());
+
+        // Subject revoked for tokens issued until now: a new sign-in works
+        let entry = list
+            .revoke(
+                "admin",
+                NewRevocation {
+                    sub: Some("user:default/bob".to_string()),
+                    issued_before: Some(Utc::now()),
+                    reason: Some("laptop stolen".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let err = list.check(&bob, None).unwrap_err();
+        assert!(err.to_string().contains("laptop stolen"));
+        assert!(list
+            .check(&claims("user:default/bob", None, now + 10, 3600), None)
+            .is_ok());
+
+        list.remove(&entry.id).unwrap();
+        assert!(list.check(&bob, None).is_ok());
+
+        // A tenant's revocation leaves the same subject elsewhere alone
+        list.revoke(
+            "acme/admin",
+            NewRevocation {
+                sub: Some("user:default/bob".to_string()),
+                tenant: Some("acme".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(list.check(&bob, Some("acme")).is_err());
+        assert!(list.check(&bob, Some("globex")).is_ok());
+        assert!(matches!(
+            list.revoke("admin", NewRevocation::default()),
+            Err(RevocationError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_revocations_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = RevocationConfig {
+            store_path: Some(dir.path().join("revocations.json")),
+            ..RevocationConfig::default()
+        };
+        let list = RevocationList::open(config.clone()).unwrap();
+        list.revoke(
+            "admin",
+            NewRevocation {
+                sub: Some("user:default/mallory".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        list.revoke(
+            "admin",
+            NewRevocation {
+                jti: Some("old".to_string()),
+                expires_at: Some(Utc::now() - chrono::Duration::seconds(1)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let reopened = RevocationList::open(config).unwrap();
+        assert_eq!(reopened.list().len(), 1);
+        let mallory = claims("user:default/mallory", None, Utc::now().timestamp(), 60);
+        assert!(reopened.check(&mallory, None).is_err());
+
+        let broken = RevocationList::unavailable("bad file");
+        assert!(matches!(
+            broken.check(&mallory, None),
+            Err(RevocationError::Unavailable(_))
+        ));
+    }
+
+    #[test]
+    fn test_one_time_tickets() {
+        let list = RevocationList::open(RevocationConfig {
+            one_time_tickets: true,
+            ticket_lifetime: Duration::from_secs(60),
+            ..RevocationConfig::default()
+        })
+        .unwrap();
+        let now = Utc::now().timestamp();
+
+        let ticket = claims("user:default/alice", Some("once"), now, 30);
+        assert!(list.consume_ticket(&ticket).is_ok());
+        assert!(matches!(
+            list.consume_ticket(&ticket),
+            Err(RevocationError::TicketReused(_))
+        ));
+        assert!(matches!(
+            list.consume_ticket(&claims("user:default/alice", None, now, 30)),
+            Err(RevocationError::TicketWithoutJti)
+        ));
+
+        // Regular tokens are not tickets
+        let token = claims("user:default/alice", Some("long"), now, 3600);
+        assert!(list.consume_ticket(&token).is_ok());
+        assert!(list.consume_ticket(&token).is_ok());
+    }
+}
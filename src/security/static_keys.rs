@@ -0,0 +1,459 @@
+// Per 011-authentication-spec.md section 3.1: Static key providers
+// Responsibilities:
+// - Load JWKS documents, PEM public keys and HMAC secrets from disk
+// - Reload key material when the file changes
+// - Select the verification key for a token's kid and algorithm
+//
+// Lets offline deployments (no reachable identity provider) validate locally
+// minted tokens through the same JwtValidator pipeline.
+
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::time::SystemTime;
+use thiserror::Error;
+
+use crate::config::auth::{JwksProvider as ProviderConfig, StaticKeySource};
+use crate::config::AuthConfig;
+use crate::security::jwt_validator::ValidationError;
+
+/// Shortest accepted HMAC secret (RFC 7518 section 3.2: at least the hash size)
+const MIN_SECRET_BYTES: usize = 32;
+
+/// Static key loading error types
+#[derive(Error, Debug)]
+pub enum StaticKeyError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid JWKS file {path}: {reason}")]
+    InvalidJwks { path: PathBuf, reason: String },
+
+    #[error("Invalid PEM public key {path}: expected an RSA, EC or Ed25519 public key")]
+    InvalidPem { path: PathBuf },
+
+    #[error("HMAC secret in {path} is {len} bytes, at least {MIN_SECRET_BYTES} are required")]
+    WeakSecret { path: PathBuf, len: usize },
+}
+
+/// Key families, each usable with a fixed set of algorithms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyFamily {
+    Hmac,
+    Rsa,
+    Ec,
+    Ed,
+}
+
+impl KeyFamily {
+    fn algorithms(self) -> &'static [Algorithm] {
+        match self {
+            Self::Hmac => &[Algorithm::HS256, Algorithm::HS384, Algorithm::HS512],
+            Self::Rsa => &[
+                Algorithm::RS256,
+                Algorithm::RS384,
+                Algorithm::RS512,
+                Algorithm::PS256,
+                Algorithm::PS384,
+                Algorithm::PS512,
+            ],
+            Self::Ec => &[Algorithm::ES256, Algorithm::ES384],
+            Self::Ed => &[Algorithm::EdDSA],
+        }
+    }
+}
+
+/// A verification key and the algorithms it may be used with
+#[derive(Clone)]
+struct StaticKey {
+    kid: Option<String>,
+    key: DecodingKey,
+    algorithms: Vec<Algorithm>,
+}
+
+/// Keys from the last successful load
+#[derive(Default)]
+struct LoadedKeys {
+    keys: Vec<StaticKey>,
+    /// Modification time of the file when last read (successfully or not)
+    modified: Option<SystemTime>,
+}
+
+/// Provider whose keys come from a local file
+pub struct StaticKeyProvider {
+    pub name: String,
+    pub issuer: String,
+    /// Audience tokens must be issued for
+    pub audience: String,
+    /// Configured algorithms (narrow each key's family defaults)
+    configured: Vec<Algorithm>,
+    source: StaticKeySource,
+    loaded: RwLock<LoadedKeys>,
+}
+
+impl StaticKeyProvider {
+    /// Create a provider and load its keys (a load failure is logged and
+    /// leaves the provider without keys until the file is fixed)
+    pub fn new(config: &ProviderConfig, source: StaticKeySource) -> Self {
+        let provider = Self::unloaded(config, source);
+        provider.reload(true);
+        provider
+    }
+
+    /// Create a provider, failing if its keys cannot be loaded
+    pub fn try_new(
+        config: &ProviderConfig,
+        source: StaticKeySource,
+    ) -> Result<Self, StaticKeyError> {
+        let provider = Self::unloaded(config, source);
+        let modified = std::fs::metadata(provider.source.path())
+            .and_then(|m| m.modified())
+            .ok();
+        let keys = provider.load()?;
+        *provider.loaded.write().expect("static key lock poisoned") = LoadedKeys { keys, modified };
+        Ok(provider)
+    }
+
+    fn unloaded(config: &ProviderConfig, source: StaticKeySource) -> Self {
+        Self {
+            name: config.name.clone(),
+            issuer: config.issuer.clone(),
+            audience: config.audience.clone(),
+            configured: config
+                .algorithms
+                .iter()
+                .filter_map(|alg| Algorithm::from_str(alg).ok())
+                .collect(),
+            source,
+            loaded: RwLock::new(LoadedKeys::default()),
+        }
+    }
+
+    /// Verification key for a token's `kid` and algorithm
+    pub fn decoding_key(
+        &self,
+        kid: Option<&str>,
+        algorithm: Algorithm,
+    ) -> Result<DecodingKey, ValidationError> {
+        self.reload(false);
+
+        let loaded = self.loaded.read().expect("static key lock poisoned");
+        let usable: Vec<&StaticKey> = loaded
+            .keys
+            .iter()
+            .filter(|k| k.algorithms.contains(&algorithm))
+            .collect();
+        if usable.is_empty() {
+            return Err(ValidationError::UnsupportedAlgorithm(algorithm));
+        }
+
+        // Keys without a kid match any token; a token without a kid matches
+        // only when there is no ambiguity
+        let key = match kid {
+            Some(kid) => usable
+                .iter()
+                .find(|k| k.kid.as_deref().is_none_or(|k| k == kid)),
+            None if usable.len() == 1 => usable.first(),
+            None => usable.iter().find(|k| k.kid.is_none()),
+        };
+
+        key.map(|k| k.key.clone())
+            .ok_or_else(|| ValidationError::KeyNotFound(kid.unwrap_or("<none>").to_string()))
+    }
+
+    /// Re-read the key file if it changed (always, when `force` is set);
+    /// on failure the previous keys stay in use
+    fn reload(&self, force: bool) {
+        let path = self.source.path();
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+        let last_modified = self
+            .loaded
+            .read()
+            .expect("static key lock poisoned")
+            .modified;
+        if !force && modified == last_modified {
+            return;
+        }
+
+        let result = self.load();
+
+        let mut loaded = self.loaded.write().expect("static key lock poisoned");
+        loaded.modified = modified;
+        match result {
+            Ok(keys) => {
+                tracing::info!(
+                    provider = %self.name,
+                    path = %path.display(),
+                    keys_count = keys.len(),
+                    "Loaded static keys"
+                );
+                loaded.keys = keys;
+            }
+            Err(e) => {
+                tracing::error!(
+                    provider = %self.name,
+                    error = %e,
+                    keys_count = loaded.keys.len(),
+                    "Failed to load static keys, keeping previous keys"
+                );
+            }
+        }
+    }
+
+    /// Read the key file
+    fn load(&self) -> Result<Vec<StaticKey>, StaticKeyError> {
+        Ok(load_keys(&self.source)?
+            .into_iter()
+            .map(|(kid, key, family, pinned)| StaticKey {
+                kid,
+                key,
+                algorithms: self.algorithms_for(family, pinned),
+            })
+            .collect())
+    }
+
+    /// Algorithms a key may verify: the key's `alg` if it pins one, otherwise
+    /// the configured algorithms of its family (all of them if none are)
+    fn algorithms_for(&self, family: KeyFamily, pinned: Option<Algorithm>) -> Vec<Algorithm> {
+        if let Some(alg) = pinned {
+            return family
+                .algorithms()
+                .iter()
+                .copied()
+                .filter(|a| *a == alg)
+                .collect();
+        }
+
+        let configured: Vec<Algorithm> = family
+            .algorithms()
+            .iter()
+            .copied()
+            .filter(|a| self.configured.contains(a))
+            .collect();
+        if configured.is_empty() {
+            family.algorithms().to_vec()
+        } else {
+            configured
+        }
+    }
+}
+
+/// Loaded key: (kid, key, family, algorithm pinned by the key itself)
+type RawKey = (Option<String>, DecodingKey, KeyFamily, Option<Algorithm>);
+
+/// Read and parse a key file
+fn load_keys(source: &StaticKeySource) -> Result<Vec<RawKey>, StaticKeyError> {
+    let path = source.path();
+    let contents = std::fs::read(path).map_err(|source| StaticKeyError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    match source {
+        StaticKeySource::JwksFile(path) => {
+            let invalid = |reason: String| StaticKeyError::InvalidJwks {
+                path: path.clone(),
+                reason,
+            };
+            let set: JwkSet =
+                serde_json::from_slice(&contents).map_err(|e| invalid(e.to_string()))?;
+            if set.keys.is_empty() {
+                return Err(invalid("no keys".to_string()));
+            }
+            set.keys
+                .iter()
+                .map(|jwk| {
+                    let key = DecodingKey::from_jwk(jwk).map_err(|e| invalid(e.to_string()))?;
+                    Ok((
+                        jwk.common.key_id.clone(),
+                        key,
+                        jwk_family(jwk),
+                        jwk.common
+                            .key_algorithm
+                            .and_then(|alg| Algorithm::from_str(&alg.to_string()).ok()),
+                    ))
+                })
+                .collect()
+        }
+        StaticKeySource::PemFile(path) => {
+            let (key, family) = DecodingKey::from_rsa_pem(&contents)
+                .map(|key| (key, KeyFamily::Rsa))
+                .or_else(|_| DecodingKey::from_ec_pem(&contents).map(|key| (key, KeyFamily::Ec)))
+                .or_else(|_| DecodingKey::from_ed_pem(&contents).map(|key| (key, KeyFamily::Ed)))
+                .map_err(|_| StaticKeyError::InvalidPem { path: path.clone() })?;
+            Ok(vec![(None, key, family, None)])
+        }
+        StaticKeySource::SecretFile(path) => {
+            let secret = contents.trim_ascii();
+            if secret.len() < MIN_SECRET_BYTES {
+                return Err(StaticKeyError::WeakSecret {
+                    path: path.clone(),
+                    len: secret.len(),
+                });
+            }
+            Ok(vec![(
+                None,
+                DecodingKey::from_secret(secret),
+                KeyFamily::Hmac,
+                None,
+            )])
+        }
+    }
+}
+
+fn jwk_family(jwk: &Jwk) -> KeyFamily {
+    match jwk.algorithm {
+        AlgorithmParameters::RSA(_) => KeyFamily::Rsa,
+        AlgorithmParameters::EllipticCurve(_) => KeyFamily::Ec,
+        AlgorithmParameters::OctetKeyPair(_) => KeyFamily::Ed,
+        AlgorithmParameters::OctetKey(_) => KeyFamily::Hmac,
+    }
+}
+
+/// Providers configured with local key material
+/// Per 011-authentication-spec.md section 3.1: Static key providers
+#[derive(Default)]
+pub struct StaticKeyStore {
+    providers: Vec<StaticKeyProvider>,
+}
+
+impl StaticKeyStore {
+    /// Load every provider that has `keys` configured
+    pub fn new(auth_config: &AuthConfig) -> Self {
+        let providers = auth_config
+            .jwks
+            .providers
+            .iter()
+            .filter_map(|p| {
+                p.keys
+                    .clone()
+                    .map(|source| StaticKeyProvider::new(p, source))
+            })
+            .collect();
+
+        Self { providers }
+    }
+
+    /// Like [`Self::new`], but fail on the first key file that cannot be
+    /// loaded, so a bad configuration can be rejected before it replaces a
+    /// working one
+    /// Per 011-authentication-spec.md: Configuration Reload
+    pub fn try_new(auth_config: &AuthConfig) -> Result<Self, StaticKeyError> {
+        let providers = auth_config
+            .jwks
+            .providers
+            .iter()
+            .filter_map(|p| {
+                p.keys
+                    .clone()
+                    .map(|source| StaticKeyProvider::try_new(p, source))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { providers })
+    }
+
+    /// Find provider by issuer
+    pub fn find_provider_by_issuer(&self, issuer: &str) -> Option<&StaticKeyProvider> {
+        self.providers.iter().find(|p| p.issuer == issuer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+    fn provider_config(source: StaticKeySource, algorithms: &[&str]) -> ProviderConfig {
+        ProviderConfig {
+            name: "lab".to_string(),
+            url: None,
+            keys: Some(source),
+            issuer: "web-terminal-lab".to_string(),
+            audience: "web-terminal".to_string(),
+            algorithms: algorithms.iter().map(|a| a.to_string()).collect(),
+            cache_ttl: Duration::from_secs(3600),
+            refresh_interval: Duration::from_secs(900),
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    fn provider(source: StaticKeySource, algorithms: &[&str]) -> StaticKeyProvider {
+        StaticKeyProvider::new(&provider_config(source.clone(), algorithms), source)
+    }
+
+    #[test]
+    fn test_secret_file_algorithms() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hmac.key");
+        std::fs::write(&path, format!("{}\n", SECRET)).unwrap();
+
+        // Default (RS/ES) algorithms do not apply: every HS algorithm does
+        let any = provider(StaticKeySource::SecretFile(path.clone()), &["RS256"]);
+        assert!(any.decoding_key(None, Algorithm::HS512).is_ok());
+        assert!(matches!(
+            any.decoding_key(None, Algorithm::RS256),
+            Err(ValidationError::UnsupportedAlgorithm(_))
+        ));
+
+        let only_hs512 = provider(StaticKeySource::SecretFile(path), &["HS512"]);
+        assert!(only_hs512
+            .decoding_key(Some("any"), Algorithm::HS512)
+            .is_ok());
+        assert!(only_hs512.decoding_key(None, Algorithm::HS256).is_err());
+    }
+
+    #[test]
+    fn test_weak_secret_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hmac.key");
+        std::fs::write(&path, "short").unwrap();
+
+        assert!(matches!(
+            load_keys(&StaticKeySource::SecretFile(path)),
+            Err(StaticKeyError::WeakSecret { len: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn test_jwks_file_matches_kid() {
+        let path = PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/test-jwks.json"
+        ));
+        let provider = provider(StaticKeySource::JwksFile(path), &["RS256"]);
+
+        assert!(provider
+            .decoding_key(Some("test-key"), Algorithm::RS256)
+            .is_ok());
+        assert!(matches!(
+            provider.decoding_key(Some("other-key"), Algorithm::RS256),
+            Err(ValidationError::KeyNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_reload_keeps_keys_on_bad_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hmac.key");
+        std::fs::write(&path, SECRET).unwrap();
+        let provider = provider(StaticKeySource::SecretFile(path.clone()), &[]);
+        assert!(provider.decoding_key(None, Algorithm::HS256).is_ok());
+
+        // A broken rewrite is ignored; the previous secret keeps working
+        std::fs::write(&path, "short").unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+        assert!(provider.decoding_key(None, Algorithm::HS256).is_ok());
+    }
+}
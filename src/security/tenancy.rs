@@ -0,0 +1,317 @@
+// Tenant resolution
+// Per 011-authentication-spec.md: Multi-Tenancy
+// Responsibilities:
+// - Derive a verified credential's tenant from its provider or a claim
+// - Qualify user IDs by tenant so overlapping subjects never collide
+// - Decide which tenants an administrator manages
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::config::auth::{TenancyConfig, TenantConfig};
+use crate::security::admission::claim_values;
+use crate::security::api_keys::{ApiKey, API_KEY_PROVIDER};
+use crate::security::jwt_validator::Claims;
+use crate::session::state::UserId;
+
+/// Tenancy error types
+#[derive(Error, Debug)]
+pub enum TenantError {
+    #[error("No tenant for this credential (provider '{0}')")]
+    NoTenant(String),
+
+    #[error("Unknown tenant '{0}'")]
+    UnknownTenant(String),
+
+    #[error("Tenant '{tenant}' does not accept credentials from provider '{provider}'")]
+    ProviderNotAllowed { tenant: String, provider: String },
+
+    #[error("Invalid tenancy configuration: {0}")]
+    InvalidConfig(String),
+}
+
+/// The tenant a verified user belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Tenant {
+    pub name: String,
+    /// Administrators of an operator tenant manage every tenant
+    pub operator: bool,
+}
+
+impl Tenant {
+    /// Whether this tenant's administrators manage resources of `tenant`
+    /// (`None` for server-wide state)
+    pub fn manages(&self, tenant: Option<&str>) -> bool {
+        self.operator || tenant == Some(self.name.as_str())
+    }
+
+    /// `sub` qualified by this tenant
+    pub fn user_id(&self, sub: &str) -> UserId {
+        UserId::new(format!("{}/{}", self.name, sub))
+    }
+}
+
+/// Maps verified credentials to tenants
+/// Per 011-authentication-spec.md: Multi-Tenancy
+#[derive(Debug, Default)]
+pub struct TenantResolver {
+    config: TenancyConfig,
+    /// Provider name -> tenant name
+    providers: HashMap<String, String>,
+}
+
+impl TenantResolver {
+    /// Check `config` and index its tenants by provider
+    pub fn new(config: TenancyConfig) -> Result<Self, TenantError> {
+        let mut providers = HashMap::new();
+        for (i, tenant) in config.tenants.iter().enumerate() {
+            if tenant.name.is_empty() || tenant.name.contains('/') {
+                return Err(TenantError::InvalidConfig(format!(
+                    "tenant name '{}' must be non-empty and contain no '/'",
+                    tenant.name
+                )));
+            }
+            if config.tenants[..i].iter().any(|t| t.name == tenant.name) {
+                return Err(TenantError::InvalidConfig(format!(
+                    "tenant '{}' is configured twice",
+                    tenant.name
+                )));
+            }
+            for provider in &tenant.providers {
+                if let Some(other) = providers.insert(provider.clone(), tenant.name.clone()) {
+                    return Err(TenantError::InvalidConfig(format!(
+                        "provider '{}' belongs to both '{}' and '{}'",
+                        provider, other, tenant.name
+                    )));
+                }
+            }
+        }
+        if let Some(name) = &config.default_tenant {
+            if !config.tenants.iter().any(|t| &t.name == name) {
+                return Err(TenantError::UnknownTenant(name.clone()));
+            }
+        }
+
+        Ok(Self { config, providers })
+    }
+
+    /// A resolver that puts nobody in a tenant
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// A resolver that places nobody, refusing every credential, for when
+    /// the configured tenants are invalid
+    pub fn refuse_all() -> Self {
+        Self {
+            config: TenancyConfig {
+                enabled: true,
+                ..TenancyConfig::default()
+            },
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Whether users are qualified by tenant
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Settings of the tenant called `name`
+    pub fn config(&self, name: &str) -> Option<&TenantConfig> {
+        self.config.tenants.iter().find(|t| t.name == name)
+    }
+
+    /// The tenant called `name`
+    pub fn tenant(&self, name: &str) -> Option<Tenant> {
+        self.config(name).map(|config| Tenant {
+            name: config.name.clone(),
+            operator: config.operator,
+        })
+    }
+
+    /// Tenant of a credential issued by `provider` to the holder of
+    /// `claims`: named by the tenancy claim when configured, otherwise the
+    /// tenant listing the provider, otherwise the default tenant
+    ///
+    /// A tenant named by the claim must list `provider`, so one identity
+    /// provider cannot place its users in another's tenant. `None` when
+    /// tenancy is disabled.
+    pub fn resolve(&self, provider: &str, claims: &Claims) -> Result<Option<Tenant>, TenantError> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+
+        let named = match &self.config.claim {
+            Some(claim) => {
+                let document = serde_json::to_value(claims).unwrap_or(Value::Null);
+                let named = claim_values(&document, claim).into_iter().next();
+                if let Some(name) = &named {
+                    let listed = self
+                        .config(name)
+                        .map(|t| t.providers.iter().any(|p| p == provider));
+                    if listed == Some(false) {
+                        return Err(TenantError::ProviderNotAllowed {
+                            tenant: name.clone(),
+                            provider: provider.to_string(),
+                        });
+                    }
+                }
+                named
+            }
+            None => self.providers.get(provider).cloned(),
+        };
+        self.named(named, provider)
+    }
+
+    /// Tenant of an API key: the one it was created in, or the default
+    /// tenant for keys created before tenancy was enabled
+    pub fn resolve_key(&self, key: &ApiKey) -> Result<Option<Tenant>, TenantError> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+        self.named(key.tenant.clone(), API_KEY_PROVIDER)
+    }
+
+    fn named(&self, name: Option<String>, provider: &str) -> Result<Option<Tenant>, TenantError> {
+        let name = name
+            .or_else(|| self.config.default_tenant.clone())
+            .ok_or_else(|| TenantError::NoTenant(provider.to_string()))?;
+
+        self.tenant(&name)
+            .map(Some)
+            .ok_or(TenantError::UnknownTenant(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::testing::claims_with;
+
+    fn claims(sub: &str, org: Option<&str>) -> Claims {
+        claims_with(sub, serde_json::json!({ "org": org }))
+    }
+
+    fn tenant(name: &str, providers: &[&str]) -> TenantConfig {
+        TenantConfig {
+            name: name.to_string(),
+            providers: providers.iter().map(|p| p.to_string()).collect(),
+            workspace_root: None,
+            max_sessions_per_user: None,
+            max_sessions: None,
+            permissions_file: None,
+            operator: false,
+        }
+    }
+
+    fn resolver(configure: impl FnOnce(&mut TenancyConfig)) -> TenantResolver {
+        let mut config = TenancyConfig {
+            enabled: true,
+            tenants: vec![
+                tenant("acme", &["acme-idp"]),
+                tenant("globex", &["globex-idp"]),
+            ],
+            ..TenancyConfig::default()
+        };
+        configure(&mut config);
+        TenantResolver::new(config).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_by_provider_and_qualify() {
+        let resolver = resolver(|_| {});
+
+        let acme = resolver
+            .resolve("acme-idp", &claims("alice", None))
+            .unwrap()
+            .unwrap();
+        let globex = resolver
+            .resolve("globex-idp", &claims("alice", None))
+            .unwrap()
+            .unwrap();
+        assert_eq!(acme.name, "acme");
+        assert_ne!(acme.user_id("alice"), globex.user_id("alice"));
+        assert_eq!(acme.user_id("alice").as_str(), "acme/alice");
+
+        assert!(matches!(
+            resolver.resolve("other-idp", &claims("alice", None)),
+            Err(TenantError::NoTenant(_))
+        ));
+        assert!(TenantResolver::disabled()
+            .resolve("acme-idp", &claims("alice", None))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_resolve_by_claim_and_default() {
+        let resolver = resolver(|config| {
+            config.claim = Some("org".to_string());
+            config.default_tenant = Some("acme".to_string());
+        });
+
+        let tenant = |provider, org| {
+            resolver
+                .resolve(provider, &claims("alice", org))
+                .map(|t| t.unwrap().name)
+        };
+        assert_eq!(tenant("globex-idp", Some("globex")).unwrap(), "globex");
+        assert_eq!(tenant("any", None).unwrap(), "acme");
+        assert!(matches!(
+            tenant("any", Some("initech")),
+            Err(TenantError::UnknownTenant(_))
+        ));
+
+        // A provider cannot place its users in a tenant that does not list it
+        assert!(matches!(
+            tenant("any", Some("globex")),
+            Err(TenantError::ProviderNotAllowed { .. })
+        ));
+        assert!(matches!(
+            tenant("acme-idp", Some("globex")),
+            Err(TenantError::ProviderNotAllowed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_admin_scope() {
+        let acme = Tenant {
+            name: "acme".to_string(),
+            operator: false,
+        };
+        assert!(acme.manages(Some("acme")));
+        assert!(!acme.manages(Some("globex")));
+        assert!(!acme.manages(None));
+
+        let operator = Tenant {
+            operator: true,
+            ..acme
+        };
+        assert!(operator.manages(Some("globex")));
+        assert!(operator.manages(None));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let invalid = |configure: fn(&mut TenancyConfig)| {
+            let mut config = TenancyConfig {
+                enabled: true,
+                tenants: vec![tenant("acme", &["shared"]), tenant("globex", &[])],
+                ..TenancyConfig::default()
+            };
+            configure(&mut config);
+            TenantResolver::new(config).is_err()
+        };
+        assert!(!invalid(|_| {}));
+        assert!(invalid(
+            |c| c.tenants[1].providers = vec!["shared".to_string()]
+        ));
+        assert!(invalid(|c| c.tenants[1].name = "acme".to_string()));
+        assert!(invalid(|c| c.tenants[1].name = "a/b".to_string()));
+        assert!(invalid(|c| c.default_tenant = Some("initech".to_string())));
+    }
+}
@@ -0,0 +1,108 @@
+// Test helpers shared by the security modules
+// Responsibilities:
+// - Build JWT claims for unit tests
+// - Sign tokens for a local HMAC provider and validate them
+// - Build configurations from their defaults
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde_json::Value;
+use tempfile::TempDir;
+
+use crate::config::auth::{JwksProvider, StaticKeySource};
+use crate::config::AuthConfig;
+use crate::security::jwks_client::JwksClient;
+use crate::security::jwt_validator::{Claims, JwtValidator};
+
+/// Issuer of the `lab` provider trusted by `lab_validator`
+pub(crate) const LAB_ISSUER: &str = "web-terminal-lab";
+
+/// HS256 secret the `lab` provider signs with
+const LAB_SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+/// Claims of a token for `sub` listing the entity refs `ent`, issued by a
+/// test provider for `web-terminal` and valid until 2096
+pub(crate) fn claims(sub: &str, ent: &[&str]) -> Claims {
+    claims_with(sub, serde_json::json!({ "ent": ent }))
+}
+
+/// Claims of a token for `sub` with the claims in `extra` (e.g. `org`,
+/// `jti`, or `iat` and `exp`) added or replaced; null values are left out
+pub(crate) fn claims_with(sub: &str, extra: Value) -> Claims {
+    let mut claims = serde_json::json!({
+        "sub": sub,
+        "iss": "https://idp.example.com",
+        "aud": "web-terminal",
+        "exp": 4_000_000_000i64,
+        "iat": 1_700_000_000i64,
+    });
+    merge(&mut claims, extra);
+    serde_json::from_value(claims).expect("test claims")
+}
+
+/// A validator trusting only the `lab` provider, whose secret lives in the
+/// returned directory
+pub(crate) fn lab_validator() -> (TempDir, JwtValidator) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("hmac.key");
+    std::fs::write(&path, LAB_SECRET).unwrap();
+
+    let mut auth_config = AuthConfig::default();
+    auth_config.jwks.providers = vec![JwksProvider {
+        name: "lab".to_string(),
+        url: None,
+        keys: Some(StaticKeySource::SecretFile(path)),
+        issuer: LAB_ISSUER.to_string(),
+        audience: "web-terminal".to_string(),
+        algorithms: vec!["HS256".to_string()],
+        cache_ttl: Duration::from_secs(3600),
+        refresh_interval: Duration::from_secs(900),
+        timeout: Duration::from_secs(30),
+    }];
+    let jwks_client = Arc::new(JwksClient::new(auth_config.clone()));
+    (dir, JwtValidator::new(jwks_client, auth_config))
+}
+
+/// A token for `sub` signed by the `lab` provider, valid for five minutes,
+/// with the claims in `extra` (e.g. `aud` or `exp`) added or replaced
+pub(crate) fn lab_token(sub: &str, extra: Value) -> String {
+    let now = chrono::Utc::now().timestamp();
+    let mut claims = serde_json::json!({
+        "sub": sub,
+        "iss": LAB_ISSUER,
+        "aud": "web-terminal",
+        "exp": now + 300,
+        "iat": now,
+    });
+    merge(&mut claims, extra);
+    jsonwebtoken::encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(LAB_SECRET),
+    )
+    .unwrap()
+}
+
+/// The header and claims of `token` under a signature nobody made
+pub(crate) fn forge(token: &str) -> String {
+    let (signed, _) = token.rsplit_once('.').expect("JWT");
+    format!("{}.Zm9yZ2VkLXNpZ25hdHVyZQ", signed)
+}
+
+/// Add or replace the non-null claims of `extra` in `claims`
+fn merge(claims: &mut Value, extra: Value) {
+    if let Value::Object(extra) = extra {
+        for (name, value) in extra.into_iter().filter(|(_, value)| !value.is_null()) {
+            claims[name] = value;
+        }
+    }
+}
+
+/// The default configuration adjusted by `configure`
+pub(crate) fn configured<C: Default>(configure: impl FnOnce(&mut C)) -> C {
+    let mut config = C::default();
+    configure(&mut config);
+    config
+}
@@ -0,0 +1,1090 @@
+// Local control channel
+// Per spec-kit/005-cli-spec.md: Process Management
+// Responsibilities:
+// - Hold the pidfile while the server runs, refusing a second server
+// - Answer status, stop and restart requests on a Unix socket that only the
+//   server's user can open (and refuse peers running as anyone else)
+// - Report uptime, sessions, attached clients and listener addresses
+// - Admin API: list, kill and clean up sessions, metrics and health
+// - Client side used by the server and session management commands
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::task::JoinHandle;
+
+use crate::config::server::current_uid;
+use crate::monitoring::metrics;
+use crate::pty::PtyManager;
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::server::shutdown::{ShutdownCoordinator, ShutdownSummary};
+use crate::session::{Session, SessionId, SessionManager};
+
+/// Longest request line accepted
+const MAX_REQUEST: u64 = 64 * 1024;
+
+/// Control channel error types
+#[derive(Error, Debug)]
+pub enum ControlError {
+    #[error("Server already running (pid {0})")]
+    AlreadyRunning(u32),
+
+    #[error("Server already running: control socket {0} is in use")]
+    SocketInUse(PathBuf),
+
+    #[error("Server not running: no control socket at {0}")]
+    NotRunning(PathBuf),
+
+    #[error("Control socket {0}: permission denied")]
+    PermissionDenied(PathBuf),
+
+    #[error("Server cannot connect to control socket {path}: {reason}")]
+    Connect { path: PathBuf, reason: String },
+
+    #[error("Control channel error at {path}: {reason}")]
+    Io { path: PathBuf, reason: String },
+
+    #[error("Invalid control message: {0}")]
+    Protocol(String),
+
+    /// Refused or failed by the server
+    #[error("{0}")]
+    Server(String),
+}
+
+impl From<ControlError> for std::io::Error {
+    fn from(e: ControlError) -> Self {
+        let kind = match e {
+            ControlError::AlreadyRunning(_) | ControlError::SocketInUse(_) => {
+                std::io::ErrorKind::AddrInUse
+            }
+            ControlError::PermissionDenied(_) => std::io::ErrorKind::PermissionDenied,
+            _ => std::io::ErrorKind::Other,
+        };
+        std::io::Error::new(kind, e)
+    }
+}
+
+/// A request on the control socket, one JSON line per connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlRequest {
+    Status,
+    /// Drain within `timeout_secs` (default `server.shutdown.timeout`) and
+    /// exit; `force` hangs up terminals at once
+    Stop {
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+        #[serde(default)]
+        force: bool,
+    },
+    /// Drain, then start again in the same process
+    Restart {
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+    /// Sessions, optionally only those of a user or tenant
+    ListSessions {
+        #[serde(default)]
+        user: Option<String>,
+        #[serde(default)]
+        tenant: Option<String>,
+    },
+    /// Hang up the session's terminal, give it `server.shutdown.kill_after`
+    /// to exit and destroy the session; `force` kills it at once
+    KillSession {
+        session_id: String,
+        #[serde(default)]
+        force: bool,
+    },
+    /// Kill expired sessions (every session with `all`); `dry_run` only
+    /// reports them
+    CleanupSessions {
+        #[serde(default)]
+        dry_run: bool,
+        #[serde(default)]
+        all: bool,
+    },
+    Metrics,
+    Health,
+}
+
+/// Replies, one JSON line each; `stop` and `restart` get `draining` at once
+/// and `stopped` when the drain is done
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum ControlResponse {
+    Status(ServerStatus),
+    Draining {
+        seconds: u64,
+    },
+    Stopped(ShutdownSummary),
+    Sessions {
+        sessions: Vec<SessionInfo>,
+    },
+    Killed {
+        session: SessionInfo,
+    },
+    CleanedUp {
+        sessions: Vec<SessionInfo>,
+        dry_run: bool,
+    },
+    Metrics(MetricsSnapshot),
+    Health(HealthReport),
+    Error {
+        message: String,
+    },
+}
+
+/// What `web-terminal status` reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerStatus {
+    pub pid: u32,
+    pub version: String,
+    pub started_at: DateTime<Utc>,
+    pub uptime_secs: u64,
+    /// e.g. `http://0.0.0.0:8080`
+    pub listeners: Vec<String>,
+    pub sessions: usize,
+    /// WebSocket and ttyd connections
+    pub clients: usize,
+    /// Whether a shutdown or restart is under way
+    pub draining: bool,
+}
+
+/// A session as listed by `web-terminal sessions list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub user: String,
+    pub tenant: Option<String>,
+    pub profile: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    pub idle_secs: u64,
+    /// Idle for longer than `session.timeout`
+    pub expired: bool,
+    /// Whether the session's terminal is still running
+    pub terminal_running: bool,
+    /// Connections attached to the terminal's output
+    pub clients: usize,
+}
+
+/// Live figures for `web-terminal metrics`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub uptime_secs: u64,
+    pub sessions: usize,
+    /// Distinct session owners
+    pub users: usize,
+    pub clients: usize,
+    /// Terminal processes tracked, and how many are still running
+    pub terminals: usize,
+    pub terminals_running: usize,
+    /// Resident memory (Linux only)
+    pub memory_rss_bytes: Option<u64>,
+    /// User plus system CPU time used by the server
+    pub cpu_seconds: f64,
+    pub draining: bool,
+    /// Everything `GET /api/v1/metrics` exports
+    #[serde(default)]
+    pub prometheus: String,
+}
+
+/// Result of `web-terminal health`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub version: String,
+    pub uptime_secs: u64,
+    pub checks: Vec<HealthCheck>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthCheck {
+    pub name: String,
+    /// `ok`, `degraded` or `draining`
+    pub status: String,
+    pub detail: String,
+}
+
+impl HealthCheck {
+    fn new(name: &str, status: &str, detail: String) -> Self {
+        Self {
+            name: name.to_string(),
+            status: status.to_string(),
+            detail,
+        }
+    }
+}
+
+/// The pidfile of the running server, removed when dropped
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+    pid: u32,
+}
+
+impl PidFile {
+    /// Write this process's ID to `path`, unless the pidfile names another
+    /// live process
+    pub fn acquire(path: &Path) -> Result<Self, ControlError> {
+        let pid = std::process::id();
+        if let Some(other) = Self::read(path) {
+            if other != pid && process_alive(other) {
+                return Err(ControlError::AlreadyRunning(other));
+            }
+        }
+
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            create_private_dir(dir)?;
+        }
+        std::fs::write(path, format!("{}\n", pid)).map_err(|e| io_error(path, e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            pid,
+        })
+    }
+
+    /// Process ID recorded in `path`, if any
+    pub fn read(path: &Path) -> Option<u32> {
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    /// Process ID recorded in `path`, if that process is running
+    pub fn running(path: &Path) -> Option<u32> {
+        Self::read(path).filter(|&pid| process_alive(pid))
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // Leave a pidfile another server has since taken over
+        if Self::read(&self.path) == Some(self.pid) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Whether a process with this ID exists
+pub fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 only checks that the process exists and may be
+    // signalled
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Serves the control socket of the running server
+/// Per spec-kit/005-cli-spec.md: Process Management
+pub struct ControlServer {
+    path: PathBuf,
+    started_at: DateTime<Utc>,
+    started: Instant,
+    listeners: Vec<String>,
+    session_manager: Arc<SessionManager>,
+    pty_manager: Arc<PtyManager>,
+    shutdown: Arc<ShutdownCoordinator>,
+    audit: Arc<AuditLogger>,
+    /// Connections being answered
+    active: AtomicUsize,
+    accept_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ControlServer {
+    pub fn new(
+        path: PathBuf,
+        listeners: Vec<String>,
+        session_manager: Arc<SessionManager>,
+        pty_manager: Arc<PtyManager>,
+        shutdown: Arc<ShutdownCoordinator>,
+        audit: Arc<AuditLogger>,
+    ) -> Self {
+        Self {
+            path,
+            started_at: Utc::now(),
+            started: Instant::now(),
+            listeners,
+            session_manager,
+            pty_manager,
+            shutdown,
+            audit,
+            active: AtomicUsize::new(0),
+            accept_task: Mutex::new(None),
+        }
+    }
+
+    pub fn status(&self) -> ServerStatus {
+        ServerStatus {
+            pid: std::process::id(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at: self.started_at,
+            uptime_secs: self.started.elapsed().as_secs(),
+            listeners: self.listeners.clone(),
+            sessions: self.session_manager.session_count(),
+            clients: self.shutdown.attached_clients(),
+            draining: self.shutdown.is_requested(),
+        }
+    }
+
+    /// Bind the socket (owner-only, in an owner-only directory) and answer
+    /// requests until closed
+    pub fn start(self: Arc<Self>) -> Result<(), ControlError> {
+        if let Some(dir) = self.path.parent() {
+            create_private_dir(dir)?;
+        }
+        if self.path.exists() {
+            if std::os::unix::net::UnixStream::connect(&self.path).is_ok() {
+                return Err(ControlError::SocketInUse(self.path.clone()));
+            }
+            // Left behind by a server that did not stop cleanly
+            std::fs::remove_file(&self.path).map_err(|e| io_error(&self.path, e))?;
+        }
+
+        let listener = UnixListener::bind(&self.path).map_err(|e| io_error(&self.path, e))?;
+        std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| io_error(&self.path, e))?;
+        tracing::info!("Control socket: {}", self.path.display());
+
+        let server = self.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        server.active.fetch_add(1, Ordering::SeqCst);
+                        let server = server.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = server.serve(stream).await {
+                                tracing::warn!("Control request failed: {}", e);
+                            }
+                            server.active.fetch_sub(1, Ordering::SeqCst);
+                        });
+                    }
+                    Err(e) => {
+                        tracing::error!("Control socket accept failed: {}", e);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                }
+            }
+        });
+        *self.accept_task.lock().expect("control lock poisoned") = Some(task);
+        Ok(())
+    }
+
+    /// Stop accepting, give in-flight replies up to `grace` to be written,
+    /// and remove the socket
+    pub async fn close(&self, grace: Duration) {
+        if let Some(task) = self
+            .accept_task
+            .lock()
+            .expect("control lock poisoned")
+            .take()
+        {
+            task.abort();
+        }
+        let deadline = Instant::now() + grace;
+        while self.active.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+
+    async fn serve(&self, stream: UnixStream) -> Result<(), ControlError> {
+        // The socket mode already keeps other users out; this also holds if
+        // the file or its directory have been opened up
+        let peer_uid = stream
+            .peer_cred()
+            .map_err(|e| io_error(&self.path, e))?
+            .uid();
+        let (read, mut write) = stream.into_split();
+        if peer_uid != current_uid() && peer_uid != 0 {
+            tracing::warn!("Control socket: refused connection from uid {}", peer_uid);
+            let message = "Control socket: permission denied".to_string();
+            return reply(&mut write, &ControlResponse::Error { message }).await;
+        }
+
+        let mut line = String::new();
+        BufReader::new(read.take(MAX_REQUEST))
+            .read_line(&mut line)
+            .await
+            .map_err(|e| io_error(&self.path, e))?;
+
+        let request = match serde_json::from_str::<ControlRequest>(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let message = format!("Invalid control request: {}", e);
+                return reply(&mut write, &ControlResponse::Error { message }).await;
+            }
+        };
+        tracing::info!("Control request: {:?}", request);
+
+        match request {
+            ControlRequest::Status => {
+                reply(&mut write, &ControlResponse::Status(self.status())).await
+            }
+            ControlRequest::Stop {
+                timeout_secs,
+                force,
+            } => {
+                match (force, timeout_secs) {
+                    (true, _) => self.shutdown.request(Duration::ZERO, "stop (forced)"),
+                    (false, Some(secs)) => self.shutdown.request(Duration::from_secs(secs), "stop"),
+                    (false, None) => self.shutdown.request_graceful("stop"),
+                }
+                self.await_drain(&mut write).await
+            }
+            ControlRequest::Restart { timeout_secs } => {
+                self.shutdown
+                    .request_restart(timeout_secs.map(Duration::from_secs));
+                self.await_drain(&mut write).await
+            }
+            ControlRequest::ListSessions { user, tenant } => {
+                let sessions = self
+                    .sessions()
+                    .await
+                    .into_iter()
+                    .filter(|s| user.as_ref().is_none_or(|user| &s.user == user))
+                    .filter(|s| tenant.is_none() || s.tenant == tenant)
+                    .collect();
+                reply(&mut write, &ControlResponse::Sessions { sessions }).await
+            }
+            ControlRequest::KillSession { session_id, force } => {
+                let response = match self.kill_session(&session_id, force, peer_uid).await {
+                    Ok(session) => ControlResponse::Killed { session },
+                    Err(e) => ControlResponse::Error {
+                        message: e.to_string(),
+                    },
+                };
+                reply(&mut write, &response).await
+            }
+            ControlRequest::CleanupSessions { dry_run, all } => {
+                let candidates: Vec<SessionInfo> = self
+                    .sessions()
+                    .await
+                    .into_iter()
+                    .filter(|s| all || s.expired)
+                    .collect();
+                let mut sessions = Vec::new();
+                for session in candidates {
+                    if dry_run {
+                        sessions.push(session);
+                        continue;
+                    }
+                    // Sessions may end by themselves meanwhile
+                    match self.kill_session(&session.id, all, peer_uid).await {
+                        Ok(killed) => sessions.push(killed),
+                        Err(e) => tracing::warn!("Cleanup skipped {}: {}", session.id, e),
+                    }
+                }
+                reply(
+                    &mut write,
+                    &ControlResponse::CleanedUp { sessions, dry_run },
+                )
+                .await
+            }
+            ControlRequest::Metrics => {
+                let metrics = self.metrics().await;
+                reply(&mut write, &ControlResponse::Metrics(metrics)).await
+            }
+            ControlRequest::Health => {
+                let health = self.health().await;
+                reply(&mut write, &ControlResponse::Health(health)).await
+            }
+        }
+    }
+
+    async fn session_info(&self, session: &Session) -> SessionInfo {
+        let now = Utc::now();
+        let since = |at: Instant| {
+            now - chrono::Duration::from_std(at.elapsed())
+                .unwrap_or_else(|_| chrono::Duration::zero())
+        };
+        let idle = session.last_activity.elapsed();
+        let terminal = session.get_pty().await;
+        let (terminal_running, clients) = match &terminal {
+            Some(id) => (
+                self.pty_manager.is_alive(id).await,
+                self.pty_manager.subscriber_count(id),
+            ),
+            None => (false, 0),
+        };
+        SessionInfo {
+            id: session.id.to_string(),
+            user: session.user_id.to_string(),
+            tenant: session.tenant.clone(),
+            profile: session.profile.clone(),
+            created_at: since(session.created_at),
+            last_activity: since(session.last_activity),
+            idle_secs: idle.as_secs(),
+            expired: self
+                .session_manager
+                .expired_sessions()
+                .contains(&session.id),
+            terminal_running,
+            clients,
+        }
+    }
+
+    async fn sessions(&self) -> Vec<SessionInfo> {
+        let mut sessions = Vec::new();
+        for session in self.session_manager.list_sessions().await {
+            sessions.push(self.session_info(&session).await);
+        }
+        sessions
+    }
+
+    /// Hang up (or with `force` kill) the session's terminal and destroy
+    /// the session
+    async fn kill_session(
+        &self,
+        session_id: &str,
+        force: bool,
+        peer_uid: u32,
+    ) -> crate::error::Result<SessionInfo> {
+        let session_id = SessionId::new(session_id.to_string());
+        let session = self.session_manager.get_session(&session_id).await?;
+        let info = self.session_info(&session).await;
+
+        if let Some(pty) = session.get_pty().await {
+            if !force && self.pty_manager.hangup(&pty).await.is_ok() {
+                let deadline = Instant::now() + self.shutdown.kill_after();
+                while self.pty_manager.is_alive(&pty).await && Instant::now() < deadline {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            }
+            // Gone already if its shell exited and the connection cleaned up
+            if self.pty_manager.get(&pty).is_ok() {
+                if let Err(e) = self.pty_manager.kill(&pty).await {
+                    tracing::warn!("Failed to kill PTY process {}: {}", pty, e);
+                }
+            }
+        }
+        self.session_manager.destroy_session(&session_id).await?;
+
+        self.audit.record(
+            AuditEvent::new(AuditAction::SessionKilled)
+                .user(format!("uid:{}", peer_uid))
+                .session(&session_id)
+                .detail(format!(
+                    "owned by {} via control socket{}",
+                    info.user,
+                    if force { " (forced)" } else { "" }
+                )),
+        );
+        Ok(info)
+    }
+
+    pub async fn metrics(&self) -> MetricsSnapshot {
+        let sessions = self.session_manager.list_sessions().await;
+        let mut users: Vec<&str> = sessions.iter().map(|s| s.user_id.as_str()).collect();
+        users.sort_unstable();
+        users.dedup();
+
+        let terminals = self.pty_manager.list();
+        let mut terminals_running = 0;
+        for id in &terminals {
+            if self.pty_manager.is_alive(id).await {
+                terminals_running += 1;
+            }
+        }
+
+        MetricsSnapshot {
+            uptime_secs: self.started.elapsed().as_secs(),
+            sessions: sessions.len(),
+            users: users.len(),
+            clients: self.shutdown.attached_clients(),
+            terminals: terminals.len(),
+            terminals_running,
+            memory_rss_bytes: memory_rss_bytes(),
+            cpu_seconds: cpu_seconds(),
+            draining: self.shutdown.is_requested(),
+            prometheus: metrics::gather_text(),
+        }
+    }
+
+    pub async fn health(&self) -> HealthReport {
+        let metrics = self.metrics().await;
+        let expired = self.session_manager.expired_sessions().len();
+        let stale = metrics.terminals - metrics.terminals_running;
+
+        let mut checks = vec![
+            if metrics.draining {
+                HealthCheck::new("server", "draining", "shutting down".to_string())
+            } else {
+                HealthCheck::new("server", "ok", self.listeners.join(", "))
+            },
+            HealthCheck::new(
+                "sessions",
+                "ok",
+                format!("{} active, {} expired", metrics.sessions, expired),
+            ),
+            HealthCheck::new(
+                "terminals",
+                if stale == 0 { "ok" } else { "degraded" },
+                format!("{} running, {} exited", metrics.terminals_running, stale),
+            ),
+        ];
+        if let Some(rss) = metrics.memory_rss_bytes {
+            checks.push(HealthCheck::new(
+                "memory",
+                "ok",
+                format!("{} MB resident", rss / (1024 * 1024)),
+            ));
+        }
+
+        HealthReport {
+            healthy: checks.iter().all(|check| check.status == "ok"),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_secs: metrics.uptime_secs,
+            checks,
+        }
+    }
+
+    async fn await_drain(&self, write: &mut OwnedWriteHalf) -> Result<(), ControlError> {
+        let seconds = self
+            .shutdown
+            .time_remaining()
+            .map(|remaining| remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0))
+            .unwrap_or(0);
+        reply(write, &ControlResponse::Draining { seconds }).await?;
+        let summary = self.shutdown.finished().await;
+        reply(write, &ControlResponse::Stopped(summary)).await
+    }
+}
+
+async fn reply(write: &mut OwnedWriteHalf, response: &ControlResponse) -> Result<(), ControlError> {
+    let mut line =
+        serde_json::to_string(response).map_err(|e| ControlError::Protocol(e.to_string()))?;
+    line.push('\n');
+    write
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| ControlError::Protocol(e.to_string()))
+}
+
+/// Client end of the control socket
+pub struct ControlClient {
+    path: PathBuf,
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl ControlClient {
+    pub async fn connect(path: &Path) -> Result<Self, ControlError> {
+        let stream = UnixStream::connect(path)
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused => {
+                    ControlError::NotRunning(path.to_path_buf())
+                }
+                std::io::ErrorKind::PermissionDenied => {
+                    ControlError::PermissionDenied(path.to_path_buf())
+                }
+                _ => ControlError::Connect {
+                    path: path.to_path_buf(),
+                    reason: e.to_string(),
+                },
+            })?;
+        let (read, writer) = stream.into_split();
+        Ok(Self {
+            path: path.to_path_buf(),
+            reader: BufReader::new(read),
+            writer,
+        })
+    }
+
+    pub async fn send(&mut self, request: &ControlRequest) -> Result<(), ControlError> {
+        let mut line =
+            serde_json::to_string(request).map_err(|e| ControlError::Protocol(e.to_string()))?;
+        line.push('\n');
+        self.writer
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| io_error(&self.path, e))
+    }
+
+    /// Next reply, or `None` once the server closes the connection
+    pub async fn receive(&mut self) -> Result<Option<ControlResponse>, ControlError> {
+        let mut line = String::new();
+        let read = self
+            .reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| io_error(&self.path, e))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        match serde_json::from_str(&line).map_err(|e| ControlError::Protocol(e.to_string()))? {
+            ControlResponse::Error { message } => Err(ControlError::Server(message)),
+            response => Ok(Some(response)),
+        }
+    }
+
+    /// Status of the server listening on `path`
+    pub async fn status(path: &Path) -> Result<ServerStatus, ControlError> {
+        match Self::request(path, &ControlRequest::Status).await? {
+            ControlResponse::Status(status) => Ok(status),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Send one request and return the single reply
+    pub async fn request(
+        path: &Path,
+        request: &ControlRequest,
+    ) -> Result<ControlResponse, ControlError> {
+        let mut client = Self::connect(path).await?;
+        client.send(request).await?;
+        client
+            .receive()
+            .await?
+            .ok_or_else(|| ControlError::Protocol("connection closed without a reply".to_string()))
+    }
+}
+
+/// Error for a reply of the wrong kind
+pub fn unexpected(response: &ControlResponse) -> ControlError {
+    ControlError::Protocol(format!("unexpected reply {:?}", response))
+}
+
+/// Resident set size from /proc (Linux only)
+fn memory_rss_bytes() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    // SAFETY: sysconf has no preconditions
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    Some(pages * u64::try_from(page_size).ok()?)
+}
+
+/// User plus system CPU time of this process
+fn cpu_seconds() -> f64 {
+    // SAFETY: getrusage writes a rusage struct we own
+    let usage = unsafe {
+        let mut usage: libc::rusage = std::mem::zeroed();
+        if libc::getrusage(libc::RUSAGE_SELF, &mut usage) != 0 {
+            return 0.0;
+        }
+        usage
+    };
+    let seconds = |tv: libc::timeval| tv.tv_sec as f64 + tv.tv_usec as f64 / 1e6;
+    seconds(usage.ru_utime) + seconds(usage.ru_stime)
+}
+
+/// Create `dir` readable by its owner only (an existing directory is kept
+/// as it is)
+fn create_private_dir(dir: &Path) -> Result<(), ControlError> {
+    if dir.as_os_str().is_empty() || dir.is_dir() {
+        return Ok(());
+    }
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .map_err(|e| io_error(dir, e))
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> ControlError {
+    if e.kind() == std::io::ErrorKind::PermissionDenied {
+        return ControlError::PermissionDenied(path.to_path_buf());
+    }
+    ControlError::Io {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::auth::AuditConfig;
+    use crate::config::ShutdownConfig;
+    use crate::session::{SessionConfig, UserId};
+
+    fn control_server(dir: &Path) -> Arc<ControlServer> {
+        control_server_with(dir, SessionConfig::default())
+    }
+
+    fn control_server_with(dir: &Path, sessions: SessionConfig) -> Arc<ControlServer> {
+        let session_manager = Arc::new(SessionManager::new(sessions));
+        let pty_manager = Arc::new(PtyManager::with_defaults());
+        let audit = Arc::new(AuditLogger::new(AuditConfig::default()));
+        let shutdown = Arc::new(ShutdownCoordinator::new(
+            ShutdownConfig::default(),
+            session_manager.clone(),
+            pty_manager.clone(),
+            audit.clone(),
+        ));
+        Arc::new(ControlServer::new(
+            dir.join("run").join("control.sock"),
+            vec!["http://127.0.0.1:8080".to_string()],
+            session_manager,
+            pty_manager,
+            shutdown,
+            audit,
+        ))
+    }
+
+    #[test]
+    fn test_pidfile_refuses_a_second_live_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run").join("web-terminal.pid");
+
+        let pidfile = PidFile::acquire(&path).unwrap();
+        assert_eq!(PidFile::running(&path), Some(std::process::id()));
+        drop(pidfile);
+        assert!(!path.exists());
+
+        // Parent of this process: alive, so its pidfile is honoured
+        let parent = std::os::unix::process::parent_id();
+        std::fs::write(&path, format!("{}\n", parent)).unwrap();
+        assert!(matches!(
+            PidFile::acquire(&path),
+            Err(ControlError::AlreadyRunning(pid)) if pid == parent
+        ));
+
+        // A stale pidfile is taken over
+        std::fs::write(&path, "999999999\n").unwrap();
+        let pidfile = PidFile::acquire(&path).unwrap();
+        assert_eq!(PidFile::read(&path), Some(std::process::id()));
+        drop(pidfile);
+    }
+
+    #[tokio::test]
+    async fn test_status_over_owner_only_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = control_server(dir.path());
+        server
+            .session_manager
+            .create_session(UserId::new("alice".to_string()))
+            .await
+            .unwrap();
+        server.clone().start().unwrap();
+
+        let mode = std::fs::metadata(&server.path)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let mode = std::fs::metadata(dir.path().join("run"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        let status = ControlClient::status(&server.path).await.unwrap();
+        assert_eq!(status.pid, std::process::id());
+        assert_eq!(status.sessions, 1);
+        assert_eq!(status.listeners, vec!["http://127.0.0.1:8080"]);
+        assert!(!status.draining);
+
+        // A second server on the same socket is refused
+        assert!(matches!(
+            control_server(dir.path()).start(),
+            Err(ControlError::SocketInUse(_))
+        ));
+
+        server.close(Duration::from_secs(1)).await;
+        assert!(matches!(
+            ControlClient::status(&server.path).await,
+            Err(ControlError::NotRunning(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stop_reports_the_drain_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = control_server(dir.path());
+        server.clone().start().unwrap();
+
+        let mut client = ControlClient::connect(&server.path).await.unwrap();
+        client
+            .send(&ControlRequest::Stop {
+                timeout_secs: Some(5),
+                force: false,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            client.receive().await.unwrap(),
+            Some(ControlResponse::Draining { .. })
+        ));
+
+        let summary = server.shutdown.drain().await;
+        assert_eq!(summary.reason, "stop");
+        match client.receive().await.unwrap() {
+            Some(ControlResponse::Stopped(reported)) => {
+                assert_eq!(reported.reason, "stop");
+                assert!(!reported.restart);
+            }
+            other => panic!("expected stopped, got {:?}", other),
+        }
+        server.close(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn test_admin_lists_kills_and_cleans_up_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = control_server(dir.path());
+        let manager = &server.session_manager;
+        let alice = manager
+            .create_session(UserId::new("alice".to_string()))
+            .await
+            .unwrap();
+        manager
+            .create_session(UserId::new("bob".to_string()))
+            .await
+            .unwrap();
+        server.clone().start().unwrap();
+
+        let request = ControlRequest::ListSessions {
+            user: Some("alice".to_string()),
+            tenant: None,
+        };
+        match ControlClient::request(&server.path, &request)
+            .await
+            .unwrap()
+        {
+            ControlResponse::Sessions { sessions } => {
+                assert_eq!(sessions.len(), 1);
+                assert_eq!(sessions[0].id, alice.id.to_string());
+                assert!(!sessions[0].expired);
+                assert!(!sessions[0].terminal_running);
+            }
+            other => panic!("expected sessions, got {:?}", other),
+        }
+
+        // Nothing has expired, so cleanup only kills with `all`
+        let cleanup = |all| ControlRequest::CleanupSessions { dry_run: true, all };
+        match ControlClient::request(&server.path, &cleanup(false)).await {
+            Ok(ControlResponse::CleanedUp { sessions, dry_run }) => {
+                assert!(dry_run);
+                assert!(sessions.is_empty());
+            }
+            other => panic!("expected cleanup, got {:?}", other),
+        }
+        match ControlClient::request(&server.path, &cleanup(true)).await {
+            Ok(ControlResponse::CleanedUp { sessions, .. }) => assert_eq!(sessions.len(), 2),
+            other => panic!("expected cleanup, got {:?}", other),
+        }
+        assert_eq!(manager.session_count(), 2);
+
+        let kill = ControlRequest::KillSession {
+            session_id: alice.id.to_string(),
+            force: false,
+        };
+        match ControlClient::request(&server.path, &kill).await.unwrap() {
+            ControlResponse::Killed { session } => assert_eq!(session.user, "alice"),
+            other => panic!("expected killed, got {:?}", other),
+        }
+        assert_eq!(manager.session_count(), 1);
+        assert!(matches!(
+            ControlClient::request(&server.path, &kill).await,
+            Err(ControlError::Server(message)) if message.contains("not found")
+        ));
+
+        server.close(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn test_kill_session_hangs_up_its_terminal() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = control_server(dir.path());
+        let session = server
+            .session_manager
+            .create_session(UserId::new("alice".to_string()))
+            .await
+            .unwrap();
+        let pty = server.pty_manager.spawn(None).unwrap();
+        session.set_pty(pty.id().to_string()).await;
+        assert!(server.sessions().await[0].terminal_running);
+
+        let killed = tokio::time::timeout(
+            Duration::from_secs(10),
+            server.kill_session(session.id.as_str(), false, 0),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(killed.terminal_running);
+        assert!(!pty.is_alive().await);
+        assert_eq!(server.pty_manager.count(), 0);
+        assert_eq!(server.session_manager.session_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_kills_expired_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = control_server_with(
+            dir.path(),
+            SessionConfig {
+                timeout: Duration::ZERO,
+                ..SessionConfig::default()
+            },
+        );
+        server
+            .session_manager
+            .create_session(UserId::new("alice".to_string()))
+            .await
+            .unwrap();
+        server.clone().start().unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let request = ControlRequest::CleanupSessions {
+            dry_run: false,
+            all: false,
+        };
+        match ControlClient::request(&server.path, &request)
+            .await
+            .unwrap()
+        {
+            ControlResponse::CleanedUp { sessions, dry_run } => {
+                assert!(!dry_run);
+                assert_eq!(sessions.len(), 1);
+                assert!(sessions[0].expired);
+            }
+            other => panic!("expected cleanup, got {:?}", other),
+        }
+        assert_eq!(server.session_manager.session_count(), 0);
+        server.close(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn test_metrics_and_health() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = control_server(dir.path());
+        for user in ["alice", "alice", "bob"] {
+            server
+                .session_manager
+                .create_session(UserId::new(user.to_string()))
+                .await
+                .unwrap();
+        }
+
+        let metrics = server.metrics().await;
+        assert_eq!(metrics.sessions, 3);
+        assert_eq!(metrics.users, 2);
+        assert_eq!(metrics.terminals, 0);
+        assert!(!metrics.draining);
+
+        let health = server.health().await;
+        assert!(health.healthy);
+        assert_eq!(health.checks[0].name, "server");
+
+        server.shutdown.request_graceful("SIGTERM");
+        let health = server.health().await;
+        assert!(!health.healthy);
+        assert_eq!(health.checks[0].status, "draining");
+    }
+}
@@ -7,18 +7,37 @@ use actix_files::Files;
 use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer, Result};
 use actix_web_actors::ws;
 
-use crate::config::Config;
+use crate::config::auth::TokenSource;
+use crate::config::{Config, ConfigSources};
 use crate::handlers;
 use crate::pty::PtyManager;
+use crate::security::access_grants::AccessGrantStore;
+use crate::security::admission::AdmissionPolicy;
+use crate::security::api_keys::ApiKeyStore;
+use crate::security::audit::AuditLogger;
+use crate::security::authorization::AuthorizationService;
 use crate::security::jwks_client::JwksClient;
 use crate::security::jwt_validator::JwtValidator;
-use crate::server::middleware::auth::{JwtAuthMiddleware, UserContext};
+use crate::security::lockout::LockoutTracker;
+use crate::security::reload::{self, AuthReloader};
+use crate::security::revocation::RevocationList;
+use crate::security::tenancy::TenantResolver;
+#[cfg(unix)]
+use crate::server::control::{ControlServer, PidFile};
+use crate::server::middleware::auth::{
+    locked_out_response, CredentialVerifier, JwtAuthMiddleware, UserContext,
+};
 use crate::server::middleware::{
-    cors::CorsConfig as CorsMiddlewareConfig,
+    cors::{CorsConfig as CorsMiddlewareConfig, LiveOrigins},
     security_headers::{
         SecurityHeadersConfig as SecurityHeadersMiddlewareConfig, SecurityHeadersMiddleware,
     },
+    token_source::{TokenExtractor, BEARER_SUBPROTOCOL},
+    ScrubbedRootSpanBuilder,
 };
+use crate::server::reload::{ConfigReloader, LogFilter};
+use crate::server::shutdown::{ShutdownCoordinator, ShutdownSummary};
+use crate::server::ttyd::TtydSession;
 use crate::server::websocket::WebSocketSession;
 use crate::session::{SessionId, SessionManager};
 
@@ -32,40 +51,233 @@ use crate::server::tls::{load_tls_config, validate_tls_files};
 /// All HTTP, WebSocket, and static assets served from ONE port (default 8080)
 pub struct Server {
     config: Arc<Config>,
+    /// Configuration as loaded, before the auth reload file was applied
+    loaded: Config,
+    /// Where configuration reloads read from
+    sources: ConfigSources,
+    log_filter: Option<LogFilter>,
     session_manager: Arc<SessionManager>,
+    pty_manager: Arc<PtyManager>,
+    jwks_client: Arc<JwksClient>,
     jwt_validator: Arc<JwtValidator>,
+    audit: Arc<AuditLogger>,
+    token_extractor: TokenExtractor,
+    api_keys: Arc<ApiKeyStore>,
+    admission: Arc<AdmissionPolicy>,
+    authz: Arc<AuthorizationService>,
+    reloader: Arc<AuthReloader>,
+    revocations: Arc<RevocationList>,
+    lockouts: Arc<LockoutTracker>,
+    tenants: Arc<TenantResolver>,
+    access_grants: Arc<AccessGrantStore>,
+    shutdown: Arc<ShutdownCoordinator>,
+    /// Hold the pidfile and serve the control socket while running
+    control: bool,
 }
 
 impl Server {
     /// Create a new HTTP server instance
     /// Per spec-kit/003-backend-spec.md section 1.1
     /// Per spec-kit/011-authentication-spec.md: JWT authentication
-    pub fn new(config: Config, session_manager: SessionManager) -> Self {
+    pub fn new(mut config: Config, session_manager: SessionManager) -> Self {
         tracing::info!(
             "Initializing HTTP server on {}:{}",
             config.server.host,
             config.server.port
         );
+        let loaded = config.clone();
+
+        // Providers and authorization settings from the reloadable auth
+        // config file, if one is configured
+        // Per spec-kit/011-authentication-spec.md: Configuration Reload
+        match reload::apply_config_file(&config.auth) {
+            Ok(auth) => config.auth = auth,
+            Err(e) => tracing::error!("Ignoring auth config file: {}", e),
+        }
 
         // Initialize JWKS client and JWT validator
         // Per spec-kit/011-authentication-spec.md section 2.1
         let jwks_client = Arc::new(JwksClient::new(config.auth.clone()));
-        let jwt_validator = Arc::new(JwtValidator::new(jwks_client, config.auth.clone()));
+        let jwt_validator = Arc::new(JwtValidator::new(jwks_client.clone(), config.auth.clone()));
+
+        // Hash-chained audit file, continuing the existing chain; events
+        // still reach the tracing target if it cannot be opened
+        // Per spec-kit/011-authentication-spec.md: Audit logging
+        let audit = Arc::new(
+            AuditLogger::open(config.auth.security.audit.clone()).unwrap_or_else(|e| {
+                tracing::error!("Audit log file unavailable, logging to tracing only: {}", e);
+                AuditLogger::new(config.auth.security.audit.clone())
+            }),
+        );
+        let token_extractor =
+            TokenExtractor::new(config.auth.security.allowed_token_sources.clone());
+
+        // A store that fails to load accepts no keys rather than starting
+        // empty and overwriting the file
+        // Per spec-kit/011-authentication-spec.md: API Keys
+        let api_keys = Arc::new(
+            ApiKeyStore::open(config.auth.api_keys.clone()).unwrap_or_else(|e| {
+                tracing::error!("API keys disabled: {}", e);
+                ApiKeyStore::disabled()
+            }),
+        );
+
+        // A revocation store that fails to load refuses every credential
+        // rather than forgetting revocations
+        // Per spec-kit/011-authentication-spec.md: Token Revocation
+        let revocations = Arc::new(
+            RevocationList::open(config.auth.revocation.clone()).unwrap_or_else(|e| {
+                tracing::error!(
+                    "Revocation list unavailable, refusing all credentials: {}",
+                    e
+                );
+                RevocationList::unavailable(e.to_string())
+            }),
+        );
+
+        // Failed authentications per address and verified subject, across
+        // REST, WebSocket and ttyd
+        // Per spec-kit/011-authentication-spec.md: Brute-Force Protection
+        let lockouts = Arc::new(
+            LockoutTracker::new(config.auth.security.rate_limit.clone()).with_audit(audit.clone()),
+        );
+
+        // Who may use the server at all, checked after every credential
+        // validation
+        // Per spec-kit/011-authentication-spec.md: Authorization Model
+        let admission = Arc::new(AdmissionPolicy::new(&config.auth.authorization));
+        if admission.admits_nobody() {
+            tracing::warn!(
+                "No allowed_users or allowed_groups configured and deny_by_default is set: \
+                 every request will be denied"
+            );
+        }
+
+        // Access to other users' sessions, granted by their owners
+        // Per spec-kit/011-authentication-spec.md: Just-in-Time Access
+        let access_grants = Arc::new(
+            AccessGrantStore::new(config.auth.access_requests.clone()).with_audit(audit.clone()),
+        );
+
+        // Role permissions for every session action; rules that fail to load
+        // grant nothing rather than falling back to the defaults
+        // Per spec-kit/011-authentication-spec.md section 5: Authorization Model
+        let authz = Arc::new(
+            AuthorizationService::from_config(&config.auth)
+                .unwrap_or_else(|e| {
+                    tracing::error!(
+                        "Authorization rules unavailable, denying all actions: {}",
+                        e
+                    );
+                    AuthorizationService::deny_all()
+                })
+                .with_audit(audit.clone())
+                .with_access_grants(access_grants.clone()),
+        );
+
+        // Tenants qualifying users, sessions and admin scope; an invalid
+        // tenant list refuses every credential rather than merging tenants
+        // Per spec-kit/011-authentication-spec.md: Multi-Tenancy
+        let tenants = Arc::new(
+            TenantResolver::new(config.auth.tenancy.clone()).unwrap_or_else(|e| {
+                tracing::error!("Tenancy unavailable, refusing all credentials: {}", e);
+                TenantResolver::refuse_all()
+            }),
+        );
+        let session_manager = session_manager.with_tenants(&config.auth.tenancy);
+
+        let reloader = Arc::new(AuthReloader::new(
+            config.auth.clone(),
+            jwt_validator.clone(),
+            jwks_client.clone(),
+            admission.clone(),
+            authz.clone(),
+            audit.clone(),
+        ));
+
+        // Drains sessions and clients before the server stops
+        // Per spec-kit/005-cli-spec.md: Graceful Shutdown
+        let session_manager = Arc::new(session_manager);
+        let pty_manager = Arc::new(PtyManager::with_defaults());
+        let shutdown = Arc::new(ShutdownCoordinator::new(
+            config.server.shutdown.clone(),
+            session_manager.clone(),
+            pty_manager.clone(),
+            audit.clone(),
+        ));
 
         Self {
             config: Arc::new(config),
-            session_manager: Arc::new(session_manager),
+            loaded,
+            sources: ConfigSources::default(),
+            log_filter: None,
+            session_manager,
+            pty_manager,
+            jwks_client,
             jwt_validator,
+            audit,
+            token_extractor,
+            api_keys,
+            admission,
+            authz,
+            reloader,
+            revocations,
+            lockouts,
+            tenants,
+            access_grants,
+            shutdown,
+            control: false,
         }
     }
 
+    /// Re-read configuration from `sources` on reload (default: `config.toml`
+    /// and the environment)
+    /// Per spec-kit/005-cli-spec.md: Live Reload
+    pub fn with_config_sources(mut self, sources: ConfigSources) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Apply `logging.level` changes on reload through `log_filter`
+    pub fn with_log_filter(mut self, log_filter: LogFilter) -> Self {
+        self.log_filter = Some(log_filter);
+        self
+    }
+
+    /// Write the pidfile and answer `stop`, `restart` and `status` on the
+    /// control socket while running
+    /// Per spec-kit/005-cli-spec.md: Process Management
+    pub fn with_control_channel(mut self) -> Self {
+        self.control = true;
+        self
+    }
+
+    /// Coordinator that drains and stops the running server when asked
+    /// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+    pub fn shutdown_handle(&self) -> Arc<ShutdownCoordinator> {
+        self.shutdown.clone()
+    }
+
     /// Start the HTTP server
     /// Per spec-kit/003-backend-spec.md: Single-port deployment
     /// CRITICAL: All services (HTTP, WebSocket, static files) on ONE port
     /// Per spec-kit/009-deployment-spec.md: TLS and security headers
-    pub async fn run(self) -> std::io::Result<()> {
+    ///
+    /// Runs until shutdown is requested (SIGTERM, SIGINT or through
+    /// [`Server::shutdown_handle`]), then drains sessions and returns what
+    /// the drain did
+    pub async fn run(self) -> std::io::Result<ShutdownSummary> {
         let bind_addr = format!("{}:{}", self.config.server.host, self.config.server.port);
 
+        // Refuse to start next to a running server before binding anything
+        // Per spec-kit/005-cli-spec.md: Process Management
+        #[cfg(unix)]
+        let _pid_file = if self.control {
+            Some(PidFile::acquire(&self.config.server.control.pid_file())?)
+        } else {
+            None
+        };
+
         // Check if TLS is enabled
         let tls_enabled = self.config.server.tls.is_some();
         let protocol = if tls_enabled { "https" } else { "http" };
@@ -77,14 +289,82 @@ impl Server {
             protocol.to_uppercase()
         );
         tracing::info!("WebSocket endpoint: {}://{}/ws", ws_protocol, bind_addr);
+        tracing::info!(
+            "ttyd-compatible endpoint: {}://{}/ttyd/ws",
+            ws_protocol,
+            bind_addr
+        );
         tracing::info!("Health check: {}://{}/api/v1/health", protocol, bind_addr);
 
+        // Keep JWKS keys (and discovered provider metadata) fresh
+        // Per spec-kit/011-authentication-spec.md section 3.3: Key Rotation Handling
+        // (providers may also appear later through a reload)
+        if !self.config.auth.jwks.providers.is_empty()
+            || self.config.auth.reload.config_file.is_some()
+        {
+            self.jwks_client.clone().start_refresh_task();
+        }
+
+        // Swap in new providers and authorization settings on file changes
+        // Per spec-kit/011-authentication-spec.md: Configuration Reload
+        self.reloader.clone().start();
+        self.lockouts.clone().start_sweeper();
+
+        // Origins and security headers shared by every worker, replaced on
+        // reload
+        let cors_origins = Arc::new(LiveOrigins::new(
+            self.config.server.cors.allowed_origins.clone(),
+        ));
+        let security_headers = SecurityHeadersMiddleware::new(
+            SecurityHeadersMiddlewareConfig::from(&self.config.server.security_headers),
+        );
+
+        // Re-read the whole configuration on SIGHUP or an admin request and
+        // apply what can change in place
+        // Per spec-kit/005-cli-spec.md: Live Reload
+        let mut config_reloader = ConfigReloader::new(
+            self.sources.clone(),
+            self.loaded.clone(),
+            self.session_manager.clone(),
+            self.lockouts.clone(),
+            cors_origins.clone(),
+            security_headers.clone(),
+            self.reloader.clone(),
+            self.audit.clone(),
+        );
+        if let Some(log_filter) = self.log_filter.clone() {
+            config_reloader = config_reloader.with_log_filter(log_filter);
+        }
+        let config_reloader = Arc::new(config_reloader);
+        config_reloader.clone().start();
+
+        let config = self.config.clone();
         let session_manager = self.session_manager.clone();
+        let pty_manager = self.pty_manager.clone();
         let jwt_validator = self.jwt_validator.clone();
-
-        // Create JWT auth middleware
+        let audit = self.audit.clone();
+        let token_extractor = self.token_extractor.clone();
+        let api_keys = self.api_keys.clone();
+        let admission = self.admission.clone();
+        let authz = self.authz.clone();
+        let revocations = self.revocations.clone();
+        let lockouts = self.lockouts.clone();
+        let tenants = self.tenants.clone();
+        let access_grants = self.access_grants.clone();
+        let jwks_client = self.jwks_client.clone();
+        let shutdown = self.shutdown.clone();
+
+        // Create JWT auth middleware (also accepting API keys)
         // Per spec-kit/011-authentication-spec.md: HTTP auth middleware
-        let auth_middleware = JwtAuthMiddleware::new(jwt_validator.clone());
+        let auth_middleware = JwtAuthMiddleware::new(jwt_validator.clone())
+            .with_token_extractor(token_extractor.clone())
+            .with_api_keys(api_keys.clone())
+            .with_admission(admission.clone())
+            .with_authorization(authz.clone())
+            .with_revocations(revocations.clone())
+            .with_lockouts(lockouts.clone())
+            .with_tenancy(tenants.clone())
+            .with_audit(audit.clone());
 
         // Build CORS middleware config
         // Per spec-kit/002-architecture.md Layer 1: Network Security
@@ -96,28 +376,31 @@ impl Server {
             supports_credentials: self.config.server.cors.supports_credentials,
         };
 
-        // Build security headers middleware from config
-        // Per spec-kit/002-architecture.md: Defense in depth strategy
-        let security_headers_config = SecurityHeadersMiddlewareConfig {
-            enable_hsts: self.config.server.security_headers.enable_hsts,
-            hsts_max_age: self.config.server.security_headers.hsts_max_age,
-            enable_csp: self.config.server.security_headers.enable_csp,
-            csp_policy: self.config.server.security_headers.csp_policy.clone(),
-            enable_frame_options: self.config.server.security_headers.enable_frame_options,
-            frame_options: self.config.server.security_headers.frame_options.clone(),
-        };
-        let security_headers = SecurityHeadersMiddleware::new(security_headers_config);
-
         let server = HttpServer::new(move || {
             // Build CORS middleware inside closure (Cors is not Clone)
-            let cors = cors_config.build();
+            let cors = cors_config.build_with_origins(cors_origins.clone());
 
             App::new()
                 // Shared application state
+                .app_data(web::Data::new(config.clone()))
                 .app_data(web::Data::new(session_manager.clone()))
+                .app_data(web::Data::new(pty_manager.clone()))
                 .app_data(web::Data::new(jwt_validator.clone()))
+                .app_data(web::Data::new(audit.clone()))
+                .app_data(web::Data::new(token_extractor.clone()))
+                .app_data(web::Data::new(api_keys.clone()))
+                .app_data(web::Data::new(admission.clone()))
+                .app_data(web::Data::new(authz.clone()))
+                .app_data(web::Data::new(revocations.clone()))
+                .app_data(web::Data::new(lockouts.clone()))
+                .app_data(web::Data::new(tenants.clone()))
+                .app_data(web::Data::new(access_grants.clone()))
+                .app_data(web::Data::new(jwks_client.clone()))
+                .app_data(web::Data::new(config_reloader.clone()))
+                .app_data(web::Data::new(shutdown.clone()))
                 // Middleware (applied in order)
-                .wrap(tracing_actix_web::TracingLogger::default())
+                // Query string tokens are scrubbed from request logs
+                .wrap(tracing_actix_web::TracingLogger::<ScrubbedRootSpanBuilder>::new())
                 .wrap(security_headers.clone())
                 .wrap(cors)
                 // API routes (per docs/spec-kit/006-api-spec.md)
@@ -129,6 +412,7 @@ impl Server {
                         .service(
                             web::scope("")
                                 .wrap(auth_middleware.clone())
+                                .route("/metrics", web::get().to(handlers::metrics))
                                 .route("/sessions", web::post().to(handlers::create_session))
                                 .route("/sessions", web::get().to(handlers::list_sessions))
                                 .route("/sessions/{id}", web::get().to(handlers::get_session))
@@ -136,21 +420,79 @@ impl Server {
                                 .route(
                                     "/sessions/{id}/history",
                                     web::get().to(handlers::get_session_history),
+                                )
+                                .route(
+                                    "/sessions/{id}/access-requests",
+                                    web::post().to(handlers::request_access),
+                                )
+                                .route(
+                                    "/access-requests",
+                                    web::get().to(handlers::list_access_requests),
+                                )
+                                .route(
+                                    "/access-requests/{id}/approve",
+                                    web::post().to(handlers::approve_access_request),
+                                )
+                                .route(
+                                    "/access-requests/{id}/deny",
+                                    web::post().to(handlers::deny_access_request),
+                                )
+                                .route(
+                                    "/access-requests/{id}",
+                                    web::delete().to(handlers::revoke_access_request),
+                                )
+                                .route("/tokens", web::post().to(handlers::create_token))
+                                .route("/tokens", web::get().to(handlers::list_tokens))
+                                .route("/tokens/{id}", web::delete().to(handlers::revoke_token))
+                                .route("/authz/explain", web::post().to(handlers::explain))
+                                .route(
+                                    "/admin/revocations",
+                                    web::get().to(handlers::list_revocations),
+                                )
+                                .route(
+                                    "/admin/revocations",
+                                    web::post().to(handlers::create_revocation),
+                                )
+                                .route(
+                                    "/admin/revocations/{id}",
+                                    web::delete().to(handlers::delete_revocation),
+                                )
+                                .route("/admin/lockouts", web::get().to(handlers::list_lockouts))
+                                .route(
+                                    "/admin/lockouts",
+                                    web::delete().to(handlers::clear_lockouts),
+                                )
+                                .route(
+                                    "/admin/lockouts/{key}",
+                                    web::delete().to(handlers::clear_lockout),
+                                )
+                                .route(
+                                    "/admin/config/reload",
+                                    web::post().to(handlers::reload_config),
                                 ),
                         ),
                 )
-                // WebSocket endpoint (authentication via Authenticate message)
+                // WebSocket endpoint (authentication via upgrade request token
+                // or Authenticate message)
                 // Per spec-kit/007-websocket-spec.md: WebSocket authentication
                 .route("/ws", web::get().to(websocket_handler))
+                // ttyd-compatible endpoint (authentication via upgrade request
+                // token or handshake AuthToken)
+                // Per spec-kit/007-websocket-spec.md: Alternative client protocols
+                .route("/ttyd/ws", web::get().to(ttyd_handler))
                 // Static files served from same port
                 .service(Files::new("/", "./static").index_file("index.html"))
         })
-        .workers(self.config.server.worker_threads);
+        .workers(self.config.server.worker_threads)
+        // Signals start a drain instead; connections left once it is done
+        // get as long as terminals do to close
+        .disable_signals()
+        .shutdown_timeout(self.config.server.shutdown.kill_after.as_secs().max(1));
 
         // Configure TLS if enabled
         // Per spec-kit/009-deployment-spec.md: TLS 1.2+ enforcement
         #[cfg(feature = "tls")]
-        if let Some(ref tls_config) = self.config.server.tls {
+        let server = if let Some(ref tls_config) = self.config.server.tls {
             tracing::info!("TLS enabled - loading certificates");
             validate_tls_files(&tls_config.cert_path, &tls_config.key_path)
                 .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
@@ -158,14 +500,62 @@ impl Server {
             let rustls_config = load_tls_config(tls_config)
                 .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
 
-            return server
-                .bind_rustls_0_23(&bind_addr, rustls_config)?
-                .run()
-                .await;
-        }
+            server.bind_rustls_0_23(&bind_addr, rustls_config)?
+        } else {
+            server.bind(&bind_addr)?
+        };
 
         // Non-TLS binding
-        server.bind(&bind_addr)?.run().await
+        #[cfg(not(feature = "tls"))]
+        let server = server.bind(&bind_addr)?;
+
+        #[cfg(unix)]
+        let control = if self.control {
+            let listeners = server
+                .addrs()
+                .iter()
+                .map(|addr| format!("{}://{}", protocol, addr))
+                .collect();
+            let control = Arc::new(ControlServer::new(
+                self.config.server.control.socket_path(),
+                listeners,
+                self.session_manager.clone(),
+                self.pty_manager.clone(),
+                self.shutdown.clone(),
+                self.audit.clone(),
+            ));
+            control.clone().start()?;
+            Some(control)
+        } else {
+            None
+        };
+
+        let server = server.run();
+        let handle = server.handle();
+        let mut server = Box::pin(server);
+        self.shutdown.clone().start();
+
+        // Per spec-kit/005-cli-spec.md: Graceful Shutdown
+        let stopped = tokio::select! {
+            result = &mut server => {
+                result?;
+                self.shutdown.request(std::time::Duration::ZERO, "server stopped");
+                true
+            }
+            _ = self.shutdown.requested() => false,
+        };
+        let summary = self.shutdown.drain().await;
+        // Let `stop` and `restart` callers see the summary
+        #[cfg(unix)]
+        if let Some(control) = control {
+            control.close(std::time::Duration::from_secs(2)).await;
+        }
+        if !stopped {
+            // The server future handles the stop command, so poll both
+            let (_, result) = tokio::join!(handle.stop(true), server);
+            result?;
+        }
+        Ok(summary)
     }
 }
 
@@ -280,48 +670,213 @@ async fn get_session_history(
     }
 }
 
+/// Validate a token presented on a WebSocket upgrade request
+///
+/// Returns `Ok(None)` when the request carries no token (the client may still
+/// authenticate in-band), or an error response when the token is invalid.
+/// Per spec-kit/011-authentication-spec.md section 7: Token sources
+async fn authenticate_upgrade(
+    req: &HttpRequest,
+    token_extractor: &TokenExtractor,
+    credentials: &CredentialVerifier,
+    audit: &AuditLogger,
+) -> std::result::Result<Option<UserContext>, HttpResponse> {
+    let Some(token) = token_extractor.extract(req) else {
+        return Ok(None);
+    };
+
+    let client_ip = req.peer_addr().map(|addr| addr.ip());
+    match credentials.verify(&token.token, client_ip).await {
+        Ok(user_context) => Ok(Some(user_context)),
+        Err(e) => {
+            tracing::warn!(
+                "WebSocket upgrade token ({:?}) rejected: {}",
+                token.source,
+                e
+            );
+            audit.record(e.audit_event());
+            if let Some(lockout) = e.lockout() {
+                return Err(locked_out_response(lockout));
+            }
+            if e.is_denied() {
+                return Err(HttpResponse::Forbidden().json(serde_json::json!({
+                    "error": "Access denied by authorization policy"
+                })));
+            }
+            Err(HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Invalid or expired token"
+            })))
+        }
+    }
+}
+
 /// WebSocket handler
 /// Per spec-kit/007-websocket-spec.md: WebSocket endpoint at /ws
-/// Per spec-kit/011-authentication-spec.md: WebSocket authentication via Authenticate message
+/// Per spec-kit/011-authentication-spec.md: WebSocket authentication via the
+/// upgrade request (header, query, subprotocol) or the Authenticate message
 /// CRITICAL: Relative path /ws (no hardcoded host/port)
+#[allow(clippy::too_many_arguments)]
 async fn websocket_handler(
     req: HttpRequest,
     stream: web::Payload,
-    session_manager: web::Data<SessionManager>,
+    config: web::Data<Arc<Config>>,
+    session_manager: web::Data<Arc<SessionManager>>,
+    pty_manager: web::Data<Arc<PtyManager>>,
     jwt_validator: web::Data<Arc<JwtValidator>>,
+    audit: web::Data<Arc<AuditLogger>>,
+    token_extractor: web::Data<TokenExtractor>,
+    api_keys: web::Data<Arc<ApiKeyStore>>,
+    admission: web::Data<Arc<AdmissionPolicy>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    revocations: web::Data<Arc<RevocationList>>,
+    lockouts: web::Data<Arc<LockoutTracker>>,
+    tenants: web::Data<Arc<TenantResolver>>,
+    access_grants: web::Data<Arc<AccessGrantStore>>,
+    shutdown: web::Data<Arc<ShutdownCoordinator>>,
 ) -> Result<HttpResponse> {
-    // Create temporary session for WebSocket
-    // User will authenticate via Authenticate message
+    let credentials = CredentialVerifier::new((**jwt_validator).clone())
+        .with_api_keys((**api_keys).clone())
+        .with_admission((**admission).clone())
+        .with_authorization((**authz).clone())
+        .with_revocations((**revocations).clone())
+        .with_single_use_tickets()
+        .with_lockouts((**lockouts).clone())
+        .with_tenancy((**tenants).clone());
+    let upgrade_user =
+        match authenticate_upgrade(&req, &token_extractor, &credentials, &audit).await {
+            Ok(user) => user,
+            Err(response) => return Ok(response),
+        };
+
+    // The session is created (or attached, with ?session=<id>) once the
+    // client is authenticated
     // Per spec-kit/011-authentication-spec.md: Authentication required before processing
-    let user_id = "pending_auth".to_string().into();
+    let query = web::Query::<AttachQuery>::from_query(req.query_string())
+        .map(|q| q.into_inner())
+        .unwrap_or_default();
+
+    tracing::info!(
+        "WebSocket connection ({}, attach to {:?})",
+        if upgrade_user.is_some() {
+            "authenticated on upgrade"
+        } else {
+            "pending auth"
+        },
+        query.session
+    );
+
+    let mut ws_session = WebSocketSession::new(
+        (**session_manager).clone(),
+        (**pty_manager).clone(),
+        (**jwt_validator).clone(),
+    )
+    .with_compression(config.server.compression.clone())
+    .with_latency(config.server.latency.clone())
+    .with_audit((**audit).clone())
+    .with_token_expiry(
+        config.auth.validation.expiry_warning,
+        config.auth.validation.on_expiry,
+    )
+    .with_message_auth(token_extractor.allows(TokenSource::Message))
+    .with_api_keys((**api_keys).clone())
+    .with_admission((**admission).clone())
+    .with_authorization((**authz).clone())
+    .with_revocations((**revocations).clone())
+    .with_lockouts((**lockouts).clone())
+    .with_tenancy((**tenants).clone())
+    .with_access_grants((**access_grants).clone())
+    .with_shutdown((**shutdown).clone())
+    .with_client_ip(req.peer_addr().map(|addr| addr.ip()));
+
+    if let Some(session_id) = query.session {
+        ws_session = ws_session.with_attach_target(SessionId::new(session_id));
+    }
+    if let Some(profile) = query.profile {
+        ws_session = ws_session.with_profile(profile);
+    }
+    if let Some(user_context) = upgrade_user {
+        ws_session = ws_session.with_authenticated_user(user_context);
+    }
 
-    match session_manager.create_session(user_id).await {
-        Ok(session) => {
-            let session_id = session.id.clone();
-            tracing::info!(
-                "WebSocket connection for session: {} (pending auth)",
-                session_id
-            );
+    // Echo the "bearer" marker, never the token-bearing protocol entry
+    ws::WsResponseBuilder::new(ws_session, &req, stream)
+        .protocols(&[BEARER_SUBPROTOCOL])
+        .start()
+}
 
-            // Create PTY manager per-connection (avoids Sync issues)
-            let pty_manager = PtyManager::with_defaults();
+/// Query parameters accepted by the WebSocket endpoint
+/// Per spec-kit/007-websocket-spec.md: Session attach
+#[derive(Debug, Default, serde::Deserialize)]
+struct AttachQuery {
+    /// Existing session to attach to instead of creating a new one
+    session: Option<String>,
+    /// Session profile to start a new session with
+    profile: Option<String>,
+}
 
-            let ws_session = WebSocketSession::new(
-                session_id,
-                session_manager.clone().into_inner(),
-                pty_manager,
-                (**jwt_validator).clone(),
-            );
+/// ttyd-compatible WebSocket handler
+/// Per spec-kit/007-websocket-spec.md: Alternative client protocols
+/// Negotiates the ttyd "tty" subprotocol; the session and PTY are created
+/// once the client's handshake arrives and its token (or the upgrade
+/// request's token) has been validated
+#[allow(clippy::too_many_arguments)]
+async fn ttyd_handler(
+    req: HttpRequest,
+    stream: web::Payload,
+    config: web::Data<Arc<Config>>,
+    session_manager: web::Data<Arc<SessionManager>>,
+    pty_manager: web::Data<Arc<PtyManager>>,
+    jwt_validator: web::Data<Arc<JwtValidator>>,
+    audit: web::Data<Arc<AuditLogger>>,
+    token_extractor: web::Data<TokenExtractor>,
+    api_keys: web::Data<Arc<ApiKeyStore>>,
+    admission: web::Data<Arc<AdmissionPolicy>>,
+    authz: web::Data<Arc<AuthorizationService>>,
+    revocations: web::Data<Arc<RevocationList>>,
+    lockouts: web::Data<Arc<LockoutTracker>>,
+    tenants: web::Data<Arc<TenantResolver>>,
+    shutdown: web::Data<Arc<ShutdownCoordinator>>,
+) -> Result<HttpResponse> {
+    let credentials = CredentialVerifier::new((**jwt_validator).clone())
+        .with_api_keys((**api_keys).clone())
+        .with_admission((**admission).clone())
+        .with_authorization((**authz).clone())
+        .with_revocations((**revocations).clone())
+        .with_single_use_tickets()
+        .with_lockouts((**lockouts).clone())
+        .with_tenancy((**tenants).clone());
+    let upgrade_user =
+        match authenticate_upgrade(&req, &token_extractor, &credentials, &audit).await {
+            Ok(user) => user,
+            Err(response) => return Ok(response),
+        };
 
-            ws::start(ws_session, &req, stream)
-        }
-        Err(e) => {
-            tracing::error!("Failed to create session for WebSocket: {}", e);
-            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": e.to_string()
-            })))
-        }
+    tracing::info!("ttyd WebSocket connection (pending handshake)");
+
+    let mut ttyd_session = TtydSession::new(
+        (**session_manager).clone(),
+        (**pty_manager).clone(),
+        (**jwt_validator).clone(),
+    )
+    .with_latency(config.server.latency.clone())
+    .with_audit((**audit).clone())
+    .with_message_auth(token_extractor.allows(TokenSource::Message))
+    .with_api_keys((**api_keys).clone())
+    .with_admission((**admission).clone())
+    .with_authorization((**authz).clone())
+    .with_revocations((**revocations).clone())
+    .with_lockouts((**lockouts).clone())
+    .with_tenancy((**tenants).clone())
+    .with_shutdown((**shutdown).clone())
+    .with_client_ip(req.peer_addr().map(|addr| addr.ip()));
+
+    if let Some(user_context) = upgrade_user {
+        ttyd_session = ttyd_session.with_authenticated_user(user_context);
     }
+
+    ws::WsResponseBuilder::new(ttyd_session, &req, stream)
+        .protocols(&[crate::protocol::ttyd::SUBPROTOCOL, BEARER_SUBPROTOCOL])
+        .start()
 }
 
 #[cfg(test)]
@@ -334,9 +889,8 @@ mod tests {
     fn test_server_creation() {
         let config = Config::default();
         let session_manager = SessionManager::new(SessionConfig::default());
-        let jwt_secret = b"test_secret_key_at_least_32_bytes_long";
 
-        let server = Server::new(config, session_manager, jwt_secret);
+        let server = Server::new(config, session_manager);
         assert!(Arc::strong_count(&server.config) >= 1);
     }
 }
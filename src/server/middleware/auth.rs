@@ -3,22 +3,36 @@
 // Per spec-kit/003-backend-spec.md section 4.1
 
 use std::future::{ready, Ready};
+use std::net::IpAddr;
 use std::sync::Arc;
 
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage,
+    http::header,
+    Error, HttpMessage, HttpResponse,
 };
+use chrono::Utc;
 use futures_util::future::LocalBoxFuture;
+use thiserror::Error;
 
+use super::token_source::TokenExtractor;
+use crate::handlers::api_types::ErrorResponse;
+use crate::security::admission::{AdmissionDenied, AdmissionPolicy};
+use crate::security::api_keys::{ApiKey, ApiKeyError, ApiKeyStore, API_KEY_PROVIDER};
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::security::authorization::{AuthorizationService, Decision, Permission};
 use crate::security::jwt_validator::{Claims, JwtValidator, ValidationError};
+use crate::security::lockout::{self, Lockout, LockoutTracker};
+use crate::security::policy::{AccessRequest, Resource};
+use crate::security::revocation::{RevocationError, RevocationList};
+use crate::security::tenancy::{Tenant, TenantError, TenantResolver};
 use crate::session::UserId;
 
 /// User context extracted from validated JWT
 /// Per spec-kit/011-authentication-spec.md: "Authentication Flow"
 #[derive(Debug, Clone)]
 pub struct UserContext {
-    /// User identifier from JWT sub claim
+    /// User identifier from JWT sub claim (`<tenant>/<sub>` with tenancy)
     pub user_id: UserId,
     /// User email (if available from claims)
     pub email: Option<String>,
@@ -28,6 +42,16 @@ pub struct UserContext {
     pub provider: String,
     /// Raw JWT claims for additional data
     pub claims: Claims,
+    /// Permissions the credential is limited to (API keys); `None` means
+    /// the user's own permissions apply unrestricted
+    pub scopes: Option<Vec<Permission>>,
+    /// Roles resolved from the claims (empty until resolved, which grants
+    /// only the default permissions)
+    pub roles: Vec<String>,
+    /// Address the credential was presented from, for policy conditions
+    pub source_ip: Option<IpAddr>,
+    /// Tenant the user belongs to (`None` without tenancy)
+    pub tenant: Option<Tenant>,
 }
 
 impl UserContext {
@@ -54,26 +78,504 @@ impl UserContext {
             groups,
             provider,
             claims,
+            scopes: None,
+            roles: Vec::new(),
+            source_ip: None,
+            tenant: None,
         }
     }
 
+    /// Create for a verified API key, limited to the key's scopes
+    /// Per spec-kit/011-authentication-spec.md: API Keys
+    pub fn from_api_key(key: &ApiKey) -> Self {
+        Self {
+            scopes: Some(key.scopes.clone()),
+            ..Self::from_claims(key.claims(), API_KEY_PROVIDER.to_string())
+        }
+    }
+
+    /// Place the user in `tenant`, qualifying their user ID by it
+    /// Per spec-kit/011-authentication-spec.md: Multi-Tenancy
+    pub fn with_tenant(mut self, tenant: Option<Tenant>) -> Self {
+        if let Some(tenant) = &tenant {
+            self.user_id = tenant.user_id(&self.claims.sub);
+        }
+        self.tenant = tenant;
+        self
+    }
+
+    /// Name of the user's tenant
+    pub fn tenant_name(&self) -> Option<&str> {
+        self.tenant.as_ref().map(|tenant| tenant.name.as_str())
+    }
+
+    /// Whether the user's administration reaches resources of `tenant`
+    /// (`None` for server-wide state); always without tenancy
+    pub fn manages(&self, tenant: Option<&str>) -> bool {
+        self.tenant.as_ref().is_none_or(|own| own.manages(tenant))
+    }
+
+    /// Reject administration of `tenant` (`None` for server-wide state)
+    /// outside the user's tenant
+    /// Per spec-kit/011-authentication-spec.md: Multi-Tenancy
+    pub fn require_manages(&self, tenant: Option<&str>) -> crate::error::Result<()> {
+        if self.manages(tenant) {
+            Ok(())
+        } else {
+            Err(crate::error::Error::forbidden(match tenant {
+                Some(tenant) => format!("Tenant {} is outside your administration", tenant),
+                None => "Server-wide administration needs an operator tenant".to_string(),
+            }))
+        }
+    }
+
+    /// Whether the caller authenticated with an API key
+    pub fn is_api_key(&self) -> bool {
+        self.provider == API_KEY_PROVIDER
+    }
+
+    /// Whether the credential's scopes cover `permission`
+    pub fn has_scope(&self, permission: Permission) -> bool {
+        self.scopes
+            .as_ref()
+            .is_none_or(|scopes| scopes.contains(&permission))
+    }
+
+    /// Reject requests outside the credential's scopes
+    pub fn require_scope(&self, permission: Permission) -> crate::error::Result<()> {
+        if self.has_scope(permission) {
+            Ok(())
+        } else {
+            Err(crate::error::Error::forbidden(format!(
+                "API key is not scoped for {}",
+                permission.as_str()
+            )))
+        }
+    }
+
+    /// Authorize `permission`, on a session owned by `owner` if given
+    /// Per spec-kit/011-authentication-spec.md section 5: Authorization Model
+    pub fn authorize(
+        &self,
+        authz: &AuthorizationService,
+        permission: Permission,
+        owner: Option<&UserId>,
+    ) -> crate::error::Result<()> {
+        self.authorize_resource(authz, permission, Resource::owned_by(owner))
+    }
+
+    /// Authorize `permission` on `resource`: the credential must be scoped
+    /// for it and the policy rules or one of the user's roles must grant it
+    /// Per spec-kit/011-authentication-spec.md section 5: Authorization Model
+    pub fn authorize_resource(
+        &self,
+        authz: &AuthorizationService,
+        permission: Permission,
+        resource: Resource<'_>,
+    ) -> crate::error::Result<()> {
+        let decision = self.decide(authz, permission, &self.access_request(resource));
+        if decision.allowed {
+            Ok(())
+        } else {
+            authz.record_denial(&self.user_id, &decision);
+            Err(crate::error::Error::forbidden(decision.reason))
+        }
+    }
+
+    /// The request this user makes on `resource` now
+    pub fn access_request<'a>(&'a self, resource: Resource<'a>) -> AccessRequest<'a> {
+        AccessRequest {
+            user_id: &self.user_id,
+            claims: &self.claims,
+            roles: &self.roles,
+            source_ip: self.source_ip,
+            at: Utc::now(),
+            tenant: self.tenant.as_ref(),
+            resource,
+        }
+    }
+
+    /// Evaluate `request` for `permission`, denying it outright when the
+    /// credential is not scoped for it
+    /// Per spec-kit/011-authentication-spec.md: Policy Rules
+    pub fn decide(
+        &self,
+        authz: &AuthorizationService,
+        permission: Permission,
+        request: &AccessRequest<'_>,
+    ) -> Decision {
+        let foreign = request
+            .resource
+            .owner
+            .is_some_and(|owner| *owner != self.user_id);
+        let required =
+            std::iter::once(permission).chain(permission.any_owner().filter(|_| foreign));
+        for scope in required {
+            if let Err(e) = self.require_scope(scope) {
+                return Decision::denied(permission, e.to_string());
+            }
+        }
+
+        authz.evaluate(permission, request)
+    }
+
     /// Extract UserContext from actix request extensions
     pub fn from_request(req: &actix_web::HttpRequest) -> Option<Self> {
         req.extensions().get::<UserContext>().cloned()
     }
 }
 
+/// Credential verification failure
+#[derive(Debug, Error)]
+pub enum CredentialError {
+    #[error(transparent)]
+    Jwt(#[from] ValidationError),
+
+    #[error(transparent)]
+    ApiKey(#[from] ApiKeyError),
+
+    #[error(transparent)]
+    Revoked(#[from] RevocationError),
+
+    /// The credential is valid but its holder is not allowed in
+    #[error("Access denied: {0}")]
+    Denied(#[from] AdmissionDenied),
+
+    /// Too many failed attempts from this address or for this subject
+    #[error(transparent)]
+    LockedOut(#[from] Lockout),
+
+    /// The credential is valid but belongs to no configured tenant
+    #[error("Access denied: {0}")]
+    Tenant(#[from] TenantError),
+}
+
+impl CredentialError {
+    /// Whether the credential was valid but refused by the admission policy
+    /// (403 rather than 401)
+    pub fn is_denied(&self) -> bool {
+        matches!(self, Self::Denied(_) | Self::Tenant(_))
+    }
+
+    /// The lockout refusing this attempt (429 rather than 401)
+    pub fn lockout(&self) -> Option<&Lockout> {
+        match self {
+            Self::LockedOut(lockout) => Some(lockout),
+            _ => None,
+        }
+    }
+
+    /// Whether this failure counts towards a lockout: bad, expired or
+    /// revoked credentials do, server-side problems and admission denials
+    /// of valid credentials do not
+    /// Per spec-kit/011-authentication-spec.md: Brute-Force Protection
+    pub fn counts_as_failure(&self) -> bool {
+        match self {
+            Self::Jwt(e) => !matches!(e, ValidationError::JwksError(_)),
+            Self::ApiKey(e) => matches!(
+                e,
+                ApiKeyError::InvalidKey
+                    | ApiKeyError::Revoked(_)
+                    | ApiKeyError::Expired { .. }
+                    | ApiKeyError::AddressNotAllowed { .. }
+            ),
+            Self::Revoked(e) => matches!(
+                e,
+                RevocationError::Revoked(_)
+                    | RevocationError::TicketReused(_)
+                    | RevocationError::TicketWithoutJti
+            ),
+            Self::Denied(_) | Self::LockedOut(_) | Self::Tenant(_) => false,
+        }
+    }
+
+    /// Audit record for this failure
+    /// Per spec-kit/011-authentication-spec.md: Audit logging
+    pub fn audit_event(&self) -> AuditEvent {
+        match self {
+            Self::Denied(denied) => AuditEvent::new(AuditAction::AuthorizationDenied)
+                .user(denied.subject())
+                .detail(denied.to_string()),
+            Self::Tenant(e) => {
+                AuditEvent::new(AuditAction::AuthorizationDenied).detail(e.to_string())
+            }
+            _ => AuditEvent::new(AuditAction::AuthFailure).detail(self.to_string()),
+        }
+    }
+}
+
+/// Verifies bearer credentials: JWTs from the configured providers and,
+/// when enabled, server-issued API keys, then applies the admission policy
+/// Per spec-kit/011-authentication-spec.md: API Keys, Authorization Model
+#[derive(Clone)]
+pub struct CredentialVerifier {
+    validator: Arc<JwtValidator>,
+    api_keys: Option<Arc<ApiKeyStore>>,
+    admission: Option<Arc<AdmissionPolicy>>,
+    authz: Option<Arc<AuthorizationService>>,
+    revocations: Option<Arc<RevocationList>>,
+    single_use_tickets: bool,
+    lockouts: Option<Arc<LockoutTracker>>,
+    tenants: Option<Arc<TenantResolver>>,
+}
+
+impl CredentialVerifier {
+    /// Verify JWTs only
+    pub fn new(validator: Arc<JwtValidator>) -> Self {
+        Self {
+            validator,
+            api_keys: None,
+            admission: None,
+            authz: None,
+            revocations: None,
+            single_use_tickets: false,
+            lockouts: None,
+            tenants: None,
+        }
+    }
+
+    /// Also accept keys from `store`
+    pub fn with_api_keys(mut self, store: Arc<ApiKeyStore>) -> Self {
+        self.api_keys = Some(store);
+        self
+    }
+
+    /// Only admit identities allowed by `policy`
+    /// Per spec-kit/011-authentication-spec.md: Authorization Model
+    pub fn with_admission(mut self, policy: Arc<AdmissionPolicy>) -> Self {
+        self.admission = Some(policy);
+        self
+    }
+
+    /// Resolve the roles of verified users with `authz`
+    /// Per spec-kit/011-authentication-spec.md section 5: Authorization Model
+    pub fn with_authorization(mut self, authz: Arc<AuthorizationService>) -> Self {
+        self.authz = Some(authz);
+        self
+    }
+
+    /// Refuse credentials revoked in `list`
+    /// Per spec-kit/011-authentication-spec.md: Token Revocation
+    pub fn with_revocations(mut self, list: Arc<RevocationList>) -> Self {
+        self.revocations = Some(list);
+        self
+    }
+
+    /// Accept each WebSocket ticket only once (when enabled in the
+    /// revocation settings)
+    /// Per spec-kit/011-authentication-spec.md: Token Revocation
+    pub fn with_single_use_tickets(mut self) -> Self {
+        self.single_use_tickets = true;
+        self
+    }
+
+    /// Count failed attempts in `tracker` and refuse locked out addresses
+    /// and subjects
+    /// Per spec-kit/011-authentication-spec.md: Brute-Force Protection
+    pub fn with_lockouts(mut self, tracker: Arc<LockoutTracker>) -> Self {
+        self.lockouts = Some(tracker);
+        self
+    }
+
+    /// Place verified users in their tenant, refusing those in none
+    /// Per spec-kit/011-authentication-spec.md: Multi-Tenancy
+    pub fn with_tenancy(mut self, tenants: Arc<TenantResolver>) -> Self {
+        self.tenants = Some(tenants);
+        self
+    }
+
+    /// Verify a credential presented from `client_ip`
+    ///
+    /// Every counted failure is charged to the address. Only credentials
+    /// whose signature or secret checked out (expired, revoked, a reused
+    /// ticket) are also charged to their provider and subject, so forged
+    /// tokens naming someone else cannot lock that user out.
+    /// Per spec-kit/011-authentication-spec.md: Brute-Force Protection
+    pub async fn verify(
+        &self,
+        token: &str,
+        client_ip: Option<IpAddr>,
+    ) -> Result<UserContext, CredentialError> {
+        let Some(tracker) = &self.lockouts else {
+            let user_context = self.authenticate(token, client_ip).await?;
+            return self.admit(user_context, client_ip);
+        };
+
+        tracker.check(client_ip, None)?;
+        let (result, subject) = match self.authenticate(token, client_ip).await {
+            Ok(user_context) => {
+                let subject =
+                    lockout::subject_key(&user_context.provider, &user_context.claims.sub);
+                let result = match tracker.check(None, Some(&subject)) {
+                    Ok(()) => self.admit(user_context, client_ip),
+                    Err(lockout) => Err(lockout.into()),
+                };
+                (result, Some(subject))
+            }
+            Err(e) => {
+                let subject = self.verified_subject(token, &e);
+                (Err(e), subject)
+            }
+        };
+
+        match &result {
+            Ok(_) => {
+                if let Some(subject) = &subject {
+                    tracker.record_success(subject);
+                }
+            }
+            Err(e) if e.counts_as_failure() => {
+                tracker.record_failure(client_ip, subject.as_deref());
+            }
+            Err(_) => {}
+        }
+        result
+    }
+
+    /// Lockout subject of a credential refused after its signature or
+    /// secret was verified
+    fn verified_subject(&self, token: &str, error: &CredentialError) -> Option<String> {
+        match error {
+            CredentialError::Jwt(ValidationError::TokenExpired {
+                provider, subject, ..
+            }) => Some(lockout::subject_key(provider, subject)),
+            CredentialError::ApiKey(
+                ApiKeyError::Revoked(_)
+                | ApiKeyError::Expired { .. }
+                | ApiKeyError::AddressNotAllowed { .. },
+            ) => {
+                let owner = self.api_keys.as_ref()?.claimed_owner(token)?;
+                Some(lockout::subject_key(API_KEY_PROVIDER, &owner))
+            }
+            _ => None,
+        }
+    }
+
+    /// Check the credential's signature or secret and place its holder in
+    /// their tenant
+    async fn authenticate(
+        &self,
+        token: &str,
+        client_ip: Option<IpAddr>,
+    ) -> Result<UserContext, CredentialError> {
+        if ApiKeyStore::is_api_key(token) {
+            let store = self.api_keys.as_ref().ok_or(ApiKeyError::Disabled)?;
+            let key = store.authenticate(token, client_ip).await?;
+            let tenant = match &self.tenants {
+                Some(tenants) => tenants.resolve_key(&key)?,
+                None => None,
+            };
+            Ok(UserContext::from_api_key(&key).with_tenant(tenant))
+        } else {
+            let validated = self.validator.validate(token).await?;
+            let tenant = match &self.tenants {
+                Some(tenants) => tenants.resolve(&validated.provider, &validated.claims)?,
+                None => None,
+            };
+            Ok(UserContext::from_claims(validated.claims, validated.provider).with_tenant(tenant))
+        }
+    }
+
+    /// Apply revocations, single-use tickets and the admission policy to an
+    /// authenticated user, then resolve their roles
+    fn admit(
+        &self,
+        mut user_context: UserContext,
+        client_ip: Option<IpAddr>,
+    ) -> Result<UserContext, CredentialError> {
+        // API keys act as their owner's user reference, so revoking or
+        // denying the owner stops their keys too
+        if let Some(revocations) = &self.revocations {
+            revocations.check(&user_context.claims, user_context.tenant_name())?;
+            if self.single_use_tickets && !user_context.is_api_key() {
+                revocations.consume_ticket(&user_context.claims)?;
+            }
+        }
+        if let Some(policy) = &self.admission {
+            policy.check(&user_context.claims)?;
+        }
+        if let Some(authz) = &self.authz {
+            user_context.roles = authz.resolve_roles(&user_context.claims);
+        }
+        user_context.source_ip = client_ip;
+        Ok(user_context)
+    }
+}
+
 /// JWT authentication middleware using JWKS validation
 /// Per spec-kit/011-authentication-spec.md: "HTTP Request Authentication"
 #[derive(Clone)]
 pub struct JwtAuthMiddleware {
-    validator: Arc<JwtValidator>,
+    credentials: CredentialVerifier,
+    extractor: TokenExtractor,
+    audit: Arc<AuditLogger>,
 }
 
 impl JwtAuthMiddleware {
     /// Create new JWT authentication middleware with JWKS validator
+    /// (tokens read from the Authorization header)
     pub fn new(validator: Arc<JwtValidator>) -> Self {
-        Self { validator }
+        Self {
+            credentials: CredentialVerifier::new(validator),
+            extractor: TokenExtractor::default(),
+            audit: Arc::new(AuditLogger::default()),
+        }
+    }
+
+    /// Also accept API keys from `store`
+    /// Per spec-kit/011-authentication-spec.md: API Keys
+    pub fn with_api_keys(mut self, store: Arc<ApiKeyStore>) -> Self {
+        self.credentials = self.credentials.with_api_keys(store);
+        self
+    }
+
+    /// Only admit identities allowed by `policy`
+    /// Per spec-kit/011-authentication-spec.md: Authorization Model
+    pub fn with_admission(mut self, policy: Arc<AdmissionPolicy>) -> Self {
+        self.credentials = self.credentials.with_admission(policy);
+        self
+    }
+
+    /// Resolve the roles of authenticated users with `authz`
+    /// Per spec-kit/011-authentication-spec.md section 5: Authorization Model
+    pub fn with_authorization(mut self, authz: Arc<AuthorizationService>) -> Self {
+        self.credentials = self.credentials.with_authorization(authz);
+        self
+    }
+
+    /// Refuse credentials revoked in `list`
+    /// Per spec-kit/011-authentication-spec.md: Token Revocation
+    pub fn with_revocations(mut self, list: Arc<RevocationList>) -> Self {
+        self.credentials = self.credentials.with_revocations(list);
+        self
+    }
+
+    /// Count failed attempts in `tracker` and refuse locked out clients
+    /// Per spec-kit/011-authentication-spec.md: Brute-Force Protection
+    pub fn with_lockouts(mut self, tracker: Arc<LockoutTracker>) -> Self {
+        self.credentials = self.credentials.with_lockouts(tracker);
+        self
+    }
+
+    /// Place authenticated users in their tenant
+    /// Per spec-kit/011-authentication-spec.md: Multi-Tenancy
+    pub fn with_tenancy(mut self, tenants: Arc<TenantResolver>) -> Self {
+        self.credentials = self.credentials.with_tenancy(tenants);
+        self
+    }
+
+    /// Set the audit logger
+    /// Per spec-kit/011-authentication-spec.md: Audit logging
+    pub fn with_audit(mut self, audit: Arc<AuditLogger>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Read tokens from the configured sources instead of only the header
+    /// Per spec-kit/011-authentication-spec.md section 7: Token sources
+    pub fn with_token_extractor(mut self, extractor: TokenExtractor) -> Self {
+        self.extractor = extractor;
+        self
     }
 }
 
@@ -92,14 +594,18 @@ where
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(JwtAuthMiddlewareService {
             service: Arc::new(service),
-            validator: self.validator.clone(),
+            credentials: self.credentials.clone(),
+            extractor: self.extractor.clone(),
+            audit: self.audit.clone(),
         }))
     }
 }
 
 pub struct JwtAuthMiddlewareService<S> {
     service: Arc<S>,
-    validator: Arc<JwtValidator>,
+    credentials: CredentialVerifier,
+    extractor: TokenExtractor,
+    audit: Arc<AuditLogger>,
 }
 
 impl<S, B> Service<ServiceRequest> for JwtAuthMiddlewareService<S>
@@ -115,28 +621,33 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let validator = self.validator.clone();
+        let credentials = self.credentials.clone();
+        let audit = self.audit.clone();
         let service = self.service.clone();
+        let token = self.extractor.extract(req.request());
+        let client_ip = req.peer_addr().map(|addr| addr.ip());
 
         Box::pin(async move {
-            // Extract Authorization header
-            let auth_header = req
-                .headers()
-                .get("Authorization")
-                .and_then(|h| h.to_str().ok())
-                .and_then(|s| s.strip_prefix("Bearer "))
-                .ok_or_else(|| {
-                    actix_web::error::ErrorUnauthorized("Missing or invalid Authorization header")
-                })?;
-
-            // Validate JWT token using JWKS
-            let validated = validator.validate(auth_header).await.map_err(|e| {
-                tracing::warn!("JWT validation failed: {}", e);
-                actix_web::error::ErrorUnauthorized(format!("Invalid token: {}", e))
+            // Extract bearer token from the configured sources
+            let token = token.ok_or_else(|| {
+                actix_web::error::ErrorUnauthorized("Missing or invalid bearer token")
             })?;
 
-            // Create user context
-            let user_context = UserContext::from_claims(validated.claims, validated.provider);
+            // Validate the JWT (using JWKS) or API key, then check admission
+            let user_context = credentials
+                .verify(&token.token, client_ip)
+                .await
+                .map_err(|e| {
+                    tracing::warn!("Credential validation failed: {}", e);
+                    audit.record(e.audit_event());
+                    if let Some(lockout) = e.lockout() {
+                        locked_out_error(lockout)
+                    } else if e.is_denied() {
+                        actix_web::error::ErrorForbidden("Access denied by authorization policy")
+                    } else {
+                        actix_web::error::ErrorUnauthorized(format!("Invalid token: {}", e))
+                    }
+                })?;
 
             tracing::debug!(
                 "Authenticated user: {} (provider: {})",
@@ -153,33 +664,93 @@ where
     }
 }
 
+/// 429 response for an attempt refused by a lockout, with Retry-After
+/// Per spec-kit/011-authentication-spec.md: Brute-Force Protection
+pub fn locked_out_response(lockout: &Lockout) -> HttpResponse {
+    let retry_after = lockout.retry_after_secs();
+    HttpResponse::TooManyRequests()
+        .insert_header((header::RETRY_AFTER, retry_after.to_string()))
+        .json(ErrorResponse::auth_locked_out(retry_after))
+}
+
+fn locked_out_error(lockout: &Lockout) -> Error {
+    actix_web::error::InternalError::from_response(
+        lockout.to_string(),
+        locked_out_response(lockout),
+    )
+    .into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::auth::RateLimitConfig;
+    use crate::security::jwt_validator::Audience;
+    use crate::security::testing::{forge, lab_token, lab_validator};
 
     #[test]
     fn test_user_context_from_claims() {
         let claims = Claims {
             sub: "user:default/testuser".to_string(),
             iss: "https://example.com".to_string(),
-            aud: Some("web-terminal".to_string()),
+            aud: Audience::Single("web-terminal".to_string()),
             exp: 1234567890,
             nbf: None,
-            iat: Some(1234567800),
+            iat: 1234567800,
             email: Some("test@example.com".to_string()),
             groups: Some(vec!["group:default/admins".to_string()]),
             ent: Some(vec![
                 "group:default/admins".to_string(),
                 "user:default/testuser".to_string(),
             ]),
-            additional: Default::default(),
+            usc: None,
+            custom: Default::default(),
         };
 
         let user_ctx = UserContext::from_claims(claims, "backstage".to_string());
 
-        assert_eq!(user_ctx.user_id.as_ref(), "user:default/testuser");
+        assert_eq!(user_ctx.user_id.as_str(), "user:default/testuser");
         assert_eq!(user_ctx.email, Some("test@example.com".to_string()));
         assert_eq!(user_ctx.groups, vec!["group:default/admins"]);
         assert_eq!(user_ctx.provider, "backstage");
     }
+
+    #[tokio::test]
+    async fn test_forged_tokens_cannot_lock_out_a_user() {
+        let (_dir, validator) = lab_validator();
+        let tracker = Arc::new(LockoutTracker::new(RateLimitConfig {
+            enabled: true,
+            max_failed_attempts: 3,
+            window: std::time::Duration::from_secs(300),
+            lockout: std::time::Duration::from_secs(900),
+        }));
+        let verifier = CredentialVerifier::new(Arc::new(validator)).with_lockouts(tracker);
+        let ip = |host: u8| Some(IpAddr::from([192, 0, 2, host]));
+        let admin = || lab_token("admin", serde_json::json!({}));
+
+        // Tokens naming the admin under a bad signature, from many addresses
+        let forged = forge(&admin());
+        for host in 1..=10 {
+            assert!(verifier.verify(&forged, ip(host)).await.is_err());
+        }
+        let user = verifier.verify(&admin(), ip(100)).await.unwrap();
+        assert_eq!(user.claims.sub, "admin");
+
+        // ...only lock out the address they came from
+        for _ in 0..3 {
+            assert!(verifier.verify(&forged, ip(11)).await.is_err());
+        }
+        let err = verifier.verify(&admin(), ip(11)).await.unwrap_err();
+        assert_eq!(err.lockout().unwrap().key.to_string(), "ip:192.0.2.11");
+
+        // Expired tokens carry a verified signature, so they count against
+        // the subject wherever they come from
+        let exp = Utc::now().timestamp() - 3600;
+        let expired = lab_token("admin", serde_json::json!({ "exp": exp }));
+        for host in 20..23 {
+            assert!(verifier.verify(&expired, ip(host)).await.is_err());
+        }
+        let err = verifier.verify(&admin(), ip(100)).await.unwrap_err();
+        assert_eq!(err.lockout().unwrap().key.to_string(), "sub:lab/admin");
+    }
 }
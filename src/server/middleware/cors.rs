@@ -3,6 +3,47 @@
 
 use actix_cors::Cors;
 use actix_web::http::header;
+use std::sync::{Arc, RwLock};
+
+/// Allowed origins shared by every worker's CORS middleware, replaceable
+/// while the server runs
+/// Per spec-kit/005-cli-spec.md: Live Reload
+#[derive(Debug)]
+pub struct LiveOrigins {
+    origins: RwLock<Arc<Vec<String>>>,
+}
+
+impl LiveOrigins {
+    pub fn new(origins: Vec<String>) -> Self {
+        warn_if_any(&origins);
+        Self {
+            origins: RwLock::new(Arc::new(origins)),
+        }
+    }
+
+    /// Swap in `origins`; requests already checked are not affected
+    pub fn replace(&self, origins: Vec<String>) {
+        warn_if_any(&origins);
+        *self.origins.write().expect("CORS origins lock poisoned") = Arc::new(origins);
+    }
+
+    /// Whether `origin` may make cross-origin requests
+    pub fn allows(&self, origin: &str) -> bool {
+        self.origins
+            .read()
+            .expect("CORS origins lock poisoned")
+            .iter()
+            .any(|allowed| allowed == "*" || allowed == origin)
+    }
+}
+
+fn warn_if_any(origins: &[String]) {
+    if origins.iter().any(|origin| origin == "*") {
+        tracing::warn!("CORS: Allowing all origins (*). NOT recommended for production!");
+    } else {
+        tracing::info!("CORS: Allowed origins: {:?}", origins);
+    }
+}
 
 /// CORS configuration
 /// Per spec-kit/002-architecture.md: CORS policy for cross-origin requests
@@ -60,6 +101,21 @@ impl CorsConfig {
             tracing::info!("CORS: Allowed origins: {:?}", self.allowed_origins);
         }
 
+        self.configure(cors)
+    }
+
+    /// Build Actix-Web CORS middleware checking origins against `origins`
+    /// on every request instead of `allowed_origins`
+    /// Per spec-kit/005-cli-spec.md: Live Reload
+    pub fn build_with_origins(&self, origins: Arc<LiveOrigins>) -> Cors {
+        let cors = Cors::default().allowed_origin_fn(move |origin, _| {
+            origin.to_str().is_ok_and(|origin| origins.allows(origin))
+        });
+        self.configure(cors)
+    }
+
+    /// Methods, headers, preflight caching and credentials
+    fn configure(&self, mut cors: Cors) -> Cors {
         // Configure allowed methods
         for method in &self.allowed_methods {
             cors = cors.allowed_methods(vec![method.as_str()]);
@@ -154,6 +210,44 @@ mod tests {
         assert_eq!(config.allowed_origins, vec!["*"]);
     }
 
+    #[actix_web::test]
+    async fn test_live_origins_replaced() {
+        use actix_web::{test, web, App, HttpResponse};
+
+        let origins = Arc::new(LiveOrigins::new(vec!["https://a.example.com".to_string()]));
+        let app = test::init_service(
+            App::new()
+                .wrap(CorsConfig::default().build_with_origins(origins.clone()))
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+        let request = |origin: &str| {
+            test::TestRequest::get()
+                .uri("/")
+                .insert_header((header::ORIGIN, origin))
+                .to_request()
+        };
+
+        let resp = test::call_service(&app, request("https://a.example.com")).await;
+        assert!(resp
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+        let resp = test::call_service(&app, request("https://b.example.com")).await;
+        assert!(!resp
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        origins.replace(vec!["https://b.example.com".to_string()]);
+        let resp = test::call_service(&app, request("https://b.example.com")).await;
+        assert_eq!(
+            resp.headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            "https://b.example.com"
+        );
+        assert!(!origins.allows("https://a.example.com"));
+    }
+
     #[test]
     fn test_cors_build() {
         let config = CorsConfig::default();
@@ -4,14 +4,18 @@
 pub mod auth;
 pub mod cors;
 pub mod rate_limit;
+pub mod request_logging;
 pub mod security_headers;
+pub mod token_source;
 pub mod websocket_rate_limit;
 
 // Re-export middleware components
-pub use auth::{JwtAuthMiddleware, UserContext};
-pub use cors::CorsConfig;
+pub use auth::{CredentialError, CredentialVerifier, JwtAuthMiddleware, UserContext};
+pub use cors::{CorsConfig, LiveOrigins};
 pub use rate_limit::{RateLimitConfig, RateLimitMetrics, RateLimitMiddleware};
+pub use request_logging::ScrubbedRootSpanBuilder;
 pub use security_headers::{SecurityHeadersConfig, SecurityHeadersMiddleware};
+pub use token_source::{RequestToken, TokenExtractor};
 pub use websocket_rate_limit::{
     RateLimitResult, RateLimitWarning, RateLimitedWebSocket, WebSocketRateLimitConfig,
     WebSocketRateLimiter,
@@ -0,0 +1,59 @@
+// Request logging span builder
+// Per spec-kit/011-authentication-spec.md section 7: Token sources
+//
+// Same fields as tracing-actix-web's default root span, except that query
+// string tokens are scrubbed from `http.target` before it is recorded.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::{Error, HttpMessage};
+use tracing::Span;
+use tracing_actix_web::{DefaultRootSpanBuilder, RequestId, RootSpanBuilder};
+
+use super::token_source::scrub_query_tokens;
+
+/// Root span builder for `TracingLogger` that never records query tokens
+pub struct ScrubbedRootSpanBuilder;
+
+impl RootSpanBuilder for ScrubbedRootSpanBuilder {
+    fn on_request_start(request: &ServiceRequest) -> Span {
+        let user_agent = request
+            .headers()
+            .get("User-Agent")
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or("");
+        let route = request.match_pattern().unwrap_or_else(|| "default".into());
+        let target = request
+            .uri()
+            .path_and_query()
+            .map(|p| scrub_query_tokens(p.as_str()).into_owned())
+            .unwrap_or_default();
+        let request_id = request
+            .extensions()
+            .get::<RequestId>()
+            .map(|id| id.to_string())
+            .unwrap_or_default();
+        let connection_info = request.connection_info();
+
+        tracing::info_span!(
+            "HTTP request",
+            http.method = %request.method(),
+            http.route = %route,
+            http.host = %connection_info.host(),
+            http.client_ip = %connection_info.realip_remote_addr().unwrap_or(""),
+            http.user_agent = %user_agent,
+            http.target = %target,
+            http.status_code = tracing::field::Empty,
+            otel.name = %format!("{} {}", request.method(), route),
+            otel.kind = "server",
+            otel.status_code = tracing::field::Empty,
+            request_id = %request_id,
+            exception.message = tracing::field::Empty,
+            exception.details = tracing::field::Empty,
+        )
+    }
+
+    fn on_request_end<B: MessageBody>(span: Span, outcome: &Result<ServiceResponse<B>, Error>) {
+        DefaultRootSpanBuilder::on_request_end(span, outcome);
+    }
+}
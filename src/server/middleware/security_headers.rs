@@ -1,12 +1,14 @@
 // Security headers middleware
 // Per spec-kit/002-architecture.md Layer 1: Network Security
 
+use actix_web::http::header::HeaderValue;
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
     Error,
 };
 use futures_util::future::LocalBoxFuture;
 use std::future::{ready, Ready};
+use std::sync::{Arc, RwLock};
 
 /// Security headers middleware
 /// Per spec-kit/002-architecture.md: Defense in depth strategy
@@ -18,9 +20,12 @@ use std::future::{ready, Ready};
 /// - X-Content-Type-Options
 /// - X-XSS-Protection
 /// - Referrer-Policy
+///
+/// Clones share their configuration, which can be replaced while the
+/// server runs (see [`Self::replace`]).
 #[derive(Clone)]
 pub struct SecurityHeadersMiddleware {
-    config: SecurityHeadersConfig,
+    config: Arc<RwLock<Arc<SecurityHeadersConfig>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -57,9 +62,46 @@ impl Default for SecurityHeadersConfig {
     }
 }
 
+impl From<&crate::config::server::SecurityHeadersConfig> for SecurityHeadersConfig {
+    fn from(config: &crate::config::server::SecurityHeadersConfig) -> Self {
+        Self {
+            enable_hsts: config.enable_hsts,
+            hsts_max_age: config.hsts_max_age,
+            enable_csp: config.enable_csp,
+            csp_policy: config.csp_policy.clone(),
+            enable_frame_options: config.enable_frame_options,
+            frame_options: config.frame_options.clone(),
+        }
+    }
+}
+
+impl SecurityHeadersConfig {
+    /// Check that the configured values are valid header values
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enable_csp && HeaderValue::from_str(&self.csp_policy).is_err() {
+            return Err(format!("invalid csp_policy: {:?}", self.csp_policy));
+        }
+        if self.enable_frame_options && HeaderValue::from_str(&self.frame_options).is_err() {
+            return Err(format!("invalid frame_options: {:?}", self.frame_options));
+        }
+        Ok(())
+    }
+}
+
 impl SecurityHeadersMiddleware {
     pub fn new(config: SecurityHeadersConfig) -> Self {
-        Self { config }
+        Self {
+            config: Arc::new(RwLock::new(Arc::new(config))),
+        }
+    }
+
+    /// Swap in `config` for every clone of this middleware, unless its
+    /// values are not valid headers
+    /// Per spec-kit/005-cli-spec.md: Live Reload
+    pub fn replace(&self, config: SecurityHeadersConfig) -> Result<(), String> {
+        config.validate()?;
+        *self.config.write().expect("security headers lock poisoned") = Arc::new(config);
+        Ok(())
     }
 }
 
@@ -91,7 +133,7 @@ where
 
 pub struct SecurityHeadersMiddlewareService<S> {
     service: S,
-    config: SecurityHeadersConfig,
+    config: Arc<RwLock<Arc<SecurityHeadersConfig>>>,
 }
 
 impl<S, B> Service<ServiceRequest> for SecurityHeadersMiddlewareService<S>
@@ -107,7 +149,11 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let config = self.config.clone();
+        let config = self
+            .config
+            .read()
+            .expect("security headers lock poisoned")
+            .clone();
         let fut = self.service.call(req);
 
         Box::pin(async move {
@@ -195,8 +241,44 @@ mod tests {
         assert!(headers.contains_key(actix_web::http::header::REFERRER_POLICY));
     }
 
-    #[test]
-    fn test_security_headers_config_default() {
+    #[actix_web::test]
+    async fn test_security_headers_replaced() {
+        let middleware = SecurityHeadersMiddleware::default();
+        let app = test::init_service(
+            App::new()
+                .wrap(middleware.clone())
+                .route("/", web::get().to(test_handler)),
+        )
+        .await;
+
+        let invalid = SecurityHeadersConfig {
+            csp_policy: "default-src\n'self'".to_string(),
+            ..SecurityHeadersConfig::default()
+        };
+        assert!(middleware.replace(invalid).is_err());
+
+        middleware
+            .replace(SecurityHeadersConfig {
+                enable_hsts: false,
+                frame_options: "SAMEORIGIN".to_string(),
+                ..SecurityHeadersConfig::default()
+            })
+            .unwrap();
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let resp = test::call_service(&app, req).await;
+        let headers = resp.headers();
+        assert!(!headers.contains_key(actix_web::http::header::STRICT_TRANSPORT_SECURITY));
+        assert_eq!(
+            headers
+                .get(actix_web::http::header::X_FRAME_OPTIONS)
+                .unwrap(),
+            "SAMEORIGIN"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_security_headers_config_default() {
         let config = SecurityHeadersConfig::default();
         assert!(config.enable_hsts);
         assert_eq!(config.hsts_max_age, 31536000);
@@ -0,0 +1,216 @@
+// Bearer token extraction from HTTP and WebSocket upgrade requests
+// Per spec-kit/011-authentication-spec.md section 7: Token sources
+//
+// Tokens may arrive in the Authorization header, the query string, or the
+// Sec-WebSocket-Protocol header; the in-band Authenticate message is handled
+// by the WebSocket actors themselves.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use actix_web::{http::header, web, HttpRequest};
+
+use crate::config::auth::TokenSource;
+
+/// Query parameters that may carry a token (RFC 6750 `access_token` first)
+pub const QUERY_TOKEN_PARAMS: &[&str] = &["access_token", "token"];
+
+/// Subprotocol a client offers alongside its token entry; echoed back so the
+/// token itself never appears in the handshake response
+pub const BEARER_SUBPROTOCOL: &str = "bearer";
+
+/// Prefix of the `Sec-WebSocket-Protocol` entry carrying the token
+pub const BEARER_SUBPROTOCOL_PREFIX: &str = "bearer.";
+
+/// Placeholder written in place of scrubbed query tokens
+const REDACTED: &str = "[REDACTED]";
+
+/// A token found on a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestToken {
+    pub token: String,
+    pub source: TokenSource,
+}
+
+/// Looks up bearer tokens in the configured request locations
+/// Per spec-kit/011-authentication-spec.md section 7: Token sources
+#[derive(Debug, Clone)]
+pub struct TokenExtractor {
+    sources: Vec<TokenSource>,
+}
+
+impl Default for TokenExtractor {
+    /// Authorization header only
+    fn default() -> Self {
+        Self::new(vec![TokenSource::Header])
+    }
+}
+
+impl TokenExtractor {
+    /// Create an extractor checking `sources` in order
+    pub fn new(sources: Vec<TokenSource>) -> Self {
+        Self { sources }
+    }
+
+    /// Whether tokens are accepted from `source`
+    pub fn allows(&self, source: TokenSource) -> bool {
+        self.sources.contains(&source)
+    }
+
+    /// First token found in the request, checking sources in configured order
+    pub fn extract(&self, req: &HttpRequest) -> Option<RequestToken> {
+        self.sources.iter().find_map(|&source| {
+            let token = match source {
+                TokenSource::Header => header_token(req),
+                TokenSource::Query => query_token(req.query_string()),
+                TokenSource::Subprotocol => subprotocol_token(req),
+                TokenSource::Message => None,
+            }?;
+            Some(RequestToken { token, source })
+        })
+    }
+}
+
+/// `Authorization: Bearer <token>`
+fn header_token(req: &HttpRequest) -> Option<String> {
+    let value = req.headers().get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then(|| token.to_string())
+}
+
+/// `?access_token=<token>` or `?token=<token>`
+fn query_token(query: &str) -> Option<String> {
+    let params = web::Query::<HashMap<String, String>>::from_query(query).ok()?;
+    QUERY_TOKEN_PARAMS
+        .iter()
+        .find_map(|name| params.get(*name).filter(|t| !t.is_empty()).cloned())
+}
+
+/// `Sec-WebSocket-Protocol: bearer, bearer.<token>`
+fn subprotocol_token(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find_map(|protocol| {
+            protocol
+                .trim()
+                .strip_prefix(BEARER_SUBPROTOCOL_PREFIX)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+        })
+}
+
+/// Replace token values in a path-and-query string so it can be logged
+pub fn scrub_query_tokens(path_and_query: &str) -> Cow<'_, str> {
+    let Some((path, query)) = path_and_query.split_once('?') else {
+        return Cow::Borrowed(path_and_query);
+    };
+
+    // Decode names the way `query_token` does, so `access%5Ftoken` is caught too
+    let is_token = |pair: &str| {
+        web::Query::<HashMap<String, String>>::from_query(pair).is_ok_and(|params| {
+            params
+                .keys()
+                .any(|name| QUERY_TOKEN_PARAMS.contains(&name.as_str()))
+        })
+    };
+    if !query.split('&').any(is_token) {
+        return Cow::Borrowed(path_and_query);
+    }
+
+    let scrubbed: Vec<Cow<'_, str>> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_token(pair) => Cow::Owned(format!("{}={}", name, REDACTED)),
+            _ => Cow::Borrowed(pair),
+        })
+        .collect();
+    Cow::Owned(format!("{}?{}", path, scrubbed.join("&")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_extract_follows_configured_order() {
+        let req = TestRequest::get()
+            .uri("/ws?access_token=from-query")
+            .insert_header((header::AUTHORIZATION, "Bearer from-header"))
+            .insert_header((
+                header::SEC_WEBSOCKET_PROTOCOL,
+                "bearer, bearer.from-protocol",
+            ))
+            .to_http_request();
+
+        let all = TokenExtractor::new(vec![
+            TokenSource::Subprotocol,
+            TokenSource::Header,
+            TokenSource::Query,
+        ]);
+        assert_eq!(
+            all.extract(&req),
+            Some(RequestToken {
+                token: "from-protocol".to_string(),
+                source: TokenSource::Subprotocol,
+            })
+        );
+
+        let query_only = TokenExtractor::new(vec![TokenSource::Query]);
+        assert_eq!(query_only.extract(&req).unwrap().token, "from-query");
+
+        let message_only = TokenExtractor::new(vec![TokenSource::Message]);
+        assert_eq!(message_only.extract(&req), None);
+    }
+
+    #[test]
+    fn test_header_requires_bearer_scheme() {
+        let req = TestRequest::get()
+            .insert_header((header::AUTHORIZATION, "Basic dXNlcjpwYXNz"))
+            .to_http_request();
+        assert_eq!(TokenExtractor::default().extract(&req), None);
+
+        let req = TestRequest::get()
+            .insert_header((header::AUTHORIZATION, "bearer abc.def.ghi"))
+            .to_http_request();
+        assert_eq!(
+            TokenExtractor::default().extract(&req).unwrap().token,
+            "abc.def.ghi"
+        );
+    }
+
+    #[test]
+    fn test_scrub_query_tokens() {
+        assert_eq!(scrub_query_tokens("/ws"), "/ws");
+        assert_eq!(scrub_query_tokens("/ws?session=abc"), "/ws?session=abc");
+        assert_eq!(
+            scrub_query_tokens("/ws?session=abc&access_token=secret"),
+            "/ws?session=abc&access_token=[REDACTED]"
+        );
+        assert_eq!(
+            scrub_query_tokens("/ws?token=secret"),
+            "/ws?token=[REDACTED]"
+        );
+    }
+
+    #[test]
+    fn test_scrub_encoded_query_token_names() {
+        let req = TestRequest::get()
+            .uri("/ws?access%5Ftoken=secret")
+            .to_http_request();
+        let extractor = TokenExtractor::new(vec![TokenSource::Query]);
+        assert_eq!(extractor.extract(&req).unwrap().token, "secret");
+
+        assert_eq!(
+            scrub_query_tokens("/ws?access%5Ftoken=secret"),
+            "/ws?access%5Ftoken=[REDACTED]"
+        );
+        assert_eq!(
+            scrub_query_tokens("/ws?session=abc&%74oken=secret"),
+            "/ws?session=abc&%74oken=[REDACTED]"
+        );
+    }
+}
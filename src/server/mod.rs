@@ -1,8 +1,13 @@
 // HTTP/WebSocket server module
 // Per spec-kit/003-backend-spec.md section 2.2
 
+#[cfg(unix)]
+pub mod control;
 pub mod http;
 pub mod middleware;
+pub mod reload;
+pub mod shutdown;
+pub mod ttyd;
 pub mod websocket;
 
 #[cfg(feature = "tls")]
@@ -10,7 +15,13 @@ pub mod tls;
 
 pub use http::Server;
 pub use middleware::{JwtAuthMiddleware, RateLimitMiddleware};
+pub use reload::{ConfigReloader, LogFilter};
+pub use shutdown::{ShutdownCoordinator, ShutdownSummary};
+pub use ttyd::TtydSession;
 pub use websocket::WebSocketSession;
 
+#[cfg(unix)]
+pub use control::{ControlClient, ControlError, ControlRequest, ControlResponse, PidFile};
+
 #[cfg(feature = "tls")]
 pub use tls::{load_tls_config, validate_tls_files, TlsConfig as TlsServerConfig};
@@ -0,0 +1,393 @@
+// Live configuration reload
+// Per spec-kit/005-cli-spec.md: Live Reload
+// Responsibilities:
+// - Re-read every configuration layer on SIGHUP or an admin request
+// - Validate the new configuration before applying any of it
+// - Apply hot-reloadable settings in place, keeping every terminal running
+// - Report changed settings that only take effect after a restart
+// - Record every reload attempt in the audit log
+
+use serde::Serialize;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+use crate::config::{Config, ConfigError, ConfigSources};
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::security::lockout::LockoutTracker;
+use crate::security::reload::{AuthReloader, ReloadError, ReloadSummary, ReloadTrigger};
+use crate::server::middleware::cors::LiveOrigins;
+use crate::server::middleware::security_headers::{
+    SecurityHeadersConfig as SecurityHeadersMiddlewareConfig, SecurityHeadersMiddleware,
+};
+use crate::session::SessionManager;
+
+/// Settings applied in place by a reload (a key and everything below it);
+/// changes to anything else take effect after a restart
+pub const HOT_RELOADABLE: &[&str] = &[
+    "logging.level",
+    "server.cors.allowed_origins",
+    "server.security_headers",
+    "session",
+    "auth.jwks",
+    "auth.authorization",
+    "auth.security.rate_limit",
+];
+
+/// Replaces the log filter of the running process with the given
+/// directives (e.g. `info,web_terminal=debug`)
+pub type LogFilter = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Reload error types
+#[derive(Error, Debug)]
+pub enum ConfigReloadError {
+    #[error(transparent)]
+    Load(#[from] ConfigError),
+
+    #[error("Invalid configuration: logging.level: {0}")]
+    LogLevel(String),
+
+    #[error("Invalid configuration: server.security_headers: {0}")]
+    SecurityHeaders(String),
+
+    #[error(transparent)]
+    Auth(#[from] ReloadError),
+}
+
+/// What a successful reload changed
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigReloadReport {
+    /// Changed settings now in effect
+    pub applied: Vec<String>,
+    /// Changed settings that take effect after a restart
+    pub restart_required: Vec<String>,
+    /// Keys set by some layer that no setting reads
+    pub unknown_keys: Vec<String>,
+    /// Authentication configuration now in effect
+    pub auth: ReloadSummary,
+}
+
+/// Re-reads the server configuration and applies it to the running server
+/// Per spec-kit/005-cli-spec.md: Live Reload
+pub struct ConfigReloader {
+    sources: ConfigSources,
+    /// Configuration in effect (also serializes reloads)
+    current: Mutex<Config>,
+    session_manager: Arc<SessionManager>,
+    lockouts: Arc<LockoutTracker>,
+    cors_origins: Arc<LiveOrigins>,
+    security_headers: SecurityHeadersMiddleware,
+    auth: Arc<AuthReloader>,
+    audit: Arc<AuditLogger>,
+    log_filter: Option<LogFilter>,
+}
+
+impl ConfigReloader {
+    /// Create a reloader for a server started with `current`, loaded from
+    /// `sources`
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        sources: ConfigSources,
+        current: Config,
+        session_manager: Arc<SessionManager>,
+        lockouts: Arc<LockoutTracker>,
+        cors_origins: Arc<LiveOrigins>,
+        security_headers: SecurityHeadersMiddleware,
+        auth: Arc<AuthReloader>,
+        audit: Arc<AuditLogger>,
+    ) -> Self {
+        Self {
+            sources,
+            current: Mutex::new(current),
+            session_manager,
+            lockouts,
+            cors_origins,
+            security_headers,
+            auth,
+            audit,
+            log_filter: None,
+        }
+    }
+
+    /// Apply `logging.level` changes through `log_filter`; without it they
+    /// are reported as needing a restart
+    pub fn with_log_filter(mut self, log_filter: LogFilter) -> Self {
+        self.log_filter = Some(log_filter);
+        self
+    }
+
+    /// Load the configuration again and apply it, or keep the running one
+    /// if anything fails to load; either outcome is audited
+    pub async fn reload(
+        &self,
+        trigger: ReloadTrigger,
+    ) -> Result<ConfigReloadReport, ConfigReloadError> {
+        let result = self.apply().await;
+
+        match &result {
+            Ok(report) => {
+                for key in &report.restart_required {
+                    tracing::warn!(key = %key, "Configuration change takes effect after a restart");
+                }
+                tracing::info!(
+                    trigger = trigger.as_str(),
+                    applied = report.applied.len(),
+                    restart_required = report.restart_required.len(),
+                    "Configuration reloaded"
+                );
+                self.audit
+                    .record(AuditEvent::new(AuditAction::ConfigReloaded).detail(format!(
+                        "{}: applied [{}], restart required [{}]",
+                        trigger.as_str(),
+                        report.applied.join(", "),
+                        report.restart_required.join(", ")
+                    )));
+            }
+            Err(e) => {
+                tracing::error!(
+                    trigger = trigger.as_str(),
+                    error = %e,
+                    "Configuration reload failed, keeping previous configuration"
+                );
+                self.audit.record(
+                    AuditEvent::new(AuditAction::ConfigReloadFailed).detail(format!(
+                        "{}: {}",
+                        trigger.as_str(),
+                        e
+                    )),
+                );
+            }
+        }
+
+        result
+    }
+
+    /// Validate everything that can fail first, then replace the live
+    /// settings one after another
+    async fn apply(&self) -> Result<ConfigReloadReport, ConfigReloadError> {
+        let mut current = self.current.lock().await;
+        let loaded = Config::load(&self.sources)?;
+        let next = loaded.config;
+
+        let changed = changed_keys(
+            &serde_json::to_value(&*current).map_err(ConfigError::from)?,
+            &serde_json::to_value(&next).map_err(ConfigError::from)?,
+        );
+        let level_changed = next.logging.level != current.logging.level;
+        if level_changed {
+            tracing_subscriber::EnvFilter::try_new(&next.logging.level)
+                .map_err(|e| ConfigReloadError::LogLevel(e.to_string()))?;
+        }
+        let security_headers = SecurityHeadersMiddlewareConfig::from(&next.server.security_headers);
+        security_headers
+            .validate()
+            .map_err(ConfigReloadError::SecurityHeaders)?;
+
+        // Authentication last of the checks: it swaps in place once valid
+        let auth = self.auth.apply(next.auth.clone()).await?;
+
+        if level_changed {
+            if let Some(log_filter) = &self.log_filter {
+                if let Err(e) = log_filter(&next.logging.level) {
+                    tracing::warn!(error = %e, "Log filter not replaced");
+                }
+            }
+        }
+        self.security_headers
+            .replace(security_headers)
+            .map_err(ConfigReloadError::SecurityHeaders)?;
+        self.cors_origins
+            .replace(next.server.cors.allowed_origins.clone());
+        self.session_manager.replace_config(next.session.clone());
+        self.lockouts
+            .replace_config(next.auth.security.rate_limit.clone());
+
+        let (applied, restart_required) = changed
+            .into_iter()
+            .partition(|key| self.hot_reloadable(key));
+        *current = next;
+
+        Ok(ConfigReloadReport {
+            applied,
+            restart_required,
+            unknown_keys: loaded.unknown_keys,
+            auth,
+        })
+    }
+
+    fn hot_reloadable(&self, key: &str) -> bool {
+        if key == "logging.level" && self.log_filter.is_none() {
+            return false;
+        }
+        HOT_RELOADABLE.iter().any(|prefix| {
+            key.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+    }
+
+    /// Reload on SIGHUP, if enabled
+    pub fn start(self: Arc<Self>) {
+        #[cfg(unix)]
+        tokio::spawn(async move {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            if !self.current.lock().await.auth.reload.on_sighup {
+                return;
+            }
+            let mut hangups = match signal(SignalKind::hangup()) {
+                Ok(hangups) => hangups,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Cannot listen for SIGHUP, reload disabled");
+                    return;
+                }
+            };
+            while hangups.recv().await.is_some() {
+                let _ = self.reload(ReloadTrigger::Signal).await;
+            }
+        });
+    }
+}
+
+/// Dotted paths of the settings that differ between `old` and `new`
+fn changed_keys(old: &serde_json::Value, new: &serde_json::Value) -> Vec<String> {
+    let mut changed = Vec::new();
+    collect_changes("", old, new, &mut changed);
+    changed
+}
+
+fn collect_changes(
+    prefix: &str,
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+    changed: &mut Vec<String>,
+) {
+    let (Some(old_table), Some(new_table)) = (old.as_object(), new.as_object()) else {
+        if old != new {
+            changed.push(prefix.to_string());
+        }
+        return;
+    };
+
+    let mut keys: Vec<&String> = old_table.keys().chain(new_table.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match (old_table.get(key), new_table.get(key)) {
+            (Some(old), Some(new)) => collect_changes(&path, old, new, changed),
+            _ => changed.push(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::admission::AdmissionPolicy;
+    use crate::security::authorization::AuthorizationService;
+    use crate::security::jwks_client::JwksClient;
+    use crate::security::jwt_validator::JwtValidator;
+    use crate::session::UserId;
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    fn reloader(file: &Path) -> (ConfigReloader, Arc<SessionManager>, Arc<LiveOrigins>) {
+        let sources = ConfigSources::default()
+            .with_file(file)
+            .with_env(HashMap::new());
+        let config = Config::load(&sources).unwrap().config;
+        let jwks_client = Arc::new(JwksClient::new(config.auth.clone()));
+        let auth = Arc::new(AuthReloader::new(
+            config.auth.clone(),
+            Arc::new(JwtValidator::new(jwks_client.clone(), config.auth.clone())),
+            jwks_client,
+            Arc::new(AdmissionPolicy::new(&config.auth.authorization)),
+            Arc::new(AuthorizationService::from_config(&config.auth).unwrap()),
+            Arc::new(AuditLogger::default()),
+        ));
+        let session_manager = Arc::new(SessionManager::new(config.session.clone()));
+        let origins = Arc::new(LiveOrigins::new(config.server.cors.allowed_origins.clone()));
+
+        let reloader = ConfigReloader::new(
+            sources,
+            config.clone(),
+            session_manager.clone(),
+            Arc::new(LockoutTracker::new(config.auth.security.rate_limit.clone())),
+            origins.clone(),
+            SecurityHeadersMiddleware::default(),
+            auth,
+            Arc::new(AuditLogger::default()),
+        );
+        (reloader, session_manager, origins)
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_and_reports_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("config.toml");
+        std::fs::write(&file, "[session]\nmax_sessions_per_user = 5\n").unwrap();
+        let (reloader, sessions, origins) = reloader(&file);
+
+        std::fs::write(
+            &file,
+            "[server]\nport = 9000\n\n[server.cors]\nallowed_origins = [\"https://a.example.com\"]\n\n\
+             [session]\nmax_sessions_per_user = 1\n",
+        )
+        .unwrap();
+        let report = reloader.reload(ReloadTrigger::Manual).await.unwrap();
+
+        assert_eq!(
+            report.applied,
+            vec![
+                "server.cors.allowed_origins",
+                "session.max_sessions_per_user"
+            ]
+        );
+        assert_eq!(report.restart_required, vec!["server.port"]);
+        assert!(origins.allows("https://a.example.com"));
+        assert!(!origins.allows("https://b.example.com"));
+
+        // New limits hold for new sessions
+        let user = UserId::new("alice".to_string());
+        sessions.create_session(user.clone()).await.unwrap();
+        assert!(sessions.create_session(user).await.is_err());
+
+        // Nothing changed, nothing reported
+        let report = reloader.reload(ReloadTrigger::Signal).await.unwrap();
+        assert!(report.applied.is_empty() && report.restart_required.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_reload_keeps_running_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("config.toml");
+        std::fs::write(&file, "").unwrap();
+        let (reloader, _, origins) = reloader(&file);
+
+        // Nothing is applied when any part is invalid
+        std::fs::write(
+            &file,
+            "[server.cors]\nallowed_origins = [\"https://a.example.com\"]\n\n\
+             [logging]\nlevel = \"web_terminal=loud\"\n",
+        )
+        .unwrap();
+        let result = reloader.reload(ReloadTrigger::Manual).await;
+        assert!(matches!(result, Err(ConfigReloadError::LogLevel(_))));
+        assert!(origins.allows("https://b.example.com"));
+
+        std::fs::write(&file, "[session]\ntimeout = \"soon\"\n").unwrap();
+        let result = reloader.reload(ReloadTrigger::Manual).await;
+        assert!(matches!(result, Err(ConfigReloadError::Load(_))));
+    }
+
+    #[test]
+    fn test_changed_keys() {
+        let old = serde_json::json!({"a": {"b": 1, "c": [1]}, "d": null});
+        let new = serde_json::json!({"a": {"b": 1, "c": [2]}, "e": true});
+        assert_eq!(changed_keys(&old, &new), vec!["a.c", "d", "e"]);
+    }
+}
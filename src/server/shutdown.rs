@@ -0,0 +1,511 @@
+// Graceful shutdown
+// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+// Responsibilities:
+// - Turn SIGTERM/SIGINT (or a stop request) into a coordinated drain
+// - Refuse new sessions while draining
+// - Warn attached clients with a countdown until the deadline
+// - Hang up the terminals still running at the deadline, then kill them
+// - Flush the audit log and report what the drain did
+// - Tell the caller whether to start again (restart)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, watch};
+
+use crate::config::ShutdownConfig;
+use crate::protocol::ServerMessage;
+use crate::pty::PtyManager;
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::session::SessionManager;
+
+/// Seconds remaining at which attached clients are warned again
+pub const NOTICE_AT: &[u64] = &[300, 120, 60, 30, 10, 5];
+
+/// How often the drain checks whether every client has gone
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A request to shut down by `deadline`
+#[derive(Debug, Clone)]
+pub struct ShutdownRequest {
+    pub reason: String,
+    pub deadline: Instant,
+    /// Start the server again once drained
+    pub restart: bool,
+}
+
+/// Countdown warning sent to every attached client
+#[derive(Debug, Clone)]
+pub struct ShutdownNotice {
+    pub reason: String,
+    pub deadline: DateTime<Utc>,
+    pub seconds_remaining: u64,
+}
+
+impl ShutdownNotice {
+    /// The last notice, sent just before connections are closed
+    pub fn is_final(&self) -> bool {
+        self.seconds_remaining == 0
+    }
+
+    pub fn to_message(&self) -> ServerMessage {
+        ServerMessage::ServerShutdown {
+            reason: self.reason.clone(),
+            deadline: self.deadline.timestamp(),
+            seconds_remaining: self.seconds_remaining,
+        }
+    }
+}
+
+/// What a drain did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShutdownSummary {
+    pub reason: String,
+    pub duration_ms: u64,
+    /// Sessions open when the drain began
+    pub sessions: usize,
+    /// Clients attached when the drain began
+    pub clients_notified: usize,
+    /// Clients still attached at the deadline
+    pub clients_remaining: usize,
+    /// Terminals sent SIGHUP
+    pub hung_up: usize,
+    /// Terminals killed
+    pub killed: usize,
+    /// Whether the deadline was cut short by a forced stop
+    pub forced: bool,
+    /// Whether the server starts again
+    #[serde(default)]
+    pub restart: bool,
+}
+
+impl std::fmt::Display for ShutdownSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} after {:.1}s: {} session(s), {} of {} client(s) detached, {} terminal(s) hung up, {} killed{}",
+            self.reason,
+            self.duration_ms as f64 / 1000.0,
+            self.sessions,
+            self.clients_notified.saturating_sub(self.clients_remaining),
+            self.clients_notified,
+            self.hung_up,
+            self.killed,
+            if self.forced { " (forced)" } else { "" }
+        )?;
+        if self.restart {
+            write!(f, ", restarting")?;
+        }
+        Ok(())
+    }
+}
+
+/// Coordinates the drain of sessions and clients before the server stops
+/// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+pub struct ShutdownCoordinator {
+    config: ShutdownConfig,
+    session_manager: Arc<SessionManager>,
+    pty_manager: Arc<PtyManager>,
+    audit: Arc<AuditLogger>,
+    requests: watch::Sender<Option<ShutdownRequest>>,
+    /// One receiver per attached client
+    notices: broadcast::Sender<ShutdownNotice>,
+    /// Latest notice, for clients connecting mid-drain
+    current: Mutex<Option<ShutdownNotice>>,
+    /// Set once the drain is done
+    finished: watch::Sender<Option<ShutdownSummary>>,
+}
+
+impl ShutdownCoordinator {
+    pub fn new(
+        config: ShutdownConfig,
+        session_manager: Arc<SessionManager>,
+        pty_manager: Arc<PtyManager>,
+        audit: Arc<AuditLogger>,
+    ) -> Self {
+        let (requests, _) = watch::channel(None);
+        let (notices, _) = broadcast::channel(16);
+        let (finished, _) = watch::channel(None);
+        Self {
+            config,
+            session_manager,
+            pty_manager,
+            audit,
+            requests,
+            notices,
+            current: Mutex::new(None),
+            finished,
+        }
+    }
+
+    /// Shut down within `timeout`; a request made while already draining
+    /// can only bring the deadline forward
+    pub fn request(&self, timeout: Duration, reason: impl Into<String>) {
+        self.submit(timeout, reason.into(), false);
+    }
+
+    /// Drain within `timeout` (default the configured timeout), then start
+    /// again
+    /// Per spec-kit/005-cli-spec.md: Process Management
+    pub fn request_restart(&self, timeout: Option<Duration>) {
+        let timeout = timeout.unwrap_or(self.config.timeout);
+        self.submit(timeout, "restart".to_string(), true);
+    }
+
+    fn submit(&self, timeout: Duration, reason: String, restart: bool) {
+        let request = ShutdownRequest {
+            reason,
+            deadline: Instant::now() + timeout,
+            restart,
+        };
+        self.requests.send_if_modified(|current| match current {
+            Some(current) if current.deadline <= request.deadline => false,
+            _ => {
+                tracing::info!(
+                    "Shutdown requested ({}), draining for up to {}s",
+                    request.reason,
+                    timeout.as_secs()
+                );
+                *current = Some(request);
+                true
+            }
+        });
+    }
+
+    /// Shut down within the configured timeout
+    pub fn request_graceful(&self, reason: impl Into<String>) {
+        self.request(self.config.timeout, reason);
+    }
+
+    /// How long hung-up terminals get before they are killed
+    pub fn kill_after(&self) -> Duration {
+        self.config.kill_after
+    }
+
+    /// Whether shutdown has been requested
+    pub fn is_requested(&self) -> bool {
+        self.requests.borrow().is_some()
+    }
+
+    /// Time left until the deadline, once shutdown is requested
+    pub fn time_remaining(&self) -> Option<Duration> {
+        self.requests
+            .borrow()
+            .as_ref()
+            .map(|request| request.deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Resolves once shutdown is requested
+    pub async fn requested(&self) {
+        let mut requests = self.requests.subscribe();
+        let _ = requests.wait_for(Option::is_some).await;
+    }
+
+    /// Resolves with the summary once the drain is done
+    pub async fn finished(&self) -> ShutdownSummary {
+        let mut finished = self.finished.subscribe();
+        let summary = match finished.wait_for(Option::is_some).await {
+            Ok(summary) => summary.clone().unwrap_or_default(),
+            Err(_) => ShutdownSummary::default(),
+        };
+        summary
+    }
+
+    /// Receive countdown notices, with the latest one if already draining
+    pub fn subscribe(&self) -> (broadcast::Receiver<ShutdownNotice>, Option<ShutdownNotice>) {
+        let rx = self.notices.subscribe();
+        let current = self.current.lock().expect("shutdown lock poisoned").clone();
+        (rx, current)
+    }
+
+    /// Connections currently subscribed to notices
+    pub fn attached_clients(&self) -> usize {
+        self.notices.receiver_count()
+    }
+
+    /// Drain on SIGTERM or SIGINT; a second signal stops at once
+    pub fn start(self: Arc<Self>) {
+        tokio::spawn(async move {
+            #[cfg(unix)]
+            {
+                use tokio::signal::unix::{signal, SignalKind};
+
+                let (mut terms, mut ints) = match (
+                    signal(SignalKind::terminate()),
+                    signal(SignalKind::interrupt()),
+                ) {
+                    (Ok(terms), Ok(ints)) => (terms, ints),
+                    (Err(e), _) | (_, Err(e)) => {
+                        tracing::error!(error = %e, "Cannot listen for shutdown signals");
+                        return;
+                    }
+                };
+                loop {
+                    let name = tokio::select! {
+                        _ = terms.recv() => "SIGTERM",
+                        _ = ints.recv() => "SIGINT",
+                    };
+                    self.on_signal(name);
+                }
+            }
+
+            #[cfg(not(unix))]
+            while tokio::signal::ctrl_c().await.is_ok() {
+                self.on_signal("Ctrl-C");
+            }
+        });
+    }
+
+    fn on_signal(&self, name: &str) {
+        if self.is_requested() {
+            self.request(Duration::ZERO, format!("{} (forced)", name));
+        } else {
+            self.request_graceful(name);
+        }
+    }
+
+    /// Refuse new sessions, warn attached clients until they have all gone
+    /// or the deadline passes, then hang up and kill the remaining terminals
+    /// and flush the audit log
+    pub async fn drain(&self) -> ShutdownSummary {
+        let started = Instant::now();
+        let mut requests = self.requests.subscribe();
+        let mut request = requests
+            .borrow_and_update()
+            .clone()
+            .unwrap_or_else(|| ShutdownRequest {
+                reason: "shutdown".to_string(),
+                deadline: started,
+                restart: false,
+            });
+
+        self.session_manager.start_draining();
+        let mut summary = ShutdownSummary {
+            reason: request.reason.clone(),
+            sessions: self.session_manager.session_count(),
+            clients_notified: self.attached_clients(),
+            forced: request.deadline <= started,
+            ..Default::default()
+        };
+
+        let mut last_notice: Option<u64> = None;
+        loop {
+            let remaining = request.deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || self.attached_clients() == 0 {
+                break;
+            }
+
+            let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+            let due = match last_notice {
+                None => true,
+                Some(last) => NOTICE_AT.iter().any(|&at| seconds <= at && at < last),
+            };
+            if due {
+                self.notify(&request, seconds);
+                last_notice = Some(seconds);
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(remaining.min(POLL_INTERVAL)) => {}
+                changed = requests.changed() => {
+                    if changed.is_ok() {
+                        if let Some(next) = requests.borrow_and_update().clone() {
+                            request = next;
+                            summary.reason = request.reason.clone();
+                            summary.forced = true;
+                            last_notice = None;
+                        }
+                    }
+                }
+            }
+        }
+
+        summary.restart = request.restart;
+        summary.clients_remaining = self.attached_clients();
+        self.notify(&request, 0);
+
+        let (hung_up, killed) = self.stop_terminals(summary.forced).await;
+        summary.hung_up = hung_up;
+        summary.killed = killed;
+        summary.duration_ms = started.elapsed().as_millis() as u64;
+
+        self.audit
+            .record(AuditEvent::new(AuditAction::ServerShutdown).detail(summary.to_string()));
+        if let Err(e) = self.audit.flush() {
+            tracing::error!("Failed to flush audit log: {}", e);
+        }
+
+        tracing::info!("Shutdown complete: {}", summary);
+        self.finished.send_replace(Some(summary.clone()));
+        summary
+    }
+
+    fn notify(&self, request: &ShutdownRequest, seconds_remaining: u64) {
+        let remaining = request.deadline.saturating_duration_since(Instant::now());
+        let notice = ShutdownNotice {
+            reason: request.reason.clone(),
+            deadline: Utc::now()
+                + chrono::Duration::from_std(remaining)
+                    .unwrap_or_else(|_| chrono::Duration::zero()),
+            seconds_remaining,
+        };
+        *self.current.lock().expect("shutdown lock poisoned") = Some(notice.clone());
+        // No receivers just means no client is attached
+        let _ = self.notices.send(notice);
+    }
+
+    /// SIGHUP every terminal and kill those still running after
+    /// `kill_after` (at once when forced); returns (hung up, killed)
+    async fn stop_terminals(&self, forced: bool) -> (usize, usize) {
+        let hung_up = if forced {
+            0
+        } else {
+            let hung_up = self.pty_manager.hangup_all().await.len();
+            let deadline = Instant::now() + self.config.kill_after;
+            while Instant::now() < deadline && self.running_terminals().await > 0 {
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+            hung_up
+        };
+
+        let mut killed = 0;
+        for id in self.pty_manager.list() {
+            if self.pty_manager.is_alive(&id).await {
+                match self.pty_manager.kill(&id).await {
+                    Ok(()) => killed += 1,
+                    Err(e) => tracing::error!("Failed to kill PTY process {}: {}", id, e),
+                }
+            }
+        }
+        (hung_up, killed)
+    }
+
+    async fn running_terminals(&self) -> usize {
+        let mut running = 0;
+        for id in self.pty_manager.list() {
+            if self.pty_manager.is_alive(&id).await {
+                running += 1;
+            }
+        }
+        running
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::auth::AuditConfig;
+    use crate::session::{SessionConfig, UserId};
+
+    fn coordinator(timeout: Duration) -> ShutdownCoordinator {
+        ShutdownCoordinator::new(
+            ShutdownConfig {
+                timeout,
+                kill_after: Duration::from_secs(5),
+            },
+            Arc::new(SessionManager::new(SessionConfig::default())),
+            Arc::new(PtyManager::with_defaults()),
+            Arc::new(AuditLogger::new(AuditConfig::default())),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_drain_without_clients_finishes_at_once() {
+        let shutdown = coordinator(Duration::from_secs(30));
+        shutdown
+            .session_manager
+            .create_session(UserId::new("alice".to_string()))
+            .await
+            .unwrap();
+
+        shutdown.request_graceful("SIGTERM");
+        let summary = tokio::time::timeout(Duration::from_secs(5), shutdown.drain())
+            .await
+            .unwrap();
+
+        assert_eq!(summary.reason, "SIGTERM");
+        assert_eq!(summary.sessions, 1);
+        assert_eq!(summary.clients_notified, 0);
+        assert!(!summary.forced);
+        assert!(shutdown.session_manager.is_draining());
+    }
+
+    #[tokio::test]
+    async fn test_restart_request_is_reported_when_finished() {
+        let shutdown = Arc::new(coordinator(Duration::from_secs(30)));
+        let finished = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.finished().await }
+        });
+
+        shutdown.request_restart(Some(Duration::from_secs(10)));
+        let summary = shutdown.drain().await;
+        assert!(summary.restart);
+        assert_eq!(summary.reason, "restart");
+
+        let finished = tokio::time::timeout(Duration::from_secs(5), finished)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(finished.restart);
+    }
+
+    #[tokio::test]
+    async fn test_attached_client_gets_countdown_until_it_detaches() {
+        let shutdown = Arc::new(coordinator(Duration::from_secs(30)));
+        let (mut rx, current) = shutdown.subscribe();
+        assert!(current.is_none());
+
+        shutdown.request_graceful("SIGTERM");
+        let drain = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.drain().await }
+        });
+
+        let notice = rx.recv().await.unwrap();
+        assert_eq!(notice.seconds_remaining, 30);
+        assert!(!notice.is_final());
+        assert!(matches!(
+            notice.to_message(),
+            ServerMessage::ServerShutdown {
+                seconds_remaining: 30,
+                ..
+            }
+        ));
+        assert_eq!(shutdown.subscribe().1.unwrap().seconds_remaining, 30);
+
+        drop(rx);
+        let summary = tokio::time::timeout(Duration::from_secs(5), drain)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.clients_notified, 1);
+        assert_eq!(summary.clients_remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn test_second_request_cuts_the_deadline_short() {
+        let shutdown = Arc::new(coordinator(Duration::from_secs(30)));
+        let (mut rx, _) = shutdown.subscribe();
+
+        shutdown.request_graceful("SIGTERM");
+        shutdown.request(Duration::from_secs(60), "later");
+        let drain = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.drain().await }
+        });
+        assert_eq!(rx.recv().await.unwrap().seconds_remaining, 30);
+
+        shutdown.request(Duration::ZERO, "SIGTERM (forced)");
+        let summary = tokio::time::timeout(Duration::from_secs(5), drain)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(summary.forced);
+        assert!(!summary.restart);
+        assert_eq!(summary.reason, "SIGTERM (forced)");
+        assert_eq!(summary.clients_remaining, 1);
+        assert!(rx.recv().await.unwrap().is_final());
+    }
+}
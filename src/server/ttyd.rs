@@ -0,0 +1,673 @@
+// ttyd-compatible WebSocket handler
+// Per spec-kit/007-websocket-spec.md: Alternative client protocols
+// Per spec-kit/011-authentication-spec.md: WebSocket authentication
+//
+// Speaks the ttyd single-byte-prefix protocol (see protocol::ttyd) on top of
+// the same PtyManager, SessionManager and JWT validation used by /ws.
+
+use actix::{
+    Actor, ActorContext, ActorFutureExt, AsyncContext, Handler, StreamHandler, WrapFuture,
+};
+use actix_web_actors::ws;
+use std::collections::VecDeque;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::config::LatencyConfig;
+use crate::monitoring::{metrics, LatencyTracker};
+use crate::protocol::ttyd::{TtydClientMessage, TtydHandshake, TtydServerMessage};
+use crate::protocol::ClientMessage;
+use crate::pty::{PtyConfig, PtyManager};
+use crate::security::admission::AdmissionPolicy;
+use crate::security::api_keys::ApiKeyStore;
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::security::authorization::{AuthorizationService, Permission};
+use crate::security::jwt_validator::JwtValidator;
+use crate::security::lockout::LockoutTracker;
+use crate::security::revocation::{RevocationError, RevocationList};
+use crate::security::tenancy::TenantResolver;
+use crate::server::middleware::auth::{CredentialVerifier, UserContext};
+use crate::server::shutdown::ShutdownCoordinator;
+use crate::server::websocket::{
+    forward_pty_output, forward_shutdown_notices, reap_detached_session, record_heartbeat_rtt,
+    token_time_remaining, PtyClosed, PtyOutput, ShutdownWarning, CLIENT_TIMEOUT,
+    HEARTBEAT_INTERVAL, MAX_MESSAGE_SIZE,
+};
+use crate::session::{SessionId, SessionManager};
+
+/// Maximum output buffered while the client has paused the stream: 1 MB
+/// Per spec-kit/007-websocket-spec.md: Flow control and backpressure
+const MAX_PAUSED_OUTPUT: usize = 1024 * 1024;
+
+/// ttyd-compatible WebSocket session actor
+///
+/// Per FR-3.3: Real-time streaming via WebSocket
+/// Per spec-kit/011-authentication-spec.md: Authentication required before processing
+pub struct TtydSession {
+    /// Session manager
+    session_manager: Arc<SessionManager>,
+    /// PTY manager (shared so sessions can be attached from several connections)
+    pty_manager: Arc<PtyManager>,
+    /// Verifier for the handshake AuthToken (JWT or API key)
+    credentials: CredentialVerifier,
+    /// Client address, checked against API key address bindings
+    client_ip: Option<IpAddr>,
+    /// Session created after a successful handshake
+    session_id: Option<SessionId>,
+    /// PTY process ID
+    pty_id: Option<String>,
+    /// User context from authenticated JWT
+    user_context: Option<UserContext>,
+    /// Handshake received (validation may still be in flight)
+    handshake_received: bool,
+    /// Last heartbeat timestamp
+    last_heartbeat: Instant,
+    /// Client requested a pause of the output stream
+    paused: bool,
+    /// Output held back while paused
+    paused_output: VecDeque<Vec<u8>>,
+    /// Bytes currently held in `paused_output`
+    paused_bytes: usize,
+    /// Heartbeat round-trip latency
+    latency: LatencyTracker,
+    /// Security audit log
+    audit: Arc<AuditLogger>,
+    /// User authenticated from the upgrade request (handshake token ignored)
+    upgrade_user: Option<UserContext>,
+    /// Whether the handshake AuthToken is accepted
+    message_auth: bool,
+    /// Role permissions checked for session creation and input
+    authz: Arc<AuthorizationService>,
+    /// Revoked tokens and subjects, rechecked on every heartbeat
+    revocations: Option<Arc<RevocationList>>,
+    /// Shutdown countdown written to the terminal
+    shutdown: Option<Arc<ShutdownCoordinator>>,
+}
+
+impl TtydSession {
+    /// Create a new ttyd session
+    /// Per spec-kit/011-authentication-spec.md: Authentication required
+    pub fn new(
+        session_manager: Arc<SessionManager>,
+        pty_manager: Arc<PtyManager>,
+        jwt_validator: Arc<JwtValidator>,
+    ) -> Self {
+        Self {
+            session_manager,
+            pty_manager,
+            credentials: CredentialVerifier::new(jwt_validator),
+            client_ip: None,
+            session_id: None,
+            pty_id: None,
+            user_context: None,
+            handshake_received: false,
+            last_heartbeat: Instant::now(),
+            paused: false,
+            paused_output: VecDeque::new(),
+            paused_bytes: 0,
+            latency: LatencyTracker::new(LatencyConfig::default()),
+            audit: Arc::new(AuditLogger::default()),
+            upgrade_user: None,
+            message_auth: true,
+            authz: Arc::new(AuthorizationService::with_defaults()),
+            revocations: None,
+            shutdown: None,
+        }
+    }
+
+    /// Set the thresholds used to flag sustained high latency
+    /// Per spec-kit/007-websocket-spec.md: Heartbeat mechanism
+    pub fn with_latency(mut self, latency: LatencyConfig) -> Self {
+        self.latency = LatencyTracker::new(latency);
+        self
+    }
+
+    /// Set the audit logger
+    /// Per spec-kit/011-authentication-spec.md: Audit logging
+    pub fn with_audit(mut self, audit: Arc<AuditLogger>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Skip handshake token validation: the upgrade request carried a token
+    /// Per spec-kit/011-authentication-spec.md section 7: Token sources
+    pub fn with_authenticated_user(mut self, user_context: UserContext) -> Self {
+        self.upgrade_user = Some(user_context);
+        self
+    }
+
+    /// Set whether the handshake AuthToken is accepted
+    /// Per spec-kit/011-authentication-spec.md section 7: Token sources
+    pub fn with_message_auth(mut self, enabled: bool) -> Self {
+        self.message_auth = enabled;
+        self
+    }
+
+    /// Accept API keys from `store` as the handshake AuthToken
+    /// Per spec-kit/011-authentication-spec.md: API Keys
+    pub fn with_api_keys(mut self, store: Arc<ApiKeyStore>) -> Self {
+        self.credentials = self.credentials.with_api_keys(store);
+        self
+    }
+
+    /// Check session actions against `authz` (and resolve roles with it)
+    /// Per spec-kit/011-authentication-spec.md section 5: Authorization Model
+    pub fn with_authorization(mut self, authz: Arc<AuthorizationService>) -> Self {
+        self.credentials = self.credentials.with_authorization(authz.clone());
+        self.authz = authz;
+        self
+    }
+
+    /// Only admit identities allowed by `policy`
+    /// Per spec-kit/011-authentication-spec.md: Authorization Model
+    pub fn with_admission(mut self, policy: Arc<AdmissionPolicy>) -> Self {
+        self.credentials = self.credentials.with_admission(policy);
+        self
+    }
+
+    /// Set the client address checked against API key address bindings
+    pub fn with_client_ip(mut self, client_ip: Option<IpAddr>) -> Self {
+        self.client_ip = client_ip;
+        self
+    }
+
+    /// Refuse revoked credentials and close the connection once its token
+    /// or subject is revoked; handshake tickets are single-use
+    /// Per spec-kit/011-authentication-spec.md: Token Revocation
+    pub fn with_revocations(mut self, list: Arc<RevocationList>) -> Self {
+        self.credentials = self
+            .credentials
+            .with_revocations(list.clone())
+            .with_single_use_tickets();
+        self.revocations = Some(list);
+        self
+    }
+
+    /// Count failed authentications in `tracker` and refuse locked out
+    /// clients
+    /// Per spec-kit/011-authentication-spec.md: Brute-Force Protection
+    pub fn with_lockouts(mut self, tracker: Arc<LockoutTracker>) -> Self {
+        self.credentials = self.credentials.with_lockouts(tracker);
+        self
+    }
+
+    /// Place authenticated users in their tenant
+    /// Per spec-kit/011-authentication-spec.md: Multi-Tenancy
+    pub fn with_tenancy(mut self, tenants: Arc<TenantResolver>) -> Self {
+        self.credentials = self.credentials.with_tenancy(tenants);
+        self
+    }
+
+    /// Write shutdown warnings to the terminal (ttyd has no control
+    /// message for them), and close the connection at the deadline
+    /// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+    pub fn with_shutdown(mut self, shutdown: Arc<ShutdownCoordinator>) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Close the connection if the authenticated token has been revoked
+    /// Per spec-kit/011-authentication-spec.md: Token Revocation
+    fn close_if_revoked(&mut self, ctx: &mut ws::WebsocketContext<Self>) -> bool {
+        let (Some(list), Some(user)) = (&self.revocations, &self.user_context) else {
+            return false;
+        };
+        let Err(RevocationError::Revoked(detail)) = list.check(&user.claims, user.tenant_name())
+        else {
+            return false;
+        };
+
+        tracing::info!("Closing ttyd session for {}: {}", user.user_id, detail);
+        let mut event = AuditEvent::new(AuditAction::ConnectionRevoked)
+            .user(user.user_id.as_str())
+            .detail(detail);
+        if let Some(session_id) = &self.session_id {
+            event = event.session(session_id);
+        }
+        self.audit.record(event);
+        ctx.close(Some(ws::CloseCode::Policy.into()));
+        ctx.stop();
+        true
+    }
+
+    /// Handle the ttyd JSON handshake: authenticate, create session, spawn PTY
+    /// Per spec-kit/011-authentication-spec.md: WebSocket authentication flow
+    fn handle_handshake(&mut self, handshake: TtydHandshake, ctx: &mut ws::WebsocketContext<Self>) {
+        if self.handshake_received {
+            tracing::warn!("Duplicate ttyd handshake ignored");
+            return;
+        }
+        self.handshake_received = true;
+
+        let size = ClientMessage::Resize {
+            cols: handshake.columns,
+            rows: handshake.rows,
+        };
+        if let Err(e) = size.validate() {
+            tracing::warn!("ttyd handshake rejected: {}", e);
+            ctx.close(Some(ws::CloseCode::Invalid.into()));
+            ctx.stop();
+            return;
+        }
+
+        let credentials = self.credentials.clone();
+        let client_ip = self.client_ip;
+        let session_manager = self.session_manager.clone();
+        let token = handshake.auth_token;
+        let audit = self.audit.clone();
+        let upgrade_user = self.upgrade_user.take();
+        let message_auth = self.message_auth;
+        let authz = self.authz.clone();
+
+        ctx.spawn(
+            async move {
+                let user_context = match upgrade_user {
+                    Some(user_context) => user_context,
+                    None if !message_auth => {
+                        return Err("Handshake AuthToken is not accepted".to_string());
+                    }
+                    None => credentials.verify(&token, client_ip).await.map_err(|e| {
+                        audit.record(e.audit_event());
+                        format!("Authentication failed: {}", e)
+                    })?,
+                };
+                user_context
+                    .authorize(&authz, Permission::CreateSession, None)
+                    .map_err(|e| e.to_string())?;
+                let session = session_manager
+                    .create_tenant_session(
+                        user_context.user_id.clone(),
+                        user_context.tenant_name(),
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let base = PtyConfig {
+                    cols: handshake.columns,
+                    rows: handshake.rows,
+                    ..PtyConfig::default()
+                };
+                let pty_config = session_manager
+                    .pty_config(&session, &base)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok::<_, String>((user_context, session, pty_config))
+            }
+            .into_actor(self)
+            .map(move |result, actor, ctx| match result {
+                Ok((user_context, session, pty_config)) => {
+                    tracing::info!(
+                        "ttyd client authenticated: user={}, session={}",
+                        user_context.user_id,
+                        session.id
+                    );
+                    actor.audit.record(
+                        AuditEvent::new(AuditAction::AuthSuccess)
+                            .user(user_context.user_id.as_str())
+                            .session(&session.id),
+                    );
+                    actor.audit.record(
+                        AuditEvent::new(AuditAction::SessionCreated)
+                            .user(user_context.user_id.as_str())
+                            .session(&session.id),
+                    );
+
+                    // ttyd has no in-band re-authentication: close when the token lapses
+                    let exp = user_context.claims.exp;
+                    ctx.run_later(token_time_remaining(exp), |act, ctx| {
+                        if let (Some(user), Some(session_id)) = (&act.user_context, &act.session_id)
+                        {
+                            tracing::warn!("ttyd token for {} expired", user.user_id);
+                            act.audit.record(
+                                AuditEvent::new(AuditAction::TokenExpired)
+                                    .user(user.user_id.as_str())
+                                    .session(session_id)
+                                    .detail("connection closed"),
+                            );
+                        }
+                        ctx.close(Some(ws::CloseCode::Policy.into()));
+                        ctx.stop();
+                    });
+
+                    actor.session_id = Some(session.id.clone());
+                    actor.user_context = Some(user_context);
+                    actor.start_pty(pty_config, ctx);
+                }
+                Err(e) => {
+                    tracing::warn!("ttyd handshake failed: {}", e);
+                    ctx.close(Some(ws::CloseCode::Policy.into()));
+                    ctx.stop();
+                }
+            }),
+        );
+    }
+
+    /// Spawn the PTY and start forwarding its output to the client
+    /// Per FR-1.2.1: Start processes for executed commands
+    fn start_pty(&mut self, config: PtyConfig, ctx: &mut ws::WebsocketContext<Self>) {
+        let handle = match self.pty_manager.spawn(Some(config)) {
+            Ok(handle) => handle,
+            Err(e) => {
+                tracing::error!("Failed to spawn PTY for ttyd client: {}", e);
+                ctx.close(Some(ws::CloseCode::Error.into()));
+                ctx.stop();
+                return;
+            }
+        };
+        let pty_id = handle.id().to_string();
+
+        let subscription = match self.pty_manager.subscribe(&pty_id) {
+            Ok(subscription) => subscription,
+            Err(e) => {
+                tracing::error!("Failed to attach to PTY output: {}", e);
+                ctx.close(Some(ws::CloseCode::Error.into()));
+                ctx.stop();
+                return;
+            }
+        };
+
+        if let Some(session_id) = self.session_id.clone() {
+            let session_manager = self.session_manager.clone();
+            let pty_id = pty_id.clone();
+            actix_web::rt::spawn(async move {
+                if let Ok(session) = session_manager.get_session(&session_id).await {
+                    session.set_pty(pty_id).await;
+                }
+            });
+        }
+
+        // Forward PTY output into the actor mailbox
+        forward_pty_output(subscription.receiver, ctx);
+
+        self.pty_id = Some(pty_id);
+
+        self.send(TtydServerMessage::SetWindowTitle(self.window_title()), ctx);
+        self.send(
+            TtydServerMessage::SetPreferences(serde_json::json!({})),
+            ctx,
+        );
+    }
+
+    /// Window title shown by ttyd clients
+    fn window_title(&self) -> String {
+        match (&self.user_context, &self.session_id) {
+            (Some(user), Some(session_id)) => {
+                format!("web-terminal: {} ({})", user.user_id, session_id)
+            }
+            _ => "web-terminal".to_string(),
+        }
+    }
+
+    /// Write client input to the PTY
+    /// Per FR-2.2.1: Capture keyboard input in real-time
+    fn handle_input(&mut self, data: Vec<u8>) {
+        let Some(pty_id) = &self.pty_id else {
+            return;
+        };
+        if !self.can_send_input() {
+            return;
+        }
+
+        match self.pty_manager.create_writer(pty_id) {
+            Ok(writer) => {
+                actix_web::rt::spawn(async move {
+                    if let Err(e) = writer.write(&data).await {
+                        tracing::error!("Failed to write to PTY: {}", e);
+                    }
+                });
+            }
+            Err(e) => tracing::error!("Failed to create PTY writer: {}", e),
+        }
+    }
+
+    /// Whether the user may send input to their session (ttyd has no error
+    /// frame, so refused input is only logged)
+    /// Per spec-kit/011-authentication-spec.md section 5: Authorization Model
+    fn can_send_input(&self) -> bool {
+        let Some(user) = &self.user_context else {
+            return false;
+        };
+        match user.authorize(&self.authz, Permission::SendInput, Some(&user.user_id)) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::debug!("ttyd input dropped: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Resize the PTY
+    /// Per FR-2.1.5: Support terminal dimensions
+    fn handle_resize(&mut self, cols: u16, rows: u16) {
+        if let Err(e) = (ClientMessage::Resize { cols, rows }).validate() {
+            tracing::warn!("ttyd resize rejected: {}", e);
+            return;
+        }
+
+        let Some(pty_id) = &self.pty_id else {
+            return;
+        };
+
+        match self.pty_manager.get(pty_id) {
+            Ok(handle) => {
+                actix_web::rt::spawn(async move {
+                    if let Err(e) = handle.resize(cols, rows).await {
+                        tracing::error!("Failed to resize PTY: {}", e);
+                    }
+                });
+            }
+            Err(e) => tracing::error!("Failed to resize PTY: {}", e),
+        }
+    }
+
+    /// Resume output and flush anything held back while paused
+    /// Per spec-kit/007-websocket-spec.md: Flow control and backpressure
+    fn handle_resume(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        self.paused = false;
+        while let Some(data) = self.paused_output.pop_front() {
+            self.send(TtydServerMessage::Output(data), ctx);
+        }
+        self.paused_bytes = 0;
+    }
+
+    /// Send a ttyd frame to the client
+    fn send(&self, msg: TtydServerMessage, ctx: &mut ws::WebsocketContext<Self>) {
+        ctx.binary(msg.encode());
+    }
+
+    /// Handle a decoded client frame
+    fn handle_frame(&mut self, frame: &[u8], ctx: &mut ws::WebsocketContext<Self>) {
+        if frame.len() > MAX_MESSAGE_SIZE {
+            tracing::warn!(
+                "ttyd frame size {} exceeds maximum {}",
+                frame.len(),
+                MAX_MESSAGE_SIZE
+            );
+            return;
+        }
+
+        let msg = match TtydClientMessage::decode(frame) {
+            Ok(msg) => msg,
+            Err(e) => {
+                tracing::warn!("Invalid ttyd frame: {}", e);
+                return;
+            }
+        };
+
+        match msg {
+            TtydClientMessage::Handshake(handshake) => self.handle_handshake(handshake, ctx),
+            _ if self.user_context.is_none() => {
+                tracing::warn!("Unauthenticated ttyd message rejected");
+            }
+            TtydClientMessage::Input(data) => self.handle_input(data),
+            TtydClientMessage::Resize { cols, rows } => self.handle_resize(cols, rows),
+            TtydClientMessage::Pause => self.paused = true,
+            TtydClientMessage::Resume => self.handle_resume(ctx),
+        }
+    }
+}
+
+impl Actor for TtydSession {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        tracing::info!("ttyd WebSocket session started");
+
+        // Heartbeat: 5s interval, 30s timeout
+        ctx.run_interval(HEARTBEAT_INTERVAL, |act, ctx| {
+            if Instant::now().duration_since(act.last_heartbeat) > CLIENT_TIMEOUT {
+                tracing::warn!("ttyd heartbeat timeout");
+                ctx.stop();
+                return;
+            }
+            if act.close_if_revoked(ctx) {
+                return;
+            }
+            ctx.ping(&act.latency.ping_payload());
+        });
+
+        if let Some(shutdown) = self.shutdown.clone() {
+            forward_shutdown_notices(&shutdown, ctx);
+        }
+
+        // Handshake must arrive within the client timeout
+        ctx.run_later(CLIENT_TIMEOUT, |act, ctx| {
+            if act.user_context.is_none() {
+                tracing::warn!("ttyd authentication timeout");
+                ctx.close(Some(ws::CloseCode::Policy.into()));
+                ctx.stop();
+            }
+        });
+    }
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        tracing::info!("ttyd WebSocket session stopped");
+
+        if self.latency.is_high_latency() {
+            metrics::WEBSOCKET_HIGH_LATENCY_CONNECTIONS.dec();
+        }
+
+        // Detach; the session is reaped unless another client attaches to it
+        if let Some(session_id) = self.session_id.take() {
+            reap_detached_session(
+                self.pty_manager.clone(),
+                self.session_manager.clone(),
+                session_id,
+                self.pty_id.take(),
+            );
+        }
+    }
+}
+
+impl Handler<ShutdownWarning> for TtydSession {
+    type Result = ();
+
+    /// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+    fn handle(&mut self, msg: ShutdownWarning, ctx: &mut Self::Context) {
+        if msg.0.is_final() {
+            ctx.close(Some(ws::CloseCode::Away.into()));
+            ctx.stop();
+            return;
+        }
+        if self.user_context.is_some() {
+            let line = format!(
+                "\r\n[web-terminal] Server shutting down ({}): terminal closes in {}s\r\n",
+                msg.0.reason, msg.0.seconds_remaining
+            );
+            self.send(TtydServerMessage::Output(line.into_bytes()), ctx);
+        }
+    }
+}
+
+impl Handler<PtyOutput> for TtydSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: PtyOutput, ctx: &mut Self::Context) {
+        if !self.paused {
+            self.send(TtydServerMessage::Output(msg.0), ctx);
+            return;
+        }
+
+        // Hold output while paused, dropping the oldest chunks past the limit
+        self.paused_bytes += msg.0.len();
+        self.paused_output.push_back(msg.0);
+        while self.paused_bytes > MAX_PAUSED_OUTPUT {
+            match self.paused_output.pop_front() {
+                Some(dropped) => self.paused_bytes -= dropped.len(),
+                None => break,
+            }
+        }
+    }
+}
+
+impl Handler<PtyClosed> for TtydSession {
+    type Result = ();
+
+    fn handle(&mut self, _msg: PtyClosed, ctx: &mut Self::Context) {
+        tracing::info!("PTY output closed, ending ttyd session");
+        ctx.close(Some(ws::CloseCode::Normal.into()));
+        ctx.stop();
+    }
+}
+
+impl StreamHandler<std::result::Result<ws::Message, ws::ProtocolError>> for TtydSession {
+    fn handle(
+        &mut self,
+        msg: std::result::Result<ws::Message, ws::ProtocolError>,
+        ctx: &mut Self::Context,
+    ) {
+        match msg {
+            Ok(ws::Message::Text(text)) => self.handle_frame(text.as_bytes(), ctx),
+            Ok(ws::Message::Binary(bin)) => self.handle_frame(&bin, ctx),
+            Ok(ws::Message::Ping(msg)) => {
+                self.last_heartbeat = Instant::now();
+                ctx.pong(&msg);
+            }
+            Ok(ws::Message::Pong(msg)) => {
+                self.last_heartbeat = Instant::now();
+                if let Some(session_id) = &self.session_id {
+                    record_heartbeat_rtt(
+                        &mut self.latency,
+                        "ttyd",
+                        &msg,
+                        &self.session_manager,
+                        session_id,
+                    );
+                }
+            }
+            Ok(ws::Message::Close(reason)) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            Err(e) => {
+                tracing::error!("ttyd WebSocket protocol error: {}", e);
+                ctx.stop();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::jwks_client::JwksClient;
+    use crate::session::manager::SessionConfig;
+
+    #[test]
+    fn test_ttyd_session_creation() {
+        let session_manager = Arc::new(SessionManager::new(SessionConfig::default()));
+        let config = crate::config::Config::default();
+        let jwks_client = Arc::new(JwksClient::new(config.auth.clone()));
+        let jwt_validator = Arc::new(JwtValidator::new(jwks_client, config.auth));
+
+        let session = TtydSession::new(
+            session_manager,
+            Arc::new(PtyManager::with_defaults()),
+            jwt_validator,
+        );
+        assert!(session.user_context.is_none());
+        assert_eq!(session.window_title(), "web-terminal");
+    }
+}
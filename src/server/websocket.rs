@@ -2,28 +2,212 @@
 // Per spec-kit/007-websocket-spec.md
 // Per spec-kit/011-authentication-spec.md: WebSocket authentication
 
-use actix::{Actor, ActorContext, ActorFutureExt, AsyncContext, StreamHandler, WrapFuture};
+use actix::{
+    Actor, ActorContext, ActorFutureExt, AsyncContext, Handler, Message, SpawnHandle,
+    StreamHandler, WrapFuture,
+};
 use actix_web_actors::ws;
+use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 
-use crate::protocol::{error_codes, ClientMessage, ConnectionStatus, ServerMessage, Signal};
-use crate::pty::PtyManager;
+use crate::config::auth::TokenExpiryAction;
+use crate::config::{CompressionConfig, LatencyConfig};
+use crate::error::Error;
+use crate::monitoring::{metrics, LatencyTracker};
+use crate::protocol::{
+    error_codes, ClientMessage, CompressionAlgorithm, ConnectionStatus, OutputEncoder,
+    ServerMessage, Signal,
+};
+use crate::pty::{PtyConfig, PtyManager, SCROLLBACK_BYTES};
+use crate::security::access_grants::{AccessGrant, AccessGrantStore, GrantStatus};
+use crate::security::admission::AdmissionPolicy;
+use crate::security::api_keys::ApiKeyStore;
+use crate::security::audit::{AuditAction, AuditEvent, AuditLogger};
+use crate::security::authorization::{AuthorizationService, Permission};
 use crate::security::jwt_validator::JwtValidator;
-use crate::server::middleware::auth::UserContext;
-use crate::session::{SessionId, SessionManager};
+use crate::security::lockout::LockoutTracker;
+use crate::security::policy::Resource;
+use crate::security::revocation::{RevocationError, RevocationList};
+use crate::security::tenancy::TenantResolver;
+use crate::server::middleware::auth::{CredentialVerifier, UserContext};
+use crate::server::shutdown::{ShutdownCoordinator, ShutdownNotice};
+use crate::session::{SessionId, SessionManager, UserId};
 
 /// Heartbeat interval: 5 seconds
 /// Per spec-kit/007-websocket-spec.md: Heartbeat mechanism
-const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+pub(crate) const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 
 /// Client timeout: 30 seconds
 /// Per spec-kit/007-websocket-spec.md: Connection timeout
-const CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
+pub(crate) const CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Maximum message size: 1 MB
 /// Per spec-kit/007-websocket-spec.md: Message validation
-const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+pub(crate) const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// PTY output chunk forwarded to a WebSocket actor
+#[derive(Message)]
+#[rtype(result = "()")]
+pub(crate) struct PtyOutput(pub Vec<u8>);
+
+/// PTY output stream reached EOF (shell exited)
+#[derive(Message)]
+#[rtype(result = "()")]
+pub(crate) struct PtyClosed;
+
+/// Change to an access request, forwarded to a WebSocket actor
+#[derive(Message)]
+#[rtype(result = "()")]
+pub(crate) struct AccessGrantChanged(pub AccessGrant);
+
+/// Shutdown countdown, forwarded to a WebSocket actor
+#[derive(Message)]
+#[rtype(result = "()")]
+pub(crate) struct ShutdownWarning(pub ShutdownNotice);
+
+/// Forward shutdown notices into the actor's mailbox, starting with the
+/// current one if the server is already draining
+///
+/// The subscription lives as long as the connection, so the coordinator
+/// can count the clients still attached.
+/// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+pub(crate) fn forward_shutdown_notices<A>(
+    shutdown: &ShutdownCoordinator,
+    ctx: &mut ws::WebsocketContext<A>,
+) where
+    A: Actor<Context = ws::WebsocketContext<A>> + Handler<ShutdownWarning>,
+{
+    let (mut rx, current) = shutdown.subscribe();
+    let addr = ctx.address();
+    if let Some(notice) = current {
+        addr.do_send(ShutdownWarning(notice));
+    }
+    ctx.spawn(actix::fut::wrap_future(async move {
+        loop {
+            match rx.recv().await {
+                Ok(notice) => addr.do_send(ShutdownWarning(notice)),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }));
+}
+
+/// How long a PTY with no attached clients is kept for reattach: 60 seconds
+/// Per spec-kit/007-websocket-spec.md: Reconnection
+pub(crate) const DETACH_GRACE: Duration = Duration::from_secs(60);
+
+/// Forward a PTY subscription into the actor's mailbox until the PTY closes
+///
+/// The forwarding future belongs to the actor, so it (and its subscription)
+/// is dropped when the connection stops.
+/// Per FR-3.3: Real-time streaming
+pub(crate) fn forward_pty_output<A>(
+    mut rx: broadcast::Receiver<Vec<u8>>,
+    ctx: &mut ws::WebsocketContext<A>,
+) where
+    A: Actor<Context = ws::WebsocketContext<A>> + Handler<PtyOutput> + Handler<PtyClosed>,
+{
+    let addr = ctx.address();
+    ctx.spawn(actix::fut::wrap_future(async move {
+        loop {
+            match rx.recv().await {
+                Ok(data) => addr.do_send(PtyOutput(data)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Slow client skipped {} PTY output chunks", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+        addr.do_send(PtyClosed);
+    }));
+}
+
+/// Reap a session once no client has re-attached within [`DETACH_GRACE`]
+///
+/// Clients that drop and reconnect (or a second client attached to the same
+/// session) keep the shell alive; otherwise the PTY is killed and the session
+/// destroyed.
+/// Per FR-4.1.5: Clean up session resources on close
+pub(crate) fn reap_detached_session(
+    pty_manager: Arc<PtyManager>,
+    session_manager: Arc<SessionManager>,
+    session_id: SessionId,
+    pty_id: Option<String>,
+) {
+    actix_web::rt::spawn(async move {
+        tokio::time::sleep(DETACH_GRACE).await;
+
+        if let Some(pty_id) = &pty_id {
+            if pty_manager.subscriber_count(pty_id) > 0 {
+                return;
+            }
+            if let Ok(handle) = pty_manager.remove(pty_id) {
+                if let Err(e) = handle.kill().await {
+                    tracing::debug!("PTY {} already closed: {}", pty_id, e);
+                }
+            }
+        }
+
+        if session_manager.destroy_session(&session_id).await.is_ok() {
+            tracing::info!("Reaped detached session {}", session_id);
+        }
+    });
+}
+
+/// Time left before a token's `exp` (zero once expired)
+/// Per spec-kit/011-authentication-spec.md: Token expiry
+pub(crate) fn token_time_remaining(exp: i64) -> Duration {
+    let remaining = exp.saturating_sub(chrono::Utc::now().timestamp());
+    Duration::from_secs(remaining.max(0) as u64)
+}
+
+/// Record a heartbeat pong as an RTT sample
+///
+/// Updates the connection's tracker, the global RTT histogram and the
+/// high-latency gauge, and publishes the snapshot on the session so it shows
+/// up in session listings.
+/// Per spec-kit/007-websocket-spec.md: Heartbeat mechanism
+pub(crate) fn record_heartbeat_rtt(
+    tracker: &mut LatencyTracker,
+    protocol: &str,
+    payload: &[u8],
+    session_manager: &Arc<SessionManager>,
+    session_id: &SessionId,
+) {
+    let Some(rtt) = tracker.rtt_from_pong(payload) else {
+        return;
+    };
+
+    metrics::WEBSOCKET_RTT_SECONDS
+        .with_label_values(&[protocol])
+        .observe(rtt.as_secs_f64());
+
+    if tracker.record(rtt) {
+        if tracker.is_high_latency() {
+            metrics::WEBSOCKET_HIGH_LATENCY_CONNECTIONS.inc();
+            tracing::warn!(
+                "Sustained high latency on session {} ({} ms)",
+                session_id,
+                rtt.as_millis()
+            );
+        } else {
+            metrics::WEBSOCKET_HIGH_LATENCY_CONNECTIONS.dec();
+            tracing::info!("Latency recovered on session {}", session_id);
+        }
+    }
+
+    let snapshot = tracker.snapshot();
+    let session_manager = session_manager.clone();
+    let session_id = session_id.clone();
+    actix_web::rt::spawn(async move {
+        if let Ok(session) = session_manager.get_session(&session_id).await {
+            session.set_latency(snapshot).await;
+        }
+    });
+}
 
 /// WebSocket session actor
 ///
@@ -31,12 +215,14 @@ const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
 /// Per spec-kit/007-websocket-spec.md: WebSocket protocol
 /// Per spec-kit/011-authentication-spec.md: WebSocket authentication
 pub struct WebSocketSession {
-    /// Session ID for this WebSocket connection
+    /// Session this connection is attached to (provisional until authenticated)
     session_id: SessionId,
+    /// Existing session requested by the client, attached after authentication
+    attach_to: Option<SessionId>,
     /// Session manager
     session_manager: Arc<SessionManager>,
-    /// PTY manager (owned per-connection)
-    pty_manager: PtyManager,
+    /// PTY manager (shared so sessions can be attached from several connections)
+    pty_manager: Arc<PtyManager>,
     /// PTY process ID
     pty_id: Option<String>,
     /// Last heartbeat timestamp
@@ -44,95 +230,471 @@ pub struct WebSocketSession {
     /// User context from authenticated JWT
     /// Per spec-kit/011-authentication-spec.md: Authentication required before processing
     user_context: Option<UserContext>,
-    /// JWT validator for token authentication
-    jwt_validator: Arc<JwtValidator>,
+    /// Verifier for Authenticate message credentials (JWT or API key)
+    credentials: CredentialVerifier,
+    /// Client address, checked against API key address bindings
+    client_ip: Option<IpAddr>,
     /// Authentication timeout flag
     auth_timeout_scheduled: bool,
+    /// Output compression settings offered to this client
+    compression: CompressionConfig,
+    /// Binary output encoder, set once compression is negotiated
+    output_encoder: Option<OutputEncoder>,
+    /// Heartbeat round-trip latency
+    latency: LatencyTracker,
+    /// Security audit log
+    audit: Arc<AuditLogger>,
+    /// How long before token expiry the client is warned
+    expiry_warning: Duration,
+    /// What happens when the token expires
+    on_expiry: TokenExpiryAction,
+    /// Pending expiry warning/expiry timers for the current token
+    expiry_timers: Vec<SpawnHandle>,
+    /// Token expired with [`TokenExpiryAction::Freeze`]: input is blocked
+    frozen: bool,
+    /// Output held back while frozen (most recent bytes)
+    held_output: Vec<u8>,
+    /// User authenticated from the upgrade request, accepted once started
+    upgrade_user: Option<UserContext>,
+    /// Whether tokens are accepted in Authenticate messages
+    message_auth: bool,
+    /// Role permissions checked for every session action
+    authz: Arc<AuthorizationService>,
+    /// Owner of the session this connection is attached to
+    session_owner: Option<UserId>,
+    /// Session profile requested by the client, then the attached session's
+    profile: Option<String>,
+    /// Revoked tokens and subjects, rechecked on every heartbeat
+    revocations: Option<Arc<RevocationList>>,
+    /// Access requests prompted to the session owner
+    access_grants: Option<Arc<AccessGrantStore>>,
+    /// Shutdown countdown shown to the client
+    shutdown: Option<Arc<ShutdownCoordinator>>,
 }
 
 impl WebSocketSession {
     /// Create a new WebSocket session
     /// Per spec-kit/011-authentication-spec.md: Authentication required
     pub fn new(
-        session_id: SessionId,
         session_manager: Arc<SessionManager>,
-        pty_manager: PtyManager,
+        pty_manager: Arc<PtyManager>,
         jwt_validator: Arc<JwtValidator>,
     ) -> Self {
         Self {
-            session_id,
+            session_id: SessionId::generate(),
+            attach_to: None,
             session_manager,
             pty_manager,
             pty_id: None,
             last_heartbeat: Instant::now(),
             user_context: None,
-            jwt_validator,
+            credentials: CredentialVerifier::new(jwt_validator),
+            client_ip: None,
             auth_timeout_scheduled: false,
+            compression: CompressionConfig::default(),
+            output_encoder: None,
+            latency: LatencyTracker::new(LatencyConfig::default()),
+            audit: Arc::new(AuditLogger::default()),
+            expiry_warning: Duration::from_secs(60),
+            on_expiry: TokenExpiryAction::default(),
+            expiry_timers: Vec::new(),
+            frozen: false,
+            held_output: Vec::new(),
+            upgrade_user: None,
+            message_auth: true,
+            authz: Arc::new(AuthorizationService::with_defaults()),
+            session_owner: None,
+            profile: None,
+            revocations: None,
+            access_grants: None,
+            shutdown: None,
         }
     }
 
+    /// Start the new session with the named profile
+    /// Per spec-kit/007-websocket-spec.md: Session profiles
+    pub fn with_profile(mut self, profile: String) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Attach to an existing session instead of creating one
+    /// Per spec-kit/007-websocket-spec.md: Session attach
+    pub fn with_attach_target(mut self, session_id: SessionId) -> Self {
+        self.attach_to = Some(session_id);
+        self
+    }
+
+    /// Set the thresholds used to flag sustained high latency
+    /// Per spec-kit/007-websocket-spec.md: Heartbeat mechanism
+    pub fn with_latency(mut self, latency: LatencyConfig) -> Self {
+        self.latency = LatencyTracker::new(latency);
+        self
+    }
+
+    /// Set the output compression settings offered to this client
+    /// Per spec-kit/007-websocket-spec.md: Binary output framing
+    pub fn with_compression(mut self, compression: CompressionConfig) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Set the audit logger
+    /// Per spec-kit/011-authentication-spec.md: Audit logging
+    pub fn with_audit(mut self, audit: Arc<AuditLogger>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Set how token expiry is handled on this connection
+    /// Per spec-kit/011-authentication-spec.md: Token expiry
+    pub fn with_token_expiry(mut self, warning: Duration, on_expiry: TokenExpiryAction) -> Self {
+        self.expiry_warning = warning;
+        self.on_expiry = on_expiry;
+        self
+    }
+
+    /// Start already authenticated by a token from the upgrade request
+    /// Per spec-kit/011-authentication-spec.md section 7: Token sources
+    pub fn with_authenticated_user(mut self, user_context: UserContext) -> Self {
+        self.upgrade_user = Some(user_context);
+        self
+    }
+
+    /// Set whether tokens are accepted in Authenticate messages
+    /// Per spec-kit/011-authentication-spec.md section 7: Token sources
+    pub fn with_message_auth(mut self, enabled: bool) -> Self {
+        self.message_auth = enabled;
+        self
+    }
+
+    /// Accept API keys from `store` in Authenticate messages
+    /// Per spec-kit/011-authentication-spec.md: API Keys
+    pub fn with_api_keys(mut self, store: Arc<ApiKeyStore>) -> Self {
+        self.credentials = self.credentials.with_api_keys(store);
+        self
+    }
+
+    /// Check session actions against `authz` (and resolve roles with it)
+    /// Per spec-kit/011-authentication-spec.md section 5: Authorization Model
+    pub fn with_authorization(mut self, authz: Arc<AuthorizationService>) -> Self {
+        self.credentials = self.credentials.with_authorization(authz.clone());
+        self.authz = authz;
+        self
+    }
+
+    /// Only admit identities allowed by `policy`
+    /// Per spec-kit/011-authentication-spec.md: Authorization Model
+    pub fn with_admission(mut self, policy: Arc<AdmissionPolicy>) -> Self {
+        self.credentials = self.credentials.with_admission(policy);
+        self
+    }
+
+    /// Set the client address checked against API key address bindings
+    pub fn with_client_ip(mut self, client_ip: Option<IpAddr>) -> Self {
+        self.client_ip = client_ip;
+        self
+    }
+
+    /// Refuse revoked credentials and close the connection once its token
+    /// or subject is revoked; Authenticate message tickets are single-use
+    /// Per spec-kit/011-authentication-spec.md: Token Revocation
+    pub fn with_revocations(mut self, list: Arc<RevocationList>) -> Self {
+        self.credentials = self
+            .credentials
+            .with_revocations(list.clone())
+            .with_single_use_tickets();
+        self.revocations = Some(list);
+        self
+    }
+
+    /// Count failed authentications in `tracker` and refuse locked out
+    /// clients
+    /// Per spec-kit/011-authentication-spec.md: Brute-Force Protection
+    pub fn with_lockouts(mut self, tracker: Arc<LockoutTracker>) -> Self {
+        self.credentials = self.credentials.with_lockouts(tracker);
+        self
+    }
+
+    /// Place authenticated users in their tenant
+    /// Per spec-kit/011-authentication-spec.md: Multi-Tenancy
+    pub fn with_tenancy(mut self, tenants: Arc<TenantResolver>) -> Self {
+        self.credentials = self.credentials.with_tenancy(tenants);
+        self
+    }
+
+    /// Prompt the session owner with requests for access to the session,
+    /// and tell requesters how theirs were decided
+    /// Per spec-kit/011-authentication-spec.md: Just-in-Time Access
+    pub fn with_access_grants(mut self, store: Arc<AccessGrantStore>) -> Self {
+        self.access_grants = Some(store);
+        self
+    }
+
+    /// Warn the client when the server shuts down, and close the connection
+    /// at the deadline
+    /// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+    pub fn with_shutdown(mut self, shutdown: Arc<ShutdownCoordinator>) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
     /// Authenticate WebSocket connection with JWT token
+    ///
+    /// A connection that is already authenticated may send a fresh token for
+    /// the same subject to extend (or, when frozen, resume) the connection.
     /// Per spec-kit/011-authentication-spec.md: WebSocket authentication flow
     fn authenticate(&mut self, token: String, ctx: &mut ws::WebsocketContext<Self>) {
-        let validator = self.jwt_validator.clone();
-        let session_id_clone = self.session_id.clone();
+        if !self.message_auth {
+            self.send_error(
+                error_codes::AUTHENTICATION_FAILED,
+                "Authenticate messages are not accepted by this server",
+                ctx,
+            );
+            if self.user_context.is_none() {
+                ctx.close(Some(ws::CloseCode::Policy.into()));
+                ctx.stop();
+            }
+            return;
+        }
+
+        let credentials = self.credentials.clone();
+        let client_ip = self.client_ip;
 
         // Spawn async validation task
         ctx.spawn(
-            async move { validator.validate(&token).await }
+            async move { credentials.verify(&token, client_ip).await }
                 .into_actor(self)
                 .map(move |result, actor, ctx| {
+                    let refreshing = actor.user_context.is_some();
                     match result {
-                        Ok(validated_token) => {
-                            let user_context = UserContext::from_claims(
-                                validated_token.claims,
-                                validated_token.provider,
-                            );
-                            tracing::info!(
-                                "WebSocket authenticated: user={}, session={}",
-                                user_context.user_id.as_str(),
-                                session_id_clone
-                            );
-
-                            // Send authenticated message
-                            let msg = ServerMessage::Authenticated {
-                                user_id: user_context.user_id.as_str().to_string(),
-                                email: user_context.email.clone(),
-                                groups: Some(
-                                    user_context
-                                        .groups
-                                        .iter()
-                                        .map(|g| g.as_str().to_string())
-                                        .collect(),
-                                ),
-                            };
-                            if let Ok(json) = serde_json::to_string(&msg) {
-                                ctx.text(json);
+                        Ok(user_context) => {
+                            if let Some(current) = &actor.user_context {
+                                if current.user_id != user_context.user_id {
+                                    tracing::warn!(
+                                        "Re-authentication as {} rejected on session {} (owned by {})",
+                                        user_context.user_id.as_str(),
+                                        actor.session_id,
+                                        current.user_id.as_str()
+                                    );
+                                    actor.audit.record(
+                                        AuditEvent::new(AuditAction::TokenRefreshRejected)
+                                            .user(current.user_id.as_str())
+                                            .session(&actor.session_id)
+                                            .detail(format!(
+                                                "token subject {} does not match",
+                                                user_context.user_id.as_str()
+                                            )),
+                                    );
+                                    actor.send_error(
+                                        error_codes::PERMISSION_DENIED,
+                                        "Re-authentication must use a token for the same user",
+                                        ctx,
+                                    );
+                                    return;
+                                }
                             }
 
-                            actor.user_context = Some(user_context);
+                            actor.accept_user(user_context, ctx);
                         }
                         Err(e) => {
                             tracing::warn!("WebSocket authentication failed: {}", e);
+                            actor.audit.record(e.audit_event().session(&actor.session_id));
+                            let (code, message) = if e.lockout().is_some() {
+                                (
+                                    error_codes::AUTHENTICATION_LOCKED,
+                                    "Too many failed authentication attempts",
+                                )
+                            } else if e.is_denied() {
+                                (
+                                    error_codes::PERMISSION_DENIED,
+                                    "Access denied by authorization policy",
+                                )
+                            } else {
+                                (
+                                    error_codes::AUTHENTICATION_FAILED,
+                                    "Authentication failed: Invalid or expired token",
+                                )
+                            };
                             let msg = ServerMessage::Error {
-                                code: error_codes::AUTHENTICATION_FAILED.to_string(),
-                                message: "Authentication failed: Invalid or expired token"
-                                    .to_string(),
-                                details: None,
+                                code: code.to_string(),
+                                message: message.to_string(),
+                                details: e.lockout().map(|lockout| {
+                                    serde_json::json!({ "retry_after": lockout.retry_after_secs() })
+                                }),
                             };
                             if let Ok(json) = serde_json::to_string(&msg) {
                                 ctx.text(json);
                             }
-                            ctx.close(Some(ws::CloseCode::Policy.into()));
+
+                            // A failed refresh leaves the current token in force
+                            if !refreshing {
+                                ctx.close(Some(ws::CloseCode::Policy.into()));
+                            }
                         }
                     }
                 }),
         );
     }
 
+    /// Accept a validated user: confirm to the client, start tracking token
+    /// expiry, and attach the session (or resume it, on re-authentication)
+    /// Per spec-kit/011-authentication-spec.md: WebSocket authentication flow
+    fn accept_user(&mut self, user_context: UserContext, ctx: &mut ws::WebsocketContext<Self>) {
+        let refreshing = self.user_context.is_some();
+        tracing::info!(
+            "WebSocket authenticated: user={}, session={}",
+            user_context.user_id.as_str(),
+            self.session_id
+        );
+
+        // Send authenticated message
+        let msg = ServerMessage::Authenticated {
+            user_id: user_context.user_id.as_str().to_string(),
+            email: user_context.email.clone(),
+            groups: Some(
+                user_context
+                    .groups
+                    .iter()
+                    .map(|g| g.as_str().to_string())
+                    .collect(),
+            ),
+        };
+        if let Ok(json) = serde_json::to_string(&msg) {
+            ctx.text(json);
+        }
+
+        let action = if refreshing {
+            AuditAction::TokenRefreshed
+        } else {
+            AuditAction::AuthSuccess
+        };
+        self.audit.record(
+            AuditEvent::new(action)
+                .user(user_context.user_id.as_str())
+                .session(&self.session_id),
+        );
+
+        self.user_context = Some(user_context);
+        self.schedule_token_expiry(ctx);
+
+        if refreshing {
+            self.unfreeze(ctx);
+        } else {
+            self.claim_session(ctx);
+        }
+    }
+
+    /// Schedule the expiry warning and expiry for the current token,
+    /// replacing the timers of any previous token
+    /// Per spec-kit/011-authentication-spec.md: Token expiry
+    fn schedule_token_expiry(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        for handle in self.expiry_timers.drain(..) {
+            ctx.cancel_future(handle);
+        }
+
+        let Some(exp) = self.user_context.as_ref().map(|u| u.claims.exp) else {
+            return;
+        };
+        let remaining = token_time_remaining(exp);
+
+        let warn_in = remaining.saturating_sub(self.expiry_warning);
+        self.expiry_timers
+            .push(ctx.run_later(warn_in, move |act, ctx| act.warn_token_expiring(exp, ctx)));
+        self.expiry_timers
+            .push(ctx.run_later(remaining, |act, ctx| act.expire_token(ctx)));
+    }
+
+    /// Tell the client to re-authenticate before its token expires
+    fn warn_token_expiring(&mut self, exp: i64, ctx: &mut ws::WebsocketContext<Self>) {
+        let expires_in = token_time_remaining(exp).as_secs();
+        if expires_in == 0 {
+            return;
+        }
+
+        if let Some(user) = &self.user_context {
+            self.audit.record(
+                AuditEvent::new(AuditAction::TokenExpiring)
+                    .user(user.user_id.as_str())
+                    .session(&self.session_id),
+            );
+        }
+
+        let msg = ServerMessage::TokenExpiring {
+            expires_at: exp,
+            expires_in,
+        };
+        if let Ok(json) = serde_json::to_string(&msg) {
+            ctx.text(json);
+        }
+    }
+
+    /// The token lapsed without a refresh: close or freeze the connection
+    fn expire_token(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        let Some(user) = &self.user_context else {
+            return;
+        };
+        tracing::warn!(
+            "Token for {} expired on session {} ({:?})",
+            user.user_id.as_str(),
+            self.session_id,
+            self.on_expiry
+        );
+        self.audit.record(
+            AuditEvent::new(AuditAction::TokenExpired)
+                .user(user.user_id.as_str())
+                .session(&self.session_id)
+                .detail(match self.on_expiry {
+                    TokenExpiryAction::Close => "connection closed",
+                    TokenExpiryAction::Freeze => "session frozen",
+                }),
+        );
+
+        match self.on_expiry {
+            TokenExpiryAction::Close => {
+                self.send_error(
+                    error_codes::TOKEN_EXPIRED,
+                    "Token expired. Reconnect with a fresh token.",
+                    ctx,
+                );
+                ctx.close(Some(ws::CloseCode::Policy.into()));
+                ctx.stop();
+            }
+            TokenExpiryAction::Freeze => {
+                self.frozen = true;
+                self.send_error(
+                    error_codes::TOKEN_EXPIRED,
+                    "Token expired. Send authenticate with a fresh token to resume.",
+                    ctx,
+                );
+            }
+        }
+    }
+
+    /// Resume a frozen connection, flushing the output held back meanwhile
+    fn unfreeze(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        if !self.frozen {
+            return;
+        }
+        self.frozen = false;
+
+        let held = std::mem::take(&mut self.held_output);
+        if !held.is_empty() {
+            self.send_output(&held, ctx);
+        }
+    }
+
     /// Check if WebSocket is authenticated
     /// Per spec-kit/011-authentication-spec.md: Require authentication before processing
     fn require_auth(&self, ctx: &mut ws::WebsocketContext<Self>) -> bool {
+        if self.frozen {
+            self.send_error(
+                error_codes::TOKEN_EXPIRED,
+                "Token expired. Send authenticate with a fresh token to resume.",
+                ctx,
+            );
+            return false;
+        }
         if self.user_context.is_none() {
             tracing::warn!("Unauthenticated WebSocket message rejected");
             self.send_error(
@@ -145,6 +707,38 @@ impl WebSocketSession {
         true
     }
 
+    /// Record a file transfer requested on this connection
+    /// Per spec-kit/011-authentication-spec.md: Audit logging
+    fn audit_file_transfer(&self, action: AuditAction, detail: String) {
+        let mut event = AuditEvent::new(action)
+            .session(&self.session_id)
+            .detail(detail);
+        if let Some(user) = &self.user_context {
+            event = event.user(user.user_id.as_str());
+        }
+        self.audit.record(event);
+    }
+
+    /// Check that the user may perform `permission` on the attached session
+    /// Per spec-kit/011-authentication-spec.md section 5: Authorization Model
+    fn authorize(&self, permission: Permission, ctx: &mut ws::WebsocketContext<Self>) -> bool {
+        let Some(user) = &self.user_context else {
+            return false;
+        };
+        if let Err(e) = user.authorize_resource(&self.authz, permission, self.resource()) {
+            self.send_error(error_codes::PERMISSION_DENIED, &e.to_string(), ctx);
+            return false;
+        }
+        true
+    }
+
+    /// The session this connection is attached to, as an authorization resource
+    fn resource(&self) -> Resource<'_> {
+        Resource::owned_by(self.session_owner.as_ref())
+            .with_profile(self.profile.as_deref())
+            .with_session(&self.session_id)
+    }
+
     /// Start heartbeat task
     /// Per spec-kit/007-websocket-spec.md: Heartbeat mechanism (5s interval, 30s timeout)
     fn start_heartbeat(&self, ctx: &mut ws::WebsocketContext<Self>) {
@@ -156,11 +750,164 @@ impl WebSocketSession {
                 return;
             }
 
-            // Send ping
-            ctx.ping(b"");
+            if act.close_if_revoked(ctx) || act.close_if_access_ended(ctx) {
+                return;
+            }
+
+            // Send ping stamped with the send time so the pong yields an RTT
+            ctx.ping(&act.latency.ping_payload());
         });
     }
 
+    /// Close the connection if the authenticated token has been revoked
+    /// Per spec-kit/011-authentication-spec.md: Token Revocation
+    fn close_if_revoked(&mut self, ctx: &mut ws::WebsocketContext<Self>) -> bool {
+        let (Some(list), Some(user)) = (&self.revocations, &self.user_context) else {
+            return false;
+        };
+        let Err(RevocationError::Revoked(detail)) = list.check(&user.claims, user.tenant_name())
+        else {
+            return false;
+        };
+
+        tracing::info!(
+            "Closing WebSocket session {} for {}: {}",
+            self.session_id,
+            user.user_id,
+            detail
+        );
+        self.audit.record(
+            AuditEvent::new(AuditAction::ConnectionRevoked)
+                .user(user.user_id.as_str())
+                .session(&self.session_id)
+                .detail(detail),
+        );
+        self.send_error(error_codes::TOKEN_REVOKED, "Token has been revoked", ctx);
+        ctx.close(Some(ws::CloseCode::Policy.into()));
+        ctx.stop();
+        true
+    }
+
+    /// Close a connection to another user's session once the user may no
+    /// longer view it, e.g. when granted access expires or is revoked
+    /// Per spec-kit/011-authentication-spec.md: Just-in-Time Access
+    fn close_if_access_ended(&mut self, ctx: &mut ws::WebsocketContext<Self>) -> bool {
+        let (Some(user), Some(owner)) = (&self.user_context, &self.session_owner) else {
+            return false;
+        };
+        if *owner == user.user_id {
+            return false;
+        }
+        let decision = user.decide(
+            &self.authz,
+            Permission::ViewSession,
+            &user.access_request(self.resource()),
+        );
+        if decision.allowed {
+            return false;
+        }
+
+        tracing::info!(
+            "Closing WebSocket session {} for {}: {}",
+            self.session_id,
+            user.user_id,
+            decision.reason
+        );
+        self.send_error(
+            error_codes::ACCESS_ENDED,
+            "Access to this session has ended",
+            ctx,
+        );
+        ctx.close(Some(ws::CloseCode::Policy.into()));
+        ctx.stop();
+        true
+    }
+
+    /// Forward access request changes into the actor's mailbox and prompt
+    /// the owner with requests still waiting for an answer
+    /// Per spec-kit/011-authentication-spec.md: Just-in-Time Access
+    fn watch_access_requests(&self, ctx: &mut ws::WebsocketContext<Self>) {
+        let (Some(store), Some(user)) = (&self.access_grants, &self.user_context) else {
+            return;
+        };
+        let mut rx = store.subscribe();
+        let addr = ctx.address();
+        ctx.spawn(actix::fut::wrap_future(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(grant) => addr.do_send(AccessGrantChanged(grant)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Slow client skipped {} access request changes", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }));
+
+        for grant in store.list_for(&user.user_id) {
+            if grant.status == GrantStatus::Pending && grant.session_id == self.session_id {
+                self.send_access_grant(&grant, ctx);
+            }
+        }
+    }
+
+    /// Show `grant` to this connection if it concerns it: a prompt for the
+    /// session owner while pending, a status update otherwise
+    fn send_access_grant(&self, grant: &AccessGrant, ctx: &mut ws::WebsocketContext<Self>) {
+        let Some(user) = &self.user_context else {
+            return;
+        };
+        let owner_here = grant.owner == user.user_id && grant.session_id == self.session_id;
+        let msg = if grant.status == GrantStatus::Pending {
+            if !owner_here {
+                return;
+            }
+            ServerMessage::AccessRequested {
+                request_id: grant.id.clone(),
+                requester: grant.requester.to_string(),
+                level: grant.level.as_str().to_string(),
+                duration_secs: grant.duration_secs,
+                reason: grant.reason.clone(),
+                expires_at: grant.pending_until.timestamp(),
+            }
+        } else {
+            if !owner_here && grant.requester != user.user_id {
+                return;
+            }
+            ServerMessage::AccessUpdated {
+                request_id: grant.id.clone(),
+                session_id: grant.session_id.to_string(),
+                status: grant.status.as_str().to_string(),
+                expires_at: grant.expires_at.map(|at| at.timestamp()),
+            }
+        };
+        if let Ok(json) = serde_json::to_string(&msg) {
+            ctx.text(json);
+        }
+    }
+
+    /// Answer an access request to the attached session
+    /// Per spec-kit/011-authentication-spec.md: Just-in-Time Access
+    fn handle_access_decision(
+        &mut self,
+        request_id: String,
+        approve: bool,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        let (Some(store), Some(user)) = (&self.access_grants, &self.user_context) else {
+            self.send_error(
+                error_codes::INVALID_MESSAGE,
+                "Access requests are not enabled",
+                ctx,
+            );
+            return;
+        };
+        // Decided status reaches this connection as an access_updated message
+        if let Err(e) = store.decide(&request_id, &user.user_id, approve) {
+            self.send_error(error_codes::INVALID_MESSAGE, &e.to_string(), ctx);
+        }
+    }
+
     /// Schedule authentication timeout
     /// Per spec-kit/007-websocket-spec.md: Must authenticate within 30 seconds
     fn schedule_auth_timeout(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
@@ -183,6 +930,203 @@ impl WebSocketSession {
         }
     }
 
+    /// Create a session for the authenticated user, or attach to the requested one
+    /// Per spec-kit/007-websocket-spec.md: Session attach
+    /// Per spec-kit/011-authentication-spec.md: Attaching to another user's
+    /// session needs list_all_sessions and gives view-only access
+    fn claim_session(&mut self, ctx: &mut ws::WebsocketContext<Self>) {
+        let Some(user) = self.user_context.clone() else {
+            return;
+        };
+        if self.attach_to.is_none() && !self.authorize(Permission::CreateSession, ctx) {
+            ctx.close(Some(ws::CloseCode::Policy.into()));
+            ctx.stop();
+            return;
+        }
+        let session_manager = self.session_manager.clone();
+        let authz = self.authz.clone();
+        let attach_to = self.attach_to.take();
+        let attaching = attach_to.is_some();
+        let profile = self.profile.clone();
+        let base_config = self.pty_manager.default_config().clone();
+
+        ctx.spawn(
+            async move {
+                let session = match attach_to {
+                    Some(session_id) => {
+                        let session = session_manager.get_session(&session_id).await?;
+                        user.authorize_resource(
+                            &authz,
+                            Permission::ViewSession,
+                            Resource::session(&session),
+                        )?;
+                        session
+                    }
+                    None => {
+                        session_manager
+                            .create_tenant_session(
+                                user.user_id.clone(),
+                                user.tenant_name(),
+                                profile,
+                            )
+                            .await?
+                    }
+                };
+                let pty_config = session_manager.pty_config(&session, &base_config).await?;
+                Ok((
+                    session.id.clone(),
+                    session.user_id.clone(),
+                    session.profile.clone(),
+                    session.get_pty().await,
+                    pty_config,
+                ))
+            }
+            .into_actor(self)
+            .map(move |result, actor, ctx| match result {
+                Ok((session_id, owner, profile, pty_id, pty_config)) => {
+                    tracing::info!("WebSocket attached to session {}", session_id);
+                    let action = if attaching {
+                        AuditAction::SessionAttached
+                    } else {
+                        AuditAction::SessionCreated
+                    };
+                    let mut event = AuditEvent::new(action).session(&session_id);
+                    if let Some(user) = &actor.user_context {
+                        event = event.user(user.user_id.as_str());
+                    }
+                    actor.audit.record(event);
+                    actor.session_id = session_id;
+                    actor.session_owner = Some(owner);
+                    actor.profile = profile;
+
+                    let msg = ServerMessage::ConnectionStatus {
+                        status: ConnectionStatus::Connected,
+                        session_id: Some(actor.session_id.to_string()),
+                    };
+                    if let Ok(json) = serde_json::to_string(&msg) {
+                        ctx.text(json);
+                    }
+
+                    actor.start_pty(pty_id, pty_config, ctx);
+                    actor.watch_access_requests(ctx);
+                }
+                Err(e) => {
+                    tracing::warn!("WebSocket session attach failed: {}", e);
+                    let code = match e {
+                        Error::SessionNotFound(_) => error_codes::SESSION_EXPIRED,
+                        Error::SessionLimitExceeded(_) => error_codes::RESOURCE_LIMIT,
+                        Error::ShuttingDown => error_codes::SERVER_SHUTTING_DOWN,
+                        Error::Forbidden(_) => error_codes::PERMISSION_DENIED,
+                        Error::ValidationError(_) => error_codes::INVALID_MESSAGE,
+                        _ => error_codes::INTERNAL_ERROR,
+                    };
+                    actor.send_error(code, &e.to_string(), ctx);
+                    ctx.close(Some(ws::CloseCode::Policy.into()));
+                    ctx.stop();
+                }
+            }),
+        );
+    }
+
+    /// Attach to the session's PTY, spawning one from `config` if it has
+    /// none, and stream its output
+    /// Per FR-1.2.1: Start processes for executed commands
+    fn start_pty(
+        &mut self,
+        existing: Option<String>,
+        config: PtyConfig,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        if self.pty_id.is_some() {
+            return;
+        }
+
+        let pty_id = match existing.filter(|id| self.pty_manager.get(id).is_ok()) {
+            Some(pty_id) => pty_id,
+            None => match self.pty_manager.spawn(Some(config)) {
+                Ok(handle) => {
+                    let pty_id = handle.id().to_string();
+                    let session_manager = self.session_manager.clone();
+                    let session_id = self.session_id.clone();
+                    let session_pty_id = pty_id.clone();
+                    actix_web::rt::spawn(async move {
+                        if let Ok(session) = session_manager.get_session(&session_id).await {
+                            session.set_pty(session_pty_id).await;
+                        }
+                    });
+                    pty_id
+                }
+                Err(e) => {
+                    tracing::error!("Failed to spawn PTY for session {}: {}", self.session_id, e);
+                    self.send_error(error_codes::INTERNAL_ERROR, "Failed to start shell", ctx);
+                    return;
+                }
+            },
+        };
+
+        match self.pty_manager.subscribe(&pty_id) {
+            Ok(subscription) => {
+                if !subscription.scrollback.is_empty() {
+                    self.send_output(&subscription.scrollback, ctx);
+                }
+                forward_pty_output(subscription.receiver, ctx);
+                self.pty_id = Some(pty_id);
+            }
+            Err(e) => {
+                tracing::error!("Failed to attach to PTY {}: {}", pty_id, e);
+                self.send_error(
+                    error_codes::INTERNAL_ERROR,
+                    "Failed to attach to shell",
+                    ctx,
+                );
+            }
+        }
+    }
+
+    /// Send PTY output, as a binary frame once compression is negotiated
+    /// Per spec-kit/007-websocket-spec.md: Binary output framing
+    fn send_output(&self, data: &[u8], ctx: &mut ws::WebsocketContext<Self>) {
+        if let Some(encoder) = &self.output_encoder {
+            ctx.binary(encoder.encode(data));
+            return;
+        }
+
+        let msg = ServerMessage::Output {
+            stream: None,
+            data: String::from_utf8_lossy(data).into_owned(),
+        };
+        if let Ok(json) = serde_json::to_string(&msg) {
+            ctx.text(json);
+        }
+    }
+
+    /// Handle compression negotiation
+    /// Per spec-kit/007-websocket-spec.md: Binary output framing
+    fn handle_negotiate_compression(
+        &mut self,
+        algorithms: Vec<CompressionAlgorithm>,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        let algorithm = self.compression.negotiate(&algorithms);
+        self.output_encoder = algorithm.map(|algorithm| {
+            OutputEncoder::new(algorithm, self.compression.min_size, self.compression.level)
+        });
+
+        tracing::debug!(
+            "Compression negotiated for session {}: {:?}",
+            self.session_id,
+            algorithm
+        );
+
+        let msg = ServerMessage::CompressionNegotiated {
+            algorithm,
+            min_size: self.compression.min_size,
+        };
+        if let Ok(json) = serde_json::to_string(&msg) {
+            ctx.text(json);
+        }
+    }
+
     /// Handle client command
     /// Per spec-kit/007-websocket-spec.md: Command execution
     fn handle_command(&mut self, data: String, ctx: &mut ws::WebsocketContext<Self>) {
@@ -196,7 +1140,7 @@ impl WebSocketSession {
 
         // Write command to PTY
         match self.pty_manager.create_writer(&pty_id) {
-            Ok(mut writer) => {
+            Ok(writer) => {
                 // Spawn async write task
                 actix_web::rt::spawn(async move {
                     if let Err(e) = writer.write(data.as_bytes()).await {
@@ -222,15 +1166,23 @@ impl WebSocketSession {
             }
         };
 
-        let pty_manager = &self.pty_manager;
-        let result = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current()
-                .block_on(async { pty_manager.resize(&pty_id, cols, rows).await })
-        });
-
-        if let Err(e) = result {
-            tracing::error!("Failed to resize PTY: {}", e);
-            self.send_error(error_codes::INTERNAL_ERROR, &e.to_string(), ctx);
+        match self.pty_manager.get(&pty_id) {
+            Ok(handle) => {
+                ctx.spawn(
+                    async move { handle.resize(cols, rows).await }
+                        .into_actor(self)
+                        .map(|result, actor, ctx| {
+                            if let Err(e) = result {
+                                tracing::error!("Failed to resize PTY: {}", e);
+                                actor.send_error(error_codes::INTERNAL_ERROR, &e.to_string(), ctx);
+                            }
+                        }),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Failed to resize PTY: {}", e);
+                self.send_error(error_codes::INTERNAL_ERROR, &e.to_string(), ctx);
+            }
         }
     }
 
@@ -246,20 +1198,26 @@ impl WebSocketSession {
         };
 
         // Handle signal directly by killing the PTY
-        let pty_manager = &self.pty_manager;
-        let result = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                match signal {
-                    Signal::SIGINT | Signal::SIGTERM | Signal::SIGKILL => {
-                        pty_manager.kill(&pty_id).await
-                    }
-                }
-            })
-        });
+        let handle = match signal {
+            Signal::SIGINT | Signal::SIGTERM | Signal::SIGKILL => self.pty_manager.remove(&pty_id),
+        };
 
-        if let Err(e) = result {
-            tracing::error!("Failed to send signal to PTY: {}", e);
-            self.send_error(error_codes::COMMAND_KILLED, &e.to_string(), ctx);
+        match handle {
+            Ok(handle) => {
+                self.pty_id = None;
+                ctx.spawn(async move { handle.kill().await }.into_actor(self).map(
+                    |result, actor, ctx| {
+                        if let Err(e) = result {
+                            tracing::error!("Failed to send signal to PTY: {}", e);
+                            actor.send_error(error_codes::COMMAND_KILLED, &e.to_string(), ctx);
+                        }
+                    },
+                ));
+            }
+            Err(e) => {
+                tracing::error!("Failed to send signal to PTY: {}", e);
+                self.send_error(error_codes::COMMAND_KILLED, &e.to_string(), ctx);
+            }
         }
     }
 
@@ -385,31 +1343,104 @@ impl Actor for WebSocketSession {
         // Schedule authentication timeout
         self.schedule_auth_timeout(ctx);
 
-        // Send connection status
+        if let Some(shutdown) = self.shutdown.clone() {
+            forward_shutdown_notices(&shutdown, ctx);
+        }
+
+        // Send connection status; the session ID follows once authenticated
         let msg = ServerMessage::ConnectionStatus {
             status: ConnectionStatus::Connected,
-            session_id: Some(self.session_id.to_string()),
+            session_id: None,
         };
 
         if let Ok(json) = serde_json::to_string(&msg) {
             ctx.text(json);
         }
+
+        // Token presented on the upgrade request
+        if let Some(user_context) = self.upgrade_user.take() {
+            self.accept_user(user_context, ctx);
+        }
     }
 
     fn stopped(&mut self, _ctx: &mut Self::Context) {
         tracing::info!("WebSocket session stopped: {}", self.session_id);
 
-        // Clean up PTY
-        if let Some(pty_id) = &self.pty_id {
-            let pty_id = pty_id.clone();
-            let pty_manager = &self.pty_manager;
-            let _ = tokio::task::block_in_place(|| {
-                tokio::runtime::Handle::current().block_on(async {
-                    if let Err(e) = pty_manager.kill(&pty_id).await {
-                        tracing::error!("Failed to kill PTY on session close: {}", e);
-                    }
-                })
-            });
+        if self.latency.is_high_latency() {
+            metrics::WEBSOCKET_HIGH_LATENCY_CONNECTIONS.dec();
+        }
+
+        // Detach; the PTY and session outlive the connection briefly so the
+        // client can reconnect
+        if self.user_context.is_some() {
+            reap_detached_session(
+                self.pty_manager.clone(),
+                self.session_manager.clone(),
+                self.session_id.clone(),
+                self.pty_id.take(),
+            );
+        }
+    }
+}
+
+impl Handler<PtyOutput> for WebSocketSession {
+    type Result = ();
+
+    /// Per FR-3.3: Real-time streaming
+    /// Per spec-kit/007-websocket-spec.md: Binary output framing once negotiated
+    fn handle(&mut self, msg: PtyOutput, ctx: &mut Self::Context) {
+        if self.frozen {
+            self.held_output.extend_from_slice(&msg.0);
+            let excess = self.held_output.len().saturating_sub(SCROLLBACK_BYTES);
+            self.held_output.drain(..excess);
+            return;
+        }
+        self.send_output(&msg.0, ctx);
+    }
+}
+
+impl Handler<PtyClosed> for WebSocketSession {
+    type Result = ();
+
+    fn handle(&mut self, _msg: PtyClosed, ctx: &mut Self::Context) {
+        tracing::info!("PTY output closed for session {}", self.session_id);
+        self.pty_id = None;
+        let msg = ServerMessage::ConnectionStatus {
+            status: ConnectionStatus::Disconnected,
+            session_id: Some(self.session_id.to_string()),
+        };
+        if let Ok(json) = serde_json::to_string(&msg) {
+            ctx.text(json);
+        }
+        ctx.close(Some(ws::CloseCode::Normal.into()));
+        ctx.stop();
+    }
+}
+
+impl Handler<ShutdownWarning> for WebSocketSession {
+    type Result = ();
+
+    /// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+    fn handle(&mut self, msg: ShutdownWarning, ctx: &mut Self::Context) {
+        if let Ok(json) = serde_json::to_string(&msg.0.to_message()) {
+            ctx.text(json);
+        }
+        if msg.0.is_final() {
+            ctx.close(Some(ws::CloseCode::Away.into()));
+            ctx.stop();
+        }
+    }
+}
+
+impl Handler<AccessGrantChanged> for WebSocketSession {
+    type Result = ();
+
+    /// Per spec-kit/011-authentication-spec.md: Just-in-Time Access
+    fn handle(&mut self, msg: AccessGrantChanged, ctx: &mut Self::Context) {
+        self.send_access_grant(&msg.0, ctx);
+        // Revoked access ends now rather than on the next heartbeat
+        if msg.0.session_id == self.session_id && msg.0.status == GrantStatus::Revoked {
+            self.close_if_access_ended(ctx);
         }
     }
 }
@@ -447,39 +1478,55 @@ impl StreamHandler<std::result::Result<ws::Message, ws::ProtocolError>> for WebS
                                 self.authenticate(token, ctx);
                             }
                             ClientMessage::Command { data } => {
-                                if !self.require_auth(ctx) {
+                                if !self.require_auth(ctx)
+                                    || !self.authorize(Permission::SendInput, ctx)
+                                {
                                     return;
                                 }
                                 self.handle_command(data, ctx);
                             }
                             ClientMessage::Resize { cols, rows } => {
-                                if !self.require_auth(ctx) {
+                                if !self.require_auth(ctx)
+                                    || !self.authorize(Permission::SendInput, ctx)
+                                {
                                     return;
                                 }
                                 self.handle_resize(cols, rows, ctx);
                             }
                             ClientMessage::Signal { signal } => {
-                                if !self.require_auth(ctx) {
+                                if !self.require_auth(ctx)
+                                    || !self.authorize(Permission::SendInput, ctx)
+                                {
                                     return;
                                 }
                                 self.handle_signal(signal, ctx);
                             }
                             ClientMessage::EnvSet { key, value } => {
-                                if !self.require_auth(ctx) {
+                                if !self.require_auth(ctx)
+                                    || !self.authorize(Permission::SendInput, ctx)
+                                {
                                     return;
                                 }
                                 self.handle_env_set(key, value, ctx);
                             }
                             ClientMessage::Chdir { path } => {
-                                if !self.require_auth(ctx) {
+                                if !self.require_auth(ctx)
+                                    || !self.authorize(Permission::SendInput, ctx)
+                                {
                                     return;
                                 }
                                 self.handle_chdir(path, ctx);
                             }
-                            ClientMessage::FileUploadStart { .. } => {
-                                if !self.require_auth(ctx) {
+                            ClientMessage::FileUploadStart { path, size, .. } => {
+                                if !self.require_auth(ctx)
+                                    || !self.authorize(Permission::SendInput, ctx)
+                                {
                                     return;
                                 }
+                                self.audit_file_transfer(
+                                    AuditAction::FileUpload,
+                                    format!("{} ({} bytes)", path, size),
+                                );
                                 // TODO: Implement file upload
                                 self.send_error(
                                     error_codes::INTERNAL_ERROR,
@@ -488,15 +1535,25 @@ impl StreamHandler<std::result::Result<ws::Message, ws::ProtocolError>> for WebS
                                 );
                             }
                             ClientMessage::FileUploadComplete { .. } => {
-                                if !self.require_auth(ctx) {
+                                if !self.require_auth(ctx)
+                                    || !self.authorize(Permission::SendInput, ctx)
+                                {
                                     return;
                                 }
                                 // TODO: Implement file upload
+                                self.send_error(
+                                    error_codes::INTERNAL_ERROR,
+                                    "File upload not yet implemented",
+                                    ctx,
+                                );
                             }
-                            ClientMessage::FileDownload { .. } => {
-                                if !self.require_auth(ctx) {
+                            ClientMessage::FileDownload { path } => {
+                                if !self.require_auth(ctx)
+                                    || !self.authorize(Permission::SendInput, ctx)
+                                {
                                     return;
                                 }
+                                self.audit_file_transfer(AuditAction::FileDownload, path);
                                 // TODO: Implement file download
                                 self.send_error(
                                     error_codes::INTERNAL_ERROR,
@@ -504,17 +1561,21 @@ impl StreamHandler<std::result::Result<ws::Message, ws::ProtocolError>> for WebS
                                     ctx,
                                 );
                             }
-                            ClientMessage::Ping => {
+                            ClientMessage::Ping { timestamp } => {
                                 self.last_heartbeat = Instant::now();
-                                let msg = ServerMessage::Pong {
-                                    timestamp: Some(
+                                // Echo the client's timestamp so it can compute its own RTT
+                                let timestamp = timestamp.or_else(|| {
+                                    Some(
                                         std::time::SystemTime::now()
                                             .duration_since(std::time::UNIX_EPOCH)
                                             .unwrap()
                                             .as_millis()
                                             as u64,
-                                    ),
-                                    latency_ms: None,
+                                    )
+                                });
+                                let msg = ServerMessage::Pong {
+                                    timestamp,
+                                    latency_ms: self.latency.last_ms(),
                                 };
                                 if let Ok(json) = serde_json::to_string(&msg) {
                                     ctx.text(json);
@@ -523,6 +1584,17 @@ impl StreamHandler<std::result::Result<ws::Message, ws::ProtocolError>> for WebS
                             ClientMessage::Echo { data } => {
                                 self.handle_echo(data, ctx);
                             }
+                            ClientMessage::NegotiateCompression { algorithms } => {
+                                self.handle_negotiate_compression(algorithms, ctx);
+                            }
+                            ClientMessage::AccessDecision {
+                                request_id,
+                                approve,
+                            } => {
+                                if self.require_auth(ctx) {
+                                    self.handle_access_decision(request_id, approve, ctx);
+                                }
+                            }
                         }
                     }
                     Err(e) => {
@@ -541,7 +1613,7 @@ impl StreamHandler<std::result::Result<ws::Message, ws::ProtocolError>> for WebS
                     return;
                 }
 
-                if !self.require_auth(ctx) {
+                if !self.require_auth(ctx) || !self.authorize(Permission::SendInput, ctx) {
                     return;
                 }
 
@@ -549,7 +1621,7 @@ impl StreamHandler<std::result::Result<ws::Message, ws::ProtocolError>> for WebS
                 if let Some(pty_id) = &self.pty_id {
                     let pty_id = pty_id.clone();
                     match self.pty_manager.create_writer(&pty_id) {
-                        Ok(mut writer) => {
+                        Ok(writer) => {
                             actix_web::rt::spawn(async move {
                                 if let Err(e) = writer.write(&bin).await {
                                     tracing::error!("Failed to write binary to PTY: {}", e);
@@ -566,8 +1638,15 @@ impl StreamHandler<std::result::Result<ws::Message, ws::ProtocolError>> for WebS
                 self.last_heartbeat = Instant::now();
                 ctx.pong(&msg);
             }
-            Ok(ws::Message::Pong(_)) => {
+            Ok(ws::Message::Pong(msg)) => {
                 self.last_heartbeat = Instant::now();
+                record_heartbeat_rtt(
+                    &mut self.latency,
+                    "json",
+                    &msg,
+                    &self.session_manager,
+                    &self.session_id,
+                );
             }
             Ok(ws::Message::Close(reason)) => {
                 tracing::info!("WebSocket close: {:?}", reason);
@@ -591,14 +1670,16 @@ mod tests {
 
     #[test]
     fn test_websocket_session_creation() {
-        let session_id = SessionId::generate();
         let session_manager = Arc::new(SessionManager::new(SessionConfig::default()));
-        let pty_manager = PtyManager::with_defaults();
+        let pty_manager = Arc::new(PtyManager::with_defaults());
         let config = crate::config::Config::default();
         let jwks_client = Arc::new(JwksClient::new(config.auth.clone()));
         let jwt_validator = Arc::new(JwtValidator::new(jwks_client, config.auth));
 
-        let _ws_session =
-            WebSocketSession::new(session_id, session_manager, pty_manager, jwt_validator);
+        let target = SessionId::generate();
+        let ws_session = WebSocketSession::new(session_manager, pty_manager, jwt_validator)
+            .with_attach_target(target.clone());
+        assert_eq!(ws_session.attach_to, Some(target));
+        assert!(ws_session.user_context.is_none());
     }
 }
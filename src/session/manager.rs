@@ -3,15 +3,19 @@
 //! Implements SessionManager with DashMap for in-memory storage
 //! as specified in spec-kit/003-backend-spec.md section 2.1
 
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 
 use super::state::{Session, SessionId, UserId};
+use crate::config::auth::{TenancyConfig, TenantConfig};
 use crate::error::{Error, Result};
+use crate::pty::PtyConfig;
 
 /// Session configuration
 /// Per spec-kit/003-backend-spec.md section 2.1
@@ -26,6 +30,35 @@ pub struct SessionConfig {
     pub workspace_quota: u64,
     /// Maximum processes per session
     pub max_processes: usize,
+    /// Named shell setups sessions can be started with
+    #[serde(default)]
+    pub profiles: HashMap<String, SessionProfile>,
+}
+
+/// Shell setup a session can request by name, e.g. a `restricted` profile
+/// running `rbash`
+/// Per spec-kit/003-backend-spec.md section 2.1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionProfile {
+    /// Shell to run
+    pub shell: PathBuf,
+    /// Shell arguments
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment variables added to the shell's
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+impl SessionProfile {
+    /// PTY configuration running this profile, otherwise as `base`
+    pub fn pty_config(&self, base: &PtyConfig) -> PtyConfig {
+        let mut config = base.clone();
+        config.shell.shell_path = self.shell.clone();
+        config.shell.args = self.args.clone();
+        config.env.extend(self.env.clone());
+        config
+    }
 }
 
 impl Default for SessionConfig {
@@ -35,6 +68,7 @@ impl Default for SessionConfig {
             max_sessions_per_user: 10,
             workspace_quota: 1024 * 1024 * 1024, // 1GB
             max_processes: 10,
+            profiles: HashMap::new(),
         }
     }
 }
@@ -47,8 +81,12 @@ pub struct SessionManager {
     sessions: DashMap<SessionId, Arc<Session>>,
     /// User sessions tracking (user_id -> Vec<SessionId>)
     user_sessions: DashMap<UserId, Vec<SessionId>>,
-    /// Configuration
-    config: SessionConfig,
+    /// Configuration (replaced on reload)
+    config: RwLock<Arc<SessionConfig>>,
+    /// Workspace roots and limits by tenant name
+    tenants: HashMap<String, TenantConfig>,
+    /// Set once shutdown begins; no new sessions are created
+    draining: AtomicBool,
 }
 
 impl SessionManager {
@@ -58,28 +96,141 @@ impl SessionManager {
         Self {
             sessions: DashMap::new(),
             user_sessions: DashMap::new(),
-            config,
+            config: RwLock::new(Arc::new(config)),
+            tenants: HashMap::new(),
+            draining: AtomicBool::new(false),
         }
     }
 
+    /// Apply the workspace roots and session limits of `tenancy`'s tenants
+    /// Per spec-kit/011-authentication-spec.md: Multi-Tenancy
+    pub fn with_tenants(mut self, tenancy: &TenancyConfig) -> Self {
+        self.tenants = tenancy
+            .tenants
+            .iter()
+            .map(|tenant| (tenant.name.clone(), tenant.clone()))
+            .collect();
+        self
+    }
+
+    /// Swap in new limits, timeout and profiles; existing sessions keep
+    /// running and are held to the new limits from now on
+    /// Per spec-kit/005-cli-spec.md: Live Reload
+    pub fn replace_config(&self, config: SessionConfig) {
+        *self.config.write().expect("session config lock poisoned") = Arc::new(config);
+    }
+
+    /// Refuse new sessions from now on; existing sessions can still be
+    /// attached until they are hung up
+    /// Per spec-kit/005-cli-spec.md: Graceful Shutdown
+    pub fn start_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether shutdown has begun
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    fn config(&self) -> Arc<SessionConfig> {
+        self.config
+            .read()
+            .expect("session config lock poisoned")
+            .clone()
+    }
+
     /// Create a new session for a user
     /// Per spec-kit/003-backend-spec.md section 2.1
     pub async fn create_session(&self, user_id: UserId) -> Result<Arc<Session>> {
+        self.create_session_with_profile(user_id, None).await
+    }
+
+    /// Create a new session for a user running the named profile
+    /// Per spec-kit/003-backend-spec.md section 2.1
+    pub async fn create_session_with_profile(
+        &self,
+        user_id: UserId,
+        profile: Option<String>,
+    ) -> Result<Arc<Session>> {
+        self.create_tenant_session(user_id, None, profile).await
+    }
+
+    /// Create a new session for a user of `tenant` (already qualified as
+    /// `<tenant>/<sub>`) running the named profile, within the tenant's
+    /// limits and workspace root
+    /// Per spec-kit/011-authentication-spec.md: Multi-Tenancy
+    pub async fn create_tenant_session(
+        &self,
+        user_id: UserId,
+        tenant: Option<&str>,
+        profile: Option<String>,
+    ) -> Result<Arc<Session>> {
+        if self.is_draining() {
+            return Err(Error::ShuttingDown);
+        }
+        let config = self.config();
+        let tenant_config = tenant.and_then(|name| self.tenants.get(name));
+        if let Some(name) = &profile {
+            if !config.profiles.contains_key(name) {
+                return Err(Error::validation(format!(
+                    "Unknown session profile: {}",
+                    name
+                )));
+            }
+        }
+
         // Check user session limit
+        let max_sessions_per_user = tenant_config
+            .and_then(|t| t.max_sessions_per_user)
+            .unwrap_or(config.max_sessions_per_user);
         if let Some(sessions) = self.user_sessions.get(&user_id) {
-            if sessions.len() >= self.config.max_sessions_per_user {
+            if sessions.len() >= max_sessions_per_user {
                 return Err(Error::SessionLimitExceeded(format!(
                     "User {} has reached maximum session limit of {}",
-                    user_id, self.config.max_sessions_per_user
+                    user_id, max_sessions_per_user
+                )));
+            }
+        }
+
+        // Check tenant session limit
+        if let (Some(name), Some(max)) = (tenant, tenant_config.and_then(|t| t.max_sessions)) {
+            if self.tenant_session_count(name) >= max {
+                return Err(Error::SessionLimitExceeded(format!(
+                    "Tenant {} has reached maximum session limit of {}",
+                    name, max
                 )));
             }
         }
 
-        // Create workspace directory
-        let workspace_root = PathBuf::from(format!("/workspace/{}", user_id));
+        // Workspace directory: the subject must stay inside the root
+        let (root, sub) = match tenant_config.and_then(|t| t.workspace_root.as_ref()) {
+            Some(root) => {
+                let prefix = format!("{}/", tenant.unwrap_or_default());
+                let sub = user_id.as_str();
+                (
+                    root.clone(),
+                    sub.strip_prefix(prefix.as_str()).unwrap_or(sub),
+                )
+            }
+            None => (PathBuf::from("/workspace"), user_id.as_str()),
+        };
+        let sub = Path::new(sub);
+        if sub.as_os_str().is_empty()
+            || !sub
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(Error::InvalidPath(format!(
+                "User {} cannot name a workspace",
+                user_id
+            )));
+        }
+        let workspace_root = root.join(sub);
 
         // Create session
-        let session = Session::new(user_id.clone(), workspace_root);
+        let mut session = Session::new(user_id.clone(), workspace_root);
+        session.profile = profile;
+        session.tenant = tenant.map(str::to_string);
         let session_id = session.id.clone();
         let session_arc = Arc::new(session);
 
@@ -97,6 +248,47 @@ impl SessionManager {
         Ok(session_arc)
     }
 
+    /// PTY configuration for `session`: its profile, otherwise as `base`,
+    /// started in its workspace
+    ///
+    /// The workspace is created if it is missing. A tenant's shell never
+    /// starts outside its workspace; other sessions fall back to `base`'s
+    /// working directory when theirs cannot be created.
+    /// Per spec-kit/011-authentication-spec.md: Multi-Tenancy
+    pub async fn pty_config(&self, session: &Session, base: &PtyConfig) -> Result<PtyConfig> {
+        let mut config = match session
+            .profile
+            .as_deref()
+            .and_then(|name| self.profile(name))
+        {
+            Some(profile) => profile.pty_config(base),
+            None => base.clone(),
+        };
+        let workspace = session.get_working_dir().await;
+        match tokio::fs::create_dir_all(&workspace).await {
+            Ok(()) => config.working_dir = workspace,
+            Err(e) if session.tenant.is_some() => {
+                return Err(Error::Internal(format!(
+                    "Cannot create workspace {}: {}",
+                    workspace.display(),
+                    e
+                )));
+            }
+            Err(e) => tracing::warn!(
+                "Cannot create workspace {}, starting in {}: {}",
+                workspace.display(),
+                config.working_dir.display(),
+                e
+            ),
+        }
+        Ok(config)
+    }
+
+    /// Look up a configured session profile
+    pub fn profile(&self, name: &str) -> Option<SessionProfile> {
+        self.config().profiles.get(name).cloned()
+    }
+
     /// Get a session by ID
     /// Per spec-kit/003-backend-spec.md section 2.1
     pub async fn get_session(&self, session_id: &SessionId) -> Result<Arc<Session>> {
@@ -170,6 +362,14 @@ impl SessionManager {
         self.sessions.len()
     }
 
+    /// Get session count for a tenant
+    pub fn tenant_session_count(&self, tenant: &str) -> usize {
+        self.sessions
+            .iter()
+            .filter(|entry| entry.value().tenant.as_deref() == Some(tenant))
+            .count()
+    }
+
     /// Get session count for a specific user
     pub fn user_session_count(&self, user_id: &UserId) -> usize {
         self.user_sessions
@@ -181,17 +381,7 @@ impl SessionManager {
     /// Clean up expired sessions
     /// Per spec-kit/003-backend-spec.md section 2.1
     pub async fn cleanup_expired_sessions(&self) -> Result<usize> {
-        let now = Instant::now();
-        let mut expired = Vec::new();
-
-        // Find expired sessions
-        for entry in self.sessions.iter() {
-            let session = entry.value();
-            if now.duration_since(session.last_activity) > self.config.timeout {
-                expired.push(entry.key().clone());
-            }
-        }
-
+        let expired = self.expired_sessions();
         let count = expired.len();
 
         // Destroy expired sessions
@@ -208,6 +398,17 @@ impl SessionManager {
         Ok(count)
     }
 
+    /// Sessions idle for longer than the session timeout
+    pub fn expired_sessions(&self) -> Vec<SessionId> {
+        let now = Instant::now();
+        let timeout = self.config().timeout;
+        self.sessions
+            .iter()
+            .filter(|entry| now.duration_since(entry.value().last_activity) > timeout)
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
+
     /// Start background cleanup task
     pub fn start_cleanup_task(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
@@ -225,6 +426,7 @@ impl SessionManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pty::PtyManager;
 
     #[tokio::test]
     async fn test_create_session() {
@@ -248,6 +450,23 @@ mod tests {
         assert_eq!(retrieved.id, session_id);
     }
 
+    #[tokio::test]
+    async fn test_draining_refuses_new_sessions() {
+        let manager = SessionManager::new(SessionConfig::default());
+        let session = manager
+            .create_session(UserId::new("alice".to_string()))
+            .await
+            .unwrap();
+
+        manager.start_draining();
+        let err = manager
+            .create_session(UserId::new("bob".to_string()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ShuttingDown));
+        assert!(manager.get_session(&session.id).await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_session_limit() {
         let config = SessionConfig {
@@ -266,6 +485,137 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_session_profiles() {
+        let mut config = SessionConfig::default();
+        config.profiles.insert(
+            "restricted".to_string(),
+            SessionProfile {
+                shell: PathBuf::from("/bin/rbash"),
+                args: vec!["--noprofile".to_string()],
+                env: HashMap::from([("HISTFILE".to_string(), "/dev/null".to_string())]),
+            },
+        );
+        let manager = SessionManager::new(config);
+        let user_id = UserId::new("test_user".to_string());
+
+        let session = manager
+            .create_session_with_profile(user_id.clone(), Some("restricted".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(session.profile.as_deref(), Some("restricted"));
+        assert!(manager
+            .create_session_with_profile(user_id, Some("root".to_string()))
+            .await
+            .is_err());
+
+        let pty = manager
+            .profile("restricted")
+            .unwrap()
+            .pty_config(&PtyConfig::default());
+        assert_eq!(pty.shell.shell_path, PathBuf::from("/bin/rbash"));
+        assert_eq!(
+            pty.env.get("HISTFILE").map(String::as_str),
+            Some("/dev/null")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tenant_sessions() {
+        let acme_root = tempfile::tempdir().unwrap();
+        let tenancy: TenancyConfig = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "tenants": [
+                {
+                    "name": "acme",
+                    "workspace_root": acme_root.path(),
+                    "max_sessions_per_user": 1,
+                    "max_sessions": 2,
+                },
+                { "name": "globex" },
+            ],
+        }))
+        .unwrap();
+        let manager = SessionManager::new(SessionConfig::default()).with_tenants(&tenancy);
+        let acme_alice = UserId::new("acme/alice".to_string());
+        let globex_alice = UserId::new("globex/alice".to_string());
+
+        let session = manager
+            .create_tenant_session(acme_alice.clone(), Some("acme"), None)
+            .await
+            .unwrap();
+        assert_eq!(session.tenant.as_deref(), Some("acme"));
+        let workspace = acme_root.path().join("alice");
+        assert_eq!(session.get_working_dir().await, workspace);
+
+        // The shell starts in the workspace, which is created on demand
+        let pty_manager = PtyManager::with_defaults();
+        let config = manager
+            .pty_config(&session, pty_manager.default_config())
+            .await
+            .unwrap();
+        assert!(workspace.is_dir());
+        let handle = pty_manager.spawn(Some(config)).unwrap();
+        let mut output = pty_manager.subscribe(handle.id()).unwrap().receiver;
+        pty_manager
+            .create_writer(handle.id())
+            .unwrap()
+            .write_str("echo cwd=$(pwd)\n")
+            .await
+            .unwrap();
+        let expected = format!("cwd={}", workspace.display());
+        let mut seen = String::new();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !seen.contains(&expected) {
+                let chunk = output.recv().await.expect("PTY output closed");
+                seen.push_str(&String::from_utf8_lossy(&chunk));
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("Shell did not start in {}: {:?}", workspace.display(), seen));
+        pty_manager.kill(handle.id()).await.unwrap();
+
+        // Subjects cannot climb out of the workspace root
+        for user in ["acme/../../etc", "acme//etc", "acme/alice/../../bob"] {
+            let err = manager
+                .create_tenant_session(UserId::new(user.to_string()), Some("acme"), None)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::InvalidPath(_)), "{}: {:?}", user, err);
+        }
+        assert!(manager
+            .create_session(UserId::new("/etc".to_string()))
+            .await
+            .is_err());
+
+        // Same subject in another tenant is a different user
+        let session = manager
+            .create_tenant_session(globex_alice.clone(), Some("globex"), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            session.get_working_dir().await,
+            PathBuf::from("/workspace/globex/alice")
+        );
+        assert_eq!(manager.user_session_count(&acme_alice), 1);
+        assert_eq!(manager.user_session_count(&globex_alice), 1);
+
+        // Per-user limit of the tenant, then the tenant-wide limit
+        assert!(manager
+            .create_tenant_session(acme_alice, Some("acme"), None)
+            .await
+            .is_err());
+        manager
+            .create_tenant_session(UserId::new("acme/bob".to_string()), Some("acme"), None)
+            .await
+            .unwrap();
+        assert!(manager
+            .create_tenant_session(UserId::new("acme/carol".to_string()), Some("acme"), None)
+            .await
+            .is_err());
+        assert_eq!(manager.tenant_session_count("acme"), 2);
+    }
+
     #[tokio::test]
     async fn test_destroy_session() {
         let manager = SessionManager::new(SessionConfig::default());
@@ -7,6 +7,6 @@ pub mod manager;
 pub mod registry;
 pub mod state;
 
-pub use manager::{SessionConfig, SessionManager};
+pub use manager::{SessionConfig, SessionManager, SessionProfile};
 pub use registry::SessionRegistry;
 pub use state::{ProcessHandle, ProcessId, Session, SessionId, SessionState, UserId};
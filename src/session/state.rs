@@ -12,6 +12,7 @@ use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::error::Result;
+use crate::monitoring::LatencySnapshot;
 
 /// Unique session identifier
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -107,6 +108,8 @@ pub struct SessionState {
     pub command_history: Vec<String>,
     /// Running processes
     pub processes: HashMap<ProcessId, ProcessHandle>,
+    /// Heartbeat latency of the attached connection
+    pub latency: Option<LatencySnapshot>,
 }
 
 impl SessionState {
@@ -117,6 +120,7 @@ impl SessionState {
             environment: Self::default_environment(),
             command_history: Vec::new(),
             processes: HashMap::new(),
+            latency: None,
         }
     }
 
@@ -143,6 +147,10 @@ pub struct Session {
     pub created_at: Instant,
     /// Last activity timestamp
     pub last_activity: Instant,
+    /// Session profile the shell runs with (`None` for the default shell)
+    pub profile: Option<String>,
+    /// Tenant of the owner (`None` without tenancy)
+    pub tenant: Option<String>,
     /// Session state (protected by RwLock for concurrent access)
     state: Arc<RwLock<SessionState>>,
 }
@@ -158,6 +166,8 @@ impl Session {
             user_id,
             created_at: now,
             last_activity: now,
+            profile: None,
+            tenant: None,
             state: Arc::new(RwLock::new(SessionState::new(workspace_root))),
         }
     }
@@ -255,6 +265,19 @@ impl Session {
         state.environment.get("PTY_ID").cloned()
     }
 
+    /// Record the latest heartbeat latency of the attached connection
+    /// Per spec-kit/007-websocket-spec.md: Heartbeat mechanism
+    pub async fn set_latency(&self, latency: LatencySnapshot) {
+        let mut state = self.state.write().await;
+        state.latency = Some(latency);
+    }
+
+    /// Get the latest heartbeat latency, if any has been measured
+    pub async fn get_latency(&self) -> Option<LatencySnapshot> {
+        let state = self.state.read().await;
+        state.latency.clone()
+    }
+
     /// Kill all processes in this session
     pub async fn kill_all_processes(&self) -> Result<()> {
         let state = self.state.read().await;
@@ -4,6 +4,14 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
 
+/// A command whose runtime directory holds no control socket, so no server
+/// is running as far as it can tell (exit code 11)
+fn without_server(runtime: &tempfile::TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("web-terminal").unwrap();
+    cmd.env("WEB_TERMINAL__SERVER__CONTROL__RUNTIME_DIR", runtime.path());
+    cmd
+}
+
 #[test]
 fn test_cli_help() {
     let mut cmd = Command::cargo_bin("web-terminal").unwrap();
@@ -32,48 +40,78 @@ fn test_start_command_help() {
 }
 
 #[test]
-fn test_config_show() {
+fn test_attach_command_help() {
     let mut cmd = Command::cargo_bin("web-terminal").unwrap();
-    cmd.args(&["config", "show"])
+    cmd.args(&["attach", "--help"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("Configuration"));
+        .stdout(predicate::str::contains("Attach the local terminal"))
+        .stdout(predicate::str::contains("--escape"));
 }
 
 #[test]
-fn test_sessions_list() {
+fn test_attach_requires_token() {
     let mut cmd = Command::cargo_bin("web-terminal").unwrap();
-    cmd.args(&["sessions", "list"])
+    cmd.env_remove("WEB_TERMINAL_TOKEN")
+        .args(&["attach", "new"])
         .assert()
-        .success()
-        .stdout(predicate::str::contains("Active Sessions"));
+        .failure()
+        .stderr(predicate::str::contains("--token"));
 }
 
 #[test]
-fn test_health_check() {
+fn test_config_show() {
+    let dir = tempfile::tempdir().unwrap();
     let mut cmd = Command::cargo_bin("web-terminal").unwrap();
-    cmd.args(&["health"])
+    cmd.current_dir(dir.path())
+        .env_remove("WEB_TERMINAL_CONFIG")
+        .env("WEB_TERMINAL__SERVER__HOST", "0123")
+        .args(&["config", "show"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("Health Check"));
+        .stdout(predicate::str::starts_with("[server]"))
+        .stdout(predicate::str::contains("host = \"0123\""))
+        .stdout(predicate::str::contains("port = 8080"));
 }
 
 #[test]
-fn test_metrics_display() {
-    let mut cmd = Command::cargo_bin("web-terminal").unwrap();
-    cmd.args(&["metrics"])
+fn test_sessions_list_without_server() {
+    let runtime = tempfile::tempdir().unwrap();
+    without_server(&runtime)
+        .args(&["sessions", "list"])
         .assert()
-        .success()
-        .stdout(predicate::str::contains("Server Metrics"));
+        .code(11)
+        .stderr(predicate::str::contains("Server not running"));
+}
+
+#[test]
+fn test_health_check_without_server() {
+    let runtime = tempfile::tempdir().unwrap();
+    without_server(&runtime)
+        .args(&["health"])
+        .assert()
+        .code(11)
+        .stderr(predicate::str::contains("Server not running"));
+}
+
+#[test]
+fn test_metrics_without_server() {
+    let runtime = tempfile::tempdir().unwrap();
+    without_server(&runtime)
+        .args(&["metrics"])
+        .assert()
+        .code(11)
+        .stderr(predicate::str::contains("Server not running"));
 }
 
 #[test]
 fn test_status_json_output() {
-    let mut cmd = Command::cargo_bin("web-terminal").unwrap();
-    cmd.args(&["status", "--json"])
+    let runtime = tempfile::tempdir().unwrap();
+    without_server(&runtime)
+        .args(&["status", "--json"])
         .assert()
-        .success()
-        .stdout(predicate::str::contains("\"running\""));
+        .code(11)
+        .stdout(predicate::str::contains("\"running\":false"));
 }
 
 #[test]
@@ -93,21 +131,84 @@ fn test_invalid_command() {
 
 #[test]
 fn test_global_verbose_flag() {
-    let mut cmd = Command::cargo_bin("web-terminal").unwrap();
-    cmd.args(&["--verbose", "status"]).assert().success();
+    let runtime = tempfile::tempdir().unwrap();
+    without_server(&runtime)
+        .args(&["--verbose", "status"])
+        .assert()
+        .code(11);
 }
 
 #[test]
 fn test_global_quiet_flag() {
-    let mut cmd = Command::cargo_bin("web-terminal").unwrap();
-    cmd.args(&["--quiet", "status"]).assert().success();
+    let runtime = tempfile::tempdir().unwrap();
+    without_server(&runtime)
+        .args(&["--quiet", "status"])
+        .assert()
+        .code(11);
 }
 
 #[test]
 fn test_config_env_override() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("custom-config.toml");
+    std::fs::write(&file, "[server]\nport = 9100\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("web-terminal").unwrap();
+    cmd.env("WEB_TERMINAL_CONFIG", &file)
+        .args(&["config", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("port = 9100"));
+
+    // A configuration file that was asked for must exist
     let mut cmd = Command::cargo_bin("web-terminal").unwrap();
-    cmd.env("WEB_TERMINAL_CONFIG", "custom-config.toml")
+    cmd.env("WEB_TERMINAL_CONFIG", dir.path().join("missing.toml"))
         .args(&["config", "show"])
         .assert()
-        .success();
+        .code(2)
+        .stderr(predicate::str::contains("Missing configuration file"));
+}
+
+#[test]
+fn test_audit_verify_requires_the_whole_chain() {
+    use web_terminal::config::auth::AuditConfig;
+    use web_terminal::security::audit::{AuditAction, AuditEvent, AuditLogger};
+
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("audit.log");
+    let key = dir.path().join("audit.key");
+    let logger = AuditLogger::open(AuditConfig {
+        path: Some(log.clone()),
+        hmac_key_file: Some(key.clone()),
+        max_file_size: 0,
+        ..AuditConfig::default()
+    })
+    .unwrap();
+    for user in ["alice", "bob"] {
+        logger.record(AuditEvent::new(AuditAction::AuthSuccess).user(user));
+    }
+    logger.flush().unwrap();
+
+    let verify = |extra: &[&str]| {
+        let mut cmd = Command::cargo_bin("web-terminal").unwrap();
+        cmd.args(&["audit", "verify"])
+            .arg(&log)
+            .arg("--key-file")
+            .arg(&key)
+            .args(extra);
+        cmd
+    };
+    verify(&[])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Records:   2"));
+
+    // Dropping the first record leaves a valid but incomplete chain
+    let content = std::fs::read_to_string(&log).unwrap();
+    std::fs::write(&log, content.lines().nth(1).unwrap()).unwrap();
+    verify(&[])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("starts at record 1"));
+    verify(&["--allow-partial"]).assert().success();
 }
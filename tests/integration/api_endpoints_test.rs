@@ -177,6 +177,7 @@ fn test_list_sessions_query_validation_logic() {
     let query = ListSessionsQuery {
         limit: Some(50),
         offset: Some(0),
+        high_latency: None,
     };
     assert!(query.validate().is_ok());
 
@@ -184,6 +185,7 @@ fn test_list_sessions_query_validation_logic() {
     let query = ListSessionsQuery {
         limit: Some(200),
         offset: Some(0),
+        high_latency: None,
     };
     assert!(query.validate().is_err());
 
@@ -191,6 +193,7 @@ fn test_list_sessions_query_validation_logic() {
     let query = ListSessionsQuery {
         limit: Some(0),
         offset: Some(0),
+        high_latency: None,
     };
     assert!(query.validate().is_err());
 }
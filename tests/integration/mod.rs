@@ -1,7 +1,6 @@
 // Integration tests
 // Per spec-kit/008-testing-spec.md
 
-mod auth_flow_test;
 mod authorization_test;
 mod concurrent_sessions_test;
 mod error_recovery_test;
@@ -1,10 +1,1374 @@
 // Integration tests for WebSocket communication
 // Per spec-kit/008-testing-spec.md - Integration Tests
 //
-// Tests WebSocket message protocol and real-time communication
+// Tests WebSocket message protocol and real-time communication, driving an
+// in-process server through the typed client library
 
+use std::sync::OnceLock;
 use std::time::Duration;
-use web_terminal::protocol::{ClientMessage, ConnectionStatus, ServerMessage, Signal};
+
+use futures_util::{SinkExt, StreamExt};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest, http::HeaderValue, Message};
+use web_terminal::client::{ClientConfig, ClientError, ClientEvent, TerminalClient};
+use web_terminal::config::auth::{JwksProvider, StaticKeySource, TokenExpiryAction, TokenSource};
+use web_terminal::config::Config;
+use web_terminal::protocol::{error_codes, ClientMessage, ConnectionStatus, ServerMessage, Signal};
+use web_terminal::server::Server;
+use web_terminal::session::{SessionManager, SessionProfile};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+const ISSUER: &str = "https://issuer.test";
+
+/// In-process server trusting the test signing key
+struct TestServer {
+    url: String,
+    _jwks: MockServer,
+}
+
+/// Keep the developer's login scripts out of the session shells
+fn isolate_home() {
+    static HOME: OnceLock<tempfile::TempDir> = OnceLock::new();
+    let home = HOME.get_or_init(|| tempfile::tempdir().expect("Failed to create HOME"));
+    std::env::set_var("HOME", home.path());
+}
+
+async fn start_server() -> TestServer {
+    start_server_with(|_| {}).await
+}
+
+async fn start_server_with(configure: impl FnOnce(&mut Config)) -> TestServer {
+    isolate_home();
+
+    let jwks = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/jwks"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            include_str!("../fixtures/test-jwks.json"),
+            "application/json",
+        ))
+        .mount(&jwks)
+        .await;
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|l| l.local_addr())
+        .expect("Failed to reserve port")
+        .port();
+
+    let mut config = Config::default();
+    config.server.host = "127.0.0.1".to_string();
+    config.server.port = port;
+    config.auth.enabled = true;
+    config.auth.jwks.providers = vec![JwksProvider {
+        name: "test".to_string(),
+        url: Some(format!("{}/jwks", jwks.uri())),
+        keys: None,
+        issuer: ISSUER.to_string(),
+        audience: "web-terminal".to_string(),
+        algorithms: vec!["RS256".to_string()],
+        cache_ttl: Duration::from_secs(3600),
+        refresh_interval: Duration::from_secs(900),
+        timeout: Duration::from_secs(5),
+    }];
+    config.auth.authorization.enable_wildcards = true;
+    config.auth.authorization.allowed_users = vec!["*".to_string()];
+    configure(&mut config);
+
+    let session_manager = SessionManager::new(config.session.clone());
+    let server = Server::new(config, session_manager);
+    actix_web::rt::spawn(server.run());
+
+    let addr = format!("127.0.0.1:{}", port);
+    for _ in 0..50 {
+        if tokio::net::TcpStream::connect(&addr).await.is_ok() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    TestServer {
+        url: format!("http://{}", addr),
+        _jwks: jwks,
+    }
+}
+
+/// Sign a token for `sub` with the test key
+fn token(sub: &str) -> String {
+    token_expiring_in(sub, 3600)
+}
+
+/// Sign a token for `sub` that expires in `secs` seconds
+fn token_expiring_in(sub: &str, secs: i64) -> String {
+    sign_token(ISSUER, sub, secs)
+}
+
+/// Sign a token from `issuer` for `sub` that expires in `secs` seconds
+fn sign_token(issuer: &str, sub: &str, secs: i64) -> String {
+    let now = chrono::Utc::now().timestamp();
+    sign_claims(serde_json::json!({
+        "sub": sub,
+        "iss": issuer,
+        "aud": "web-terminal",
+        "iat": now,
+        "exp": now + secs,
+    }))
+}
+
+/// Sign a short-lived WebSocket ticket for `sub` with token ID `jti`
+fn ticket(sub: &str, jti: &str) -> String {
+    let now = chrono::Utc::now().timestamp();
+    sign_claims(serde_json::json!({
+        "sub": sub,
+        "iss": ISSUER,
+        "aud": "web-terminal",
+        "iat": now,
+        "exp": now + 60,
+        "jti": jti,
+    }))
+}
+
+/// Sign `claims` with the test key
+fn sign_claims(claims: serde_json::Value) -> String {
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some("test-key".to_string());
+
+    let key = EncodingKey::from_rsa_pem(include_bytes!("../fixtures/test-rsa-key.pem"))
+        .expect("Invalid test key");
+    encode(&header, &claims, &key).expect("Failed to sign token")
+}
+
+/// Wait for the first server message matching `predicate`
+async fn wait_for_message(
+    client: &mut TerminalClient,
+    predicate: impl Fn(&ServerMessage) -> bool,
+) -> ServerMessage {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(event) = client.next().await {
+            if let ClientEvent::Message(msg) = event {
+                if predicate(&msg) {
+                    return msg;
+                }
+            }
+        }
+        panic!("Connection closed before the expected message");
+    })
+    .await
+    .expect("Timed out waiting for server message")
+}
+
+fn is_error(code: &'static str) -> impl Fn(&ServerMessage) -> bool {
+    move |msg| matches!(msg, ServerMessage::Error { code: c, .. } if c == code)
+}
+
+/// Collect output until `needle` appears
+async fn read_until(client: &mut TerminalClient, needle: &str) -> String {
+    let mut output = String::new();
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(event) = client.next().await {
+            if let ClientEvent::Output(data) = event {
+                output.push_str(&String::from_utf8_lossy(&data));
+                if output.contains(needle) {
+                    return;
+                }
+            }
+        }
+        panic!("Connection closed before {:?} appeared", needle);
+    })
+    .await
+    .unwrap_or_else(|_| panic!("Timed out waiting for {:?}, got {:?}", needle, output));
+    output
+}
+
+/// Test command input and streamed output over a real connection
+///
+/// Per FR-3: Real-time Communication via WebSocket
+#[actix_web::test]
+async fn test_client_streams_command_output() {
+    let server = start_server().await;
+
+    let mut client = TerminalClient::connect(ClientConfig::new(&server.url, token("alice")))
+        .await
+        .expect("Failed to connect");
+    assert_eq!(client.user_id(), "alice");
+    assert!(!client.session_id().is_empty());
+
+    // Arithmetic keeps the echoed command line from matching
+    client.send_input("echo ready-$((40+2))\n").unwrap();
+    read_until(&mut client, "ready-42").await;
+
+    client.close().await;
+}
+
+/// Test resize reaches the PTY
+///
+/// Per FR-2.1.5: Support terminal dimensions
+#[actix_web::test]
+async fn test_client_resize() {
+    let server = start_server().await;
+
+    let mut client = TerminalClient::connect(ClientConfig::new(&server.url, token("alice")))
+        .await
+        .expect("Failed to connect");
+
+    client.resize(101, 42).unwrap();
+    client.send_input("stty size\n").unwrap();
+    read_until(&mut client, "42 101").await;
+
+    client.close().await;
+}
+
+/// Test a second client attaching to an existing session
+///
+/// Per spec-kit/007-websocket-spec.md: Reconnection
+#[actix_web::test]
+async fn test_client_attach_shares_session() {
+    let server = start_server().await;
+
+    let mut first = TerminalClient::connect(ClientConfig::new(&server.url, token("alice")))
+        .await
+        .expect("Failed to connect");
+    let session_id = first.session_id();
+
+    first.send_input("echo before-$((1+1))\n").unwrap();
+    read_until(&mut first, "before-2").await;
+
+    let mut second =
+        TerminalClient::connect(ClientConfig::new(&server.url, token("alice")).attach(&session_id))
+            .await
+            .expect("Failed to attach");
+    assert_eq!(second.session_id(), session_id);
+
+    // Scrollback is replayed on attach, then both clients see new output
+    read_until(&mut second, "before-2").await;
+    second.send_input("echo after-$((2+2))\n").unwrap();
+    read_until(&mut first, "after-4").await;
+    read_until(&mut second, "after-4").await;
+
+    // The session outlives a closed client and can be re-attached
+    first.close().await;
+    second.close().await;
+    let mut third =
+        TerminalClient::connect(ClientConfig::new(&server.url, token("alice")).attach(&session_id))
+            .await
+            .expect("Failed to re-attach");
+    read_until(&mut third, "after-4").await;
+    third.close().await;
+}
+
+/// Test `web-terminal attach` proxies piped input and output
+///
+/// Per spec-kit/005-cli-spec.md
+#[actix_web::test]
+async fn test_attach_command_proxies_session() {
+    let server = start_server().await;
+    let url = server.url.clone();
+
+    let output = tokio::task::spawn_blocking(move || {
+        assert_cmd::Command::cargo_bin("web-terminal")
+            .unwrap()
+            .args(["attach", "new", "--url", &url, "--token", &token("alice")])
+            .write_stdin("echo attach-$((6*7))\nexit\n")
+            .timeout(Duration::from_secs(20))
+            .output()
+            .expect("Failed to run attach")
+    })
+    .await
+    .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "attach failed: {}", stderr);
+    assert!(
+        stdout.contains("attach-42"),
+        "unexpected output: {}",
+        stdout
+    );
+    assert!(stderr.contains("Attached to session"));
+    assert!(stderr.contains("Session ended"));
+}
+
+/// Test attaching to another user's session is denied
+///
+/// Per spec-kit/011-authentication-spec.md: Session ownership
+#[actix_web::test]
+async fn test_client_cannot_attach_to_other_users_session() {
+    let server = start_server().await;
+
+    let owner = TerminalClient::connect(ClientConfig::new(&server.url, token("alice")))
+        .await
+        .expect("Failed to connect");
+
+    let result = TerminalClient::connect(
+        ClientConfig::new(&server.url, token("mallory")).attach(owner.session_id()),
+    )
+    .await;
+    match result {
+        Err(ClientError::Server { code, .. }) => assert_eq!(code, error_codes::PERMISSION_DENIED),
+        other => panic!("Expected permission denied, got {:?}", other.err()),
+    }
+
+    owner.close().await;
+}
+
+/// Test admins can list, view (read-only) and kill other users' sessions
+/// while regular users cannot
+///
+/// Per spec-kit/011-authentication-spec.md section 5: Authorization Model
+#[actix_web::test]
+async fn test_admin_manages_other_users_sessions() {
+    let server = start_server_with(|config| {
+        config
+            .auth
+            .authorization
+            .role_mappings
+            .insert("admin".to_string(), vec!["root".to_string()]);
+    })
+    .await;
+    let http = reqwest::Client::new();
+    let session_url = |id: &str| format!("{}/api/v1/sessions/{}", server.url, id);
+
+    let owner = TerminalClient::connect(ClientConfig::new(&server.url, token("alice")))
+        .await
+        .expect("Failed to connect");
+    let session_id = owner.session_id();
+
+    for request in [
+        http.get(session_url(&session_id)),
+        http.delete(session_url(&session_id)),
+    ] {
+        let response = request.bearer_auth(token("bob")).send().await.unwrap();
+        assert_eq!(response.status(), 403);
+    }
+    let listed: serde_json::Value = http
+        .get(format!("{}/api/v1/sessions", server.url))
+        .bearer_auth(token("bob"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(listed["total"], 0);
+
+    // Other users' sessions cannot even be named in an explain request
+    let explain = |user: &str, session_id: &str| {
+        http.post(format!("{}/api/v1/authz/explain", server.url))
+            .bearer_auth(token(user))
+            .json(&serde_json::json!({
+                "permission": "send_input",
+                "session_id": session_id,
+            }))
+            .send()
+    };
+    assert_eq!(explain("bob", &session_id).await.unwrap().status(), 404);
+    assert_eq!(
+        explain("bob", "no-such-session").await.unwrap().status(),
+        404
+    );
+    assert_eq!(explain("alice", &session_id).await.unwrap().status(), 200);
+    assert_eq!(explain("root", &session_id).await.unwrap().status(), 200);
+
+    let listed: serde_json::Value = http
+        .get(format!("{}/api/v1/sessions", server.url))
+        .bearer_auth(token("root"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(listed["sessions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|s| s["id"] == session_id.as_str() && s["user_id"] == "alice"));
+    let response = http
+        .get(session_url(&session_id))
+        .bearer_auth(token("root"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    // Admins attach read-only
+    let mut viewer =
+        TerminalClient::connect(ClientConfig::new(&server.url, token("root")).attach(&session_id))
+            .await
+            .expect("Admin failed to attach");
+    viewer.send_input("echo intruder\n").unwrap();
+    wait_for_message(&mut viewer, is_error(error_codes::PERMISSION_DENIED)).await;
+    viewer
+        .send(ClientMessage::FileDownload {
+            path: "/etc/hostname".to_string(),
+        })
+        .unwrap();
+    wait_for_message(&mut viewer, is_error(error_codes::PERMISSION_DENIED)).await;
+    viewer.close().await;
+
+    let response = http
+        .delete(session_url(&session_id))
+        .bearer_auth(token("root"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+
+    owner.close().await;
+}
+
+/// Test conditional policy rules and the explain API
+///
+/// Per spec-kit/011-authentication-spec.md: Policy Rules
+#[actix_web::test]
+async fn test_policy_rules_and_explain() {
+    let dir = tempfile::tempdir().unwrap();
+    let permissions = dir.path().join("permissions.yaml");
+    std::fs::write(
+        &permissions,
+        r#"
+policies:
+  - name: contractor-restricted
+    effect: allow
+    permissions: [create_session]
+    when:
+      users: [carol]
+      source_cidrs: ["127.0.0.0/8"]
+      profiles: [restricted]
+  - name: contractor-deny
+    effect: deny
+    permissions: [create_session]
+    when:
+      users: [carol]
+  - name: oncall-kill
+    effect: allow
+    permissions: [kill_session]
+    when:
+      users: [olly]
+      owner: other
+"#,
+    )
+    .unwrap();
+    let server = start_server_with(|config| {
+        config.auth.authorization.permissions_file = Some(permissions.clone());
+        config.session.profiles.insert(
+            "restricted".to_string(),
+            SessionProfile {
+                shell: "/bin/sh".into(),
+                args: Vec::new(),
+                env: Default::default(),
+            },
+        );
+    })
+    .await;
+    let http = reqwest::Client::new();
+    let sessions_url = format!("{}/api/v1/sessions", server.url);
+
+    let response = http
+        .post(&sessions_url)
+        .bearer_auth(token("carol"))
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    let created: serde_json::Value = http
+        .post(&sessions_url)
+        .bearer_auth(token("carol"))
+        .json(&serde_json::json!({ "profile": "restricted" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(created["profile"], "restricted");
+
+    // Dry run from outside the allowed network
+    let explained: serde_json::Value = http
+        .post(format!("{}/api/v1/authz/explain", server.url))
+        .bearer_auth(token("carol"))
+        .json(&serde_json::json!({
+            "permission": "create_session",
+            "profile": "restricted",
+            "source_ip": "192.0.2.10",
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(explained["allowed"], false);
+    assert_eq!(explained["rule"], "contractor-deny");
+    assert_eq!(explained["trace"][0]["matched"], false);
+    assert!(explained["trace"][0]["reason"]
+        .as_str()
+        .unwrap()
+        .contains("192.0.2.10"));
+
+    // Rules take precedence over roles, which do not let users kill other
+    // users' sessions
+    let session_url = format!("{}/{}", sessions_url, created["id"].as_str().unwrap());
+    let response = http
+        .delete(&session_url)
+        .bearer_auth(token("bob"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+    let response = http
+        .delete(&session_url)
+        .bearer_auth(token("olly"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+}
+
+/// Test an expiring token is warned about and the connection closed
+///
+/// Per spec-kit/011-authentication-spec.md: Token expiry
+#[actix_web::test]
+async fn test_expired_token_closes_connection() {
+    let server = start_server().await;
+
+    let mut client = TerminalClient::connect(ClientConfig::new(
+        &server.url,
+        token_expiring_in("alice", 2),
+    ))
+    .await
+    .expect("Failed to connect");
+
+    wait_for_message(
+        &mut client,
+        |msg| matches!(msg, ServerMessage::TokenExpiring { expires_in, .. } if *expires_in <= 2),
+    )
+    .await;
+    wait_for_message(&mut client, is_error(error_codes::TOKEN_EXPIRED)).await;
+
+    let closed = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = client.next().await {
+            if let ClientEvent::Closed { error } = event {
+                return error;
+            }
+        }
+        None
+    })
+    .await
+    .expect("Connection was not closed");
+    assert!(closed.is_none());
+}
+
+/// Test re-authentication keeps a connection alive past the first token
+///
+/// Per spec-kit/011-authentication-spec.md: Token expiry
+#[actix_web::test]
+async fn test_reauthentication_extends_connection() {
+    let server = start_server().await;
+
+    let mut client = TerminalClient::connect(ClientConfig::new(
+        &server.url,
+        token_expiring_in("alice", 2),
+    ))
+    .await
+    .expect("Failed to connect");
+    wait_for_message(&mut client, |msg| {
+        matches!(msg, ServerMessage::TokenExpiring { .. })
+    })
+    .await;
+
+    // A token for someone else is refused and leaves the connection as it was
+    client.handle().reauthenticate(token("mallory")).unwrap();
+    wait_for_message(&mut client, is_error(error_codes::PERMISSION_DENIED)).await;
+
+    client.handle().reauthenticate(token("alice")).unwrap();
+    wait_for_message(
+        &mut client,
+        |msg| matches!(msg, ServerMessage::Authenticated { user_id, .. } if user_id == "alice"),
+    )
+    .await;
+
+    // Outlive the original token
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    client.send_input("echo alive-$((3*3))\n").unwrap();
+    read_until(&mut client, "alive-9").await;
+
+    client.close().await;
+}
+
+/// Test a frozen session blocks input until re-authentication
+///
+/// Per spec-kit/011-authentication-spec.md: Token expiry
+#[actix_web::test]
+async fn test_expired_token_freezes_session() {
+    let server = start_server_with(|config| {
+        config.auth.validation.on_expiry = TokenExpiryAction::Freeze;
+    })
+    .await;
+
+    let mut client = TerminalClient::connect(ClientConfig::new(
+        &server.url,
+        token_expiring_in("alice", 1),
+    ))
+    .await
+    .expect("Failed to connect");
+    wait_for_message(&mut client, is_error(error_codes::TOKEN_EXPIRED)).await;
+
+    client.send_input("echo frozen-$((5+5))\n").unwrap();
+    wait_for_message(&mut client, is_error(error_codes::TOKEN_EXPIRED)).await;
+
+    client.handle().reauthenticate(token("alice")).unwrap();
+    client.send_input("echo thawed-$((5+5))\n").unwrap();
+    let output = read_until(&mut client, "thawed-10").await;
+    assert!(!output.contains("frozen-10"));
+
+    client.close().await;
+}
+
+type RawSocket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Open a bare WebSocket to `path`, letting `customize` add upgrade headers
+async fn raw_connect(
+    server: &TestServer,
+    path: &str,
+    customize: impl FnOnce(&mut tungstenite::handshake::client::Request),
+) -> Result<(RawSocket, tungstenite::handshake::client::Response), tungstenite::Error> {
+    let url = format!("{}{}", server.url.replacen("http", "ws", 1), path);
+    let mut request = url.into_client_request()?;
+    customize(&mut request);
+    tokio_tungstenite::connect_async(request).await
+}
+
+/// Next JSON message on a bare WebSocket
+async fn next_json(socket: &mut RawSocket) -> Option<ServerMessage> {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(frame) = socket.next().await {
+            match frame.ok()? {
+                Message::Text(text) => return serde_json::from_str(&text).ok(),
+                Message::Close(_) => return None,
+                _ => {}
+            }
+        }
+        None
+    })
+    .await
+    .expect("Timed out waiting for server message")
+}
+
+/// Assert the connection authenticates as `user` without an Authenticate message
+async fn assert_authenticated_on_upgrade(socket: &mut RawSocket, user: &str) {
+    assert!(matches!(
+        next_json(socket).await,
+        Some(ServerMessage::ConnectionStatus {
+            session_id: None,
+            ..
+        })
+    ));
+    match next_json(socket).await {
+        Some(ServerMessage::Authenticated { user_id, .. }) => assert_eq!(user_id, user),
+        other => panic!("Expected Authenticated, got {:?}", other),
+    }
+    // Short-lived tokens are warned about while the session is being set up
+    let mut message = next_json(socket).await;
+    while matches!(message, Some(ServerMessage::TokenExpiring { .. })) {
+        message = next_json(socket).await;
+    }
+    assert!(
+        matches!(
+            message,
+            Some(ServerMessage::ConnectionStatus {
+                session_id: Some(_),
+                ..
+            })
+        ),
+        "Expected ConnectionStatus, got {:?}",
+        message
+    );
+}
+
+/// Test an Authorization header on the upgrade request authenticates
+///
+/// Per spec-kit/011-authentication-spec.md section 7: Token sources
+#[actix_web::test]
+async fn test_upgrade_header_token() {
+    let server = start_server().await;
+    let bearer = format!("Bearer {}", token("alice"));
+
+    let (mut socket, _) = raw_connect(&server, "/ws", |request| {
+        request
+            .headers_mut()
+            .insert("Authorization", HeaderValue::from_str(&bearer).unwrap());
+    })
+    .await
+    .expect("Upgrade with header token failed");
+
+    assert_authenticated_on_upgrade(&mut socket, "alice").await;
+}
+
+/// Test a query string token authenticates
+///
+/// Per spec-kit/011-authentication-spec.md section 7: Token sources
+#[actix_web::test]
+async fn test_upgrade_query_token() {
+    let server = start_server().await;
+
+    let path = format!("/ws?access_token={}", token("alice"));
+    let (mut socket, _) = raw_connect(&server, &path, |_| {})
+        .await
+        .expect("Upgrade with query token failed");
+
+    assert_authenticated_on_upgrade(&mut socket, "alice").await;
+}
+
+/// Test a subprotocol token authenticates and only the marker is echoed
+///
+/// Per spec-kit/011-authentication-spec.md section 7: Token sources
+#[actix_web::test]
+async fn test_upgrade_subprotocol_token() {
+    let server = start_server().await;
+    let protocols = format!("bearer, bearer.{}", token("alice"));
+
+    let (mut socket, response) = raw_connect(&server, "/ws", |request| {
+        request.headers_mut().insert(
+            "Sec-WebSocket-Protocol",
+            HeaderValue::from_str(&protocols).unwrap(),
+        );
+    })
+    .await
+    .expect("Upgrade with subprotocol token failed");
+
+    assert_eq!(
+        response.headers().get("Sec-WebSocket-Protocol").unwrap(),
+        "bearer"
+    );
+    assert_authenticated_on_upgrade(&mut socket, "alice").await;
+}
+
+/// Test an invalid upgrade token is refused before the upgrade
+///
+/// Per spec-kit/011-authentication-spec.md section 7: Token sources
+#[actix_web::test]
+async fn test_upgrade_invalid_token_rejected() {
+    let server = start_server().await;
+
+    let result = raw_connect(&server, "/ws?access_token=not-a-token", |_| {}).await;
+    match result {
+        Err(tungstenite::Error::Http(response)) => assert_eq!(response.status(), 401),
+        other => panic!("Expected 401, got {:?}", other.map(|(_, r)| r.status())),
+    }
+}
+
+/// Test disabled token sources are ignored
+///
+/// Per spec-kit/011-authentication-spec.md section 7: Token sources
+#[actix_web::test]
+async fn test_disabled_token_sources_ignored() {
+    let server = start_server_with(|config| {
+        config.auth.security.allowed_token_sources = vec![TokenSource::Header];
+    })
+    .await;
+
+    // The query token is not looked at: the connection waits for auth
+    let path = format!("/ws?access_token={}", token("alice"));
+    let (mut socket, _) = raw_connect(&server, &path, |_| {})
+        .await
+        .expect("Upgrade failed");
+    assert!(matches!(
+        next_json(&mut socket).await,
+        Some(ServerMessage::ConnectionStatus {
+            session_id: None,
+            ..
+        })
+    ));
+
+    // ...and Authenticate messages are refused
+    let auth = ClientMessage::Authenticate {
+        token: token("alice"),
+    };
+    socket
+        .send(Message::text(serde_json::to_string(&auth).unwrap()))
+        .await
+        .unwrap();
+    match next_json(&mut socket).await {
+        Some(ServerMessage::Error { code, .. }) => {
+            assert_eq!(code, error_codes::AUTHENTICATION_FAILED)
+        }
+        other => panic!("Expected AUTHENTICATION_FAILED, got {:?}", other),
+    }
+    assert!(next_json(&mut socket).await.is_none());
+}
+
+/// Open a ttyd-protocol WebSocket on `/ttyd/ws`
+async fn ttyd_connect(server: &TestServer) -> RawSocket {
+    let (socket, _) = raw_connect(server, "/ttyd/ws", |request| {
+        request
+            .headers_mut()
+            .insert("Sec-WebSocket-Protocol", HeaderValue::from_static("tty"));
+    })
+    .await
+    .expect("Upgrade failed");
+    socket
+}
+
+/// Send the ttyd JSON handshake carrying `token`
+async fn ttyd_handshake(socket: &mut RawSocket, token: &str) {
+    let handshake = serde_json::json!({ "AuthToken": token, "columns": 80, "rows": 24 });
+    socket
+        .send(Message::text(handshake.to_string()))
+        .await
+        .unwrap();
+}
+
+/// Send ttyd input (command byte `0`)
+async fn ttyd_input(socket: &mut RawSocket, input: &str) {
+    socket
+        .send(Message::binary(format!("0{}", input).into_bytes()))
+        .await
+        .unwrap();
+}
+
+/// Wait for the window title frame ttyd sends once the PTY is running
+async fn ttyd_title(socket: &mut RawSocket) -> String {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(frame) = socket.next().await {
+            match frame.expect("ttyd connection failed") {
+                Message::Binary(data) if data.first() == Some(&b'1') => {
+                    return String::from_utf8_lossy(&data[1..]).into_owned();
+                }
+                Message::Close(frame) => panic!("ttyd connection closed: {:?}", frame),
+                _ => {}
+            }
+        }
+        panic!("ttyd connection ended before the title frame")
+    })
+    .await
+    .expect("Timed out waiting for ttyd title")
+}
+
+/// What the server sent on a ttyd socket before the reader stopped
+#[derive(Default)]
+struct TtydFrames {
+    title: Option<String>,
+    output: String,
+    close_code: Option<u16>,
+    closed: bool,
+}
+
+/// Read ttyd frames until `needle` shows up in the output, the socket closes
+/// or `wait` elapses
+async fn ttyd_read(socket: &mut RawSocket, needle: &str, wait: Duration) -> TtydFrames {
+    let mut frames = TtydFrames::default();
+    let _ = tokio::time::timeout(wait, async {
+        while let Some(frame) = socket.next().await {
+            match frame {
+                Ok(Message::Binary(data)) if !data.is_empty() => {
+                    let text = String::from_utf8_lossy(&data[1..]).into_owned();
+                    match data[0] {
+                        b'0' => frames.output.push_str(&text),
+                        b'1' => frames.title = Some(text),
+                        _ => {}
+                    }
+                    if !needle.is_empty() && frames.output.contains(needle) {
+                        return;
+                    }
+                }
+                Ok(Message::Close(frame)) => {
+                    frames.close_code = frame.map(|f| u16::from(f.code));
+                    frames.closed = true;
+                    return;
+                }
+                Err(_) => {
+                    frames.closed = true;
+                    return;
+                }
+                _ => {}
+            }
+        }
+        frames.closed = true;
+    })
+    .await;
+    frames
+}
+
+/// Test the ttyd handshake authenticates the connection
+///
+/// Per spec-kit/007-websocket-spec.md: Alternative client protocols
+#[actix_web::test]
+async fn test_ttyd_handshake_authenticates() {
+    let server = start_server().await;
+
+    let mut socket = ttyd_connect(&server).await;
+    ttyd_handshake(&mut socket, &token("alice")).await;
+    assert!(ttyd_title(&mut socket).await.contains("alice"));
+    ttyd_input(&mut socket, "echo ttyd-$((40+2))\n").await;
+    let frames = ttyd_read(&mut socket, "ttyd-42", Duration::from_secs(10)).await;
+    assert!(frames.output.contains("ttyd-42"), "{:?}", frames.output);
+
+    // A bad token closes the connection with a policy violation
+    let mut socket = ttyd_connect(&server).await;
+    ttyd_handshake(&mut socket, "not-a-token").await;
+    let frames = ttyd_read(&mut socket, "", Duration::from_secs(10)).await;
+    assert!(frames.closed);
+    assert_eq!(frames.close_code, Some(1008));
+    assert!(frames.title.is_none());
+}
+
+/// Test ttyd input sent before the handshake is dropped
+///
+/// Per spec-kit/007-websocket-spec.md: Alternative client protocols
+#[actix_web::test]
+async fn test_ttyd_input_before_handshake_refused() {
+    let server = start_server().await;
+
+    let mut socket = ttyd_connect(&server).await;
+    ttyd_input(&mut socket, "echo before-$((1+1))\n").await;
+    ttyd_handshake(&mut socket, &token("alice")).await;
+    ttyd_title(&mut socket).await;
+    ttyd_input(&mut socket, "echo after-$((1+1))\n").await;
+
+    let frames = ttyd_read(&mut socket, "after-2", Duration::from_secs(10)).await;
+    assert!(frames.output.contains("after-2"), "{:?}", frames.output);
+    assert!(!frames.output.contains("before-2"), "{:?}", frames.output);
+}
+
+/// Test ttyd connections are held to the SendInput and CreateSession permissions
+///
+/// Per spec-kit/007-websocket-spec.md: Alternative client protocols
+#[actix_web::test]
+async fn test_ttyd_permission_denials() {
+    let server = start_server_with(|config| config.auth.api_keys.enabled = true).await;
+
+    // Without SendInput the session opens but input never reaches the shell
+    let creator = create_api_key(&server, "alice", &["create_session"]).await;
+    let mut socket = ttyd_connect(&server).await;
+    ttyd_handshake(&mut socket, creator["token"].as_str().unwrap()).await;
+    assert!(ttyd_title(&mut socket).await.contains("alice"));
+    ttyd_input(&mut socket, "echo denied-$((1+1))\n").await;
+    let frames = ttyd_read(&mut socket, "denied-2", Duration::from_secs(2)).await;
+    assert!(!frames.closed);
+    assert!(!frames.output.contains("denied-2"), "{:?}", frames.output);
+
+    // Without CreateSession the handshake is refused
+    let typist = create_api_key(&server, "alice", &["send_input"]).await;
+    let mut socket = ttyd_connect(&server).await;
+    ttyd_handshake(&mut socket, typist["token"].as_str().unwrap()).await;
+    let frames = ttyd_read(&mut socket, "", Duration::from_secs(10)).await;
+    assert!(frames.closed);
+    assert_eq!(frames.close_code, Some(1008));
+    assert!(frames.title.is_none());
+}
+
+/// Test a provider configured by issuer only is resolved via OIDC discovery
+///
+/// Per spec-kit/011-authentication-spec.md section 3.1: JWKS providers
+#[actix_web::test]
+async fn test_oidc_discovered_provider() {
+    let idp = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/.well-known/openid-configuration"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "issuer": idp.uri(),
+            "jwks_uri": format!("{}/keys", idp.uri()),
+            "id_token_signing_alg_values_supported": ["RS256"],
+        })))
+        .mount(&idp)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/keys"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            include_str!("../fixtures/test-jwks.json"),
+            "application/json",
+        ))
+        .mount(&idp)
+        .await;
+
+    let issuer = idp.uri();
+    let server = start_server_with(|config| {
+        let provider = &mut config.auth.jwks.providers[0];
+        provider.url = None;
+        provider.issuer = issuer.clone();
+    })
+    .await;
+
+    let client = TerminalClient::connect(ClientConfig::new(
+        &server.url,
+        sign_token(&idp.uri(), "alice", 3600),
+    ))
+    .await
+    .expect("Failed to connect with discovered provider");
+    assert_eq!(client.user_id(), "alice");
+    client.close().await;
+}
+
+/// Provider for locally signed tokens from `keys`
+fn static_provider(keys: StaticKeySource, algorithms: &[&str]) -> JwksProvider {
+    JwksProvider {
+        name: "lab".to_string(),
+        url: None,
+        keys: Some(keys),
+        issuer: "web-terminal-lab".to_string(),
+        audience: "web-terminal".to_string(),
+        algorithms: algorithms.iter().map(|a| a.to_string()).collect(),
+        cache_ttl: Duration::from_secs(3600),
+        refresh_interval: Duration::from_secs(900),
+        timeout: Duration::from_secs(5),
+    }
+}
+
+/// Sign an HS256 token from the lab issuer
+fn hmac_token(secret: &[u8], sub: &str) -> String {
+    let now = chrono::Utc::now().timestamp();
+    let claims = serde_json::json!({
+        "sub": sub,
+        "iss": "web-terminal-lab",
+        "aud": "web-terminal",
+        "iat": now,
+        "exp": now + 3600,
+    });
+    encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(secret),
+    )
+    .expect("Failed to sign token")
+}
+
+/// Test locally minted HMAC tokens, including a secret rotated on disk
+///
+/// Per spec-kit/011-authentication-spec.md section 3.1: Static key providers
+#[actix_web::test]
+async fn test_hmac_secret_provider_reloads() {
+    let dir = tempfile::tempdir().unwrap();
+    let secret_path = dir.path().join("hmac.key");
+    let first = b"first-secret-0123456789abcdef0123";
+    let second = b"second-secret-0123456789abcdef012";
+    std::fs::write(&secret_path, first).unwrap();
+
+    let keys = StaticKeySource::SecretFile(secret_path.clone());
+    let server = start_server_with(|config| {
+        config
+            .auth
+            .jwks
+            .providers
+            .push(static_provider(keys, &["HS256"]));
+    })
+    .await;
+
+    let client =
+        TerminalClient::connect(ClientConfig::new(&server.url, hmac_token(first, "alice")))
+            .await
+            .expect("Failed to connect with HMAC token");
+    assert_eq!(client.user_id(), "alice");
+    client.close().await;
+
+    // Rotate the secret (bump mtime in case the write lands in the same tick)
+    std::fs::write(&secret_path, second).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&secret_path)
+        .unwrap()
+        .set_modified(std::time::SystemTime::now() + Duration::from_secs(5))
+        .unwrap();
+
+    let stale =
+        TerminalClient::connect(ClientConfig::new(&server.url, hmac_token(first, "alice"))).await;
+    assert!(matches!(stale, Err(ClientError::AuthenticationFailed(_))));
+
+    let client =
+        TerminalClient::connect(ClientConfig::new(&server.url, hmac_token(second, "alice")))
+            .await
+            .expect("Failed to connect with rotated secret");
+    client.close().await;
+}
+
+/// Test tokens verified against a PEM public key on disk
+///
+/// Per spec-kit/011-authentication-spec.md section 3.1: Static key providers
+#[actix_web::test]
+async fn test_pem_public_key_provider() {
+    let pem = std::path::PathBuf::from(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/test-rsa-pub.pem"
+    ));
+    let server = start_server_with(|config| {
+        config
+            .auth
+            .jwks
+            .providers
+            .push(static_provider(StaticKeySource::PemFile(pem), &["RS256"]));
+    })
+    .await;
+
+    let token = sign_token("web-terminal-lab", "alice", 3600);
+    let client = TerminalClient::connect(ClientConfig::new(&server.url, token))
+        .await
+        .expect("Failed to connect with PEM-verified token");
+    assert_eq!(client.user_id(), "alice");
+    client.close().await;
+
+    // An HMAC token "signed" with the public key must not pass
+    let forged = hmac_token(include_bytes!("../fixtures/test-rsa-pub.pem"), "mallory");
+    let result = TerminalClient::connect(ClientConfig::new(&server.url, forged)).await;
+    assert!(matches!(result, Err(ClientError::AuthenticationFailed(_))));
+}
+
+/// Create an API key for `sub` through the REST API
+async fn create_api_key(server: &TestServer, sub: &str, scopes: &[&str]) -> serde_json::Value {
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/v1/tokens", server.url))
+        .bearer_auth(token(sub))
+        .json(&serde_json::json!({ "name": "ci", "scopes": scopes, "expires_in": 3600 }))
+        .send()
+        .await
+        .expect("Failed to create API key");
+    assert_eq!(response.status(), 201);
+    response.json().await.unwrap()
+}
+
+/// Test API keys: scoped REST and WebSocket access, management and revocation
+///
+/// Per spec-kit/011-authentication-spec.md: API Keys
+#[actix_web::test]
+async fn test_api_key_lifecycle() {
+    let server = start_server_with(|config| config.auth.api_keys.enabled = true).await;
+    let http = reqwest::Client::new();
+
+    let viewer = create_api_key(&server, "alice", &["view_session"]).await;
+    let viewer_key = viewer["token"].as_str().unwrap();
+    assert!(viewer_key.starts_with("wtk_"));
+
+    // Scoped REST access
+    let list = http
+        .get(format!("{}/api/v1/sessions", server.url))
+        .bearer_auth(viewer_key)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(list.status(), 200);
+    let create = http
+        .post(format!("{}/api/v1/sessions", server.url))
+        .bearer_auth(viewer_key)
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(create.status(), 403);
+
+    // Keys cannot manage keys
+    let mint = http
+        .post(format!("{}/api/v1/tokens", server.url))
+        .bearer_auth(viewer_key)
+        .json(&serde_json::json!({ "name": "escalate", "scopes": ["create_session"] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(mint.status(), 403);
+
+    // A key without create_session cannot open a shell over WebSocket
+    let result = TerminalClient::connect(ClientConfig::new(&server.url, viewer_key)).await;
+    match result {
+        Err(ClientError::Server { code, .. }) => assert_eq!(code, error_codes::PERMISSION_DENIED),
+        other => panic!("Expected permission denied, got {:?}", other.err()),
+    }
+
+    // A shell key authenticates in-band and acts as its owner
+    let shell = create_api_key(&server, "alice", &["create_session", "send_input"]).await;
+    let shell_key = shell["token"].as_str().unwrap();
+    let mut client = TerminalClient::connect(ClientConfig::new(&server.url, shell_key))
+        .await
+        .expect("Failed to connect with API key");
+    assert_eq!(client.user_id(), "alice");
+    client.send_input("echo key-$((20+22))\n").unwrap();
+    read_until(&mut client, "key-42").await;
+    client.close().await;
+
+    // Listing never shows secrets
+    let listed: serde_json::Value = http
+        .get(format!("{}/api/v1/tokens", server.url))
+        .bearer_auth(token("alice"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let tokens = listed["tokens"].as_array().unwrap();
+    assert_eq!(tokens.len(), 2);
+    assert!(tokens.iter().all(|t| t.get("token").is_none()));
+
+    // Revoked keys stop working; other users cannot revoke them
+    let shell_url = format!(
+        "{}/api/v1/tokens/{}",
+        server.url,
+        shell["id"].as_str().unwrap()
+    );
+    let foreign = http
+        .delete(&shell_url)
+        .bearer_auth(token("mallory"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(foreign.status(), 404);
+    let revoke = http
+        .delete(&shell_url)
+        .bearer_auth(token("alice"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(revoke.status(), 204);
+    let revoked = http
+        .get(format!("{}/api/v1/sessions", server.url))
+        .bearer_auth(shell_key)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(revoked.status(), 401);
+}
+
+/// Test an invalid token is rejected
+///
+/// Per spec-kit/011-authentication-spec.md: Authentication flow
+#[actix_web::test]
+async fn test_client_rejects_invalid_token() {
+    let server = start_server().await;
+
+    let result = TerminalClient::connect(ClientConfig::new(&server.url, "not-a-token")).await;
+    assert!(matches!(result, Err(ClientError::AuthenticationFailed(_))));
+}
+
+/// Test the admission policy on REST, the WebSocket upgrade and in-band
+/// authentication, with deny taking precedence
+///
+/// Per spec-kit/011-authentication-spec.md: Authorization Model
+#[actix_web::test]
+async fn test_admission_policy_enforced() {
+    let server = start_server_with(|config| {
+        config.auth.authorization.allowed_users = vec!["alice".to_string(), "bob".to_string()];
+        config.auth.authorization.deny_users = vec!["bob".to_string()];
+    })
+    .await;
+    let http = reqwest::Client::new();
+
+    for (user, status) in [("alice", 200), ("bob", 403), ("carol", 403)] {
+        let response = http
+            .get(format!("{}/api/v1/sessions", server.url))
+            .bearer_auth(token(user))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), status, "REST access for {}", user);
+    }
+
+    let path = format!("/ws?access_token={}", token("bob"));
+    match raw_connect(&server, &path, |_| {}).await {
+        Err(tungstenite::Error::Http(response)) => assert_eq!(response.status(), 403),
+        other => panic!("Expected 403, got {:?}", other.map(|(_, r)| r.status())),
+    }
+
+    let result = TerminalClient::connect(ClientConfig::new(&server.url, token("carol"))).await;
+    assert!(matches!(
+        result,
+        Err(ClientError::Server { code, .. }) if code == error_codes::PERMISSION_DENIED
+    ));
+
+    let client = TerminalClient::connect(ClientConfig::new(&server.url, token("alice")))
+        .await
+        .expect("Allowed user failed to connect");
+    client.close().await;
+}
+
+/// Test revoking a subject closes their live connection and refuses their
+/// tokens until lifted, and WebSocket tickets are accepted once
+///
+/// Per spec-kit/011-authentication-spec.md: Token Revocation
+#[actix_web::test]
+async fn test_token_revocation() {
+    let server = start_server_with(|config| {
+        config
+            .auth
+            .authorization
+            .role_mappings
+            .insert("admin".to_string(), vec!["root".to_string()]);
+        config.auth.revocation.one_time_tickets = true;
+    })
+    .await;
+    let http = reqwest::Client::new();
+    let revocations_url = format!("{}/api/v1/admin/revocations", server.url);
+    let sessions = |user: &str| {
+        http.get(format!("{}/api/v1/sessions", server.url))
+            .bearer_auth(token(user))
+            .send()
+    };
+
+    let mut client = TerminalClient::connect(ClientConfig::new(&server.url, token("alice")))
+        .await
+        .expect("Failed to connect");
+
+    let response = http
+        .post(&revocations_url)
+        .bearer_auth(token("bob"))
+        .json(&serde_json::json!({ "sub": "alice" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    let response = http
+        .post(&revocations_url)
+        .bearer_auth(token("root"))
+        .json(&serde_json::json!({ "sub": "alice", "reason": "offboarded" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    let revocation: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(revocation["created_by"], "root");
+
+    // The live connection is closed on its next heartbeat
+    wait_for_message(&mut client, is_error(error_codes::TOKEN_REVOKED)).await;
+    assert_eq!(sessions("alice").await.unwrap().status(), 401);
+    assert_eq!(sessions("bob").await.unwrap().status(), 200);
+
+    let listed: serde_json::Value = http
+        .get(&revocations_url)
+        .bearer_auth(token("root"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(listed["revocations"].as_array().unwrap().len(), 1);
+
+    let response = http
+        .delete(format!(
+            "{}/{}",
+            revocations_url,
+            revocation["id"].as_str().unwrap()
+        ))
+        .bearer_auth(token("root"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+    assert_eq!(sessions("alice").await.unwrap().status(), 200);
+
+    // A ticket opens one connection only
+    let path = format!("/ws?access_token={}", ticket("alice", "ticket-1"));
+    let (mut socket, _) = raw_connect(&server, &path, |_| {})
+        .await
+        .expect("Upgrade with ticket failed");
+    assert_authenticated_on_upgrade(&mut socket, "alice").await;
+    match raw_connect(&server, &path, |_| {}).await {
+        Err(tungstenite::Error::Http(response)) => assert_eq!(response.status(), 401),
+        other => panic!("Expected 401, got {:?}", other.map(|(_, r)| r.status())),
+    }
+}
 
 /// Test message serialization and deserialization
 ///
@@ -31,6 +1395,7 @@ async fn test_message_serialization() {
 
     // Test ServerMessage serialization
     let output_msg = ServerMessage::Output {
+        stream: None,
         data: "Hello World\n".to_string(),
     };
 
@@ -42,7 +1407,7 @@ async fn test_message_serialization() {
     let parsed: ServerMessage =
         serde_json::from_str(&json).expect("Failed to deserialize output message");
     match parsed {
-        ServerMessage::Output { data } => assert_eq!(data, "Hello World\n"),
+        ServerMessage::Output { data, .. } => assert_eq!(data, "Hello World\n"),
         _ => panic!("Wrong message type"),
     }
 }
@@ -106,7 +1471,9 @@ async fn test_signal_message() {
 #[tokio::test]
 async fn test_error_message() {
     let error_msg = ServerMessage::Error {
+        code: error_codes::COMMAND_NOT_FOUND.to_string(),
         message: "Command not found".to_string(),
+        details: None,
     };
 
     let json = serde_json::to_string(&error_msg).expect("Failed to serialize error message");
@@ -116,7 +1483,7 @@ async fn test_error_message() {
     let parsed: ServerMessage =
         serde_json::from_str(&json).expect("Failed to deserialize error message");
     match parsed {
-        ServerMessage::Error { message } => assert_eq!(message, "Command not found"),
+        ServerMessage::Error { message, .. } => assert_eq!(message, "Command not found"),
         _ => panic!("Wrong message type"),
     }
 }
@@ -126,21 +1493,29 @@ async fn test_error_message() {
 /// Per FR-1.2.2: Monitor running processes
 #[tokio::test]
 async fn test_process_exited_message() {
-    let exited_msg = ServerMessage::ProcessExited { exit_code: 0 };
+    let exited_msg = ServerMessage::ProcessExited {
+        pid: 1234,
+        exit_code: 0,
+        signal: None,
+    };
 
     let json = serde_json::to_string(&exited_msg).expect("Failed to serialize exited message");
     assert!(json.contains(r#""type":"process_exited""#));
     assert!(json.contains(r#""exit_code":0"#));
 
     // Test with error exit code
-    let exited_msg = ServerMessage::ProcessExited { exit_code: 127 };
+    let exited_msg = ServerMessage::ProcessExited {
+        pid: 1234,
+        exit_code: 127,
+        signal: None,
+    };
 
     let json = serde_json::to_string(&exited_msg).expect("Failed to serialize exited message");
     let parsed: ServerMessage =
         serde_json::from_str(&json).expect("Failed to deserialize exited message");
 
     match parsed {
-        ServerMessage::ProcessExited { exit_code } => assert_eq!(exit_code, 127),
+        ServerMessage::ProcessExited { exit_code, .. } => assert_eq!(exit_code, 127),
         _ => panic!("Wrong message type"),
     }
 }
@@ -151,22 +1526,31 @@ async fn test_process_exited_message() {
 #[tokio::test]
 async fn test_ping_pong_messages() {
     // Test Ping message
-    let ping_msg = ClientMessage::Ping;
+    let ping_msg = ClientMessage::Ping { timestamp: None };
     let json = serde_json::to_string(&ping_msg).expect("Failed to serialize ping message");
     assert!(json.contains(r#""type":"ping""#));
 
     let parsed: ClientMessage =
         serde_json::from_str(&json).expect("Failed to deserialize ping message");
-    matches!(parsed, ClientMessage::Ping);
+    assert!(matches!(parsed, ClientMessage::Ping { timestamp: None }));
 
     // Test Pong message
-    let pong_msg = ServerMessage::Pong;
+    let pong_msg = ServerMessage::Pong {
+        timestamp: Some(42),
+        latency_ms: None,
+    };
     let json = serde_json::to_string(&pong_msg).expect("Failed to serialize pong message");
     assert!(json.contains(r#""type":"pong""#));
 
     let parsed: ServerMessage =
         serde_json::from_str(&json).expect("Failed to deserialize pong message");
-    matches!(parsed, ServerMessage::Pong);
+    assert!(matches!(
+        parsed,
+        ServerMessage::Pong {
+            timestamp: Some(42),
+            ..
+        }
+    ));
 }
 
 /// Test connection status message format
@@ -176,6 +1560,7 @@ async fn test_ping_pong_messages() {
 async fn test_connection_status_message() {
     let status_msg = ServerMessage::ConnectionStatus {
         status: ConnectionStatus::Connected,
+        session_id: None,
     };
 
     let json =
@@ -191,17 +1576,24 @@ async fn test_connection_status_message() {
     ];
 
     for status in statuses {
-        let msg = ServerMessage::ConnectionStatus { status };
+        let msg = ServerMessage::ConnectionStatus {
+            status,
+            session_id: Some("session-1".to_string()),
+        };
         let json = serde_json::to_string(&msg).expect("Failed to serialize status");
         let parsed: ServerMessage = serde_json::from_str(&json).expect("Failed to deserialize");
 
         match parsed {
-            ServerMessage::ConnectionStatus { status: s } => {
+            ServerMessage::ConnectionStatus {
+                status: s,
+                session_id,
+            } => {
                 // Verify serialization roundtrip
                 assert_eq!(
                     format!("{:?}", s).to_lowercase(),
                     format!("{:?}", status).to_lowercase()
                 );
+                assert_eq!(session_id.as_deref(), Some("session-1"));
             }
             _ => panic!("Wrong message type"),
         }
@@ -246,7 +1638,7 @@ async fn test_message_protocol_completeness() {
         ClientMessage::Signal {
             signal: Signal::SIGINT,
         },
-        ClientMessage::Ping,
+        ClientMessage::Ping { timestamp: None },
     ];
 
     for msg in client_messages {
@@ -258,16 +1650,27 @@ async fn test_message_protocol_completeness() {
     // Test all ServerMessage variants
     let server_messages = vec![
         ServerMessage::Output {
+            stream: None,
             data: "output".to_string(),
         },
         ServerMessage::Error {
+            code: error_codes::INTERNAL_ERROR.to_string(),
             message: "error".to_string(),
+            details: None,
+        },
+        ServerMessage::ProcessExited {
+            pid: 1,
+            exit_code: 0,
+            signal: None,
         },
-        ServerMessage::ProcessExited { exit_code: 0 },
         ServerMessage::ConnectionStatus {
             status: ConnectionStatus::Connected,
+            session_id: None,
+        },
+        ServerMessage::Pong {
+            timestamp: None,
+            latency_ms: None,
         },
-        ServerMessage::Pong,
     ];
 
     for msg in server_messages {
@@ -285,6 +1688,7 @@ async fn test_large_message_handling() {
     // Test large output message (1MB)
     let large_data = "x".repeat(1024 * 1024);
     let large_msg = ServerMessage::Output {
+        stream: None,
         data: large_data.clone(),
     };
 
@@ -293,7 +1697,7 @@ async fn test_large_message_handling() {
         serde_json::from_str(&json).expect("Failed to deserialize large message");
 
     match parsed {
-        ServerMessage::Output { data } => {
+        ServerMessage::Output { data, .. } => {
             assert_eq!(data.len(), large_data.len());
             assert_eq!(data, large_data);
         }
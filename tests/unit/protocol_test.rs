@@ -84,7 +84,7 @@ fn test_client_signal_serialization() {
 #[test]
 fn test_client_ping_serialization() {
     // Arrange
-    let msg = ClientMessage::Ping;
+    let msg = ClientMessage::Ping { timestamp: None };
 
     // Act
     let json = serde_json::to_string(&msg).expect("Failed to serialize");
@@ -93,7 +93,7 @@ fn test_client_ping_serialization() {
     // Assert
     assert!(json.contains(r#""type":"ping""#));
     match parsed {
-        ClientMessage::Ping => (),
+        ClientMessage::Ping { timestamp: None } => (),
         _ => panic!("Wrong message type"),
     }
 }